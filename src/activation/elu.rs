@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// `ELUActivationFunction` represents the Exponential Linear Unit (ELU) activation
@@ -6,7 +7,7 @@ use super::ActivationFunction;
 /// that helps mitigate the dying neuron problem and reduce the vanishing gradient
 /// effect.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. The ELU function
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. The ELU function
 /// is defined as `x` for `x > 0` and `alpha * (e^x - 1)` for `x <= 0`, where `alpha`
 /// is a hyperparameter.
 ///
@@ -20,22 +21,22 @@ use super::ActivationFunction;
 /// let activated_value = elu.activate(-1.0);  // Evaluates to approximately -0.6321
 /// let derivative_value = elu.derivate(-1.0); // Evaluates to approximately 0.3679
 /// ```
-pub struct ELUActivationFunction {
-    alpha: f64,
+pub struct ELUActivationFunction<F = f64> {
+    alpha: F,
 }
 
-impl ELUActivationFunction {
+impl<F: Float> ELUActivationFunction<F> {
     /// Creates a new instance of `ELUActivationFunction` with the given alpha value.
     ///
     /// # Arguments
     ///
     /// * `alpha` - The alpha value for the ELU function, controlling the value
-    ///             to which an ELU saturates for negative net inputs.
+    ///   to which an ELU saturates for negative net inputs.
     ///
     /// # Returns
     ///
     /// A new instance of `ELUActivationFunction`.
-    pub fn new(alpha: f64) -> Self {
+    pub fn new(alpha: F) -> Self {
         ELUActivationFunction { alpha }
     }
 
@@ -44,12 +45,12 @@ impl ELUActivationFunction {
     /// # Arguments
     ///
     /// * `new_alpha` - The new value to set for the alpha parameter.
-    pub fn update_alpha(&mut self, new_alpha: f64) {
+    pub fn update_alpha(&mut self, new_alpha: F) {
         self.alpha = new_alpha;
     }
 }
 
-impl ActivationFunction<f64, f64> for ELUActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for ELUActivationFunction<F> {
     /// Computes the Exponential Linear Unit (ELU) of a given input value.
     ///
     /// For positive inputs, it returns the input itself. For non-positive inputs,
@@ -63,11 +64,11 @@ impl ActivationFunction<f64, f64> for ELUActivationFunction {
     /// # Returns
     ///
     /// The ELU of the input.
-    fn activate(&self, input: f64) -> f64 {
-        if input > 0.0 {
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() {
             input
         } else {
-            self.alpha * (input.exp() - 1.0)
+            self.alpha * (input.exp() - F::one())
         }
     }
 
@@ -83,9 +84,9 @@ impl ActivationFunction<f64, f64> for ELUActivationFunction {
     /// # Returns
     ///
     /// The derivative of the ELU function at the given input.
-    fn derivate(&self, input: f64) -> f64 {
-        if input > 0.0 {
-            1.0
+    fn derivate(&self, input: F) -> F {
+        if input > F::zero() {
+            F::one()
         } else {
             self.alpha * input.exp()
         }
@@ -0,0 +1,190 @@
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI, PI};
+
+use crate::numeric::{constant, to_f64, Float};
+use super::ActivationFunction;
+
+/// The coefficient of the cubic term in the tanh approximation of GELU.
+const TANH_COEFFICIENT: f64 = 0.044715;
+
+/// Represents the Gaussian Error Linear Unit (GELU) activation function in neural networks.
+///
+/// GELU weights its input by the probability that a standard normal variable is below it,
+/// `GELU(x) = x * Phi(x)`, where `Phi` is the standard normal cumulative distribution function.
+/// It is the activation of choice in Transformer architectures such as BERT and GPT.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`, evaluating the
+/// error function in double precision. Two variants are available:
+///
+/// * The exact form, `x * (1 + erf(x / sqrt(2))) / 2`, whose derivative is `Phi(x) + x * phi(x)`,
+///   with `phi` the standard normal density.
+/// * The tanh approximation, `x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3))) / 2`, which is
+///   cheaper and was used to train the original GPT models.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::gelu::GELUActivationFunction;
+///
+/// let gelu = GELUActivationFunction::new();
+/// assert!((gelu.activate(1.0_f64) - 0.8413447460685429).abs() < 1e-12);
+///
+/// let approximate = GELUActivationFunction::tanh_approximation();
+/// assert!((approximate.activate(1.0_f32) - gelu.activate(1.0_f32)).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GELUActivationFunction {
+    approximate: bool
+}
+
+impl GELUActivationFunction {
+    /// Creates a new instance of the exact `GELUActivationFunction`.
+    pub fn new() -> Self {
+        Self { approximate: false }
+    }
+
+    /// Creates a new instance of `GELUActivationFunction` using the tanh approximation.
+    pub fn tanh_approximation() -> Self {
+        Self { approximate: true }
+    }
+
+    /// Returns `true` if the tanh approximation is used.
+    pub fn is_approximate(&self) -> bool {
+        self.approximate
+    }
+}
+
+impl<F: Float> ActivationFunction<F, F> for GELUActivationFunction {
+    /// Computes the GELU of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the GELU.
+    ///
+    /// # Returns
+    ///
+    /// The GELU of the input, exact or approximated depending on the variant.
+    fn activate(&self, input: F) -> F {
+        let input = to_f64(input);
+        constant(if self.approximate {
+            0.5 * input * (1.0 + tanh_argument(input).tanh())
+        } else {
+            input * normal_cdf(input)
+        })
+    }
+
+    /// Computes the derivative of the GELU function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The derivative of the GELU variant at the given input.
+    fn derivate(&self, input: F) -> F {
+        let input = to_f64(input);
+        constant(if self.approximate {
+            let tanh = tanh_argument(input).tanh();
+            let argument_derivative = (2.0 / PI).sqrt() * (1.0 + 3.0 * TANH_COEFFICIENT * input * input);
+            0.5 * (1.0 + tanh) + 0.5 * input * (1.0 - tanh * tanh) * argument_derivative
+        } else {
+            let density = (-0.5 * input * input).exp() * FRAC_2_SQRT_PI * FRAC_1_SQRT_2 / 2.0;
+            normal_cdf(input) + input * density
+        })
+    }
+}
+
+/// The argument of tanh in the approximation, `sqrt(2 / pi) * (x + 0.044715 * x^3)`.
+fn tanh_argument(input: f64) -> f64 {
+    (2.0 / PI).sqrt() * (input + TANH_COEFFICIENT * input.powi(3))
+}
+
+/// The standard normal cumulative distribution function, `erfc(-x / sqrt(2)) / 2`, which keeps
+/// full relative precision in the lower tail.
+fn normal_cdf(input: f64) -> f64 {
+    0.5 * erfc(-input * FRAC_1_SQRT_2)
+}
+
+/// The complementary error function.
+///
+/// Below 2 in absolute value, `erf` is computed from its series
+/// `2 / sqrt(pi) * e^(-x^2) * sum(2^n * x^(2n + 1) / (1 * 3 * ... * (2n + 1)))`, whose terms are all
+/// of the same sign. Above, `erfc` is computed from its continued fraction, which converges
+/// quickly there and does not lose precision to cancellation.
+fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return f64::NAN;
+    }
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+    if x < 2.0 {
+        return 1.0 - erf_series(x);
+    }
+
+    let mut fraction = x;
+    for k in (1..=60).rev() {
+        fraction = x + (k as f64 / 2.0) / fraction;
+    }
+    (-x * x).exp() * FRAC_2_SQRT_PI / 2.0 / fraction
+}
+
+/// The series of the error function, accurate for small arguments.
+fn erf_series(x: f64) -> f64 {
+    let mut term = x;
+    let mut total = x;
+    for n in 1..200 {
+        term *= 2.0 * x * x / (2 * n + 1) as f64;
+        total += term;
+        if term.abs() <= 1e-17 * total.abs() {
+            break;
+        }
+    }
+
+    FRAC_2_SQRT_PI * (-x * x).exp() * total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn gelu_activate_exact() {
+        let gelu = GELUActivationFunction::new();
+
+        assert_eq!(gelu.activate(0.0), 0.0);
+        assert!((gelu.activate(1.0_f64) - 0.8413447460685429).abs() < 1e-12);
+        assert!((gelu.activate(-1.0_f64) + 0.15865525393145707).abs() < 1e-12);
+        assert!((gelu.activate(3.0_f64) - 2.99595030590511).abs() < 1e-12);
+        assert!((gelu.activate(-10.0_f64) + 7.619853024160593e-23).abs() < 1e-35, "Lower tail should keep relative precision");
+    }
+
+    #[test]
+    fn gelu_activate_tanh_approximation() {
+        let gelu = GELUActivationFunction::tanh_approximation();
+
+        assert_eq!(gelu.activate(0.0), 0.0);
+        assert!((gelu.activate(1.0_f64) - 0.8411919906082768).abs() < 1e-12);
+        assert!((gelu.activate(-1.0_f64) + 0.15880800939172324).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gelu_derivate_matches_finite_differences() {
+        for gelu in [GELUActivationFunction::new(), GELUActivationFunction::tanh_approximation()] {
+            for input in DERIVATIVE_INPUTS {
+                let expected = numerical_derivative(&gelu, input);
+                assert!((gelu.derivate(input) - expected).abs() < 1e-6, "GELU'({}) mismatch", input);
+            }
+        }
+    }
+
+    #[test]
+    fn erfc_matches_reference() {
+        assert!((erfc(0.5) - 0.4795001221869535).abs() < 1e-15);
+        assert!((erfc(2.5) - 4.069520174449589e-04).abs() < 1e-18);
+        assert!((erfc(-1.0) - 1.8427007929497148).abs() < 1e-15);
+        assert!((erfc(10.0) / 2.088487583762545e-45 - 1.0).abs() < 1e-12);
+    }
+}
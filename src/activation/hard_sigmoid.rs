@@ -0,0 +1,78 @@
+use crate::numeric::{constant, Float};
+use super::ActivationFunction;
+
+/// Represents the Hard Sigmoid activation function in neural networks.
+///
+/// Hard Sigmoid is a piecewise-linear approximation of the sigmoid, cheap to compute on
+/// constrained hardware. Following the MobileNetV3 definition, it is `relu6(x + 3) / 6`: 0 below
+/// -3, 1 above 3 and `x / 6 + 1 / 2` in between.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. Its derivative is `1 / 6`
+/// strictly between -3 and 3, and 0 elsewhere.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::hard_sigmoid::HardSigmoidActivationFunction;
+///
+/// let hard_sigmoid = HardSigmoidActivationFunction;
+/// assert_eq!(hard_sigmoid.activate(0.0), 0.5);
+/// assert_eq!(hard_sigmoid.activate(5.0), 1.0);
+/// ```
+pub struct HardSigmoidActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for HardSigmoidActivationFunction {
+    /// Computes the Hard Sigmoid of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the Hard Sigmoid.
+    ///
+    /// # Returns
+    ///
+    /// The Hard Sigmoid of the input, a value between 0 and 1.
+    fn activate(&self, input: F) -> F {
+        (input / constant(6.0) + constant(0.5)).max(F::zero()).min(F::one())
+    }
+
+    /// Computes the derivative of the Hard Sigmoid function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// `1 / 6` if the input is strictly between -3 and 3, and 0 otherwise.
+    fn derivate(&self, input: F) -> F {
+        let bound = constant::<F>(3.0);
+        if input > -bound && input < bound { constant(1.0 / 6.0) } else { F::zero() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn hard_sigmoid_activate() {
+        let hard_sigmoid = HardSigmoidActivationFunction;
+
+        assert_eq!(hard_sigmoid.activate(-3.0), 0.0);
+        assert_eq!(hard_sigmoid.activate(-1000.0), 0.0);
+        assert_eq!(hard_sigmoid.activate(1.5), 0.75);
+        assert_eq!(hard_sigmoid.activate(3.0), 1.0);
+    }
+
+    #[test]
+    fn hard_sigmoid_derivate_matches_finite_differences() {
+        let hard_sigmoid = HardSigmoidActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&hard_sigmoid, input);
+            assert!((hard_sigmoid.derivate(input) - expected).abs() < 1e-6, "HardSigmoid'({}) mismatch", input);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::numeric::{constant, Float};
+use super::{hard_sigmoid::HardSigmoidActivationFunction, ActivationFunction};
+
+/// Represents the Hard Swish activation function in neural networks.
+///
+/// Hard Swish, introduced with MobileNetV3, replaces the sigmoid of Swish by a Hard Sigmoid:
+/// `x * relu6(x + 3) / 6`. It is 0 below -3, `x` above 3 and `x * (x + 3) / 6` in between.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. Its derivative is 0 below -3,
+/// 1 above 3 and `(2x + 3) / 6` in between.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::hard_swish::HardSwishActivationFunction;
+///
+/// let hard_swish = HardSwishActivationFunction;
+/// assert_eq!(hard_swish.activate(3.0), 3.0);
+/// assert_eq!(hard_swish.activate(-1.5), -0.375);
+/// ```
+pub struct HardSwishActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for HardSwishActivationFunction {
+    /// Computes the Hard Swish of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the Hard Swish.
+    ///
+    /// # Returns
+    ///
+    /// The input times its Hard Sigmoid.
+    fn activate(&self, input: F) -> F {
+        input * HardSigmoidActivationFunction.activate(input)
+    }
+
+    /// Computes the derivative of the Hard Swish function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// 0 at or below -3, 1 at or above 3 and `(2x + 3) / 6` in between.
+    fn derivate(&self, input: F) -> F {
+        let bound = constant::<F>(3.0);
+        if input <= -bound {
+            F::zero()
+        } else if input >= bound {
+            F::one()
+        } else {
+            (constant::<F>(2.0) * input + bound) / constant(6.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn hard_swish_activate() {
+        let hard_swish = HardSwishActivationFunction;
+
+        assert_eq!(hard_swish.activate(-4.0), 0.0);
+        assert_eq!(hard_swish.activate(0.0), 0.0);
+        assert_eq!(hard_swish.activate(1000.0), 1000.0);
+    }
+
+    #[test]
+    fn hard_swish_derivate_matches_finite_differences() {
+        let hard_swish = HardSwishActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&hard_swish, input);
+            assert!((hard_swish.derivate(input) - expected).abs() < 1e-6, "HardSwish'({}) mismatch", input);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::numeric::Float;
+use super::ActivationFunction;
+
+/// Represents the identity (linear) activation function in neural networks.
+///
+/// The identity function returns its input unchanged. It is used in the output layer of
+/// regression networks, such as models predicting returns, where the output must be able to
+/// take any real value.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. The identity function is
+/// defined as `f(x) = x` and its derivative is 1 everywhere.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::identity::IdentityActivationFunction;
+///
+/// let identity = IdentityActivationFunction;
+/// assert_eq!(identity.activate(-0.5), -0.5);
+/// assert_eq!(identity.derivate(-0.5), 1.0);
+/// ```
+pub struct IdentityActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for IdentityActivationFunction {
+    /// Returns the input value unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value.
+    ///
+    /// # Returns
+    ///
+    /// The input value itself.
+    fn activate(&self, input: F) -> F {
+        input
+    }
+
+    /// Computes the derivative of the identity function, which is always 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `_input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// Always 1.
+    fn derivate(&self, _input: F) -> F {
+        F::one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_activate() {
+        let identity = IdentityActivationFunction;
+
+        assert_eq!(identity.activate(2.5), 2.5);
+        assert_eq!(identity.activate(-1000.0), -1000.0);
+    }
+
+    #[test]
+    fn identity_derivate() {
+        let identity = IdentityActivationFunction;
+
+        assert_eq!(identity.derivate(2.5), 1.0);
+        assert_eq!(identity.derivate(-1000.0), 1.0);
+    }
+}
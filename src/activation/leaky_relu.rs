@@ -1,3 +1,4 @@
+use crate::numeric::{constant, Float};
 use super::ActivationFunction;
 
 /// `LeakyReLUActivationFunction` represents the Leaky Rectified Linear Unit (Leaky ReLU)
@@ -5,7 +6,7 @@ use super::ActivationFunction;
 /// a small, non-zero gradient when the unit is not active, thus addressing the problem of
 /// dying neurons. It's particularly useful in deep learning models where this issue is prevalent.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. The Leaky ReLU function
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. The Leaky ReLU function
 /// is defined as `x` if `x > 0`, and `alpha * x` otherwise, where `alpha` is a small constant.
 /// Its derivative is 1 for positive inputs and `alpha` for non-positive inputs.
 ///
@@ -23,7 +24,7 @@ use super::ActivationFunction;
 /// ```
 pub struct LeakyReLUActivationFunction;
 
-impl ActivationFunction<f64, f64> for LeakyReLUActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for LeakyReLUActivationFunction {
     /// Computes the Leaky Rectified Linear Unit (Leaky ReLU) of a given input value.
     ///
     /// If the input is positive, it returns the input value itself. For non-positive inputs,
@@ -36,8 +37,8 @@ impl ActivationFunction<f64, f64> for LeakyReLUActivationFunction {
     /// # Returns
     ///
     /// The Leaky ReLU of the input.
-    fn activate(&self, input: f64) -> f64 {
-        if input > 0.0 { input } else { 0.01 * input }
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() { input } else { constant::<F>(0.01) * input }
     }
 
     /// Computes the derivative of the Leaky ReLU function for a given input value.
@@ -51,8 +52,8 @@ impl ActivationFunction<f64, f64> for LeakyReLUActivationFunction {
     /// # Returns
     ///
     /// The derivative of the Leaky ReLU function at the given input.
-    fn derivate(&self, input: f64) -> f64 {
-        if input > 0.0 { 1.0 } else { 0.01 }
+    fn derivate(&self, input: F) -> F {
+        if input > F::zero() { F::one() } else { constant(0.01) }
     }
 }
 
@@ -0,0 +1,193 @@
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::Float;
+use crate::tensor::Tensor;
+use super::ActivationFunction;
+
+/// Represents the Log-Softmax activation function in neural networks.
+///
+/// Log-Softmax computes the logarithm of the Softmax probabilities,
+/// `log_softmax(x)_i = x_i - log(sum_j(e^(x_j)))`, in a single numerically stable step. Taking
+/// the logarithm of an already computed Softmax loses precision and returns `-inf` for very
+/// unlikely classes, while Log-Softmax keeps those log-probabilities finite. It is typically
+/// paired with a negative log-likelihood loss.
+///
+/// This struct implements the `ActivationFunction<&Vec<F>, Vec<F>>` trait, like
+/// `SoftmaxActivationFunction`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::log_softmax::LogSoftmaxActivationFunction;
+///
+/// let log_softmax = LogSoftmaxActivationFunction;
+/// let log_probabilities = log_softmax.activate(&vec![1000.0, 0.0]);
+/// assert_eq!(log_probabilities, vec![0.0, -1000.0]);
+/// ```
+pub struct LogSoftmaxActivationFunction;
+
+impl LogSoftmaxActivationFunction {
+    /// Computes the Jacobian matrix of the Log-Softmax at a given input vector.
+    ///
+    /// With `s = softmax(x)`, the element at row `i` and column `j` is
+    /// `d log_softmax(x)_i / dx_j = delta_ij - s_j`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector at which to compute the Jacobian.
+    ///
+    /// # Returns
+    ///
+    /// The Jacobian as a square `[n, n]` tensor, one row per output.
+    pub fn jacobian<F: Float>(&self, input: &Vec<F>) -> Tensor<F> {
+        let softmax: Vec<F> = self.activate(input).iter().map(|value| value.exp()).collect();
+        let n = softmax.len();
+
+        let data = (0..n * n)
+            .map(|index| {
+                let (i, j) = (index / n, index % n);
+                if i == j { F::one() - softmax[j] } else { -softmax[j] }
+            })
+            .collect();
+
+        Tensor::new(&[n, n], data).expect("The Jacobian has n * n elements.")
+    }
+
+    /// Computes the product of a vector with the Log-Softmax Jacobian at a given input, without
+    /// building the Jacobian.
+    ///
+    /// With `s = softmax(x)`, the product is `v - s * sum(v)`. This is the product used to
+    /// backpropagate a gradient through the Log-Softmax.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector at which the Jacobian is evaluated.
+    /// * `vector` - The vector to multiply with the Jacobian, typically the gradient of the loss
+    ///   with respect to the log-probabilities.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<F>, QmachinaError>` containing the product, or an error if the input and
+    /// the vector have different lengths.
+    pub fn vector_jacobian_product<F: Float>(&self, input: &Vec<F>, vector: &[F]) -> Result<Vec<F>> {
+        ensure_same_length(input.len(), vector.len())?;
+
+        let sum = vector.iter().fold(F::zero(), |sum, &v| sum + v);
+        Ok(self.activate(input)
+            .iter()
+            .zip(vector)
+            .map(|(log_s, &v)| v - log_s.exp() * sum)
+            .collect())
+    }
+
+    /// Computes the Log-Softmax of every lane along the last axis of a tensor, such as the class
+    /// log-probabilities of each row of a batch of logits.
+    pub fn activate_tensor<F: Float>(&self, input: &Tensor<F>) -> Tensor<F> {
+        input.map_lanes(|lane| self.activate(&lane.to_vec()))
+            .expect("The Log-Softmax preserves the length of each lane.")
+    }
+}
+
+impl<F: Float> ActivationFunction<&Vec<F>, Vec<F>> for LogSoftmaxActivationFunction {
+    /// Computes the Log-Softmax of a given input vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector for which to compute the Log-Softmax.
+    ///
+    /// # Returns
+    ///
+    /// A vector of log-probabilities, whose exponentials sum up to one.
+    fn activate(&self, input: &Vec<F>) -> Vec<F> {
+        let max = input.iter().fold(F::neg_infinity(), |a, &b| a.max(b));
+        let log_sum_exp = max + input.iter().fold(F::zero(), |sum, &x| sum + (x - max).exp()).ln();
+        input.iter().map(|&x| x - log_sum_exp).collect()
+    }
+
+    /// Computes the derivative of the Log-Softmax for a given input vector.
+    ///
+    /// The derivative is the Jacobian matrix, returned flattened in row-major order. See
+    /// `jacobian` for the matrix form.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The flattened Jacobian of the Log-Softmax, with `n * n` elements.
+    fn derivate(&self, input: &Vec<F>) -> Vec<F> {
+        self.jacobian(input).into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::softmax::SoftmaxActivationFunction;
+
+    #[test]
+    fn log_softmax_matches_log_of_softmax() {
+        let input: Vec<f64> = vec![0.5, -1.0, 2.0, 0.0];
+        let log_probabilities = LogSoftmaxActivationFunction.activate(&input);
+        let probabilities = SoftmaxActivationFunction.activate(&input);
+
+        for (log_p, p) in log_probabilities.iter().zip(probabilities) {
+            assert!((log_p - p.ln()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn log_softmax_extreme() {
+        let log_probabilities = LogSoftmaxActivationFunction.activate(&vec![1000.0, -1000.0]);
+
+        assert_eq!(log_probabilities[0], 0.0);
+        assert_eq!(log_probabilities[1], -2000.0, "Should stay finite where softmax underflows");
+    }
+
+    #[test]
+    fn log_softmax_jacobian_matches_finite_differences() {
+        let log_softmax = LogSoftmaxActivationFunction;
+        let input: Vec<f64> = vec![0.5, -1.0, 2.0];
+        let derivative = log_softmax.derivate(&input);
+        let step = 1e-6;
+
+        for j in 0..input.len() {
+            let mut forward = input.clone();
+            let mut backward = input.clone();
+            forward[j] += step;
+            backward[j] -= step;
+
+            let forward = log_softmax.activate(&forward);
+            let backward = log_softmax.activate(&backward);
+            for i in 0..input.len() {
+                let expected = (forward[i] - backward[i]) / (2.0 * step);
+                assert!((derivative[i * 3 + j] - expected).abs() < 1e-6, "Jacobian ({}, {}) mismatch", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn log_softmax_vector_jacobian_product() {
+        let log_softmax = LogSoftmaxActivationFunction;
+        let input = vec![0.2, -0.4, 1.1];
+        let vector = [1.0, -2.0, 0.5];
+
+        let product = log_softmax.vector_jacobian_product(&input, &vector).unwrap();
+        let jacobian = log_softmax.jacobian(&input);
+        for (j, value) in product.iter().enumerate() {
+            let expected: f64 = (0..3).map(|i| vector[i] * jacobian.get(&[i, j]).unwrap()).sum();
+            assert!((value - expected).abs() < 1e-12);
+        }
+        assert!(log_softmax.vector_jacobian_product(&input, &vector[..2]).is_err());
+    }
+
+    #[test]
+    fn log_softmax_activate_tensor_per_row() {
+        let rows = vec![vec![1.0, 2.0], vec![1000.0, 0.0]];
+        let output = LogSoftmaxActivationFunction.activate_tensor(&Tensor::from_rows(&rows).unwrap());
+
+        assert_eq!(output.to_rows().unwrap()[1], vec![0.0, -1000.0]);
+        assert_eq!(output.to_rows().unwrap()[0], LogSoftmaxActivationFunction.activate(&rows[0]));
+    }
+}
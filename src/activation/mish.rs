@@ -0,0 +1,76 @@
+use crate::numeric::Float;
+use super::{sigmoid::SigmoidActivationFunction, softplus::SoftplusActivationFunction, ActivationFunction};
+
+/// Represents the Mish activation function in neural networks.
+///
+/// Mish, `x * tanh(softplus(x))`, is a smooth, non-monotonic activation similar to Swish. It
+/// lets small negative values through, which keeps gradients flowing, and is unbounded above.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. With `t = tanh(softplus(x))`,
+/// its derivative is `t + x * (1 - t^2) * sigmoid(x)`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::mish::MishActivationFunction;
+///
+/// let mish = MishActivationFunction;
+/// assert!((mish.activate(1.0_f64) - 0.8650983882673103).abs() < 1e-12);
+/// assert_eq!(mish.activate(0.0), 0.0);
+/// ```
+pub struct MishActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for MishActivationFunction {
+    /// Computes the Mish of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the Mish.
+    ///
+    /// # Returns
+    ///
+    /// The Mish of the input.
+    fn activate(&self, input: F) -> F {
+        input * SoftplusActivationFunction.activate(input).tanh()
+    }
+
+    /// Computes the derivative of the Mish function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The derivative of the Mish function at the given input.
+    fn derivate(&self, input: F) -> F {
+        let tanh = SoftplusActivationFunction.activate(input).tanh();
+        tanh + input * (F::one() - tanh * tanh) * SigmoidActivationFunction.activate(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn mish_activate() {
+        let mish = MishActivationFunction;
+
+        assert!((mish.activate(-1.0_f64) + 0.30340146137410895).abs() < 1e-12);
+        assert_eq!(mish.activate(1000.0), 1000.0);
+        assert!(mish.activate(-1000.0_f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mish_derivate_matches_finite_differences() {
+        let mish = MishActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&mish, input);
+            assert!((mish.derivate(input) - expected).abs() < 1e-6, "Mish'({}) mismatch", input);
+        }
+    }
+}
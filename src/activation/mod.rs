@@ -9,6 +9,19 @@ pub mod param_relu;
 pub mod elu;
 pub mod swish;
 pub mod softmax;
+pub mod identity;
+pub mod gelu;
+pub mod selu;
+pub mod softplus;
+pub mod softsign;
+pub mod mish;
+pub mod hard_sigmoid;
+pub mod hard_swish;
+pub mod silu;
+pub mod log_softmax;
+
+use crate::numeric::Float;
+use crate::tensor::Tensor;
 
 /// `ActivationFunction` trait defines a general interface for activation functions
 /// used in neural networks. Activation functions are fundamental to neural networks
@@ -58,3 +71,186 @@ pub trait ActivationFunction<X, Y> {
     /// Returns the derivative of the activation function at the given input, of type `Y`.
     fn derivate(&self, input: X) -> Y;
 }
+
+/// `BatchActivationFunction` applies a scalar activation function to every element of a slice
+/// or of a `Tensor`.
+///
+/// It is implemented for every `ActivationFunction<F, F>`, including trait objects, so any
+/// scalar activation can be applied to a layer output or to a row-major matrix stored as a flat
+/// slice. The loops are plain element-wise passes over contiguous memory, which the compiler can
+/// auto-vectorize when the activation is known statically.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::BatchActivationFunction;
+/// use qmachina::activation::relu::ReLUActivationFunction;
+///
+/// let relu = ReLUActivationFunction;
+/// assert_eq!(relu.activate_batch(&[-1.0, 0.5, 2.0]), vec![0.0, 0.5, 2.0]);
+///
+/// let mut values = [-1.0, 0.5, 2.0];
+/// relu.derivate_inplace(&mut values);
+/// assert_eq!(values, [0.0, 1.0, 1.0]);
+/// ```
+pub trait BatchActivationFunction<F: Float = f64> {
+    /// Computes the activated value of every input.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input values to the activation function.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector holding the activated value of each input, in order.
+    fn activate_batch(&self, inputs: &[F]) -> Vec<F>;
+
+    /// Replaces every value by its activated value.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The input values, overwritten with the activated values.
+    fn activate_inplace(&self, values: &mut [F]);
+
+    /// Computes the derivative of the activation function at every input.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The input values for which the derivative is to be calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector holding the derivative at each input, in order.
+    fn derivate_batch(&self, inputs: &[F]) -> Vec<F>;
+
+    /// Replaces every value by the derivative of the activation function at that value.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The input values, overwritten with the derivatives.
+    fn derivate_inplace(&self, values: &mut [F]);
+
+    /// Computes the activated value of every value of a tensor, keeping its shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::activation::BatchActivationFunction;
+    /// use qmachina::activation::relu::ReLUActivationFunction;
+    /// use qmachina::tensor::Tensor;
+    ///
+    /// let batch = Tensor::from_rows(&[vec![-1.0, 2.0], vec![3.0, -4.0]]).unwrap();
+    /// let activated = ReLUActivationFunction.activate_tensor(&batch);
+    /// assert_eq!(activated.shape(), &[2, 2]);
+    /// assert_eq!(activated.as_slice(), &[0.0, 2.0, 3.0, 0.0]);
+    /// ```
+    fn activate_tensor(&self, input: &Tensor<F>) -> Tensor<F> {
+        let mut output = input.clone();
+        self.activate_inplace(output.as_mut_slice());
+        output
+    }
+
+    /// Computes the derivative of the activation function at every value of a tensor, keeping
+    /// its shape.
+    fn derivate_tensor(&self, input: &Tensor<F>) -> Tensor<F> {
+        let mut output = input.clone();
+        self.derivate_inplace(output.as_mut_slice());
+        output
+    }
+}
+
+impl<F: Float, A: ActivationFunction<F, F> + ?Sized> BatchActivationFunction<F> for A {
+    fn activate_batch(&self, inputs: &[F]) -> Vec<F> {
+        let mut outputs = inputs.to_vec();
+        self.activate_inplace(&mut outputs);
+        outputs
+    }
+
+    fn activate_inplace(&self, values: &mut [F]) {
+        for value in values.iter_mut() {
+            *value = self.activate(*value);
+        }
+    }
+
+    fn derivate_batch(&self, inputs: &[F]) -> Vec<F> {
+        let mut outputs = inputs.to_vec();
+        self.derivate_inplace(&mut outputs);
+        outputs
+    }
+
+    fn derivate_inplace(&self, values: &mut [F]) {
+        for value in values.iter_mut() {
+            *value = self.derivate(*value);
+        }
+    }
+}
+
+/// Approximates the derivative of an activation function with a central finite difference.
+#[cfg(test)]
+pub(crate) fn numerical_derivative<A: ActivationFunction<f64, f64>>(activation: &A, input: f64) -> f64 {
+    let step = 1e-6;
+    (activation.activate(input + step) - activation.activate(input - step)) / (2.0 * step)
+}
+
+/// Inputs on both sides of zero and of the kinks of piecewise activations.
+#[cfg(test)]
+pub(crate) const DERIVATIVE_INPUTS: [f64; 12] = [-8.0, -4.0, -2.5, -1.0, -0.3, -0.01, 0.01, 0.3, 1.0, 2.5, 4.0, 8.0];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::elu::ELUActivationFunction;
+    use super::sigmoid::SigmoidActivationFunction;
+    use super::tanh::TanhActivationFunction;
+
+    const INPUTS: [f64; 7] = [-1000.0, -2.5, -0.1, 0.0, 0.1, 2.5, 1000.0];
+
+    #[test]
+    fn batch_matches_scalar() {
+        let activations: Vec<Box<dyn ActivationFunction<f64, f64>>> = vec![
+            Box::new(SigmoidActivationFunction),
+            Box::new(TanhActivationFunction),
+            Box::new(ELUActivationFunction::new(0.5))
+        ];
+
+        for activation in activations {
+            let activated = activation.activate_batch(&INPUTS);
+            let derivatives = activation.derivate_batch(&INPUTS);
+
+            for (index, &input) in INPUTS.iter().enumerate() {
+                assert_eq!(activated[index], activation.activate(input));
+                assert_eq!(derivatives[index], activation.derivate(input));
+            }
+        }
+    }
+
+    #[test]
+    fn inplace_matches_batch() {
+        let sigmoid = SigmoidActivationFunction;
+
+        let mut activated = INPUTS;
+        sigmoid.activate_inplace(&mut activated);
+        assert_eq!(activated.to_vec(), sigmoid.activate_batch(&INPUTS));
+
+        let mut derivatives = INPUTS;
+        sigmoid.derivate_inplace(&mut derivatives);
+        assert_eq!(derivatives.to_vec(), sigmoid.derivate_batch(&INPUTS));
+    }
+
+    #[test]
+    fn empty_batch() {
+        assert!(TanhActivationFunction.activate_batch(&[] as &[f64]).is_empty());
+        assert!(TanhActivationFunction.derivate_batch(&[] as &[f64]).is_empty());
+    }
+
+    #[test]
+    fn tensor_matches_batch() {
+        let tensor = Tensor::new(&[7, 1], INPUTS.to_vec()).unwrap();
+        let activation = ELUActivationFunction::new(0.5);
+
+        let activated = activation.activate_tensor(&tensor);
+        assert_eq!(activated.shape(), &[7, 1]);
+        assert_eq!(activated.as_slice(), &activation.activate_batch(&INPUTS)[..]);
+        assert_eq!(activation.derivate_tensor(&tensor).as_slice(), &activation.derivate_batch(&INPUTS)[..]);
+    }
+}
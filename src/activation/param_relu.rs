@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// `PReLUActivationFunction` represents the Parametric Rectified Linear Unit (PReLU)
@@ -5,7 +6,7 @@ use super::ActivationFunction;
 /// function where the negative part of the function is not fixed but instead
 /// parameterized by a learnable coefficient `alpha`.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. The PReLU function
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. The PReLU function
 /// is defined as `x` for `x > 0` and `alpha * x` for `x <= 0`. Unlike Leaky ReLU,
 /// in PReLU, `alpha` is a parameter that can be learned during the training process.
 ///
@@ -19,22 +20,22 @@ use super::ActivationFunction;
 /// let activated_value = prelu.activate(-2.0); // returns -0.5
 /// let derivative_value = prelu.derivate(-2.0); // returns 0.25
 /// ```
-pub struct PReLUActivationFunction {
-    alpha: f64,
+pub struct PReLUActivationFunction<F = f64> {
+    alpha: F,
 }
 
-impl PReLUActivationFunction {
+impl<F: Float> PReLUActivationFunction<F> {
     /// Creates a new instance of `PReLUActivationFunction` with the given alpha value.
     ///
     /// # Arguments
     ///
     /// * `alpha` - The initial value for the alpha coefficient, which will be learned
-    ///             and adjusted during training.
+    ///   and adjusted during training.
     ///
     /// # Returns
     ///
     /// A new instance of `PReLUActivationFunction`.
-    pub fn new(alpha: f64) -> Self {
+    pub fn new(alpha: F) -> Self {
         PReLUActivationFunction { alpha }
     }
 
@@ -43,12 +44,31 @@ impl PReLUActivationFunction {
     /// # Arguments
     ///
     /// * `new_alpha` - The new value to set for the alpha parameter.
-    pub fn update_alpha(&mut self, new_alpha: f64) {
+    pub fn update_alpha(&mut self, new_alpha: F) {
         self.alpha = new_alpha;
     }
+
+    /// Returns the current alpha parameter of the PReLU function.
+    pub fn alpha(&self) -> F {
+        self.alpha
+    }
+
+    /// Computes the derivative of the PReLU function with respect to alpha for a given input
+    /// value, which is what an optimizer needs to learn alpha.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The input itself if it is non-positive, and 0 otherwise.
+    pub fn alpha_derivate(&self, input: F) -> F {
+        if input > F::zero() { F::zero() } else { input }
+    }
 }
 
-impl ActivationFunction<f64, f64> for PReLUActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for PReLUActivationFunction<F> {
     /// Computes the Parametric Rectified Linear Unit (PReLU) of a given input value.
     ///
     /// For positive inputs, it returns the input value itself. For non-positive inputs,
@@ -61,8 +81,8 @@ impl ActivationFunction<f64, f64> for PReLUActivationFunction {
     /// # Returns
     ///
     /// The PReLU of the input.
-    fn activate(&self, input: f64) -> f64 {
-        if input > 0.0 {
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() {
             input
         } else {
             self.alpha * input
@@ -81,9 +101,9 @@ impl ActivationFunction<f64, f64> for PReLUActivationFunction {
     /// # Returns
     ///
     /// The derivative of the PReLU function at the given input.
-    fn derivate(&self, input: f64) -> f64 {
-        if input > 0.0 {
-            1.0
+    fn derivate(&self, input: F) -> F {
+        if input > F::zero() {
+            F::one()
         } else {
             self.alpha
         }
@@ -175,4 +195,15 @@ mod tests {
         let output = prelu.derivate(-1000.0);
         assert_eq!(output, ALPHA);
     }
+
+    #[test]
+    fn prelu_alpha_derivate() {
+        let mut prelu = PReLUActivationFunction::new(ALPHA);
+
+        assert_eq!(prelu.alpha_derivate(-2.0), -2.0);
+        assert_eq!(prelu.alpha_derivate(2.0), 0.0);
+
+        prelu.update_alpha(0.3);
+        assert_eq!(prelu.alpha(), 0.3);
+    }
 }
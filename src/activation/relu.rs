@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// Represents the Rectified Linear Unit (ReLU) activation function in neural networks.
@@ -6,8 +7,8 @@ use super::ActivationFunction;
 /// and zero otherwise. Known for its simplicity and efficiency, ReLU helps mitigate
 /// the vanishing gradient problem common in deep networks and speeds up training.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. It accepts a `f64`
-/// input and returns a `f64` output. The ReLU function is defined as `max(0, x)`.
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`,
+/// returning a value of the input type. The ReLU function is defined as `max(0, x)`.
 /// Its derivative is 1 for positive inputs and 0 for non-positive inputs, which is
 /// crucial during the backpropagation process in neural network training.
 ///
@@ -49,7 +50,7 @@ use super::ActivationFunction;
 /// it is conventionally treated as 0 for simplicity in most implementations.
 pub struct ReLUActivationFunction;
 
-impl ActivationFunction<f64, f64> for ReLUActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for ReLUActivationFunction {
     /// Computes the Rectified Linear Unit (ReLU) of a given input value.
     ///
     /// The ReLU function returns the input value if it's positive, and 0 if it's negative
@@ -62,8 +63,8 @@ impl ActivationFunction<f64, f64> for ReLUActivationFunction {
     /// # Returns
     ///
     /// The ReLU of the input, which is either the input itself (if positive) or 0.
-    fn activate(&self, input: f64) -> f64 {
-        if input > 0.0 { input } else { 0.0 }
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() { input } else { F::zero() }
     }
 
     /// Computes the derivative of the ReLU function for a given input value.
@@ -80,8 +81,8 @@ impl ActivationFunction<f64, f64> for ReLUActivationFunction {
     ///
     /// The derivative of the ReLU function at the given input, which is 1 if the input
     /// is positive and 0 otherwise.
-    fn derivate(&self, input: f64) -> f64 {
-        if input > 0.0 { 1.0 } else { 0.0 }
+    fn derivate(&self, input: F) -> F {
+        if input > F::zero() { F::one() } else { F::zero() }
     }
 }
 
@@ -0,0 +1,92 @@
+use crate::numeric::{constant, Float};
+use super::ActivationFunction;
+
+/// The scale `lambda` of SELU, derived so that activations converge to zero mean and unit variance.
+pub const SELU_SCALE: f64 = 1.050_700_987_355_480_5;
+
+/// The negative saturation `alpha` of SELU, derived alongside `SELU_SCALE`.
+pub const SELU_ALPHA: f64 = 1.673_263_242_354_377_3;
+
+/// Represents the Scaled Exponential Linear Unit (SELU) activation function in neural networks.
+///
+/// SELU is an ELU scaled by fixed constants, `lambda * x` for `x > 0` and
+/// `lambda * alpha * (e^x - 1)` otherwise, with `lambda ≈ 1.0507` and `alpha ≈ 1.6733`. With
+/// these constants and a suitable initialisation, activations of deep feed-forward networks
+/// self-normalise towards zero mean and unit variance, without batch normalisation.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. Its derivative is `lambda`
+/// for positive inputs and `lambda * alpha * e^x` otherwise.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::selu::SELUActivationFunction;
+///
+/// let selu = SELUActivationFunction;
+/// assert!((selu.activate(1.0_f64) - 1.0507009873554805).abs() < 1e-12);
+/// assert!(selu.activate(-100.0) > -1.7581);
+/// ```
+pub struct SELUActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for SELUActivationFunction {
+    /// Computes the SELU of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the SELU.
+    ///
+    /// # Returns
+    ///
+    /// The SELU of the input, which saturates at `-lambda * alpha` for large negative inputs.
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() {
+            constant::<F>(SELU_SCALE) * input
+        } else {
+            constant::<F>(SELU_SCALE * SELU_ALPHA) * input.exp_m1()
+        }
+    }
+
+    /// Computes the derivative of the SELU function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// `lambda` if the input is positive, and `lambda * alpha * e^x` otherwise.
+    fn derivate(&self, input: F) -> F {
+        if input > F::zero() {
+            constant(SELU_SCALE)
+        } else {
+            constant::<F>(SELU_SCALE * SELU_ALPHA) * input.exp()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn selu_activate() {
+        let selu = SELUActivationFunction;
+
+        assert_eq!(selu.activate(0.0), 0.0);
+        assert!((selu.activate(2.0_f64) - 2.101401974710961).abs() < 1e-12);
+        assert!((selu.activate(-1.0_f64) + 1.1113307378125625).abs() < 1e-12);
+        assert!((selu.activate(-1000.0) + SELU_SCALE * SELU_ALPHA).abs() < 1e-12);
+    }
+
+    #[test]
+    fn selu_derivate_matches_finite_differences() {
+        let selu = SELUActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&selu, input);
+            assert!((selu.derivate(input) - expected).abs() < 1e-6, "SELU'({}) mismatch", input);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// Represents the sigmoid activation function in neural networks.
@@ -7,8 +8,8 @@ use super::ActivationFunction;
 /// classification problems and other scenarios where a probability-like 
 /// output is needed.
 ///
-/// This structure implements the `ActivationFunction<f64, f64>` trait. It
-/// takes a `f64` input and returns a `f64` output. The sigmoid function is 
+/// This structure implements the `ActivationFunction<F, F>` trait for `f32`
+/// and `f64`, returning a value of the input type. The sigmoid function is 
 /// defined mathematically as `1 / (1 + e^(-x))`. Its derivative, which is 
 /// `sigmoid(x) * (1 - sigmoid(x))`, plays a critical role in neural network 
 /// training algorithms, particularly in the backpropagation process.
@@ -45,7 +46,7 @@ use super::ActivationFunction;
 /// conjunction with the original function output, optimizing computational efficiency.
 pub struct SigmoidActivationFunction;
 
-impl ActivationFunction<f64, f64> for SigmoidActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for SigmoidActivationFunction {
     /// Computes the sigmoid of a given input value.
     ///
     /// The sigmoid function transforms the input into a value between 0 and 1,
@@ -58,8 +59,8 @@ impl ActivationFunction<f64, f64> for SigmoidActivationFunction {
     /// # Returns
     ///
     /// The sigmoid of the input, a value between 0 and 1.
-    fn activate(&self, input: f64) -> f64 {
-        1.0 / (1.0 + (-input).exp())
+    fn activate(&self, input: F) -> F {
+        F::one() / (F::one() + (-input).exp())
     }
 
     /// Computes the derivative of the sigmoid function for a given input value.
@@ -75,9 +76,9 @@ impl ActivationFunction<f64, f64> for SigmoidActivationFunction {
     /// # Returns
     ///
     /// The derivative of the sigmoid function at the given input.
-    fn derivate(&self, input: f64) -> f64 {
+    fn derivate(&self, input: F) -> F {
         let sigmoid = self.activate(input);
-        sigmoid * (1.0 - sigmoid)
+        sigmoid * (F::one() - sigmoid)
     }
 }
 
@@ -0,0 +1,79 @@
+use crate::numeric::Float;
+use super::{sigmoid::SigmoidActivationFunction, ActivationFunction};
+
+/// Represents the Sigmoid Linear Unit (SiLU) activation function in neural networks.
+///
+/// SiLU, `x * sigmoid(x)`, is the Swish function with a fixed beta of 1. It is smooth,
+/// non-monotonic and behaves like ReLU for large inputs.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. With `s = sigmoid(x)`, its
+/// derivative is `s * (1 + x * (1 - s))`. Use `SwishActivationFunction` for a different or
+/// learnable beta.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::silu::SiLUActivationFunction;
+///
+/// let silu = SiLUActivationFunction;
+/// assert_eq!(silu.activate(0.0), 0.0);
+/// assert_eq!(silu.derivate(0.0), 0.5);
+/// ```
+pub struct SiLUActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for SiLUActivationFunction {
+    /// Computes the SiLU of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the SiLU.
+    ///
+    /// # Returns
+    ///
+    /// The input times its sigmoid.
+    fn activate(&self, input: F) -> F {
+        input * SigmoidActivationFunction.activate(input)
+    }
+
+    /// Computes the derivative of the SiLU function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The derivative of the SiLU function at the given input.
+    fn derivate(&self, input: F) -> F {
+        let sigmoid = SigmoidActivationFunction.activate(input);
+        sigmoid * (F::one() + input * (F::one() - sigmoid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::swish::SwishActivationFunction;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn silu_matches_swish_with_unit_beta() {
+        let silu = SiLUActivationFunction;
+        let swish = SwishActivationFunction::new(1.0);
+
+        for input in DERIVATIVE_INPUTS {
+            assert!((silu.activate(input) - swish.activate(input)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn silu_derivate_matches_finite_differences() {
+        let silu = SiLUActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&silu, input);
+            assert!((silu.derivate(input) - expected).abs() < 1e-6, "SiLU'({}) mismatch", input);
+        }
+    }
+}
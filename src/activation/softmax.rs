@@ -1,3 +1,6 @@
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::Float;
+use crate::tensor::Tensor;
 use super::ActivationFunction;
 
 /// Represents the Softmax activation function in neural networks.
@@ -8,7 +11,7 @@ use super::ActivationFunction;
 /// output vector is proportional to the exponential of the value, ensuring 
 /// that all probabilities sum up to one and each is in the range (0, 1).
 ///
-/// This struct implements the `ActivationFunction<Vec<f64>, Vec<f64>>` trait,
+/// This struct implements the `ActivationFunction<&Vec<F>, Vec<F>>` trait for `f32` and `f64`,
 /// allowing it to be seamlessly integrated into neural network architectures
 /// that handle multi-dimensional data.
 ///
@@ -36,12 +39,95 @@ use super::ActivationFunction;
 /// // The 'probabilities' now represent the probability distribution of classes.
 /// ```
 ///
-/// Note: The derivative of Softmax is not straightforward as it depends on all
-/// elements of the output vector. It's typically used in conjunction with a loss
-/// function, like cross-entropy, in multi-class classification problems.
+/// Note: The derivative of Softmax depends on all elements of the output vector, so
+/// it is a Jacobian matrix rather than a vector. `derivate` returns it flattened in
+/// row-major order, `jacobian` returns it as an `[n, n]` tensor, and `jacobian_vector_product`
+/// applies it to a vector without building it. When Softmax feeds a cross-entropy
+/// loss, `SoftmaxCrossEntropyLossFunction` computes the fused gradient directly.
 pub struct SoftmaxActivationFunction;
 
-impl ActivationFunction<&Vec<f64>, Vec<f64>> for SoftmaxActivationFunction {
+impl SoftmaxActivationFunction {
+    /// Computes the Jacobian matrix of the Softmax at a given input vector.
+    ///
+    /// With `s = softmax(x)`, the element at row `i` and column `j` is
+    /// `ds_i / dx_j = s_i * (delta_ij - s_j)`. The matrix is symmetric.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector at which to compute the Jacobian.
+    ///
+    /// # Returns
+    ///
+    /// The Jacobian as a square `[n, n]` tensor, one row per output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::activation::softmax::SoftmaxActivationFunction;
+    ///
+    /// let jacobian = SoftmaxActivationFunction.jacobian(&vec![0.0, 0.0]);
+    /// assert_eq!(jacobian.shape(), &[2, 2]);
+    /// assert_eq!(jacobian.as_slice(), &[0.25, -0.25, -0.25, 0.25]);
+    /// ```
+    pub fn jacobian<F: Float>(&self, input: &Vec<F>) -> Tensor<F> {
+        let softmax = self.activate(input);
+        let n = softmax.len();
+
+        let data = (0..n * n)
+            .map(|index| {
+                let (i, j) = (index / n, index % n);
+                if i == j { softmax[i] * (F::one() - softmax[j]) } else { -softmax[i] * softmax[j] }
+            })
+            .collect();
+
+        Tensor::new(&[n, n], data).expect("The Jacobian has n * n elements.")
+    }
+
+    /// Computes the product of the Softmax Jacobian at a given input with a vector, without
+    /// building the Jacobian.
+    ///
+    /// With `s = softmax(x)`, the product is `s * (v - s . v)`, element-wise. As the Jacobian is
+    /// symmetric, this is also the vector-Jacobian product used to backpropagate a gradient
+    /// through the Softmax.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector at which the Jacobian is evaluated.
+    /// * `vector` - The vector to multiply with the Jacobian.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<F>, QmachinaError>` containing the product, or an error if the input and
+    /// the vector have different lengths.
+    pub fn jacobian_vector_product<F: Float>(&self, input: &Vec<F>, vector: &[F]) -> Result<Vec<F>> {
+        ensure_same_length(input.len(), vector.len())?;
+
+        let softmax = self.activate(input);
+        let dot = softmax.iter().zip(vector).fold(F::zero(), |dot, (&s, &v)| dot + s * v);
+
+        Ok(softmax.iter().zip(vector).map(|(&s, &v)| s * (v - dot)).collect())
+    }
+
+    /// Computes the Softmax of every lane along the last axis of a tensor, such as the class
+    /// probabilities of each row of a batch of logits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::activation::softmax::SoftmaxActivationFunction;
+    /// use qmachina::tensor::Tensor;
+    ///
+    /// let logits = Tensor::from_rows(&[vec![0.0, 0.0], vec![1000.0, 0.0]]).unwrap();
+    /// let probabilities = SoftmaxActivationFunction.activate_tensor(&logits);
+    /// assert_eq!(probabilities.as_slice(), &[0.5, 0.5, 1.0, 0.0]);
+    /// ```
+    pub fn activate_tensor<F: Float>(&self, input: &Tensor<F>) -> Tensor<F> {
+        input.map_lanes(|lane| self.activate(&lane.to_vec()))
+            .expect("The Softmax preserves the length of each lane.")
+    }
+}
+
+impl<F: Float> ActivationFunction<&Vec<F>, Vec<F>> for SoftmaxActivationFunction {
     /// Computes the Softmax of a given input vector.
     ///
     /// # Arguments
@@ -51,19 +137,28 @@ impl ActivationFunction<&Vec<f64>, Vec<f64>> for SoftmaxActivationFunction {
     /// # Returns
     ///
     /// A vector representing the probability distribution resulting from the Softmax function.
-    fn activate(&self, input: &Vec<f64>) -> Vec<f64> {
-        let max = input.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        let exps: Vec<f64> = input.iter().map(|&x| (x - max).exp()).collect();
-        let sum_exps: f64 = exps.iter().sum();
+    fn activate(&self, input: &Vec<F>) -> Vec<F> {
+        let max = input.iter().fold(F::neg_infinity(), |a, &b| a.max(b));
+        let exps: Vec<F> = input.iter().map(|&x| (x - max).exp()).collect();
+        let sum_exps = exps.iter().fold(F::zero(), |sum, &exp| sum + exp);
         exps.into_iter().map(|exp| exp / sum_exps).collect()
     }
 
-    /// Softmax function does not have a straightforward derivative like other functions,
-    /// as it depends on all the elements of the output vector. This method is a placeholder.
-    fn derivate(&self, _: &Vec<f64>) -> Vec<f64> {
-        // Placeholder for the derivative. In practice, the derivative is used in the
-        // context of a loss function (like cross-entropy) in multi-class classification problems.
-        unimplemented!()
+    /// Computes the derivative of the Softmax for a given input vector.
+    ///
+    /// As each output depends on every input, the derivative is the Jacobian matrix. It is
+    /// returned flattened in row-major order, so the element `ds_i / dx_j` is at index
+    /// `i * n + j`, where `n` is the length of the input. See `jacobian` for the matrix form.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input vector for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The flattened Jacobian of the Softmax, with `n * n` elements.
+    fn derivate(&self, input: &Vec<F>) -> Vec<F> {
+        self.jacobian(input).into_vec()
     }
 }
 
@@ -88,11 +183,88 @@ mod tests {
     #[test]
     fn softmax_activate_extreme() {
         let softmax = SoftmaxActivationFunction;
-        let input = vec![1000.0, 1000.0];
+        let input: Vec<f64> = vec![1000.0, 1000.0];
         let output = softmax.activate(&input);
 
         // In extreme cases, values should be approximately equal (due to exp scaling)
         assert!((output[0] - 0.5).abs() < 1e-5);
         assert!((output[1] - 0.5).abs() < 1e-5);
     }
+
+    #[test]
+    fn softmax_jacobian_matches_finite_differences() {
+        let softmax = SoftmaxActivationFunction;
+        let input: Vec<f64> = vec![0.5, -1.0, 2.0];
+        let jacobian = softmax.jacobian(&input);
+        let step = 1e-6;
+
+        for j in 0..input.len() {
+            let mut forward = input.clone();
+            let mut backward = input.clone();
+            forward[j] += step;
+            backward[j] -= step;
+
+            let forward = softmax.activate(&forward);
+            let backward = softmax.activate(&backward);
+            for i in 0..input.len() {
+                let expected = (forward[i] - backward[i]) / (2.0 * step);
+                assert!((jacobian.get(&[i, j]).unwrap() - expected).abs() < 1e-6, "Jacobian ({}, {}) mismatch", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn softmax_derivate_is_flattened_jacobian() {
+        let softmax = SoftmaxActivationFunction;
+        let input = vec![1.0, 2.0, 3.0];
+        let derivative = softmax.derivate(&input);
+        let jacobian = softmax.jacobian(&input);
+
+        assert_eq!(derivative.len(), 9);
+        assert_eq!(derivative[5], jacobian.get(&[1, 2]).unwrap());
+        // Each row of the Jacobian sums to zero, as the outputs always sum to one.
+        for row in jacobian.to_rows().unwrap() {
+            assert!(row.iter().sum::<f64>().abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn softmax_jacobian_vector_product() {
+        let softmax = SoftmaxActivationFunction;
+        let input = vec![0.2, -0.4, 1.1, 0.0];
+        let vector = [1.0, -2.0, 0.5, 3.0];
+
+        let product = softmax.jacobian_vector_product(&input, &vector).unwrap();
+        let expected: Vec<f64> = softmax.jacobian(&input)
+            .to_rows()
+            .unwrap()
+            .iter()
+            .map(|row| row.iter().zip(vector).map(|(j, v)| j * v).sum())
+            .collect();
+
+        for (p, e) in product.iter().zip(expected) {
+            assert!((p - e).abs() < 1e-12);
+        }
+        assert!(softmax.jacobian_vector_product(&input, &vector[..3]).is_err());
+    }
+
+    #[test]
+    fn softmax_jacobian_extreme() {
+        let softmax = SoftmaxActivationFunction;
+        let jacobian = softmax.jacobian(&vec![1000.0_f64, -1000.0]);
+
+        assert!(jacobian.as_slice().iter().all(|value| value.is_finite() && value.abs() < 1e-12));
+    }
+
+    #[test]
+    fn softmax_activate_tensor_per_row() {
+        let softmax = SoftmaxActivationFunction;
+        let rows = vec![vec![1.0, 2.0, 3.0], vec![-1.0, 0.0, 5.0]];
+        let output = softmax.activate_tensor(&Tensor::from_rows(&rows).unwrap());
+
+        assert_eq!(output.shape(), &[2, 3]);
+        for (row, input) in output.to_rows().unwrap().iter().zip(&rows) {
+            assert_eq!(row, &softmax.activate(input));
+        }
+    }
 }
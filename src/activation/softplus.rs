@@ -0,0 +1,77 @@
+use crate::numeric::Float;
+use super::{sigmoid::SigmoidActivationFunction, ActivationFunction};
+
+/// Represents the Softplus activation function in neural networks.
+///
+/// Softplus, `ln(1 + e^x)`, is a smooth approximation of ReLU: it is always positive, close to
+/// zero for large negative inputs and close to `x` for large positive ones. It is also used to
+/// map unconstrained values to positive parameters, such as volatilities.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. The function is evaluated as
+/// `max(x, 0) + ln(1 + e^(-|x|))`, which never overflows, and its derivative is the sigmoid.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::softplus::SoftplusActivationFunction;
+///
+/// let softplus = SoftplusActivationFunction;
+/// assert!((softplus.activate(0.0) - 2.0_f64.ln()).abs() < 1e-12);
+/// assert_eq!(softplus.activate(1000.0), 1000.0);
+/// ```
+pub struct SoftplusActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for SoftplusActivationFunction {
+    /// Computes the Softplus of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the Softplus.
+    ///
+    /// # Returns
+    ///
+    /// The Softplus of the input, a positive value.
+    fn activate(&self, input: F) -> F {
+        input.max(F::zero()) + (-input.abs()).exp().ln_1p()
+    }
+
+    /// Computes the derivative of the Softplus function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The sigmoid of the input.
+    fn derivate(&self, input: F) -> F {
+        SigmoidActivationFunction.activate(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn softplus_activate() {
+        let softplus = SoftplusActivationFunction;
+
+        assert!((softplus.activate(1.0_f64) - 1.3132616875182228).abs() < 1e-12);
+        assert!((softplus.activate(-1.0_f64) - 0.31326168751822286).abs() < 1e-12);
+        assert!((softplus.activate(-1000.0_f64)).abs() < 1e-300);
+        assert_eq!(softplus.activate(1000.0), 1000.0);
+    }
+
+    #[test]
+    fn softplus_derivate_matches_finite_differences() {
+        let softplus = SoftplusActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&softplus, input);
+            assert!((softplus.derivate(input) - expected).abs() < 1e-6, "Softplus'({}) mismatch", input);
+        }
+    }
+}
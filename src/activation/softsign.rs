@@ -0,0 +1,75 @@
+use crate::numeric::Float;
+use super::ActivationFunction;
+
+/// Represents the Softsign activation function in neural networks.
+///
+/// Softsign, `x / (1 + |x|)`, maps inputs to the range (-1, 1) like tanh, but approaches its
+/// asymptotes polynomially rather than exponentially, so it saturates more slowly.
+///
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. Its derivative is
+/// `1 / (1 + |x|)^2`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::ActivationFunction;
+/// use qmachina::activation::softsign::SoftsignActivationFunction;
+///
+/// let softsign = SoftsignActivationFunction;
+/// assert_eq!(softsign.activate(1.0), 0.5);
+/// assert_eq!(softsign.derivate(1.0), 0.25);
+/// ```
+pub struct SoftsignActivationFunction;
+
+impl<F: Float> ActivationFunction<F, F> for SoftsignActivationFunction {
+    /// Computes the Softsign of a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the Softsign.
+    ///
+    /// # Returns
+    ///
+    /// The Softsign of the input, a value between -1 and 1.
+    fn activate(&self, input: F) -> F {
+        input / (F::one() + input.abs())
+    }
+
+    /// Computes the derivative of the Softsign function for a given input value.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The derivative `1 / (1 + |x|)^2` at the given input.
+    fn derivate(&self, input: F) -> F {
+        F::one() / (F::one() + input.abs()).powi(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::{numerical_derivative, DERIVATIVE_INPUTS};
+
+    #[test]
+    fn softsign_activate() {
+        let softsign = SoftsignActivationFunction;
+
+        assert_eq!(softsign.activate(0.0), 0.0);
+        assert_eq!(softsign.activate(-3.0), -0.75);
+        assert!((softsign.activate(1e12_f64) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn softsign_derivate_matches_finite_differences() {
+        let softsign = SoftsignActivationFunction;
+
+        for input in DERIVATIVE_INPUTS {
+            let expected = numerical_derivative(&softsign, input);
+            assert!((softsign.derivate(input) - expected).abs() < 1e-6, "Softsign'({}) mismatch", input);
+        }
+    }
+}
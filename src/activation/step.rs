@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// A `StepActivationFunction` represents a simple step activation function
@@ -9,8 +10,8 @@ use super::ActivationFunction;
 /// due to its non-differentiability at zero and the fact that its derivative is zero
 /// almost everywhere.
 ///
-/// Implements `ActivationFunction<f64, f64>`, accepting `f64` as input and returning
-/// `f64` as output.
+/// Implements `ActivationFunction<F, F>` for `f32` and `f64`, returning a value of the
+/// same type as its input.
 ///
 /// # Examples
 ///
@@ -25,18 +26,18 @@ use super::ActivationFunction;
 pub struct StepActivationFunction;
 
 
-impl ActivationFunction<f64, f64> for StepActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for StepActivationFunction {
     /// Applies the step activation function to a given input.
     ///
     /// # Arguments
     ///
-    /// * `input` - A `f64` value representing the input to the activation function.
+    /// * `input` - An `F` value representing the input to the activation function.
     ///
     /// # Returns
     ///
     /// Returns 1.0 if the input is greater than 0.0, otherwise returns 0.0.
-    fn activate(&self, input: f64) -> f64 {
-        if input > 0.0 { 1.0 } else { 0.0 }
+    fn activate(&self, input: F) -> F {
+        if input > F::zero() { F::one() } else { F::zero() }
     }
 
     /// Returns the derivative of the step activation function.
@@ -46,14 +47,14 @@ impl ActivationFunction<f64, f64> for StepActivationFunction {
     ///
     /// # Arguments
     ///
-    /// * `_`: A `f64` value representing the input. The input is not used
+    /// * `_`: An `F` value representing the input. The input is not used
     ///   since the derivative is constant.
     ///
     /// # Returns
     ///
     /// Always returns 0.0.
-    fn derivate(&self, _: f64) -> f64 {
-        0.0
+    fn derivate(&self, _: F) -> F {
+        F::zero()
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::{sigmoid::SigmoidActivationFunction, ActivationFunction};
 
 /// `SwishActivationFunction` represents the Swish activation function
@@ -5,7 +6,7 @@ use super::{sigmoid::SigmoidActivationFunction, ActivationFunction};
 /// It has been found to sometimes outperform traditional functions like ReLU
 /// in deep neural networks.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. By default,
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and `f64`. By default,
 /// the beta parameter is set to 1.0, but it can be adjusted if needed.
 ///
 /// # Examples
@@ -17,12 +18,12 @@ use super::{sigmoid::SigmoidActivationFunction, ActivationFunction};
 /// let swish = SwishActivationFunction::new(1.0);
 /// let activated_value = swish.activate(0.5); // Example usage
 /// ```
-pub struct SwishActivationFunction {
-    beta: f64,
+pub struct SwishActivationFunction<F = f64> {
+    beta: F,
     sigmoid: SigmoidActivationFunction,
 }
 
-impl SwishActivationFunction {
+impl<F: Float> SwishActivationFunction<F> {
     /// Creates a new instance of `SwishActivationFunction` with the given beta value.
     ///
     /// # Arguments
@@ -32,7 +33,7 @@ impl SwishActivationFunction {
     /// # Returns
     ///
     /// A new instance of `SwishActivationFunction`.
-    pub fn new(beta: f64) -> Self {
+    pub fn new(beta: F) -> Self {
         SwishActivationFunction {
             beta,
             sigmoid: SigmoidActivationFunction,
@@ -47,12 +48,32 @@ impl SwishActivationFunction {
     /// # Arguments
     ///
     /// * `new_beta` - The new value to set for the beta parameter.
-    pub fn update_beta(&mut self, new_beta: f64) {
+    pub fn update_beta(&mut self, new_beta: F) {
         self.beta = new_beta;
     }
+
+    /// Returns the current beta parameter of the Swish activation function.
+    pub fn beta(&self) -> F {
+        self.beta
+    }
+
+    /// Computes the derivative of the Swish function with respect to beta for a given input
+    /// value, `x^2 * sigmoid(beta * x) * (1 - sigmoid(beta * x))`, which is what an optimizer
+    /// needs to learn beta.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input value for which to compute the derivative.
+    ///
+    /// # Returns
+    ///
+    /// The derivative of the Swish function with respect to beta at the given input.
+    pub fn beta_derivate(&self, input: F) -> F {
+        input * input * self.sigmoid.derivate(self.beta * input)
+    }
 }
 
-impl ActivationFunction<f64, f64> for SwishActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for SwishActivationFunction<F> {
     /// Computes the Swish activation function for a given input value.
     ///
     /// The function is defined as `x * sigmoid(beta * x)`.
@@ -64,7 +85,7 @@ impl ActivationFunction<f64, f64> for SwishActivationFunction {
     /// # Returns
     ///
     /// The Swish of the input.
-    fn activate(&self, input: f64) -> f64 {
+    fn activate(&self, input: F) -> F {
         input * self.sigmoid.activate(self.beta * input)
     }
 
@@ -79,9 +100,9 @@ impl ActivationFunction<f64, f64> for SwishActivationFunction {
     /// # Returns
     ///
     /// The derivative of the Swish function at the given input.
-    fn derivate(&self, input: f64) -> f64 {
+    fn derivate(&self, input: F) -> F {
         let sigmoid = self.sigmoid.activate(self.beta * input);
-        sigmoid + self.beta * input * (1.0 - sigmoid)
+        sigmoid + self.beta * input * (F::one() - sigmoid)
     }
 }
 #[cfg(test)]
@@ -90,7 +111,7 @@ mod tests {
 
     #[test]
     fn swish_derivate_positive() {
-        let swish = SwishActivationFunction::new(1.0);
+        let swish: SwishActivationFunction = SwishActivationFunction::new(1.0);
 
         let input = 2.0;
         let output = swish.derivate(input);
@@ -101,7 +122,7 @@ mod tests {
 
     #[test]
     fn swish_derivate_negative() {
-        let swish = SwishActivationFunction::new(1.0);
+        let swish: SwishActivationFunction = SwishActivationFunction::new(1.0);
 
         let input = -2.0;
         let output = swish.derivate(input);
@@ -112,7 +133,7 @@ mod tests {
 
     #[test]
     fn swish_derivate_zero() {
-        let swish = SwishActivationFunction::new(1.0);
+        let swish: SwishActivationFunction = SwishActivationFunction::new(1.0);
 
         let input = 0.0;
         let output = swish.derivate(input);
@@ -122,7 +143,7 @@ mod tests {
 
     #[test]
     fn swish_derivate_extreme_positive() {
-        let swish = SwishActivationFunction::new(1.0);
+        let swish: SwishActivationFunction = SwishActivationFunction::new(1.0);
 
         let input = 1000.0;
         let output = swish.derivate(input);
@@ -132,7 +153,7 @@ mod tests {
 
     #[test]
     fn swish_derivate_extreme_negative() {
-        let swish = SwishActivationFunction::new(1.0);
+        let swish: SwishActivationFunction = SwishActivationFunction::new(1.0);
 
         let input = -1000.0;
         let output = swish.derivate(input);
@@ -141,4 +162,19 @@ mod tests {
         // For large negative x, the derivative should be close to 0, but not exactly 0
         assert!((output - expected).abs() < 1e-3);
     }
+
+    #[test]
+    fn swish_beta_derivate() {
+        let mut swish: SwishActivationFunction = SwishActivationFunction::new(1.5);
+        let input = 0.8;
+        let step = 1e-6;
+
+        let forward = SwishActivationFunction::new(1.5 + step).activate(input);
+        let backward = SwishActivationFunction::new(1.5 - step).activate(input);
+        let expected = (forward - backward) / (2.0 * step);
+        assert!((swish.beta_derivate(input) - expected).abs() < 1e-6);
+
+        swish.update_beta(2.0);
+        assert_eq!(swish.beta(), 2.0);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::numeric::Float;
 use super::ActivationFunction;
 
 /// Represents the hyperbolic tangent (tanh) activation function in neural networks.
@@ -8,8 +9,8 @@ use super::ActivationFunction;
 /// particularly useful in scenarios where negative outputs are meaningful, such as 
 /// when handling symmetrical data distributions.
 ///
-/// This struct implements the `ActivationFunction<f64, f64>` trait. It accepts a 
-/// `f64` input and produces a `f64` output. The tanh function is mathematically 
+/// This struct implements the `ActivationFunction<F, F>` trait for `f32` and
+/// `f64`, producing a value of the input type. The tanh function is mathematically 
 /// defined as `(e^x - e^(-x)) / (e^x + e^(-x))`. Its derivative, crucial in neural 
 /// network training algorithms like backpropagation, is `1 - tanh(x)^2`.
 ///
@@ -52,7 +53,7 @@ use super::ActivationFunction;
 /// ```
 pub struct TanhActivationFunction;
 
-impl ActivationFunction<f64, f64> for TanhActivationFunction {
+impl<F: Float> ActivationFunction<F, F> for TanhActivationFunction {
     /// Computes the hyperbolic tangent of a given input value.
     ///
     /// The function transforms the input to a smoothly varying output within the
@@ -62,12 +63,12 @@ impl ActivationFunction<f64, f64> for TanhActivationFunction {
     ///
     /// # Arguments
     ///
-    /// * `input` - A `f64` input value for which the hyperbolic tangent is computed.
+    /// * `input` - The input value for which the hyperbolic tangent is computed.
     ///
     /// # Returns
     ///
-    /// The `f64` hyperbolic tangent of the input.
-    fn activate(&self, input: f64) -> f64 {
+    /// The hyperbolic tangent of the input.
+    fn activate(&self, input: F) -> F {
         input.tanh()
     }
 
@@ -79,13 +80,13 @@ impl ActivationFunction<f64, f64> for TanhActivationFunction {
     ///
     /// # Arguments
     ///
-    /// * `input` - A `f64` input value for which the derivative of tanh is computed.
+    /// * `input` - The input value for which the derivative of tanh is computed.
     ///
     /// # Returns
     ///
-    /// The `f64` derivative of the hyperbolic tangent function at the specified input.
-    fn derivate(&self, input: f64) -> f64 {
-        1.0 - input.tanh().powi(2)
+    /// The derivative of the hyperbolic tangent function at the specified input.
+    fn derivate(&self, input: F) -> F {
+        F::one() - input.tanh().powi(2)
     }
 }
 
@@ -137,7 +138,7 @@ mod tests {
     fn tanh_derivate_positive() {
         let tanh = TanhActivationFunction;
 
-        let input = 1.0;
+        let input: f64 = 1.0;
         let output = tanh.derivate(input);
         let expected = 1.0 - tanh.activate(input).powi(2);
         assert_eq!(output, expected);
@@ -147,7 +148,7 @@ mod tests {
     fn tanh_derivate_negative() {
         let tanh = TanhActivationFunction;
 
-        let input = -1.0;
+        let input: f64 = -1.0;
         let output = tanh.derivate(input);
         let expected = 1.0 - tanh.activate(input).powi(2);
         assert_eq!(output, expected);
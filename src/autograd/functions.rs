@@ -0,0 +1,304 @@
+//! This module records the activation and loss functions of the crate on a `Tape`, each being
+//! differentiated with its own analytic derivative.
+
+use crate::activation::BatchActivationFunction;
+use crate::activation::log_softmax::LogSoftmaxActivationFunction;
+use crate::activation::softmax::SoftmaxActivationFunction;
+use crate::error::Result;
+use crate::loss::{BatchLossFunction, DifferentiableLoss};
+use crate::numeric::Float;
+use crate::tensor::Tensor;
+use super::{Op, Tape, Var};
+
+impl<F: Float> Tape<F> {
+    /// Records a scalar activation function applied to every value of a variable.
+    ///
+    /// Any `ActivationFunction<F, F>` can be recorded, such as Sigmoid, Tanh, the ReLU family,
+    /// ELU or Swish. Its `derivate` method provides the derivative at each input for the
+    /// backward pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::activation::tanh::TanhActivationFunction;
+    /// use qmachina::autograd::Tape;
+    ///
+    /// let mut tape = Tape::new();
+    /// let x = tape.scalar(0.5);
+    /// let y = tape.activate(x, &TanhActivationFunction).unwrap();
+    ///
+    /// let gradient = tape.backward(y).unwrap().get(x).unwrap().as_slice()[0];
+    /// assert!((gradient - (1.0 - 0.5_f64.tanh().powi(2))).abs() < 1e-12);
+    /// ```
+    pub fn activate<A: BatchActivationFunction<F> + ?Sized>(&mut self, x: Var, activation: &A) -> Result<Var> {
+        let input = self.value(x)?;
+        let value = activation.activate_tensor(input);
+        let derivative = activation.derivate_tensor(input);
+
+        Ok(self.push(value, Op::Elementwise { input: x, derivative }))
+    }
+
+    /// Records the Softmax of every lane along the last axis of a variable, such as the class
+    /// probabilities of each row of a batch of logits.
+    ///
+    /// The backward pass applies `SoftmaxActivationFunction::jacobian_vector_product` to each
+    /// lane.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn softmax(&mut self, x: Var) -> Result<Var> {
+        let value = SoftmaxActivationFunction.activate_tensor(self.value(x)?);
+        Ok(self.push(value, Op::Softmax(x)))
+    }
+
+    /// Records the Log-Softmax of every lane along the last axis of a variable.
+    ///
+    /// The backward pass applies `LogSoftmaxActivationFunction::vector_jacobian_product` to each
+    /// lane.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn log_softmax(&mut self, x: Var) -> Result<Var> {
+        let value = LogSoftmaxActivationFunction.activate_tensor(self.value(x)?);
+        Ok(self.push(value, Op::LogSoftmax(x)))
+    }
+
+    /// Records a loss between the values of a variable and constant targets, as a scalar.
+    ///
+    /// Any `DifferentiableLoss` can be recorded, such as MSE, MAE, Huber or the cross-entropies.
+    /// The loss is computed with `compute_tensor` over the values in row-major order, and its
+    /// `gradient` provides the gradient for the backward pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the targets do not have the shape of the
+    /// predictions, `QmachinaError::InvalidParameter` if the variable was not created by this
+    /// tape, and an error under the same conditions as the loss otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::autograd::Tape;
+    /// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+    /// use qmachina::tensor::Tensor;
+    ///
+    /// let mut tape = Tape::new();
+    /// let predictions = tape.variable(Tensor::from_vec(vec![1.0, 3.0]));
+    /// let targets = Tensor::from_vec(vec![0.0, 3.0]);
+    ///
+    /// let loss = tape.loss(predictions, &targets, &MeanSquaredErrorLossFunction).unwrap();
+    /// assert_eq!(tape.value(loss).unwrap().as_slice(), &[0.5]);
+    ///
+    /// let gradients = tape.backward(loss).unwrap();
+    /// assert_eq!(gradients.get(predictions).unwrap().as_slice(), &[1.0, 0.0]);
+    /// ```
+    pub fn loss<L: DifferentiableLoss<F> + ?Sized>(&mut self, predictions: Var, targets: &Tensor<F>, loss: &L) -> Result<Var> {
+        let input = self.value(predictions)?;
+        let value = loss.compute_tensor(input, targets)?;
+        let gradient = loss.gradient_tensor(input, targets)?;
+
+        Ok(self.push(Tensor::scalar(value), Op::Loss { input: predictions, gradient }))
+    }
+
+    /// Records the loss of a batch between the rows of a 2-D variable and constant targets, one
+    /// row per sample, as a scalar.
+    ///
+    /// The loss is computed with `compute_batch_tensor`, and `gradient_batch_tensor` provides the
+    /// gradient for the backward pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the targets do not have the shape of the
+    /// predictions, `QmachinaError::InvalidParameter` if the variable is not 2-D or was not
+    /// created by this tape, and an error under the same conditions as the loss otherwise.
+    pub fn batch_loss<L: BatchLossFunction<F> + ?Sized>(&mut self, predictions: Var, targets: &Tensor<F>, loss: &L) -> Result<Var> {
+        let input = self.value(predictions)?;
+        let value = loss.compute_batch_tensor(input, targets)?;
+        let gradient = loss.gradient_batch_tensor(input, targets)?;
+
+        Ok(self.push(Tensor::scalar(value), Op::Loss { input: predictions, gradient }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::activation::{ActivationFunction, DERIVATIVE_INPUTS};
+    use crate::activation::elu::ELUActivationFunction;
+    use crate::activation::gelu::GELUActivationFunction;
+    use crate::activation::leaky_relu::LeakyReLUActivationFunction;
+    use crate::activation::param_relu::PReLUActivationFunction;
+    use crate::activation::relu::ReLUActivationFunction;
+    use crate::activation::selu::SELUActivationFunction;
+    use crate::activation::sigmoid::SigmoidActivationFunction;
+    use crate::activation::silu::SiLUActivationFunction;
+    use crate::activation::swish::SwishActivationFunction;
+    use crate::activation::tanh::TanhActivationFunction;
+    use crate::autograd::{assert_close, numerical_gradient};
+    use crate::error::QmachinaError;
+    use crate::loss::bce::BinaryCrossEntropyLossFunction;
+    use crate::loss::cce::{CategoricalCrossEntropyLossFunction, SoftmaxCrossEntropyLossFunction};
+    use crate::loss::huber::HuberLossFunction;
+    use crate::loss::mae::MeanAbsoluteErrorLossFunction;
+    use crate::loss::mse::MeanSquaredErrorLossFunction;
+
+    fn logits() -> Tensor {
+        Tensor::from_rows(&[vec![0.5, -1.0, 2.0], vec![1.5, 0.25, -0.75]]).unwrap()
+    }
+
+    /// Test the gradient of every scalar activation against its `derivate` method.
+    #[test]
+    fn activations_match_their_derivatives() {
+        let activations: Vec<Box<dyn ActivationFunction<f64, f64>>> = vec![
+            Box::new(SigmoidActivationFunction),
+            Box::new(TanhActivationFunction),
+            Box::new(ReLUActivationFunction),
+            Box::new(LeakyReLUActivationFunction),
+            Box::new(PReLUActivationFunction::new(0.2)),
+            Box::new(ELUActivationFunction::new(1.0)),
+            Box::new(SwishActivationFunction::new(1.5)),
+            Box::new(SiLUActivationFunction),
+            Box::new(GELUActivationFunction::new()),
+            Box::new(SELUActivationFunction)
+        ];
+        let inputs = Tensor::from_vec(DERIVATIVE_INPUTS.to_vec());
+
+        for activation in activations {
+            let mut tape = Tape::new();
+            let x = tape.variable(inputs.clone());
+            let activated = tape.activate(x, activation.as_ref()).unwrap();
+            let output = tape.sum(activated).unwrap();
+
+            assert_eq!(tape.value(activated).unwrap().as_slice(), &activation.activate_batch(&DERIVATIVE_INPUTS)[..]);
+            assert_eq!(tape.backward(output).unwrap().get(x).unwrap().as_slice(), &activation.derivate_batch(&DERIVATIVE_INPUTS)[..]);
+        }
+    }
+
+    /// Test the gradient of composed activations against finite differences.
+    #[test]
+    fn composed_activations() {
+        let record = |inputs: &Tensor| {
+            let mut tape = Tape::new();
+            let x = tape.variable(inputs.clone());
+            let gate = tape.activate(x, &SigmoidActivationFunction).unwrap();
+            let candidate = tape.activate(x, &TanhActivationFunction).unwrap();
+            let product = tape.mul(gate, candidate).unwrap();
+            let output = tape.mean(product).unwrap();
+            (tape, x, output)
+        };
+
+        let (tape, x, output) = record(&logits());
+        let expected = numerical_gradient(|inputs| {
+            let (tape, _, output) = record(inputs);
+            tape.value(output).unwrap().sum()
+        }, &logits());
+        assert_close(tape.backward(output).unwrap().get(x).unwrap(), &expected, 1e-6);
+    }
+
+    /// Test the gradient of the Softmax and the Log-Softmax of each row against their Jacobians.
+    #[test]
+    fn softmax_matches_jacobian() {
+        let weights = Tensor::from_rows(&[vec![1.0, -2.0, 0.5], vec![0.3, 0.0, 4.0]]).unwrap();
+
+        for log in [false, true] {
+            let mut tape = Tape::new();
+            let x = tape.variable(logits());
+            let w = tape.variable(weights.clone());
+            let activated = if log { tape.log_softmax(x) } else { tape.softmax(x) }.unwrap();
+            let weighted = tape.mul(activated, w).unwrap();
+            let output = tape.sum(weighted).unwrap();
+            let gradients = tape.backward(output).unwrap();
+
+            let rows = logits().to_rows().unwrap();
+            let mut expected = Vec::new();
+            for (input, weights) in rows.iter().zip(weights.to_rows().unwrap()) {
+                let jacobian = if log {
+                    LogSoftmaxActivationFunction.jacobian(input)
+                } else {
+                    SoftmaxActivationFunction.jacobian(input)
+                };
+                expected.extend((0..input.len()).map(|j| (0..input.len()).map(|i| weights[i] * jacobian.get(&[i, j]).unwrap()).sum::<f64>()));
+            }
+
+            assert_close(gradients.get(x).unwrap(), &Tensor::new(&[2, 3], expected).unwrap(), 1e-12);
+        }
+    }
+
+    /// Test the gradient of every loss against its `gradient` method.
+    #[test]
+    fn losses_match_their_gradients() {
+        let predictions = Tensor::from_vec(vec![0.2, 0.7, 0.9, 0.4]);
+        let targets = Tensor::from_vec(vec![0.0, 1.0, 1.0, 0.0]);
+        let losses: Vec<Box<dyn DifferentiableLoss<f64>>> = vec![
+            Box::new(MeanSquaredErrorLossFunction),
+            Box::new(MeanAbsoluteErrorLossFunction),
+            Box::new(HuberLossFunction::new(0.5)),
+            Box::new(BinaryCrossEntropyLossFunction),
+            Box::new(CategoricalCrossEntropyLossFunction)
+        ];
+
+        for loss in losses {
+            let mut tape = Tape::new();
+            let x = tape.variable(predictions.clone());
+            let output = tape.loss(x, &targets, loss.as_ref()).unwrap();
+
+            let (p, t): (Arc<[f64]>, Arc<[f64]>) = (predictions.as_slice().into(), targets.as_slice().into());
+            assert_eq!(tape.value(output).unwrap().as_slice(), &[loss.compute(p.clone(), t.clone()).unwrap()]);
+            assert_eq!(tape.backward(output).unwrap().get(x).unwrap().as_slice(), &loss.gradient(p, t).unwrap()[..]);
+        }
+    }
+
+    /// Test chaining a Sigmoid into the binary cross-entropy.
+    /// Expected result is the gradient of the binary cross-entropy from logits.
+    #[test]
+    fn sigmoid_into_binary_cross_entropy() {
+        let targets = Tensor::from_vec(vec![1.0, 0.0, 1.0]);
+
+        let mut tape = Tape::new();
+        let x = tape.variable(Tensor::from_vec(vec![0.8, -1.5, 2.0]));
+        let probabilities = tape.activate(x, &SigmoidActivationFunction).unwrap();
+        let output = tape.loss(probabilities, &targets, &BinaryCrossEntropyLossFunction).unwrap();
+
+        let from_logits = BinaryCrossEntropyLossFunction.with_logits(true);
+        let expected = from_logits.gradient_tensor(tape.value(x).unwrap(), &targets).unwrap();
+        assert_close(tape.backward(output).unwrap().get(x).unwrap(), &expected, 1e-12);
+    }
+
+    /// Test chaining a Softmax into the categorical cross-entropy of a batch.
+    /// Expected result is the gradient of the fused softmax cross-entropy.
+    #[test]
+    fn softmax_into_categorical_cross_entropy() {
+        let targets = Tensor::from_rows(&[vec![0.0, 0.0, 1.0], vec![1.0, 0.0, 0.0]]).unwrap();
+
+        let mut tape = Tape::new();
+        let x = tape.variable(logits());
+        let probabilities = tape.softmax(x).unwrap();
+        let output = tape.batch_loss(probabilities, &targets, &CategoricalCrossEntropyLossFunction).unwrap();
+
+        let fused = SoftmaxCrossEntropyLossFunction;
+        let expected_loss = fused.compute_batch_tensor(&logits(), &targets).unwrap();
+        assert!((tape.value(output).unwrap().as_slice()[0] - expected_loss).abs() < 1e-12);
+
+        let expected = fused.gradient_batch_tensor(&logits(), &targets).unwrap();
+        assert_close(tape.backward(output).unwrap().get(x).unwrap(), &expected, 1e-12);
+    }
+
+    /// Test recording a loss with invalid targets.
+    /// Expected result is the error of the loss.
+    #[test]
+    fn invalid_losses() {
+        let mut tape = Tape::new();
+        let x = tape.variable(logits());
+
+        assert!(matches!(tape.loss(x, &Tensor::zeros(&[3, 2]), &MeanSquaredErrorLossFunction), Err(QmachinaError::ShapeMismatch { .. })));
+        assert!(matches!(tape.batch_loss(x, &logits(), &CategoricalCrossEntropyLossFunction), Err(QmachinaError::InvalidProbability { index: 1 })));
+    }
+}
@@ -0,0 +1,414 @@
+//! This module contains a tape-based reverse-mode automatic differentiation engine over tensors.
+//!
+//! A `Tape` records every operation applied to its variables, along with the values the
+//! operation needs to propagate a gradient. `Tape::backward` then walks the tape from an output,
+//! usually a loss, back to the variables, applying the chain rule once per operation. The
+//! gradient of any composition of the recorded operations is thus computed without writing its
+//! derivative by hand.
+//!
+//! Values are `Tensor`s, a scalar being a tensor with an empty shape. Element-wise operations
+//! broadcast their operands, and the gradient of a broadcast operand is summed back to its shape.
+//!
+//! Every scalar activation function is recorded through `Tape::activate`, which differentiates
+//! it with its `derivate` method. Softmax and Log-Softmax are recorded through `Tape::softmax`
+//! and `Tape::log_softmax`, and every `DifferentiableLoss` or `BatchLossFunction` through
+//! `Tape::loss` and `Tape::batch_loss`, using their analytic gradients.
+//!
+//! ```
+//! use qmachina::activation::sigmoid::SigmoidActivationFunction;
+//! use qmachina::autograd::Tape;
+//! use qmachina::loss::bce::BinaryCrossEntropyLossFunction;
+//! use qmachina::tensor::Tensor;
+//!
+//! // A logistic regression over two samples of two features
+//! let mut tape = Tape::new();
+//! let features = tape.variable(Tensor::from_rows(&[vec![1.0, 2.0], vec![-1.0, 0.5]]).unwrap());
+//! let weights = tape.variable(Tensor::new(&[2, 1], vec![0.1, -0.2]).unwrap());
+//! let bias = tape.scalar(0.0);
+//!
+//! let logits = tape.matmul(features, weights).unwrap();
+//! let logits = tape.add(logits, bias).unwrap();
+//! let probabilities = tape.activate(logits, &SigmoidActivationFunction).unwrap();
+//!
+//! let targets = Tensor::new(&[2, 1], vec![1.0, 0.0]).unwrap();
+//! let loss = tape.loss(probabilities, &targets, &BinaryCrossEntropyLossFunction).unwrap();
+//!
+//! let gradients = tape.backward(loss).unwrap();
+//! assert_eq!(gradients.get(weights).unwrap().shape(), &[2, 1]);
+//! assert_eq!(gradients.get(bias).unwrap().shape(), &[] as &[usize]);
+//! ```
+
+use crate::activation::log_softmax::LogSoftmaxActivationFunction;
+use crate::activation::softmax::SoftmaxActivationFunction;
+use crate::error::{QmachinaError, Result};
+use crate::numeric::{count, Float};
+use crate::tensor::{ensure_same_shape, Tensor};
+
+pub mod functions;
+pub mod ops;
+
+/// Identifies a value recorded on a `Tape`.
+///
+/// A `Var` is a plain index into the tape that created it, so it is cheap to copy and must not
+/// be used with another tape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+/// The operation that produced a value, with what its backward pass needs.
+#[derive(Debug, Clone)]
+enum Op<F> {
+    Leaf,
+    Add(Var, Var),
+    Sub(Var, Var),
+    Mul(Var, Var),
+    Div(Var, Var),
+    Scale(Var, F),
+    MatMul(Var, Var),
+    Transpose(Var),
+    Sum(Var),
+    Mean(Var),
+    Exp(Var),
+    Ln(Var),
+    /// An element-wise function, with its derivative at each input.
+    Elementwise { input: Var, derivative: Tensor<F> },
+    Softmax(Var),
+    LogSoftmax(Var),
+    /// A scalar function of the input, with its gradient.
+    Loss { input: Var, gradient: Tensor<F> }
+}
+
+#[derive(Debug, Clone)]
+struct Node<F> {
+    value: Tensor<F>,
+    op: Op<F>
+}
+
+/// Records the operations applied to tensors so that their gradients can be computed in reverse
+/// mode.
+///
+/// Variables are created with `variable` or `scalar`, and every operation returns the `Var` of
+/// its result. The value type defaults to `f64`. A tape is meant to record a single forward
+/// pass: build a new one for each step of a training loop.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::autograd::Tape;
+/// use qmachina::tensor::Tensor;
+///
+/// // d(x * y + x) / dx = y + 1 and d(x * y + x) / dy = x
+/// let mut tape = Tape::new();
+/// let x = tape.scalar(3.0);
+/// let y = tape.scalar(4.0);
+///
+/// let product = tape.mul(x, y).unwrap();
+/// let output = tape.add(product, x).unwrap();
+/// assert_eq!(tape.value(output).unwrap(), &Tensor::scalar(15.0));
+///
+/// let gradients = tape.backward(output).unwrap();
+/// assert_eq!(gradients.get(x), Some(&Tensor::scalar(5.0)));
+/// assert_eq!(gradients.get(y), Some(&Tensor::scalar(3.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tape<F = f64> {
+    nodes: Vec<Node<F>>
+}
+
+impl<F: Float> Tape<F> {
+    /// Constructs a new, empty `Tape`.
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Records a tensor as a variable whose gradient can be computed.
+    pub fn variable(&mut self, value: Tensor<F>) -> Var {
+        self.push(value, Op::Leaf)
+    }
+
+    /// Records a single value as a scalar variable.
+    pub fn scalar(&mut self, value: F) -> Var {
+        self.variable(Tensor::scalar(value))
+    }
+
+    /// Returns the value of a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn value(&self, var: Var) -> Result<&Tensor<F>> {
+        self.nodes
+            .get(var.0)
+            .map(|node| &node.value)
+            .ok_or(QmachinaError::InvalidParameter("The variable does not belong to this tape."))
+    }
+
+    /// Computes the gradient of a single-valued output, such as a loss, with respect to every
+    /// variable it depends on.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the output does not hold exactly one value or
+    /// was not created by this tape.
+    pub fn backward(&self, output: Var) -> Result<Gradients<F>> {
+        let value = self.value(output)?;
+        if value.len() != 1 {
+            return Err(QmachinaError::InvalidParameter("The output of the backward pass must hold a single value."));
+        }
+
+        self.backward_with(output, Tensor::ones(value.shape()))
+    }
+
+    /// Computes the gradients of every variable an output depends on, given the gradient of a
+    /// downstream value with respect to the output.
+    ///
+    /// This is the vector-Jacobian product of the recorded computation with `seed`, which lets
+    /// the backward pass start from a tensor output.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the seed does not have the shape of the output,
+    /// and `QmachinaError::InvalidParameter` if the output was not created by this tape.
+    pub fn backward_with(&self, output: Var, seed: Tensor<F>) -> Result<Gradients<F>> {
+        ensure_same_shape(self.value(output)?, &seed)?;
+
+        let mut gradients = vec![None; output.0 + 1];
+        gradients[output.0] = Some(seed);
+
+        // Variables are recorded after their inputs, so the reverse order of the tape visits a
+        // value once all of its uses have contributed to its gradient
+        for index in (0..=output.0).rev() {
+            let Some(gradient) = gradients[index].take() else {
+                continue;
+            };
+            for (input, input_gradient) in self.propagate(index, &gradient)? {
+                accumulate(&mut gradients[input.0], input_gradient)?;
+            }
+            gradients[index] = Some(gradient);
+        }
+
+        Ok(Gradients { gradients })
+    }
+
+    /// Records a value along with the operation that produced it.
+    fn push(&mut self, value: Tensor<F>, op: Op<F>) -> Var {
+        self.nodes.push(Node { value, op });
+        Var(self.nodes.len() - 1)
+    }
+
+    /// Applies the chain rule through the operation that produced a value, returning the
+    /// gradient of each of its inputs.
+    fn propagate(&self, index: usize, gradient: &Tensor<F>) -> Result<Vec<(Var, Tensor<F>)>> {
+        let value = |var: Var| &self.nodes[var.0].value;
+        let output = &self.nodes[index].value;
+
+        Ok(match &self.nodes[index].op {
+            Op::Leaf => Vec::new(),
+            &Op::Add(a, b) => vec![
+                (a, unbroadcast(gradient.clone(), value(a).shape())?),
+                (b, unbroadcast(gradient.clone(), value(b).shape())?)
+            ],
+            &Op::Sub(a, b) => vec![
+                (a, unbroadcast(gradient.clone(), value(a).shape())?),
+                (b, unbroadcast(gradient.scale(-F::one()), value(b).shape())?)
+            ],
+            &Op::Mul(a, b) => vec![
+                (a, unbroadcast(gradient.mul(value(b))?, value(a).shape())?),
+                (b, unbroadcast(gradient.mul(value(a))?, value(b).shape())?)
+            ],
+            &Op::Div(a, b) => {
+                let (numerator, denominator) = (value(a), value(b));
+                let quotient_gradient = gradient.mul(output)?.div(denominator)?.scale(-F::one());
+                vec![
+                    (a, unbroadcast(gradient.div(denominator)?, numerator.shape())?),
+                    (b, unbroadcast(quotient_gradient, denominator.shape())?)
+                ]
+            }
+            &Op::Scale(a, factor) => vec![(a, gradient.scale(factor))],
+            &Op::MatMul(a, b) => vec![
+                (a, gradient.matmul(&value(b).transpose())?),
+                (b, value(a).transpose().matmul(gradient)?)
+            ],
+            &Op::Transpose(a) => vec![(a, gradient.transpose())],
+            &Op::Sum(a) => vec![(a, Tensor::full(value(a).shape(), gradient.sum()))],
+            &Op::Mean(a) => {
+                let input = value(a);
+                vec![(a, Tensor::full(input.shape(), gradient.sum() / count(input.len())))]
+            }
+            &Op::Exp(a) => vec![(a, gradient.mul(output)?)],
+            &Op::Ln(a) => vec![(a, gradient.div(value(a))?)],
+            Op::Elementwise { input, derivative } => vec![(*input, gradient.mul(derivative)?)],
+            &Op::Softmax(a) => vec![(a, lanewise(value(a), gradient, |input, gradient| {
+                SoftmaxActivationFunction.jacobian_vector_product(input, gradient)
+            })?)],
+            &Op::LogSoftmax(a) => vec![(a, lanewise(value(a), gradient, |input, gradient| {
+                LogSoftmaxActivationFunction.vector_jacobian_product(input, gradient)
+            })?)],
+            Op::Loss { input, gradient: loss_gradient } => vec![(*input, loss_gradient.scale(gradient.sum()))]
+        })
+    }
+}
+
+impl<F: Float> Default for Tape<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Holds the gradients computed by a backward pass, one per variable of the tape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradients<F = f64> {
+    gradients: Vec<Option<Tensor<F>>>
+}
+
+impl<F> Gradients<F> {
+    /// Returns the gradient of the output with respect to a variable, of the shape of the
+    /// variable, or `None` if the output does not depend on it.
+    pub fn get(&self, var: Var) -> Option<&Tensor<F>> {
+        self.gradients.get(var.0).and_then(Option::as_ref)
+    }
+}
+
+/// Adds a contribution to the gradient of a value.
+fn accumulate<F: Float>(slot: &mut Option<Tensor<F>>, gradient: Tensor<F>) -> Result<()> {
+    *slot = Some(match slot.take() {
+        Some(total) => total.add(&gradient)?,
+        None => gradient
+    });
+    Ok(())
+}
+
+/// Sums the gradient of a broadcast operand over the axes it was repeated along, so that it
+/// recovers the shape of the operand.
+fn unbroadcast<F: Float>(mut gradient: Tensor<F>, shape: &[usize]) -> Result<Tensor<F>> {
+    while gradient.ndim() > shape.len() {
+        gradient = gradient.sum_axis(0)?;
+    }
+
+    for (axis, &length) in shape.iter().enumerate() {
+        if length == 1 && gradient.shape()[axis] != 1 {
+            let mut kept = gradient.shape().to_vec();
+            kept[axis] = 1;
+            gradient = gradient.sum_axis(axis)?.reshape(&kept)?;
+        }
+    }
+
+    Ok(gradient)
+}
+
+/// Applies a vector-Jacobian product to every lane along the last axis of an input and of the
+/// gradient of the output, which have the same shape.
+fn lanewise<F: Float>(
+    input: &Tensor<F>,
+    gradient: &Tensor<F>,
+    mut product: impl FnMut(&Vec<F>, &[F]) -> Result<Vec<F>>
+) -> Result<Tensor<F>> {
+    let lane = input.shape().last().copied().unwrap_or(1).max(1);
+
+    let mut data = Vec::with_capacity(input.len());
+    for (input, gradient) in input.as_slice().chunks(lane).zip(gradient.as_slice().chunks(lane)) {
+        data.extend(product(&input.to_vec(), gradient)?);
+    }
+
+    Tensor::new(input.shape(), data)
+}
+
+/// Approximates the gradient of a function of a tensor with central finite differences.
+#[cfg(test)]
+pub(crate) fn numerical_gradient(f: impl Fn(&Tensor) -> f64, at: &Tensor) -> Tensor {
+    let step = 1e-6;
+    let mut gradient = Tensor::zeros(at.shape());
+
+    for index in 0..at.len() {
+        let mut forward = at.clone();
+        let mut backward = at.clone();
+        forward.as_mut_slice()[index] += step;
+        backward.as_mut_slice()[index] -= step;
+        gradient.as_mut_slice()[index] = (f(&forward) - f(&backward)) / (2.0 * step);
+    }
+
+    gradient
+}
+
+/// Asserts that two tensors have the same shape and close values.
+#[cfg(test)]
+pub(crate) fn assert_close(actual: &Tensor, expected: &Tensor, tolerance: f64) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (a, e) in actual.as_slice().iter().zip(expected.as_slice()) {
+        assert!((a - e).abs() < tolerance, "{actual:?} differs from {expected:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the gradient of an output with respect to itself.
+    /// Expected result is 1.
+    #[test]
+    fn backward_from_a_variable() {
+        let mut tape = Tape::new();
+        let x = tape.scalar(2.5);
+
+        assert_eq!(tape.backward(x).unwrap().get(x), Some(&Tensor::scalar(1.0)));
+    }
+
+    /// Test that a variable used several times accumulates the gradient of each use, and that a
+    /// variable the output does not depend on has no gradient.
+    #[test]
+    fn backward_accumulates_and_skips() {
+        let mut tape = Tape::new();
+        let x = tape.scalar(3.0);
+        let unused = tape.scalar(1.0);
+
+        let square = tape.mul(x, x).unwrap();
+        let output = tape.mul(square, x).unwrap();
+
+        let gradients = tape.backward(output).unwrap();
+        assert_eq!(gradients.get(x), Some(&Tensor::scalar(27.0)));
+        assert_eq!(gradients.get(unused), None);
+    }
+
+    /// Test the backward pass from a tensor output.
+    /// Expected result is an error without a seed, and the seeded vector-Jacobian product otherwise.
+    #[test]
+    fn backward_from_a_tensor() {
+        let mut tape = Tape::new();
+        let x = tape.variable(Tensor::from_vec(vec![1.0, 2.0]));
+        let doubled = tape.scale(x, 2.0).unwrap();
+
+        assert!(matches!(tape.backward(doubled), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(tape.backward_with(doubled, Tensor::scalar(1.0)), Err(QmachinaError::ShapeMismatch { .. })));
+
+        let gradients = tape.backward_with(doubled, Tensor::from_vec(vec![1.0, -3.0])).unwrap();
+        assert_eq!(gradients.get(x).unwrap().as_slice(), &[2.0, -6.0]);
+    }
+
+    /// Test using a variable of another tape.
+    /// Expected result is an error.
+    #[test]
+    fn foreign_variable() {
+        let mut other = Tape::<f64>::new();
+        other.scalar(1.0);
+        let foreign = other.scalar(2.0);
+
+        let mut tape = Tape::new();
+        let x = tape.scalar(1.0);
+        assert!(matches!(tape.value(foreign), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(tape.add(x, foreign), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(tape.backward(foreign), Err(QmachinaError::InvalidParameter(_))));
+    }
+
+    /// Test summing a gradient back to the shape of a broadcast operand.
+    #[test]
+    fn unbroadcast_gradients() {
+        let gradient = Tensor::new(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+
+        assert_eq!(unbroadcast(gradient.clone(), &[2, 3]).unwrap(), gradient);
+        assert_eq!(unbroadcast(gradient.clone(), &[3]).unwrap().as_slice(), &[5.0, 7.0, 9.0]);
+        assert_eq!(unbroadcast(gradient.clone(), &[]).unwrap(), Tensor::scalar(21.0));
+
+        let column = unbroadcast(gradient, &[2, 1]).unwrap();
+        assert_eq!(column.shape(), &[2, 1]);
+        assert_eq!(column.as_slice(), &[6.0, 15.0]);
+    }
+}
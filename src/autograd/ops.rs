@@ -0,0 +1,256 @@
+//! This module contains the arithmetic operations recorded by a `Tape`: broadcasting
+//! element-wise operations, matrix multiplication, reductions, exponential and logarithm.
+
+use crate::error::{QmachinaError, Result};
+use crate::numeric::Float;
+use crate::tensor::Tensor;
+use super::{Op, Tape, Var};
+
+impl<F: Float> Tape<F> {
+    /// Records the sum of two variables, broadcasting their shapes like `Tensor::add`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together, and
+    /// `QmachinaError::InvalidParameter` if a variable was not created by this tape.
+    pub fn add(&mut self, a: Var, b: Var) -> Result<Var> {
+        let value = self.value(a)?.add(self.value(b)?)?;
+        Ok(self.push(value, Op::Add(a, b)))
+    }
+
+    /// Records the difference of two variables, broadcasting their shapes like `Tensor::sub`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `add`.
+    pub fn sub(&mut self, a: Var, b: Var) -> Result<Var> {
+        let value = self.value(a)?.sub(self.value(b)?)?;
+        Ok(self.push(value, Op::Sub(a, b)))
+    }
+
+    /// Records the element-wise product of two variables, broadcasting their shapes like
+    /// `Tensor::mul`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `add`.
+    pub fn mul(&mut self, a: Var, b: Var) -> Result<Var> {
+        let value = self.value(a)?.mul(self.value(b)?)?;
+        Ok(self.push(value, Op::Mul(a, b)))
+    }
+
+    /// Records the element-wise quotient of two variables, broadcasting their shapes like
+    /// `Tensor::div`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `add`.
+    pub fn div(&mut self, a: Var, b: Var) -> Result<Var> {
+        let value = self.value(a)?.div(self.value(b)?)?;
+        Ok(self.push(value, Op::Div(a, b)))
+    }
+
+    /// Records the product of a variable with a constant factor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn scale(&mut self, a: Var, factor: F) -> Result<Var> {
+        let value = self.value(a)?.scale(factor);
+        Ok(self.push(value, Op::Scale(a, factor)))
+    }
+
+    /// Records the matrix product of two 2-D variables, of shapes `[n, k]` and `[k, m]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if either variable is not 2-D or if their inner
+    /// dimensions differ, and `QmachinaError::InvalidParameter` if a variable was not created by
+    /// this tape.
+    pub fn matmul(&mut self, a: Var, b: Var) -> Result<Var> {
+        let value = self.value(a)?.matmul(self.value(b)?)?;
+        Ok(self.push(value, Op::MatMul(a, b)))
+    }
+
+    /// Records the transpose of a variable, with its axes reversed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn transpose(&mut self, a: Var) -> Result<Var> {
+        let value = self.value(a)?.transpose();
+        Ok(self.push(value, Op::Transpose(a)))
+    }
+
+    /// Records the sum of all values of a variable, as a scalar.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn sum(&mut self, a: Var) -> Result<Var> {
+        let value = self.value(a)?.sum();
+        Ok(self.push(Tensor::scalar(value), Op::Sum(a)))
+    }
+
+    /// Records the average of all values of a variable, as a scalar.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` if the variable holds no values, and
+    /// `QmachinaError::InvalidParameter` if it was not created by this tape.
+    pub fn mean(&mut self, a: Var) -> Result<Var> {
+        let value = self.value(a)?.mean()?;
+        Ok(self.push(Tensor::scalar(value), Op::Mean(a)))
+    }
+
+    /// Records the exponential of every value of a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the variable was not created by this tape.
+    pub fn exp(&mut self, a: Var) -> Result<Var> {
+        let value = self.value(a)?.map(F::exp);
+        Ok(self.push(value, Op::Exp(a)))
+    }
+
+    /// Records the natural logarithm of every value of a variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedLogarithm` with the index of the first value that is not
+    /// strictly positive, and `QmachinaError::InvalidParameter` if the variable was not created
+    /// by this tape.
+    pub fn ln(&mut self, a: Var) -> Result<Var> {
+        let input = self.value(a)?;
+        if let Some(index) = input.as_slice().iter().position(|&value| value <= F::zero()) {
+            return Err(QmachinaError::UndefinedLogarithm { index });
+        }
+
+        let value = input.map(F::ln);
+        Ok(self.push(value, Op::Ln(a)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::autograd::{assert_close, numerical_gradient};
+
+    fn matrix() -> Tensor {
+        Tensor::new(&[2, 3], vec![0.5, -1.0, 2.0, 1.5, 0.25, -0.75]).unwrap()
+    }
+
+    /// Records a binary operation of `left` and `right` followed by the mean of its exponential,
+    /// so that every value of the result contributes differently to the output, and checks the
+    /// gradients of both operands against finite differences.
+    fn check_binary(op: fn(&mut Tape, Var, Var) -> Result<Var>, left: &Tensor, right: &Tensor) {
+        let record = |left: &Tensor, right: &Tensor| {
+            let mut tape = Tape::new();
+            let (a, b) = (tape.variable(left.clone()), tape.variable(right.clone()));
+            let result = op(&mut tape, a, b).unwrap();
+            let exponential = tape.exp(result).unwrap();
+            let output = tape.mean(exponential).unwrap();
+            (tape, a, b, output)
+        };
+
+        let output_value = |left: &Tensor, right: &Tensor| {
+            let (tape, .., output) = record(left, right);
+            tape.value(output).unwrap().sum()
+        };
+
+        let (tape, a, b, output) = record(left, right);
+        let gradients = tape.backward(output).unwrap();
+
+        let expected = numerical_gradient(|left| output_value(left, right), left);
+        assert_close(gradients.get(a).unwrap(), &expected, 1e-6);
+
+        let expected = numerical_gradient(|right| output_value(left, right), right);
+        assert_close(gradients.get(b).unwrap(), &expected, 1e-6);
+    }
+
+    /// Test the gradients of the element-wise operations against finite differences.
+    #[test]
+    fn elementwise_gradients() {
+        let other = Tensor::new(&[2, 3], vec![1.0, 2.0, -0.5, 0.8, -1.2, 3.0]).unwrap();
+
+        check_binary(Tape::add, &matrix(), &other);
+        check_binary(Tape::sub, &matrix(), &other);
+        check_binary(Tape::mul, &matrix(), &other);
+        check_binary(Tape::div, &matrix(), &other);
+    }
+
+    /// Test the gradients of broadcast operands, which are summed back to their shapes.
+    #[test]
+    fn broadcast_gradients() {
+        let row = Tensor::from_vec(vec![0.3, -0.2, 1.1]);
+        let column = Tensor::new(&[2, 1], vec![2.0, -1.5]).unwrap();
+
+        check_binary(Tape::add, &matrix(), &row);
+        check_binary(Tape::sub, &column, &matrix());
+        check_binary(Tape::mul, &matrix(), &Tensor::scalar(0.7));
+        check_binary(Tape::div, &matrix(), &column);
+    }
+
+    /// Test the gradients of the matrix product against finite differences, and against the
+    /// closed form of the gradient of a sum of products.
+    #[test]
+    fn matmul_gradients() {
+        let other = Tensor::new(&[3, 2], vec![0.2, -0.4, 1.0, 0.6, -0.3, 0.9]).unwrap();
+        check_binary(Tape::matmul, &matrix(), &other);
+
+        let mut tape = Tape::new();
+        let (a, b) = (tape.variable(matrix()), tape.variable(other.clone()));
+        let product = tape.matmul(a, b).unwrap();
+        let output = tape.sum(product).unwrap();
+
+        let gradients = tape.backward(output).unwrap();
+        assert_eq!(gradients.get(a).unwrap(), &Tensor::ones(&[2, 2]).matmul(&other.transpose()).unwrap());
+        assert_eq!(gradients.get(b).unwrap(), &matrix().transpose().matmul(&Tensor::ones(&[2, 2])).unwrap());
+    }
+
+    /// Test the gradients of the unary operations.
+    #[test]
+    fn unary_gradients() {
+        let mut tape = Tape::new();
+        let x = tape.variable(Tensor::from_vec(vec![0.5, 2.0]));
+
+        let transposed = tape.transpose(x).unwrap();
+        let scaled = tape.scale(transposed, 3.0).unwrap();
+        let logarithm = tape.ln(scaled).unwrap();
+        let exponential = tape.exp(x).unwrap();
+        let sum = tape.add(logarithm, exponential).unwrap();
+        let output = tape.sum(sum).unwrap();
+
+        // d(ln(3x) + exp(x)) / dx = 1 / x + exp(x)
+        let expected = Tensor::from_vec(vec![2.0 + 0.5_f64.exp(), 0.5 + 2.0_f64.exp()]);
+        assert_close(tape.backward(output).unwrap().get(x).unwrap(), &expected, 1e-12);
+    }
+
+    /// Test the mean of a tensor and of an empty tensor.
+    /// Expected result is a gradient of 1 / n, and an error when empty.
+    #[test]
+    fn mean_gradient() {
+        let mut tape = Tape::new();
+        let x = tape.variable(matrix());
+        let output = tape.mean(x).unwrap();
+
+        assert_close(tape.value(output).unwrap(), &Tensor::scalar(2.5 / 6.0), 1e-12);
+        assert_eq!(tape.backward(output).unwrap().get(x), Some(&Tensor::full(&[2, 3], 1.0 / 6.0)));
+
+        let empty = tape.variable(Tensor::zeros(&[0]));
+        assert!(matches!(tape.mean(empty), Err(QmachinaError::EmptyInput)));
+    }
+
+    /// Test the operations with incompatible or invalid values.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_operations() {
+        let mut tape = Tape::new();
+        let x = tape.variable(matrix());
+        let y = tape.variable(Tensor::from_vec(vec![1.0, 2.0]));
+
+        assert!(matches!(tape.add(x, y), Err(QmachinaError::ShapeMismatch { .. })));
+        assert!(matches!(tape.matmul(x, x), Err(QmachinaError::ShapeMismatch { .. })));
+        assert!(matches!(tape.ln(x), Err(QmachinaError::UndefinedLogarithm { index: 1 })));
+    }
+}
@@ -0,0 +1,325 @@
+//! This module contains `polars` integrations for indicators and loss functions.
+//!
+//! It is only available with the `polars` cargo feature enabled. The extension traits
+//! defined here allow a `Series` or a named column of a `DataFrame` to be passed to any
+//! `Indicator` working on `f64` data, and `LossFunction`s to be applied to prediction
+//! and target columns.
+use std::sync::Arc;
+use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+
+use crate::error::{QmachinaError, Result};
+use crate::loss::LossFunction;
+use crate::technical_analysis::Indicator;
+use crate::technical_analysis::bollinger::BollingerOutput;
+use crate::technical_analysis::macd::MacdOutput;
+
+/// The `IndicatorColumns` trait describes how the output of an indicator is laid out
+/// as columns.
+///
+/// Indicators producing a single value map to one column, while indicators producing
+/// several values (such as the Bollinger Bands) map to one column per
+/// value, each named after the requested output name followed by a suffix.
+pub trait IndicatorColumns {
+    /// The suffixes appended to the output name, one per produced column.
+    const SUFFIXES: &'static [&'static str];
+
+    /// Returns the values of each column, in the same order as `SUFFIXES`.
+    fn column_values(&self) -> Vec<f64>;
+}
+
+impl IndicatorColumns for f64 {
+    const SUFFIXES: &'static [&'static str] = &[""];
+
+    fn column_values(&self) -> Vec<f64> {
+        vec![*self]
+    }
+}
+
+impl IndicatorColumns for BollingerOutput {
+    const SUFFIXES: &'static [&'static str] = &["_upper", "_middle", "_lower", "_percent_b", "_bandwidth"];
+
+    fn column_values(&self) -> Vec<f64> {
+        vec![self.upper, self.middle, self.lower, self.percent_b, self.bandwidth]
+    }
+}
+
+impl IndicatorColumns for MacdOutput {
+    const SUFFIXES: &'static [&'static str] = &["", "_signal", "_histogram"];
+
+    fn column_values(&self) -> Vec<f64> {
+        vec![self.macd, self.signal, self.histogram]
+    }
+}
+
+/// Extension trait to compute indicators directly over a `polars::Series`.
+///
+/// # Example
+///
+/// ```
+/// use polars::prelude::{NamedFrom, Series};
+/// use qmachina::dataframe::SeriesIndicatorExt;
+/// use qmachina::technical_analysis::sma::SimpleMovingAverage;
+///
+/// let close = Series::new("close", &[1.0, 2.0, 3.0, 4.0, 5.0]);
+/// let columns = close.apply_indicator(&SimpleMovingAverage::new(3), "sma_3").unwrap();
+///
+/// assert_eq!(columns[0].name(), "sma_3");
+/// assert_eq!(columns[0].null_count(), 2);
+/// ```
+pub trait SeriesIndicatorExt {
+    /// Computes the indicator for every row of the series.
+    ///
+    /// # Parameters
+    ///
+    /// * `indicator` - The indicator to compute.
+    /// * `name` - The name of the resulting column, used as a prefix for multi-column outputs.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Series>)` with one series per indicator output, aligned with the input.
+    /// Rows where the indicator is still warming up are null.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the series cannot be cast to `f64`, contains null values, or if the
+    /// indicator computation fails.
+    fn apply_indicator<I, V>(&self, indicator: &I, name: &str) -> Result<Vec<Series>>
+    where
+        I: Indicator<f64, V>,
+        V: IndicatorColumns;
+}
+
+impl SeriesIndicatorExt for Series {
+    fn apply_indicator<I, V>(&self, indicator: &I, name: &str) -> Result<Vec<Series>>
+    where
+        I: Indicator<f64, V>,
+        V: IndicatorColumns,
+    {
+        let data = series_to_vec(self)?;
+        let values = indicator.compute_series(&data)?;
+
+        let mut column_values: Vec<Vec<Option<f64>>> = V::SUFFIXES.iter()
+            .map(|_| Vec::with_capacity(values.len()))
+            .collect();
+        for value in &values {
+            match value {
+                Some(value) => {
+                    for (column, value) in column_values.iter_mut().zip(value.column_values()) {
+                        column.push(Some(value));
+                    }
+                }
+                None => column_values.iter_mut().for_each(|column| column.push(None))
+            }
+        }
+
+        let columns = V::SUFFIXES.iter()
+            .zip(column_values)
+            .map(|(suffix, column_values)| Series::new(&format!("{}{}", name, suffix), column_values))
+            .collect();
+
+        Ok(columns)
+    }
+}
+
+/// Extension trait to compute indicators and losses over the columns of a `polars::DataFrame`.
+///
+/// # Example
+///
+/// ```
+/// use polars::prelude::{DataFrame, NamedFrom, Series};
+/// use qmachina::dataframe::DataFrameExt;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+/// use qmachina::technical_analysis::bollinger::BollingerBands;
+///
+/// let mut df = DataFrame::new(vec![
+///     Series::new("close", &[100.0, 101.0, 102.0, 103.0, 102.0, 101.0]),
+///     Series::new("prediction", &[100.5, 101.0, 101.5, 103.0, 102.5, 100.0]),
+/// ]).unwrap();
+///
+/// df.with_indicator("close", &BollingerBands::new(5), "bb").unwrap();
+/// assert!(df.column("bb_upper").is_ok());
+/// assert!(df.column("bb_percent_b").is_ok());
+///
+/// let loss = df.compute_loss(&MeanSquaredErrorLossFunction, "prediction", "close").unwrap();
+/// assert!(loss > 0.0);
+/// ```
+pub trait DataFrameExt {
+    /// Computes the indicator over a column and appends the resulting columns to the frame.
+    ///
+    /// # Parameters
+    ///
+    /// * `column` - The name of the input column.
+    /// * `indicator` - The indicator to compute.
+    /// * `name` - The name of the resulting column, used as a prefix for multi-column outputs.
+    ///
+    /// # Returns
+    ///
+    /// Returns the frame with the indicator columns appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column does not exist, cannot be cast to `f64`, contains null
+    /// values, or if the indicator computation fails.
+    fn with_indicator<I, V>(&mut self, column: &str, indicator: &I, name: &str) -> Result<&mut Self>
+    where
+        I: Indicator<f64, V>,
+        V: IndicatorColumns;
+
+    /// Computes a loss between a prediction column and a target column.
+    ///
+    /// # Parameters
+    ///
+    /// * `loss` - The loss function to apply.
+    /// * `predictions` - The name of the column holding the predicted values.
+    /// * `targets` - The name of the column holding the actual target values.
+    ///
+    /// # Returns
+    ///
+    /// Returns the computed loss value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either column does not exist, cannot be cast to `f64`, contains null
+    /// values, or if the loss computation fails.
+    fn compute_loss<L>(&self, loss: &L, predictions: &str, targets: &str) -> Result<f64>
+    where
+        L: LossFunction<f64>;
+}
+
+impl DataFrameExt for DataFrame {
+    fn with_indicator<I, V>(&mut self, column: &str, indicator: &I, name: &str) -> Result<&mut Self>
+    where
+        I: Indicator<f64, V>,
+        V: IndicatorColumns,
+    {
+        let columns = self.column(column)?.apply_indicator(indicator, name)?;
+        for series in columns {
+            self.with_column(series)?;
+        }
+
+        Ok(self)
+    }
+
+    fn compute_loss<L>(&self, loss: &L, predictions: &str, targets: &str) -> Result<f64>
+    where
+        L: LossFunction<f64>,
+    {
+        let predictions: Arc<[f64]> = series_to_vec(self.column(predictions)?)?.into();
+        let targets: Arc<[f64]> = series_to_vec(self.column(targets)?)?.into();
+
+        loss.compute(predictions, targets)
+    }
+}
+
+/// Casts a series to `f64` and collects its values.
+///
+/// # Errors
+///
+/// Returns an error if the series cannot be cast to `f64` or contains null values.
+fn series_to_vec(series: &Series) -> Result<Vec<f64>> {
+    let series = series.cast(&DataType::Float64)?;
+    let values = series.f64()?;
+
+    if values.null_count() > 0 {
+        return Err(QmachinaError::NullValues { column: series.name().to_string() });
+    }
+
+    Ok(values.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::mae::MeanAbsoluteErrorLossFunction;
+    use crate::technical_analysis::bollinger::BollingerBands;
+    use crate::technical_analysis::macd::MACD;
+    use crate::technical_analysis::sma::SimpleMovingAverage;
+
+    #[test]
+    fn series_single_column_indicator() {
+        let close = Series::new("close", &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let columns = close.apply_indicator(&SimpleMovingAverage::new(3), "sma").unwrap();
+
+        assert_eq!(columns.len(), 1);
+        let values: Vec<Option<f64>> = columns[0].f64().unwrap().into_iter().collect();
+        assert_eq!(values, vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn series_integer_column_is_cast() {
+        let close = Series::new("close", &[1i64, 2, 3, 4]);
+        let columns = close.apply_indicator(&SimpleMovingAverage::new(2), "sma").unwrap();
+
+        let values: Vec<Option<f64>> = columns[0].f64().unwrap().into_iter().collect();
+        assert_eq!(values, vec![None, Some(1.5), Some(2.5), Some(3.5)]);
+    }
+
+    #[test]
+    fn series_with_nulls_fails() {
+        let close = Series::new("close", &[Some(1.0), None, Some(3.0)]);
+        let result = close.apply_indicator(&SimpleMovingAverage::new(2), "sma");
+        assert!(matches!(result, Err(QmachinaError::NullValues { column }) if column == "close"));
+    }
+
+    #[test]
+    fn dataframe_multi_column_indicator() {
+        let mut df = DataFrame::new(vec![
+            Series::new("close", &[100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0]),
+        ]).unwrap();
+
+        df.with_indicator("close", &BollingerBands::new(5), "bb").unwrap();
+
+        assert_eq!(df.width(), 6);
+        assert_eq!(df.column("bb_upper").unwrap().null_count(), 4);
+        assert_eq!(df.column("bb_middle").unwrap().null_count(), 4);
+        assert_eq!(df.column("bb_lower").unwrap().null_count(), 4);
+        assert_eq!(df.column("bb_percent_b").unwrap().null_count(), 4);
+        assert_eq!(df.column("bb_bandwidth").unwrap().null_count(), 4);
+    }
+
+    #[test]
+    fn dataframe_missing_column_fails() {
+        let mut df = DataFrame::new(vec![Series::new("close", &[1.0, 2.0])]).unwrap();
+        assert!(df.with_indicator("open", &SimpleMovingAverage::new(2), "sma").is_err());
+    }
+
+    #[test]
+    fn dataframe_loss() {
+        let df = DataFrame::new(vec![
+            Series::new("prediction", &[1.0, 2.0, 3.0]),
+            Series::new("target", &[2.0, 2.0, 5.0]),
+        ]).unwrap();
+
+        let loss = df.compute_loss(&MeanAbsoluteErrorLossFunction, "prediction", "target").unwrap();
+        assert_eq!(loss, 1.0);
+    }
+
+    #[test]
+    fn dataframe_macd_columns() {
+        let mut df = DataFrame::new(vec![
+            Series::new("close", &[10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0]),
+        ]).unwrap();
+
+        df.with_indicator("close", &MACD::new(6, 3, 4), "macd").unwrap();
+
+        assert_eq!(df.width(), 4);
+        assert_eq!(df.column("macd").unwrap().null_count(), 8);
+        assert_eq!(df.column("macd_signal").unwrap().null_count(), 8);
+        assert_eq!(df.column("macd_histogram").unwrap().null_count(), 8);
+    }
+
+    #[test]
+    fn multi_column_values_match_the_indicator() {
+        let data = [100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0];
+        let columns = Series::new("close", &data).apply_indicator(&BollingerBands::new(3), "bb").unwrap();
+        let expected = BollingerBands::new(3).compute_series(&data).unwrap();
+
+        for (index, column) in columns.iter().enumerate() {
+            let values: Vec<Option<f64>> = column.f64().unwrap().into_iter().collect();
+            let expected: Vec<Option<f64>> = expected.iter()
+                .map(|output| output.as_ref().map(|output| output.column_values()[index]))
+                .collect();
+            assert_eq!(values, expected, "Column {} should match the indicator", column.name());
+        }
+    }
+}
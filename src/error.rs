@@ -0,0 +1,165 @@
+//! This module contains the error type shared by every fallible API of the crate.
+//!
+//! Instead of opaque messages, failures are reported as `QmachinaError` variants carrying the
+//! details of what went wrong, so callers can tell, for example, a warming-up indicator apart
+//! from invalid input data:
+//!
+//! ```
+//! use qmachina::QmachinaError;
+//! use qmachina::technical_analysis::Indicator;
+//! use qmachina::technical_analysis::sma::SimpleMovingAverage;
+//!
+//! let sma: SimpleMovingAverage = SimpleMovingAverage::new(5);
+//!
+//! match sma.compute(&vec![1.0, 2.0]) {
+//!     Err(QmachinaError::InsufficientData { required, got }) => assert_eq!((required, got), (5, 2)),
+//!     other => panic!("unexpected result: {:?}", other),
+//! }
+//! ```
+use thiserror::Error;
+
+/// The errors returned by activations, losses, indicators, layers and optimizers.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum QmachinaError {
+    /// The input is too short for the computation, such as a series shorter than an indicator's
+    /// period.
+    #[error("Insufficient data: {required} values are required, got {got}.")]
+    InsufficientData { required: usize, got: usize },
+
+    /// Two inputs that must be paired element by element have different lengths.
+    #[error("Length mismatch: expected {expected} values, got {got}.")]
+    LengthMismatch { expected: usize, got: usize },
+
+    /// Two tensors have shapes that cannot be combined, such as matrices whose inner dimensions
+    /// differ in a product, or shapes that do not broadcast together.
+    #[error("Shape mismatch: {left:?} and {right:?} are incompatible.")]
+    ShapeMismatch { left: Vec<usize>, right: Vec<usize> },
+
+    /// The input is empty.
+    #[error("The input is empty.")]
+    EmptyInput,
+
+    /// The input contains an invalid value at `index`, such as NaN, infinity or a bar whose high is
+    /// below its low.
+    #[error("Invalid value encountered at index {index}.")]
+    InvalidValue { index: usize },
+
+    /// A prediction that must be a probability is outside `[0, 1]` at `index`.
+    #[error("Invalid probability at index {index}, predictions must be between 0 and 1.")]
+    InvalidProbability { index: usize },
+
+    /// A logarithm in the loss is undefined at `index`, such as a predicted probability of 0
+    /// for a positive target.
+    #[error("Undefined logarithm at index {index}.")]
+    UndefinedLogarithm { index: usize },
+
+    /// A parameter is outside of its valid domain.
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(&'static str),
+
+    /// The inputs are valid but the result is mathematically undefined, such as a volume-weighted
+    /// average without traded volume.
+    #[error("Undefined result: {0}")]
+    UndefinedResult(&'static str),
+
+    /// The operation is not valid in the current state, such as a backward pass without a
+    /// forward pass.
+    #[error("Invalid state: {0}")]
+    InvalidState(&'static str),
+
+    /// A column of a `polars::DataFrame` contains null values.
+    #[cfg(feature = "polars")]
+    #[error("Column '{column}' contains null values.")]
+    NullValues { column: String },
+
+    /// An error reported by `polars`.
+    #[cfg(feature = "polars")]
+    #[error(transparent)]
+    Polars(#[from] polars::prelude::PolarsError),
+}
+
+impl QmachinaError {
+    /// Shifts the index reported by an error raised on a slice of a larger input, such as one
+    /// sample of a batch, so that it points into the whole input.
+    pub(crate) fn offset(self, offset: usize) -> Self {
+        match self {
+            QmachinaError::InvalidValue { index } => QmachinaError::InvalidValue { index: index + offset },
+            QmachinaError::InvalidProbability { index } => QmachinaError::InvalidProbability { index: index + offset },
+            QmachinaError::UndefinedLogarithm { index } => QmachinaError::UndefinedLogarithm { index: index + offset },
+            other => other
+        }
+    }
+}
+
+/// A `Result` defaulting its error to `QmachinaError`.
+pub type Result<T, E = QmachinaError> = std::result::Result<T, E>;
+
+/// Checks that every value is finite.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InvalidValue` with the index of the first NaN or infinite value.
+pub(crate) fn ensure_finite<F: num_traits::Float>(data: &[F]) -> Result<()> {
+    match data.iter().position(|value| !value.is_finite()) {
+        Some(index) => Err(QmachinaError::InvalidValue { index }),
+        None => Ok(())
+    }
+}
+
+/// Checks that at least `required` values are available.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InsufficientData` if `got` is less than `required`.
+pub(crate) fn ensure_sufficient(required: usize, got: usize) -> Result<()> {
+    if got < required {
+        return Err(QmachinaError::InsufficientData { required, got });
+    }
+
+    Ok(())
+}
+
+/// Checks that two inputs paired element by element have the same length.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::LengthMismatch` if `got` differs from `expected`.
+pub(crate) fn ensure_same_length(expected: usize, got: usize) -> Result<()> {
+    if expected != got {
+        return Err(QmachinaError::LengthMismatch { expected, got });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_finite_reports_first_invalid_index() {
+        assert!(ensure_finite(&[1.0, 2.0]).is_ok());
+        assert!(matches!(ensure_finite(&[1.0, f64::NAN, f64::INFINITY]), Err(QmachinaError::InvalidValue { index: 1 })));
+    }
+
+    #[test]
+    fn ensure_sufficient_and_same_length() {
+        assert!(ensure_sufficient(3, 3).is_ok());
+        assert!(matches!(ensure_sufficient(3, 2), Err(QmachinaError::InsufficientData { required: 3, got: 2 })));
+        assert!(ensure_same_length(2, 2).is_ok());
+        assert!(matches!(ensure_same_length(2, 3), Err(QmachinaError::LengthMismatch { expected: 2, got: 3 })));
+    }
+
+    #[test]
+    fn offset_shifts_indices_only() {
+        assert!(matches!(QmachinaError::InvalidProbability { index: 1 }.offset(3), QmachinaError::InvalidProbability { index: 4 }));
+        assert!(matches!(QmachinaError::LengthMismatch { expected: 2, got: 1 }.offset(3), QmachinaError::LengthMismatch { expected: 2, got: 1 }));
+    }
+
+    #[test]
+    fn error_messages_include_details() {
+        let error = QmachinaError::InsufficientData { required: 5, got: 2 };
+        assert_eq!(error.to_string(), "Insufficient data: 5 values are required, got 2.");
+    }
+}
@@ -1,3 +1,17 @@
+mod numeric;
+
+pub use error::{QmachinaError, Result};
+pub use numeric::Float;
+
 pub mod activation;
+pub mod autograd;
+pub mod error;
 pub mod loss;
-pub mod technical_analysis;
\ No newline at end of file
+pub mod metrics;
+pub mod nn;
+pub mod optim;
+pub mod technical_analysis;
+pub mod tensor;
+
+#[cfg(feature = "polars")]
+pub mod dataframe;
@@ -1,15 +1,15 @@
-use std::ops::Div;
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
 
-use super::LossFunction;
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, Float};
+use super::{map_rows, mean, BatchLossFunction, DifferentiableLoss, ElementwiseLoss, LossFunction};
 
 /// Represents the Binary Cross-Entropy (BCE) loss function for binary classification models.
 ///
 /// Binary Cross-Entropy is a common loss function used in binary classification problems.
 /// It measures the performance of a classification model whose output is a probability value between 0 and 1.
 ///
-/// This struct implements the `LossFunction<f64>` trait, enabling its use in machine learning models
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`, enabling its use in machine learning models
 /// for binary classification tasks.
 ///
 /// # Mathematical Background
@@ -23,6 +23,10 @@ use super::LossFunction;
 ///
 /// where `n` is the number of samples, `y_i` is the ith actual target value, and `p_i` is the ith predicted probability.
 ///
+/// Clipping, label smoothing and raw scores (logits) are available through
+/// `BinaryCrossEntropyConfig`, built with `with_logits`, `with_epsilon` or
+/// `with_label_smoothing`.
+///
 /// # Usage
 ///
 /// `BinaryCrossEntropyLossFunction` is used when the outputs of a model are probabilities, 
@@ -43,10 +47,241 @@ use super::LossFunction;
 /// // 'loss' now contains the binary cross-entropy value
 /// ```
 ///
-/// Note: It's crucial that the predictions are probabilities (i.e., values between 0 and 1).
+/// With logits, extreme scores stay finite:
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::bce::BinaryCrossEntropyLossFunction;
+///
+/// let bce_loss = BinaryCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.1);
+/// let logits: Arc<[f64]> = Arc::new([100.0, -100.0]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0]);
+/// assert!(bce_loss.compute(logits, targets).unwrap().is_finite());
+/// ```
+///
+/// Note: Without `with_logits`, it's crucial that the predictions are probabilities (i.e., values between 0 and 1).
 pub struct BinaryCrossEntropyLossFunction;
 
-impl LossFunction<f64> for BinaryCrossEntropyLossFunction {
+impl BinaryCrossEntropyLossFunction {
+    /// Returns a `BinaryCrossEntropyConfig` over raw scores (logits) or probabilities.
+    pub fn with_logits(self, from_logits: bool) -> BinaryCrossEntropyConfig {
+        BinaryCrossEntropyConfig::new().with_logits(from_logits)
+    }
+
+    /// Returns a `BinaryCrossEntropyConfig` clipping the predicted probabilities to
+    /// `[epsilon, 1 - epsilon]`.
+    pub fn with_epsilon(self, epsilon: f64) -> BinaryCrossEntropyConfig {
+        BinaryCrossEntropyConfig::new().with_epsilon(epsilon)
+    }
+
+    /// Returns a `BinaryCrossEntropyConfig` smoothing the targets towards 1/2.
+    pub fn with_label_smoothing(self, smoothing: f64) -> BinaryCrossEntropyConfig {
+        BinaryCrossEntropyConfig::new().with_label_smoothing(smoothing)
+    }
+}
+
+/// Represents the Binary Cross-Entropy loss with clipping, label smoothing or raw scores.
+///
+/// Without options, it computes the same loss as `BinaryCrossEntropyLossFunction`.
+///
+/// # Options
+///
+/// * `with_logits` - The predictions are raw scores `z`, and the loss is the BCE of `sigmoid(z)`,
+///   computed as `max(z, 0) - z * y + log(1 + e^(-|z|))`. This never takes the logarithm of 0,
+///   however large the scores, and its gradient is simply `sigmoid(z) - y`.
+/// * `with_epsilon` - Probabilities are clipped to `[epsilon, 1 - epsilon]`, so that predictions
+///   of exactly 0 or 1 give a large but finite loss instead of an error.
+/// * `with_label_smoothing` - The targets are smoothed towards 1/2, `y * (1 - s) + s / 2`, which
+///   discourages over-confident predictions.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::bce::BinaryCrossEntropyConfig;
+///
+/// let bce_loss = BinaryCrossEntropyConfig::new().with_epsilon(1e-7);
+/// let predictions: Arc<[f64]> = Arc::new([1.0, 0.0]);
+/// let targets: Arc<[f64]> = Arc::new([0.0, 0.0]);
+/// assert!(bce_loss.compute(predictions, targets).unwrap().is_finite());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BinaryCrossEntropyConfig {
+    epsilon: Option<f64>,
+    label_smoothing: f64,
+    from_logits: bool
+}
+
+impl BinaryCrossEntropyConfig {
+    /// Constructs a new `BinaryCrossEntropyConfig` over probabilities, without clipping or label
+    /// smoothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the predictions as raw scores (logits) rather than probabilities.
+    ///
+    /// # Parameters
+    ///
+    /// * `from_logits` - Whether the predictions are logits.
+    pub fn with_logits(self, from_logits: bool) -> Self {
+        Self { from_logits, ..self }
+    }
+
+    /// Clips the predicted probabilities to `[epsilon, 1 - epsilon]`. The gradient is zero where
+    /// a prediction is clipped. This option has no effect on logits.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The clipping margin, between 0 and 1/2 (exclusive), such as `1e-7`. Other
+    ///   values are reported as `QmachinaError::InvalidParameter` when the loss is computed.
+    pub fn with_epsilon(self, epsilon: f64) -> Self {
+        Self { epsilon: Some(epsilon), ..self }
+    }
+
+    /// Smooths the targets towards 1/2, `y * (1 - smoothing) + smoothing / 2`.
+    ///
+    /// # Parameters
+    ///
+    /// * `smoothing` - The smoothing factor, between 0 (inclusive) and 1 (exclusive). Other
+    ///   values are reported as `QmachinaError::InvalidParameter` when the loss is computed.
+    pub fn with_label_smoothing(self, smoothing: f64) -> Self {
+        Self { label_smoothing: smoothing, ..self }
+    }
+
+    /// Returns the clipping margin of the probabilities, if any.
+    pub fn epsilon(&self) -> Option<f64> {
+        self.epsilon
+    }
+
+    /// Returns the label smoothing factor.
+    pub fn label_smoothing(&self) -> f64 {
+        self.label_smoothing
+    }
+
+    /// Returns whether the predictions are raw scores (logits) rather than probabilities.
+    pub fn is_from_logits(&self) -> bool {
+        self.from_logits
+    }
+
+    /// Checks the options, returning the clipping bounds of the probabilities, if any.
+    fn bounds<F: Float>(&self) -> Result<Option<(F, F)>> {
+        if !(0.0..1.0).contains(&self.label_smoothing) {
+            return Err(QmachinaError::InvalidParameter("The label smoothing must be in [0, 1)."));
+        }
+
+        match self.epsilon {
+            Some(epsilon) if !(epsilon > 0.0 && epsilon < 0.5) => {
+                Err(QmachinaError::InvalidParameter("The epsilon must be in (0, 0.5)."))
+            }
+            Some(epsilon) => Ok(Some((constant(epsilon), constant(1.0 - epsilon)))),
+            None => Ok(None)
+        }
+    }
+
+    /// Returns the smoothed target.
+    fn smooth<F: Float>(&self, target: F) -> F {
+        let smoothing = constant::<F>(self.label_smoothing);
+        target * (F::one() - smoothing) + smoothing / constant(2.0)
+    }
+
+    /// Computes the loss term of each prediction.
+    fn terms<F: Float>(&self, predictions: &[F], targets: &[F]) -> Result<Vec<F>> {
+        let bounds = self.bounds::<F>()?;
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        if self.from_logits {
+            ensure_finite(predictions)?;
+
+            return Ok(predictions.iter()
+                .zip(targets)
+                .map(|(&z, &t)| z.max(F::zero()) - z * self.smooth(t) + (-z.abs()).exp().ln_1p())
+                .collect());
+        }
+
+        predictions.iter()
+            .zip(targets)
+            .enumerate()
+            .map(|(index, (&p, &t))| {
+                let t = self.smooth(t);
+                if !(F::zero()..=F::one()).contains(&p) {
+                    return Err(QmachinaError::InvalidProbability { index });
+                }
+
+                let p = match bounds {
+                    Some((lower, upper)) => p.max(lower).min(upper),
+                    None => p
+                };
+                if p == F::zero() {
+                    if t == F::zero() {
+                        Ok(F::zero())  // log(1 - 0) = 0, so this term contributes 0 to the sum
+                    } else {
+                        Err(QmachinaError::UndefinedLogarithm { index })
+                    }
+                } else if p == F::one() {
+                    if t == F::one() {
+                        Ok(F::zero())  // log(1) = 0, so this term contributes 0 to the sum
+                    } else {
+                        Err(QmachinaError::UndefinedLogarithm { index })
+                    }
+                } else {
+                    Ok(-(t * p.ln() + (F::one() - t) * (F::one() - p).ln()))
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the derivative of each loss term with respect to its prediction.
+    fn term_gradients<F: Float>(&self, predictions: &[F], targets: &[F]) -> Result<Vec<F>> {
+        let bounds = self.bounds::<F>()?;
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        if self.from_logits {
+            ensure_finite(predictions)?;
+
+            return Ok(predictions.iter()
+                .zip(targets)
+                .map(|(&z, &t)| sigmoid(z) - self.smooth(t))
+                .collect());
+        }
+
+        predictions.iter()
+            .zip(targets)
+            .enumerate()
+            .map(|(index, (&p, &t))| {
+                let t = self.smooth(t);
+                if !(F::zero()..=F::one()).contains(&p) {
+                    return Err(QmachinaError::InvalidProbability { index });
+                }
+                if let Some((lower, upper)) = bounds {
+                    if p < lower || p > upper {
+                        return Ok(F::zero());
+                    }
+                } else if (p == F::zero() && t != F::zero()) || (p == F::one() && t != F::one()) {
+                    return Err(QmachinaError::UndefinedLogarithm { index });
+                }
+
+                let positive = if t == F::zero() { F::zero() } else { -t / p };
+                let negative = if t == F::one() { F::zero() } else { (F::one() - t) / (F::one() - p) };
+                Ok(positive + negative)
+            })
+            .collect()
+    }
+}
+
+/// Computes the logistic sigmoid without overflowing for large negative inputs.
+fn sigmoid<F: Float>(z: F) -> F {
+    if z >= F::zero() {
+        F::one() / (F::one() + (-z).exp())
+    } else {
+        let exp = z.exp();
+        exp / (F::one() + exp)
+    }
+}
+
+impl<F: Float> LossFunction<F> for BinaryCrossEntropyConfig {
     /// Computes the Binary Cross-Entropy (BCE) loss between predictions and targets.
     ///
     /// Binary Cross-Entropy loss is a widely-used loss function for binary classification tasks.
@@ -55,62 +290,151 @@ impl LossFunction<f64> for BinaryCrossEntropyLossFunction {
     ///
     /// # Parameters
     ///
-    /// * `predictions` - An `Arc<[f64]>` containing the predicted probabilities from the model.
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities from the model.
     ///   Each element should be a probability value between 0 and 1, indicating the likelihood
-    ///   of the positive class.
-    /// * `targets` - An `Arc<[f64]>` containing the actual binary targets (0 or 1).
+    ///   of the positive class, or a raw score for a loss configured with `with_logits`.
+    /// * `targets` - An `Arc<[F]>` containing the actual binary targets (0 or 1).
     ///
     /// # Returns
     ///
-    /// A `Result<f64, anyhow::Error>`, where:
+    /// A `Result<F, QmachinaError>`, where:
     ///   - The `Ok` variant contains the computed BCE loss. The loss is calculated as the
     ///     average of the BCE for each individual prediction-target pair.
     ///   - The `Err` variant encapsulates errors that occur during computation, such as:
     ///     - Mismatched lengths of the predictions and targets arrays, indicating that each
     ///       prediction does not correspond to a target.
-    ///     - Predictions not being valid probabilities (values not in the range [0, 1]).
-    ///     - Undefined logarithmic calculations when probabilities are exactly 0 or 1.
+    ///     - Predictions not being valid probabilities (values not in the range [0, 1]), or
+    ///       logits being NaN or infinite.
+    ///     - Undefined logarithmic calculations when probabilities are exactly 0 or 1 without
+    ///       clipping.
+    ///     - An epsilon or a label smoothing factor out of range.
     ///
     /// # Notes
     ///
     /// The computation carefully handles edge cases for probabilities (0 and 1) to avoid
     /// NaN values from undefined logarithmic operations. It ensures that the loss calculation
     /// is robust and reliable across various inputs.
-    fn compute(&self, predictions: Arc<[f64]>, targets: Arc<[f64]>) -> Result<f64> {
-        if predictions.len() != targets.len() {
-            return Err(anyhow!("Predictions and targets must have the same length"));
-        }
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
 
-        let bce = predictions.iter()
-            .zip(targets.iter())
-            .try_fold(0.0, |acc, (&p, &t)| {
-                if !(0.0..=1.0).contains(&p) {
-                    Err(anyhow!("Predictions must be probabilities (between 0 and 1)"))
-                } else if p == 0.0 {
-                    if t == 0.0 {
-                        Ok(acc)  // log(1 - 0) = 0, so this term contributes 0 to the sum
-                    } else {
-                        Err(anyhow!("Undefined logarithm for p = 0 with target = 1"))
-                    }
-                } else if p == 1.0 {
-                    if t == 1.0 {
-                        Ok(acc)  // log(1) = 0, so this term contributes 0 to the sum
-                    } else {
-                        Err(anyhow!("Undefined logarithm for p = 1 with target = 0"))
-                    }
-                } else {
-                    Ok(acc - (t * p.ln() + (1.0 - t) * (1.0 - p).ln()))
-                }
-            })?
-            .div(predictions.len() as f64);
+impl<F: Float> ElementwiseLoss<F> for BinaryCrossEntropyConfig {
+    /// Computes the binary cross-entropy `-(t * log(p) + (1 - t) * log(1 - p))` of each
+    /// predicted probability.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        self.terms(&predictions, &targets)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for BinaryCrossEntropyConfig {
+    /// Computes the gradient of the BCE with respect to each prediction,
+    /// `(-t / p + (1 - t) / (1 - p)) / n` for probabilities and `(sigmoid(z) - t) / n` for logits.
+    ///
+    /// Terms whose coefficient is zero are skipped, so that a prediction of exactly 0 with a
+    /// target of 0, or of exactly 1 with a target of 1, has a finite gradient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`: mismatched lengths, predictions
+    /// outside `[0, 1]`, or a prediction of 0 or 1 with a target making the loss undefined.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        let n = count::<F>(predictions.len());
+        Ok(self.term_gradients(&predictions, &targets)?.into_iter().map(|g| g / n).collect())
+    }
+}
+
+impl<F: Float> BatchLossFunction<F> for BinaryCrossEntropyConfig {
+    /// Computes the BCE of a batch of multi-label samples. The loss of a sample is the average
+    /// over its labels, and the loss of the batch the average over its samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`, and if the shape of the batch
+    /// is invalid.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F> {
+        let losses = map_rows(predictions, targets, |p, t| Ok(mean(&self.terms(p, t)?)))?;
+        Ok(mean(&losses))
+    }
+
+    /// Computes the gradient of `compute_batch` with respect to each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>> {
+        let samples = count::<F>(predictions.len());
+        map_rows(predictions, targets, |p, t| {
+            let n = samples * count::<F>(p.len());
+            Ok(self.term_gradients(p, t)?.into_iter().map(|g| g / n).collect())
+        })
+    }
+}
 
-        Ok(bce)
+impl<F: Float> LossFunction<F> for BinaryCrossEntropyLossFunction {
+    /// Computes the BCE of predicted probabilities, averaged over the predictions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lengths differ, if a prediction is not a probability, or if a
+    /// prediction of exactly 0 or 1 contradicts its target.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        BinaryCrossEntropyConfig::new().compute(predictions, targets)
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for BinaryCrossEntropyLossFunction {
+    /// Computes the binary cross-entropy of each predicted probability.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        BinaryCrossEntropyConfig::new().elementwise(predictions, targets)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for BinaryCrossEntropyLossFunction {
+    /// Computes the gradient of the BCE with respect to each prediction,
+    /// `(-t / p + (1 - t) / (1 - p)) / n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        BinaryCrossEntropyConfig::new().gradient(predictions, targets)
+    }
+}
+
+impl<F: Float> BatchLossFunction<F> for BinaryCrossEntropyLossFunction {
+    /// Computes the BCE of a batch of multi-label samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`, and if the shape of the batch
+    /// is invalid.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F> {
+        BinaryCrossEntropyConfig::new().compute_batch(predictions, targets)
+    }
+
+    /// Computes the gradient of `compute_batch` with respect to each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>> {
+        BinaryCrossEntropyConfig::new().gradient_batch(predictions, targets)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::loss::numerical_gradient;
     use std::sync::Arc;
 
     /// Test BCE with valid probabilities.
@@ -132,7 +456,7 @@ mod tests {
         let predictions = Arc::new([1.5, 0.3, 0.9]); // Invalid probability (>1.0)
         let targets = Arc::new([1.0, 0.0, 1.0]);
         let result = bce_loss.compute(predictions, targets);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(QmachinaError::InvalidProbability { index: 0 })));
     }
 
     /// Test BCE with predictions and targets of different lengths.
@@ -143,7 +467,7 @@ mod tests {
         let predictions = Arc::new([0.7, 0.3]);
         let targets = Arc::new([1.0, 0.0, 1.0]);
         let result = bce_loss.compute(predictions, targets);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(QmachinaError::LengthMismatch { expected: 2, got: 3 })));
     }
 
     /// Test BCE with perfect prediction.
@@ -153,7 +477,7 @@ mod tests {
         let bce_loss = BinaryCrossEntropyLossFunction;
         let predictions = Arc::new([1.0, 0.0, 1.0]);
         let targets = Arc::new([1.0, 0.0, 1.0]);
-        let loss = bce_loss.compute(predictions, targets).unwrap();
+        let loss: f64 = bce_loss.compute(predictions, targets).unwrap();
         assert!(loss.abs() < 1e-10); // loss should be very close to zero
     }
 
@@ -165,7 +489,158 @@ mod tests {
         let predictions = Arc::new([0.8, 0.2, 0.6]);
         let targets = Arc::new([1.0, 0.0, 0.0]);
         let loss = bce_loss.compute(predictions, targets).unwrap();
-        let expected_loss = (-((1.0 * 0.8_f64.ln()) + (1.0 - 0.0) * (1.0_f64 - 0.2_f64).ln() + (1.0 - 0.0) * (1.0_f64 - 0.6_f64).ln()) / 3.0) as f64;
+        let expected_loss = -((1.0 * 0.8_f64.ln()) + (1.0 - 0.0) * (1.0_f64 - 0.2_f64).ln() + (1.0 - 0.0) * (1.0_f64 - 0.6_f64).ln()) / 3.0;
         assert_eq!(loss, expected_loss);
     }
+
+    /// Test the BCE gradient against finite differences.
+    #[test]
+    fn bce_gradient_matches_finite_differences() {
+        let bce_loss = BinaryCrossEntropyLossFunction;
+        let predictions = [0.8, 0.2, 0.6, 0.35];
+        let targets = [1.0, 0.0, 0.0, 0.7];
+        let gradient = bce_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&bce_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test the BCE gradient on saturated predictions.
+    /// Consistent targets give a finite gradient, inconsistent ones an error.
+    #[test]
+    fn bce_gradient_saturated_predictions() {
+        let bce_loss = BinaryCrossEntropyLossFunction;
+        let gradient = bce_loss.gradient(Arc::new([1.0, 0.0]), Arc::new([1.0, 0.0])).unwrap();
+        assert_eq!(gradient, vec![-0.5, 0.5]);
+
+        assert!(matches!(bce_loss.gradient(Arc::new([0.5, 0.0]), Arc::new([1.0, 1.0])), Err(QmachinaError::UndefinedLogarithm { index: 1 })));
+        assert!(matches!(bce_loss.gradient(Arc::new([1.0]), Arc::new([0.0])), Err(QmachinaError::UndefinedLogarithm { index: 0 })));
+        assert!(matches!(bce_loss.gradient(Arc::new([1.5]), Arc::new([1.0])), Err(QmachinaError::InvalidProbability { index: 0 })));
+    }
+
+    /// Test BCE with logits against the BCE of the sigmoid of the logits.
+    /// Expected result is the same loss.
+    #[test]
+    fn bce_from_logits_matches_sigmoid() {
+        let logits = [0.5, -1.2, 2.0, 0.0];
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 0.3, 1.0]);
+        let probabilities: Vec<f64> = logits.iter().map(|z: &f64| 1.0 / (1.0 + (-z).exp())).collect();
+
+        let expected = BinaryCrossEntropyLossFunction.compute(probabilities.into(), targets.clone()).unwrap();
+        let loss = BinaryCrossEntropyLossFunction.with_logits(true).compute(Arc::new(logits), targets).unwrap();
+        assert!((loss - expected).abs() < 1e-12);
+    }
+
+    /// Test BCE with extreme logits.
+    /// Expected result is a finite loss, linear in the wrong logit.
+    #[test]
+    fn bce_from_logits_extreme_values() {
+        let bce_loss = BinaryCrossEntropyLossFunction.with_logits(true);
+        let loss: f64 = bce_loss.compute(Arc::new([1000.0, -1000.0]), Arc::new([0.0, 0.0])).unwrap();
+        assert!((loss - 500.0).abs() < 1e-9);
+
+        let gradient = bce_loss.gradient(Arc::new([1000.0, -1000.0]), Arc::new([0.0, 0.0])).unwrap();
+        assert_eq!(gradient, vec![0.5, 0.0]);
+
+        assert!(matches!(bce_loss.compute(Arc::new([f64::NAN]), Arc::new([0.0])), Err(QmachinaError::InvalidValue { index: 0 })));
+    }
+
+    /// Test the gradient of BCE with logits and label smoothing against finite differences.
+    #[test]
+    fn bce_from_logits_gradient_matches_finite_differences() {
+        let bce_loss = BinaryCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.2);
+        let logits = [0.5, -1.2, 2.0, -0.1];
+        let targets = [1.0, 0.0, 1.0, 0.0];
+        let gradient = bce_loss.gradient(Arc::new(logits), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&bce_loss, &logits, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test BCE with clipped probabilities.
+    /// Expected result is a finite loss and a zero gradient where predictions are clipped.
+    #[test]
+    fn bce_epsilon_clipping() {
+        let bce_loss = BinaryCrossEntropyLossFunction.with_epsilon(1e-7);
+        let loss: f64 = bce_loss.compute(Arc::new([0.0, 1.0]), Arc::new([1.0, 0.0])).unwrap();
+        assert!((loss + (1e-7_f64).ln()).abs() < 1e-6);
+
+        let gradient = bce_loss.gradient(Arc::new([0.0, 0.5]), Arc::new([1.0, 1.0])).unwrap();
+        assert_eq!(gradient, vec![0.0, -1.0]);
+
+        let predictions = [0.8, 0.2, 0.6, 0.35];
+        let targets = [1.0, 0.0, 0.0, 1.0];
+        let smoothed = bce_loss.with_label_smoothing(0.1);
+        let gradient = smoothed.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&smoothed, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test BCE with label smoothing.
+    /// Expected result is the BCE against targets moved towards 1/2.
+    #[test]
+    fn bce_label_smoothing() {
+        let bce_loss = BinaryCrossEntropyLossFunction.with_label_smoothing(0.2);
+        let loss: f64 = bce_loss.compute(Arc::new([0.7]), Arc::new([1.0])).unwrap();
+        let expected = -(0.9 * 0.7_f64.ln() + 0.1 * 0.3_f64.ln());
+        assert!((loss - expected).abs() < 1e-12);
+    }
+
+    /// Test BCE with options out of range.
+    /// Expected result is an error.
+    #[test]
+    fn bce_invalid_options() {
+        let predictions: Arc<[f64]> = Arc::new([0.5]);
+        let targets: Arc<[f64]> = Arc::new([1.0]);
+
+        for bce_loss in [
+            BinaryCrossEntropyLossFunction.with_epsilon(0.0),
+            BinaryCrossEntropyLossFunction.with_epsilon(0.5),
+            BinaryCrossEntropyLossFunction.with_label_smoothing(1.0),
+            BinaryCrossEntropyLossFunction.with_logits(true).with_label_smoothing(-0.1),
+        ] {
+            assert!(matches!(bce_loss.compute(predictions.clone(), targets.clone()), Err(QmachinaError::InvalidParameter(_))));
+        }
+    }
+
+    /// Test BCE over a batch of multi-label samples.
+    /// Expected result is the average of the per-sample losses and gradients.
+    #[test]
+    fn bce_batch_averages_samples() {
+        let bce_loss = BinaryCrossEntropyLossFunction.with_logits(true);
+        let predictions = vec![vec![0.5, -1.0], vec![2.0, 0.1, -0.3]];
+        let targets = vec![vec![1.0, 0.0], vec![1.0, 1.0, 0.0]];
+
+        let first: f64 = bce_loss.compute(predictions[0].clone().into(), targets[0].clone().into()).unwrap();
+        let second: f64 = bce_loss.compute(predictions[1].clone().into(), targets[1].clone().into()).unwrap();
+        let loss = bce_loss.compute_batch(&predictions, &targets).unwrap();
+        assert!((loss - (first + second) / 2.0).abs() < 1e-12);
+
+        let gradient = bce_loss.gradient_batch(&predictions, &targets).unwrap();
+        for (row, (p, t)) in gradient.iter().zip(predictions.iter().zip(&targets)) {
+            let expected = bce_loss.gradient(p.clone().into(), t.clone().into()).unwrap();
+            for (g, e) in row.iter().zip(expected) {
+                assert!((g - e / 2.0).abs() < 1e-12);
+            }
+        }
+    }
+
+    /// Test BCE over a batch with invalid rows.
+    /// Expected result is an error reporting the index in the flattened batch.
+    #[test]
+    fn bce_batch_invalid_inputs() {
+        let bce_loss = BinaryCrossEntropyLossFunction;
+        let targets = vec![vec![1.0, 0.0], vec![1.0, 0.0]];
+
+        assert!(matches!(bce_loss.compute_batch(&[vec![0.5, 0.5], vec![0.5, 1.5]], &targets), Err(QmachinaError::InvalidProbability { index: 3 })));
+        assert!(matches!(bce_loss.compute_batch(&[vec![0.5, 0.5]], &targets), Err(QmachinaError::LengthMismatch { expected: 1, got: 2 })));
+        assert!(matches!(bce_loss.gradient_batch(&[] as &[Vec<f64>], &[]), Err(QmachinaError::EmptyInput)));
+    }
 }
@@ -1,7 +1,8 @@
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
 
-use super::LossFunction;
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, Float};
+use super::{map_rows, mean, sum, BatchLossFunction, DifferentiableLoss, ElementwiseLoss, LossFunction, Reduction};
 
 /// Represents the Categorical Cross-Entropy loss function for multi-class classification models.
 ///
@@ -23,7 +24,14 @@ use super::LossFunction;
 /// \]
 ///
 /// where `N` is the number of classes, `y_i` is the actual probability for class `i`, and `p_i` is the predicted 
-/// probability for class `i`. The values of `y_i` and `p_i` are elements of their respective `Arc<[f64]>` arrays.
+/// probability for class `i`. The values of `y_i` and `p_i` are elements of their respective `Arc<[F]>` arrays.
+///
+/// A predicted probability of 0 for a class with a positive target makes the loss infinite, and
+/// is reported as `QmachinaError::UndefinedLogarithm` unless the probabilities are clipped.
+///
+/// Clipping, label smoothing and raw scores (logits) are available through
+/// `CategoricalCrossEntropyConfig`, built with `with_logits`, `with_epsilon`,
+/// `with_label_smoothing` or `with_classes`.
 ///
 /// # Example Usage
 ///
@@ -39,21 +47,297 @@ use super::LossFunction;
 /// ```
 pub struct CategoricalCrossEntropyLossFunction;
 
-impl LossFunction<f64> for CategoricalCrossEntropyLossFunction {
+impl CategoricalCrossEntropyLossFunction {
+    /// Returns a `CategoricalCrossEntropyConfig` over raw scores (logits) or probabilities.
+    pub fn with_logits(self, from_logits: bool) -> CategoricalCrossEntropyConfig {
+        CategoricalCrossEntropyConfig::new().with_logits(from_logits)
+    }
+
+    /// Returns a `CategoricalCrossEntropyConfig` clipping the predicted probabilities to
+    /// `[epsilon, 1 - epsilon]`.
+    pub fn with_epsilon(self, epsilon: f64) -> CategoricalCrossEntropyConfig {
+        CategoricalCrossEntropyConfig::new().with_epsilon(epsilon)
+    }
+
+    /// Returns a `CategoricalCrossEntropyConfig` smoothing the targets towards the uniform
+    /// distribution.
+    pub fn with_label_smoothing(self, smoothing: f64) -> CategoricalCrossEntropyConfig {
+        CategoricalCrossEntropyConfig::new().with_label_smoothing(smoothing)
+    }
+
+    /// Returns a `CategoricalCrossEntropyConfig` reading the predictions and targets as samples
+    /// of `classes` values each.
+    pub fn with_classes(self, classes: usize) -> CategoricalCrossEntropyConfig {
+        CategoricalCrossEntropyConfig::new().with_classes(classes)
+    }
+}
+
+/// Represents the Categorical Cross-Entropy loss with clipping, label smoothing or raw scores.
+///
+/// Without options, it computes the same loss as `CategoricalCrossEntropyLossFunction`.
+///
+/// # Options
+///
+/// * `with_logits` - The predictions are raw scores `z`, and the loss is the CCE of
+///   `softmax(z)`, computed with the log-sum-exp trick as `y_i * (log(sum(e^z)) - z_i)`.
+/// * `with_epsilon` - Probabilities are clipped to `[epsilon, 1 - epsilon]`, so that a
+///   probability of 0 gives a large but finite loss instead of an error.
+/// * `with_label_smoothing` - The targets are smoothed towards the uniform distribution,
+///   `y * (1 - s) + s / N`, which discourages over-confident predictions.
+/// * `with_classes` - The predictions and targets hold several samples of `N` classes each,
+///   flattened one after the other.
+///
+/// Both label smoothing and logits work over the classes of each sample. Without
+/// `with_classes`, the predictions and targets given to `compute` are a single sample, and a
+/// batch goes through `compute_batch` or a flattened slice with `with_classes`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::cce::CategoricalCrossEntropyConfig;
+///
+/// let cce_loss = CategoricalCrossEntropyConfig::new().with_epsilon(1e-7);
+/// let predictions: Arc<[f64]> = Arc::new([0.0, 1.0, 0.0]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 0.0]);
+/// assert!(cce_loss.compute(predictions, targets).unwrap().is_finite());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CategoricalCrossEntropyConfig {
+    epsilon: Option<f64>,
+    label_smoothing: f64,
+    from_logits: bool,
+    classes: Option<usize>
+}
+
+impl CategoricalCrossEntropyConfig {
+    /// Constructs a new `CategoricalCrossEntropyConfig` over probabilities, without clipping or
+    /// label smoothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the predictions as raw scores (logits) rather than probabilities.
+    ///
+    /// # Parameters
+    ///
+    /// * `from_logits` - Whether the predictions are logits.
+    pub fn with_logits(self, from_logits: bool) -> Self {
+        Self { from_logits, ..self }
+    }
+
+    /// Clips the predicted probabilities to `[epsilon, 1 - epsilon]`. The gradient is zero where
+    /// a prediction is clipped. This option has no effect on logits.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The clipping margin, between 0 and 1/2 (exclusive), such as `1e-7`. Other
+    ///   values are reported as `QmachinaError::InvalidParameter` when the loss is computed.
+    pub fn with_epsilon(self, epsilon: f64) -> Self {
+        Self { epsilon: Some(epsilon), ..self }
+    }
+
+    /// Smooths the targets towards the uniform distribution, `y * (1 - smoothing) + smoothing / N`
+    /// for `N` classes.
+    ///
+    /// # Parameters
+    ///
+    /// * `smoothing` - The smoothing factor, between 0 (inclusive) and 1 (exclusive). Other
+    ///   values are reported as `QmachinaError::InvalidParameter` when the loss is computed.
+    pub fn with_label_smoothing(self, smoothing: f64) -> Self {
+        Self { label_smoothing: smoothing, ..self }
+    }
+
+    /// Reads the predictions and targets as samples of `classes` values each, flattened one
+    /// after the other, so that label smoothing and logits apply to each sample.
+    ///
+    /// # Parameters
+    ///
+    /// * `classes` - The number of classes of each sample. Zero, or slices that are not a whole
+    ///   number of samples, are reported as errors when the loss is computed. The rows given to
+    ///   `compute_batch` are already single samples and are not split further.
+    pub fn with_classes(self, classes: usize) -> Self {
+        Self { classes: Some(classes), ..self }
+    }
+
+    /// Returns the clipping margin of the probabilities, if any.
+    pub fn epsilon(&self) -> Option<f64> {
+        self.epsilon
+    }
+
+    /// Returns the label smoothing factor.
+    pub fn label_smoothing(&self) -> f64 {
+        self.label_smoothing
+    }
+
+    /// Returns whether the predictions are raw scores (logits) rather than probabilities.
+    pub fn is_from_logits(&self) -> bool {
+        self.from_logits
+    }
+
+    /// Returns the number of classes of each sample, if the slices hold several samples.
+    pub fn classes(&self) -> Option<usize> {
+        self.classes
+    }
+
+    /// Checks the options, returning the clipping bounds of the probabilities, if any.
+    fn bounds<F: Float>(&self) -> Result<Option<(F, F)>> {
+        if !(0.0..1.0).contains(&self.label_smoothing) {
+            return Err(QmachinaError::InvalidParameter("The label smoothing must be in [0, 1)."));
+        }
+
+        match self.epsilon {
+            Some(epsilon) if !(epsilon > 0.0 && epsilon < 0.5) => {
+                Err(QmachinaError::InvalidParameter("The epsilon must be in (0, 0.5)."))
+            }
+            Some(epsilon) => Ok(Some((constant(epsilon), constant(1.0 - epsilon)))),
+            None => Ok(None)
+        }
+    }
+
+    /// Applies `sample` to each sample of flattened predictions and targets, `classes` values
+    /// at a time or all of them without a number of classes, shifting the indices of the errors
+    /// so that they point into the whole slices.
+    fn map_samples<F: Float>(
+        &self,
+        predictions: &[F],
+        targets: &[F],
+        mut sample: impl FnMut(&[F], &[F]) -> Result<Vec<F>>
+    ) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+        let classes = match self.classes {
+            Some(0) => return Err(QmachinaError::InvalidParameter("The number of classes must be positive.")),
+            Some(classes) => {
+                ensure_same_length(predictions.len().next_multiple_of(classes), predictions.len())?;
+                classes
+            }
+            None => predictions.len().max(1)
+        };
+
+        let mut values = Vec::with_capacity(predictions.len());
+        for (index, (predictions, targets)) in predictions.chunks(classes).zip(targets.chunks(classes)).enumerate() {
+            values.extend(sample(predictions, targets).map_err(|error| error.offset(index * classes))?);
+        }
+        Ok(values)
+    }
+
+    /// Returns the targets smoothed towards the uniform distribution over their classes.
+    fn smooth<F: Float>(&self, targets: &[F]) -> Vec<F> {
+        let smoothing = constant::<F>(self.label_smoothing);
+        let uniform = smoothing / count(targets.len());
+        targets.iter().map(|&t| t * (F::one() - smoothing) + uniform).collect()
+    }
+
+    /// Computes the loss term of each class of a single sample.
+    fn terms<F: Float>(&self, predictions: &[F], targets: &[F]) -> Result<Vec<F>> {
+        let bounds = self.bounds::<F>()?;
+        ensure_same_length(predictions.len(), targets.len())?;
+        let targets = self.smooth(targets);
+
+        if self.from_logits {
+            let log_sum_exp = log_sum_exp(predictions)?;
+
+            return Ok(predictions.iter()
+                .zip(targets)
+                .map(|(&z, t)| t * (log_sum_exp - z))
+                .collect());
+        }
+
+        predictions.iter()
+            .zip(targets)
+            .enumerate()
+            .map(|(index, (&p, t))| {
+                if !(F::zero()..=F::one()).contains(&p) {
+                    return Err(QmachinaError::InvalidProbability { index });
+                }
+
+                let p = match bounds {
+                    Some((lower, upper)) => p.max(lower).min(upper),
+                    None => p
+                };
+                if t == F::zero() {
+                    Ok(F::zero())  // 0 * log(p) is taken as 0, even for p = 0
+                } else if p == F::zero() {
+                    Err(QmachinaError::UndefinedLogarithm { index })
+                } else {
+                    Ok(-t * p.ln())
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the derivative of the loss of a single sample with respect to each prediction.
+    fn term_gradients<F: Float>(&self, predictions: &[F], targets: &[F]) -> Result<Vec<F>> {
+        let bounds = self.bounds::<F>()?;
+        ensure_same_length(predictions.len(), targets.len())?;
+        let targets = self.smooth(targets);
+
+        if self.from_logits {
+            return logit_gradients(predictions, &targets);
+        }
+
+        predictions.iter()
+            .zip(targets)
+            .enumerate()
+            .map(|(index, (&p, t))| {
+                if !(F::zero()..=F::one()).contains(&p) {
+                    return Err(QmachinaError::InvalidProbability { index });
+                }
+                if let Some((lower, upper)) = bounds {
+                    if p < lower || p > upper {
+                        return Ok(F::zero());
+                    }
+                }
+
+                if t == F::zero() {
+                    Ok(F::zero())
+                } else if p == F::zero() {
+                    Err(QmachinaError::UndefinedLogarithm { index })
+                } else {
+                    Ok(-t / p)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Computes the derivative of `sum(t * (log(sum(e^z)) - z))` with respect to each logit,
+/// `softmax(z) * sum(t) - t`.
+fn logit_gradients<F: Float>(logits: &[F], targets: &[F]) -> Result<Vec<F>> {
+    let log_sum_exp = log_sum_exp(logits)?;
+    let target_sum = sum(targets);
+
+    Ok(logits.iter()
+        .zip(targets)
+        .map(|(&z, &t)| (z - log_sum_exp).exp() * target_sum - t)
+        .collect())
+}
+
+/// Checks the logits and returns their log-sum-exp.
+fn log_sum_exp<F: Float>(logits: &[F]) -> Result<F> {
+    ensure_finite(logits)?;
+
+    let max = logits.iter().fold(F::neg_infinity(), |a, &b| a.max(b));
+    Ok(max + logits.iter().fold(F::zero(), |sum, &z| sum + (z - max).exp()).ln())
+}
+
+impl<F: Float> LossFunction<F> for CategoricalCrossEntropyConfig {
     /// Computes the Categorical Cross-Entropy loss.
     ///
     /// This method calculates the loss by comparing each predicted probability distribution
-    /// against the actual target distribution, both represented as `Arc<[f64]>`.
+    /// against the actual target distribution, both represented as `Arc<[F]>`.
     ///
     /// # Parameters
     ///
-    /// * `predictions` - An `Arc<[f64]>` representing the predicted probabilities for each class.
-    ///   It's expected that the sum of probabilities in this distribution equals 1.
-    /// * `targets` - An `Arc<[f64]>` representing the actual target distribution, typically in a one-hot encoded format.
+    /// * `predictions` - An `Arc<[F]>` representing the predicted probabilities for each class.
+    ///   It's expected that the sum of probabilities in this distribution equals 1. For a loss
+    ///   configured with `with_logits`, the raw scores of each class.
+    /// * `targets` - An `Arc<[F]>` representing the actual target distribution, typically in a one-hot encoded format.
     ///
     /// # Returns
     ///
-    /// A `Result<f64, anyhow::Error>`, where:
+    /// A `Result<F, QmachinaError>`, where:
     ///   - The `Ok` variant contains the computed Categorical Cross-Entropy loss, averaged over all classes.
     ///   - The `Err` variant encapsulates errors that occur during computation, such as mismatched lengths or invalid probabilities.
     ///
@@ -61,32 +345,284 @@ impl LossFunction<f64> for CategoricalCrossEntropyLossFunction {
     ///
     /// An error is returned if:
     ///   - The lengths of predictions and targets arrays are different.
-    ///   - The predictions contain values outside the range [0, 1].
-    fn compute(&self, predictions: Arc<[f64]>, targets: Arc<[f64]>) -> Result<f64> {
-        if predictions.len() != targets.len() {
-            return Err(anyhow!("Predictions and targets arrays must have the same length"));
+    ///   - The predictions contain values outside the range [0, 1], or logits are NaN or infinite.
+    ///   - A prediction is 0 while its target is not, without clipping.
+    ///   - The epsilon or the label smoothing factor is out of range.
+    ///   - The number of classes is zero or does not divide the length of the slices.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for CategoricalCrossEntropyConfig {
+    /// Computes the term `-t * log(p)` of each class, or `t * (log(sum(e^z)) - z)` for logits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        self.map_samples(&predictions, &targets, |predictions, targets| self.terms(predictions, targets))
+    }
+
+    /// Computes the gradient of `compute_with` with respect to each prediction. Over logits,
+    /// every term depends on all the logits through the softmax, and the weights scale the
+    /// targets of the classes: `softmax(z) * sum(w * t) - w * t`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_with`, and for
+    /// `Reduction::None`, whose value is not a single loss.
+    fn gradient_with(
+        &self,
+        predictions: Arc<[F]>,
+        targets: Arc<[F]>,
+        weights: Option<Arc<[F]>>,
+        reduction: Reduction
+    ) -> Result<Vec<F>> where Self: DifferentiableLoss<F> {
+        let scales = reduction.scales(predictions.len(), weights.as_deref())?;
+
+        if self.from_logits {
+            let mut scales = scales.into_iter();
+            return self.map_samples(&predictions, &targets, |logits, targets| {
+                self.bounds::<F>()?;
+                let weighted: Vec<F> = self.smooth(targets).into_iter().zip(scales.by_ref()).map(|(t, scale)| t * scale).collect();
+                logit_gradients(logits, &weighted)
+            });
         }
 
-        // Categorical Cross-Entropy computation
-        let loss = predictions.iter()
-            .zip(targets.iter())
-            .try_fold(0.0, |acc, (&p, &t)| {
-                if !(0.0..=1.0).contains(&p) {
-                    Err(anyhow!("Predictions must be probabilities (between 0 and 1)"))
-                } else {
-                    // Avoiding computation for log(0), which is undefined
-                    let log_p = if p == 0.0 { 0.0 } else { p.ln() };
-                    Ok(acc - t * log_p)
-                }
-            })?;
+        Ok(self.map_samples(&predictions, &targets, |predictions, targets| self.term_gradients(predictions, targets))?
+            .into_iter()
+            .zip(scales)
+            .map(|(gradient, scale)| gradient * scale)
+            .collect())
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for CategoricalCrossEntropyConfig {
+    /// Computes the gradient of the Categorical Cross-Entropy with respect to each prediction,
+    /// `-t / (p * n)` for probabilities and `(softmax(z) * sum(t) - t) / n` for logits.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        let n = count::<F>(predictions.len());
+        Ok(self.map_samples(&predictions, &targets, |predictions, targets| self.term_gradients(predictions, targets))?
+            .into_iter()
+            .map(|g| g / n)
+            .collect())
+    }
+}
+
+impl<F: Float> BatchLossFunction<F> for CategoricalCrossEntropyConfig {
+    /// Computes the CCE of a batch of samples, each row holding the distribution over the
+    /// classes of one sample. The loss of a sample is the sum of its terms, `-sum(t * log(p))`,
+    /// and the loss of the batch the average over its samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`, and if the shape of the batch
+    /// is invalid.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F> {
+        let losses = map_rows(predictions, targets, |p, t| Ok(sum(&self.terms(p, t)?)))?;
+        Ok(mean(&losses))
+    }
+
+    /// Computes the gradient of `compute_batch` with respect to each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>> {
+        let samples = count::<F>(predictions.len());
+        map_rows(predictions, targets, |p, t| {
+            Ok(self.term_gradients(p, t)?.into_iter().map(|g| g / samples).collect())
+        })
+    }
+}
+
+impl<F: Float> LossFunction<F> for CategoricalCrossEntropyLossFunction {
+    /// Computes the CCE of predicted probabilities, averaged over the classes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lengths differ, if a prediction is not a probability, or if a
+    /// prediction is 0 while its target is not.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        CategoricalCrossEntropyConfig::new().compute(predictions, targets)
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for CategoricalCrossEntropyLossFunction {
+    /// Computes the term `-t * log(p)` of each class.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        CategoricalCrossEntropyConfig::new().elementwise(predictions, targets)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for CategoricalCrossEntropyLossFunction {
+    /// Computes the gradient of the CCE with respect to each prediction, `-t / (p * n)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        CategoricalCrossEntropyConfig::new().gradient(predictions, targets)
+    }
+}
+
+impl<F: Float> BatchLossFunction<F> for CategoricalCrossEntropyLossFunction {
+    /// Computes the CCE of a batch of samples, summed over the classes of each sample and
+    /// averaged over the samples.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`, and if the shape of the batch
+    /// is invalid.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F> {
+        CategoricalCrossEntropyConfig::new().compute_batch(predictions, targets)
+    }
+
+    /// Computes the gradient of `compute_batch` with respect to each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>> {
+        CategoricalCrossEntropyConfig::new().gradient_batch(predictions, targets)
+    }
+}
+
+/// Represents a Softmax activation fused with the Categorical Cross-Entropy loss.
+///
+/// The predictions are raw scores (logits) rather than probabilities. The loss is the
+/// Categorical Cross-Entropy of `softmax(predictions)` against the targets, computed with the
+/// log-sum-exp trick so that large logits neither overflow nor produce `log(0)`:
+///
+/// \[
+/// L = \frac{1}{N} \sum_{i=1}^{N} y_i \cdot \left(\log \sum_{j=1}^{N} e^{z_j} - z_i\right)
+/// \]
+///
+/// Its gradient with respect to the logits has the simple form `(p * sum(y) - y) / N`, where
+/// `p = softmax(z)`, which reduces to `(p - y) / N` for targets summing to one. This avoids
+/// backpropagating through the Softmax Jacobian.
+///
+/// This is the same loss as `CategoricalCrossEntropyConfig::new().with_logits(true)`, which also
+/// supports label smoothing.
+///
+/// # Example Usage
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::{DifferentiableLoss, LossFunction};
+/// use qmachina::loss::cce::SoftmaxCrossEntropyLossFunction;
+///
+/// let loss_function = SoftmaxCrossEntropyLossFunction;
+/// let logits: Arc<[f64]> = Arc::new([1000.0, 0.0]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0]);
+///
+/// let loss = loss_function.compute(logits.clone(), targets.clone()).unwrap();
+/// assert!(loss.abs() < 1e-12);
+///
+/// let gradient = loss_function.gradient(logits, targets).unwrap();
+/// assert!(gradient.iter().all(|g| g.is_finite()));
+/// ```
+pub struct SoftmaxCrossEntropyLossFunction;
+
+impl<F: Float> LossFunction<F> for SoftmaxCrossEntropyLossFunction {
+    /// Computes the Categorical Cross-Entropy of the Softmax of the logits.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` representing the raw scores (logits) for each class.
+    /// * `targets` - An `Arc<[F]>` representing the actual target distribution, typically in a one-hot encoded format.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<F, QmachinaError>` containing the loss, averaged over all classes.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if:
+    ///   - The lengths of predictions and targets arrays are different.
+    ///   - The logits contain invalid values (NaN or infinite).
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).compute(predictions, targets)
+    }
+}
 
-        Ok(loss / predictions.len() as f64)
+impl<F: Float> ElementwiseLoss<F> for SoftmaxCrossEntropyLossFunction {
+    /// Computes the term `t * (log(sum(e^z)) - z)` of each class.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).elementwise(predictions, targets)
+    }
+
+    /// Computes the gradient of `compute_with` with respect to each logit,
+    /// `softmax(z) * sum(w * y) - w * y` for the weights `w` of the reduction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_with`, and for
+    /// `Reduction::None`, whose value is not a single loss.
+    fn gradient_with(
+        &self,
+        predictions: Arc<[F]>,
+        targets: Arc<[F]>,
+        weights: Option<Arc<[F]>>,
+        reduction: Reduction
+    ) -> Result<Vec<F>> where Self: DifferentiableLoss<F> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).gradient_with(predictions, targets, weights, reduction)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for SoftmaxCrossEntropyLossFunction {
+    /// Computes the gradient of the fused loss with respect to each logit,
+    /// `(softmax(z) * sum(y) - y) / n`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).gradient(predictions, targets)
+    }
+}
+
+impl<F: Float> BatchLossFunction<F> for SoftmaxCrossEntropyLossFunction {
+    /// Computes the fused loss of a batch of samples, each row holding the logits of one
+    /// sample, as `CategoricalCrossEntropyConfig::new().with_logits(true)` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`, and if the shape of the batch
+    /// is invalid.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).compute_batch(predictions, targets)
+    }
+
+    /// Computes the gradient of `compute_batch` with respect to each logit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>> {
+        CategoricalCrossEntropyConfig::new().with_logits(true).gradient_batch(predictions, targets)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::activation::ActivationFunction;
+    use crate::activation::softmax::SoftmaxActivationFunction;
+    use crate::loss::numerical_gradient;
     use std::sync::Arc;
 
     /// Test Categorical Cross-Entropy with valid probability distributions.
@@ -130,8 +666,254 @@ mod tests {
         let cce_loss = CategoricalCrossEntropyLossFunction;
         let predictions = Arc::new([0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
         let targets = Arc::new([0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
-        let loss = cce_loss.compute(predictions, targets).unwrap();
+        let loss: f64 = cce_loss.compute(predictions, targets).unwrap();
         // The loss should be very close to 0 for perfect predictions
         assert!(loss.abs() < 1e-6);
     }
+
+    /// Test the Categorical Cross-Entropy gradient against finite differences.
+    #[test]
+    fn cce_gradient_matches_finite_differences() {
+        let cce_loss = CategoricalCrossEntropyLossFunction;
+        let predictions = [0.1, 0.7, 0.2, 0.3, 0.1, 0.6];
+        let targets = [0.0, 1.0, 0.0, 0.2, 0.0, 0.8];
+        let gradient = cce_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&cce_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test the Categorical Cross-Entropy gradient with a zero probability on the target class.
+    /// Expected result is an error.
+    #[test]
+    fn cce_gradient_zero_probability_on_target() {
+        let cce_loss = CategoricalCrossEntropyLossFunction;
+        assert!(cce_loss.gradient(Arc::new([0.0, 1.0]), Arc::new([1.0, 0.0])).is_err());
+        assert_eq!(cce_loss.gradient(Arc::new([0.0, 1.0]), Arc::new([0.0, 1.0])).unwrap(), vec![0.0, -0.5]);
+    }
+
+    /// Test the fused Softmax Cross-Entropy against the Softmax followed by the Categorical Cross-Entropy.
+    /// Expected result is the same loss.
+    #[test]
+    fn softmax_cce_matches_composition() {
+        let logits = vec![0.5, -1.2, 2.0, 0.1];
+        let targets: Arc<[f64]> = Arc::new([0.0, 0.0, 1.0, 0.0]);
+
+        let probabilities = SoftmaxActivationFunction.activate(&logits);
+        let expected = CategoricalCrossEntropyLossFunction.compute(probabilities.into(), targets.clone()).unwrap();
+        let loss = SoftmaxCrossEntropyLossFunction.compute(logits.into(), targets).unwrap();
+
+        assert!((loss - expected).abs() < 1e-12);
+    }
+
+    /// Test the fused Softmax Cross-Entropy gradient against finite differences and `p - y`.
+    #[test]
+    fn softmax_cce_gradient() {
+        let loss_function = SoftmaxCrossEntropyLossFunction;
+        let logits = [0.5, -1.2, 2.0, 0.1];
+        let targets = [0.0, 0.3, 0.7, 0.0];
+        let gradient = loss_function.gradient(Arc::new(logits), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&loss_function, &logits, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+
+        let probabilities = SoftmaxActivationFunction.activate(&logits.to_vec());
+        for ((g, p), t) in gradient.iter().zip(probabilities).zip(targets) {
+            assert!((g - (p - t) / 4.0).abs() < 1e-12);
+        }
+    }
+
+    /// Test the fused Softmax Cross-Entropy with extreme logits.
+    /// Expected result is a finite loss and gradient.
+    #[test]
+    fn softmax_cce_extreme_logits() {
+        let loss_function = SoftmaxCrossEntropyLossFunction;
+        let logits: Arc<[f64]> = Arc::new([1000.0, -1000.0, 0.0]);
+        let targets: Arc<[f64]> = Arc::new([0.0, 1.0, 0.0]);
+
+        let loss = loss_function.compute(logits.clone(), targets.clone()).unwrap();
+        assert!((loss - 2000.0 / 3.0).abs() < 1e-9);
+
+        let gradient = loss_function.gradient(logits, targets).unwrap();
+        assert!((gradient[0] - 1.0 / 3.0).abs() < 1e-12);
+        assert!((gradient[1] + 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    /// Test the fused Softmax Cross-Entropy with invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn softmax_cce_invalid_inputs() {
+        let loss_function = SoftmaxCrossEntropyLossFunction;
+        assert!(loss_function.compute(Arc::new([1.0, 2.0]), Arc::new([1.0])).is_err());
+        assert!(loss_function.gradient(Arc::new([f64::NAN, 2.0]), Arc::new([1.0, 0.0])).is_err());
+    }
+
+    /// Test Categorical Cross-Entropy with a zero probability on a target class.
+    /// Expected result is an error, since the loss is infinite.
+    #[test]
+    fn cce_zero_probability_on_target() {
+        let cce_loss = CategoricalCrossEntropyLossFunction;
+        let result = cce_loss.compute(Arc::new([0.0, 1.0]), Arc::new([1.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedLogarithm { index: 0 })));
+    }
+
+    /// Test Categorical Cross-Entropy with clipped probabilities.
+    /// Expected result is a large but finite loss, and a zero gradient where predictions are clipped.
+    #[test]
+    fn cce_epsilon_clipping() {
+        let cce_loss = CategoricalCrossEntropyLossFunction.with_epsilon(1e-7);
+        let loss: f64 = cce_loss.compute(Arc::new([0.0, 1.0]), Arc::new([1.0, 0.0])).unwrap();
+        assert!((loss + (1e-7_f64).ln() / 2.0).abs() < 1e-6);
+
+        let gradient = cce_loss.gradient(Arc::new([0.0, 0.5]), Arc::new([0.5, 0.5])).unwrap();
+        assert_eq!(gradient, vec![0.0, -0.5]);
+    }
+
+    /// Test Categorical Cross-Entropy with label smoothing against finite differences.
+    #[test]
+    fn cce_label_smoothing() {
+        let cce_loss = CategoricalCrossEntropyLossFunction.with_label_smoothing(0.3);
+        let loss: f64 = cce_loss.compute(Arc::new([0.5, 0.25, 0.25]), Arc::new([1.0, 0.0, 0.0])).unwrap();
+        let expected = -(0.8 * 0.5_f64.ln() + 0.2 * 0.25_f64.ln()) / 3.0;
+        assert!((loss - expected).abs() < 1e-12);
+
+        let predictions = [0.5, 0.3, 0.2];
+        let targets = [0.0, 1.0, 0.0];
+        let gradient = cce_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&cce_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test Categorical Cross-Entropy over logits with label smoothing against finite differences.
+    #[test]
+    fn cce_from_logits_with_label_smoothing() {
+        let cce_loss = CategoricalCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.1);
+        let logits = [0.5, -1.2, 2.0, 0.1];
+        let targets = [0.0, 0.0, 1.0, 0.0];
+        let gradient = cce_loss.gradient(Arc::new(logits), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&cce_loss, &logits, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+
+        let unsmoothed: f64 = CategoricalCrossEntropyLossFunction.with_logits(true).compute(Arc::new(logits), Arc::new(targets)).unwrap();
+        let fused: f64 = SoftmaxCrossEntropyLossFunction.compute(Arc::new(logits), Arc::new(targets)).unwrap();
+        assert_eq!(unsmoothed, fused);
+    }
+
+    /// Test Categorical Cross-Entropy with options out of range.
+    /// Expected result is an error.
+    #[test]
+    fn cce_invalid_options() {
+        let predictions: Arc<[f64]> = Arc::new([0.5, 0.5]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0]);
+
+        for cce_loss in [
+            CategoricalCrossEntropyLossFunction.with_epsilon(-1e-7),
+            CategoricalCrossEntropyLossFunction.with_label_smoothing(f64::NAN),
+        ] {
+            assert!(matches!(cce_loss.compute(predictions.clone(), targets.clone()), Err(QmachinaError::InvalidParameter(_))));
+        }
+    }
+
+    /// Test Categorical Cross-Entropy over a batch of samples.
+    /// Expected result is the average over the samples of the sum over the classes.
+    #[test]
+    fn cce_batch_sums_classes_and_averages_samples() {
+        let cce_loss = CategoricalCrossEntropyLossFunction;
+        let predictions = vec![vec![0.7, 0.2, 0.1], vec![0.1, 0.3, 0.6]];
+        let targets = vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]];
+
+        let loss: f64 = cce_loss.compute_batch(&predictions, &targets).unwrap();
+        assert!((loss + (0.7_f64.ln() + 0.6_f64.ln()) / 2.0).abs() < 1e-12);
+
+        let gradient = cce_loss.gradient_batch(&predictions, &targets).unwrap();
+        assert!((gradient[0][0] + 1.0 / 1.4).abs() < 1e-12);
+        assert!((gradient[1][2] + 1.0 / 1.2).abs() < 1e-12);
+        assert_eq!(gradient[0][1], 0.0);
+
+        let result = cce_loss.compute_batch(&[vec![0.5, 0.5], vec![0.0, 1.0]], &[vec![1.0, 0.0], vec![1.0, 0.0]]);
+        assert!(matches!(result, Err(QmachinaError::UndefinedLogarithm { index: 2 })));
+    }
+
+    /// Test the fused Softmax Cross-Entropy over a batch of logits.
+    /// Expected result is the gradient `(softmax(z) - y) / samples` of each row.
+    #[test]
+    fn softmax_cce_batch_gradient() {
+        let logits = vec![vec![0.5, -1.2, 2.0], vec![1000.0, 0.0, -1000.0]];
+        let targets = vec![vec![0.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]];
+
+        let loss: f64 = SoftmaxCrossEntropyLossFunction.compute_batch(&logits, &targets).unwrap();
+        assert!(loss.is_finite());
+
+        let gradient = SoftmaxCrossEntropyLossFunction.gradient_batch(&logits, &targets).unwrap();
+        for (row, (z, t)) in gradient.iter().zip(logits.iter().zip(&targets)) {
+            let probabilities = SoftmaxActivationFunction.activate(z);
+            for ((g, p), t) in row.iter().zip(probabilities).zip(t) {
+                assert!((g - (p - t) / 2.0).abs() < 1e-12);
+            }
+        }
+    }
+
+    /// Test label smoothing and logits on two samples, flattened into a single slice with
+    /// `with_classes` or given as a batch.
+    /// Expected result is the same loss, and a gradient matching finite differences.
+    #[test]
+    fn cce_options_on_two_samples() {
+        let predictions = [vec![0.5, -1.2, 2.0], vec![0.3, 1.1, -0.4]];
+        let targets = vec![vec![0.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]];
+        let flat_targets = targets.concat();
+
+        for cce_loss in [
+            CategoricalCrossEntropyConfig::new().with_logits(true),
+            CategoricalCrossEntropyConfig::default().with_label_smoothing(0.1).with_logits(true),
+            CategoricalCrossEntropyLossFunction.with_label_smoothing(0.1)
+        ] {
+            let probabilities: Vec<Vec<f64>> = predictions.iter()
+                .map(|row| if cce_loss.is_from_logits() { row.clone() } else { SoftmaxActivationFunction.activate(row) })
+                .collect();
+            let flat_probabilities = probabilities.concat();
+
+            let loss: f64 = cce_loss.compute_batch(&probabilities, &targets).unwrap();
+            let flat_loss: f64 = cce_loss.with_classes(3).compute(flat_probabilities.as_slice().into(), flat_targets.as_slice().into()).unwrap();
+            assert!((3.0 * flat_loss - loss).abs() < 1e-12, "The flattened samples should give the loss of the batch");
+
+            let gradient = cce_loss.with_classes(3).gradient(flat_probabilities.as_slice().into(), flat_targets.as_slice().into()).unwrap();
+            let expected = numerical_gradient(&cce_loss.with_classes(3), &flat_probabilities, &flat_targets);
+            for (g, e) in gradient.iter().zip(expected) {
+                assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+            }
+        }
+    }
+
+    /// Test soft targets of two samples that add up to one, with and without the number of
+    /// classes.
+    /// Expected result is smoothing over each sample with `with_classes`, and over the whole
+    /// slice as a single sample without it.
+    #[test]
+    fn cce_classes_are_explicit() {
+        let predictions: Arc<[f64]> = Arc::new([0.5, 0.25, 0.25, 0.2, 0.6, 0.2]);
+        let targets: Arc<[f64]> = Arc::new([0.5, 0.0, 0.0, 0.0, 0.5, 0.0]);
+        let cce_loss = CategoricalCrossEntropyLossFunction.with_label_smoothing(0.3);
+
+        let terms = cce_loss.with_classes(3).elementwise(predictions.clone(), targets.clone()).unwrap();
+        assert!((terms[1] + 0.1 * 0.25_f64.ln()).abs() < 1e-12, "The smoothing should spread over 3 classes");
+        let terms = cce_loss.elementwise(predictions.clone(), targets.clone()).unwrap();
+        assert!((terms[1] + 0.05 * 0.25_f64.ln()).abs() < 1e-12, "The smoothing should spread over 6 classes");
+
+        let result = cce_loss.with_classes(4).compute(predictions.clone(), targets.clone());
+        assert!(matches!(result, Err(QmachinaError::LengthMismatch { expected: 8, got: 6 })));
+        assert!(matches!(cce_loss.with_classes(0).compute(predictions, targets), Err(QmachinaError::InvalidParameter(_))));
+
+        let result = CategoricalCrossEntropyLossFunction.with_classes(3).compute(Arc::new([0.5, 0.5, 0.0, 0.5, 0.0, 0.5]), Arc::new([0.0, 0.0, 0.0, 0.0, 1.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedLogarithm { index: 4 })));
+    }
 }
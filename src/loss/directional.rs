@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, sign, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
+
+/// Represents a differentiable Directional Loss, penalising predictions whose sign differs from
+/// the sign of their target.
+///
+/// In trading, predicting the direction of a move often matters more than its size. The
+/// fraction of wrong directions has a zero gradient almost everywhere, so this loss smooths the
+/// sign of the prediction with `tanh(k * prediction)`, where the sharpness `k` should be set
+/// relative to the scale of the predictions, such as 100 for daily returns.
+///
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`.
+///
+/// # Mathematical Background
+///
+/// \[
+/// L = \frac{1}{n} \sum_{i=1}^{n} |sign(target_i)| \cdot \frac{1 - \tanh(k \cdot prediction_i) \cdot sign(target_i)}{2}
+/// \]
+///
+/// Each term tends to 0 for a correct direction and to 1 for a wrong one as `k` grows, so the
+/// loss approaches the directional error rate. Targets of exactly zero have no direction and do
+/// not contribute.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::directional::DirectionalLossFunction;
+///
+/// let directional_loss = DirectionalLossFunction::new(1000.0).unwrap();
+/// let predictions: Arc<[f64]> = Arc::new([0.02, -0.01, 0.03, 0.01]);
+/// let targets: Arc<[f64]> = Arc::new([0.01, 0.02, 0.01, -0.03]);
+///
+/// // Two of the four directions are wrong.
+/// let loss = directional_loss.compute(predictions, targets).unwrap();
+/// assert!((loss - 0.5).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionalLossFunction<F = f64> {
+    sharpness: F
+}
+
+impl<F: Float> DirectionalLossFunction<F> {
+    /// Creates a new instance of the Directional Loss Function.
+    ///
+    /// # Parameters
+    ///
+    /// * `sharpness` - The positive factor `k` applied to the predictions before the `tanh`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the sharpness is not positive and finite.
+    pub fn new(sharpness: F) -> Result<Self> {
+        if !(sharpness > F::zero() && sharpness.is_finite()) {
+            return Err(QmachinaError::InvalidParameter("The sharpness must be positive and finite."));
+        }
+
+        Ok(Self { sharpness })
+    }
+
+    /// Returns the sharpness applied to the predictions.
+    pub fn sharpness(&self) -> F {
+        self.sharpness
+    }
+}
+
+impl<F: Float> LossFunction<F> for DirectionalLossFunction<F> {
+    /// Computes the Directional Loss between predictions and targets, averaged over all elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for DirectionalLossFunction<F> {
+    /// Computes the Directional Loss of each prediction, between 0 and 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let half = constant::<F>(0.5);
+        let losses = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let direction = sign(t);
+                direction.abs() * half * (F::one() - (self.sharpness * p).tanh() * direction)
+            })
+            .collect();
+
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for DirectionalLossFunction<F> {
+    /// Computes the gradient of the Directional Loss with respect to each prediction,
+    /// `-k * (1 - tanh(k * p)^2) * sign(t) / (2 * n)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let smoothed = (self.sharpness * p).tanh();
+                -self.sharpness * (F::one() - smoothed * smoothed) * sign(t) / (constant::<F>(2.0) * n)
+            })
+            .collect();
+
+        Ok(gradient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::numerical_gradient;
+
+    /// Test the Directional Loss with a large sharpness.
+    /// Expected result is close to 0 for correct directions, 1 for wrong ones and 0 without direction.
+    #[test]
+    fn directional_elementwise() {
+        let directional_loss: DirectionalLossFunction = DirectionalLossFunction::new(1e6).unwrap();
+        let losses = directional_loss.elementwise(Arc::new([1.0, -1.0, 1.0]), Arc::new([2.0, 2.0, 0.0])).unwrap();
+
+        assert!(losses[0] < 1e-9);
+        assert!((losses[1] - 1.0).abs() < 1e-9);
+        assert_eq!(losses[2], 0.0);
+    }
+
+    /// Test sharpness values that are not positive.
+    /// Expected result is an error.
+    #[test]
+    fn directional_invalid_sharpness() {
+        assert!(matches!(DirectionalLossFunction::new(0.0), Err(QmachinaError::InvalidParameter(_))));
+        assert!(DirectionalLossFunction::new(f64::INFINITY).is_err());
+    }
+
+    /// Test the Directional Loss gradient against finite differences.
+    #[test]
+    fn directional_gradient_matches_finite_differences() {
+        let directional_loss = DirectionalLossFunction::new(50.0).unwrap();
+        let predictions = [0.01, -0.02, 0.005, 0.03];
+        let targets = [0.02, 0.01, -0.01, 0.0];
+        let gradient = directional_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&directional_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use std::ops::Div;
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
 
-use super::LossFunction;
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::{constant, count, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
 
 /// Represents the Huber Loss function for regression models.
 ///
@@ -10,7 +10,7 @@ use super::LossFunction;
 /// Huber Loss is less sensitive to outliers in the data due to its combination of 
 /// squared error and absolute error.
 ///
-/// This struct implements the `LossFunction<f64>` trait, enabling its use in machine learning models
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`, enabling its use in machine learning models
 /// for regression tasks.
 ///
 /// # Mathematical Background
@@ -44,22 +44,22 @@ use super::LossFunction;
 /// let loss = huber_loss.compute(predictions, targets).expect("Failed to compute loss");
 /// // 'loss' now contains the Huber loss value
 /// ```
-pub struct HuberLossFunction {
-    delta: f64,
+pub struct HuberLossFunction<F = f64> {
+    delta: F,
 }
 
-impl HuberLossFunction {
+impl<F: Float> HuberLossFunction<F> {
     /// Creates a new instance of the Huber Loss Function with a specified delta.
     ///
     /// # Parameters
     ///
     /// * `delta` - The threshold parameter for the Huber loss function.
-    pub fn new(delta: f64) -> Self {
+    pub fn new(delta: F) -> Self {
         Self { delta }
     }
 }
 
-impl LossFunction<f64> for HuberLossFunction {
+impl<F: Float> LossFunction<F> for HuberLossFunction<F> {
     /// Computes the Huber loss between predictions and targets.
     ///
     /// The Huber loss combines the advantages of mean squared error and mean absolute error. 
@@ -68,47 +68,86 @@ impl LossFunction<f64> for HuberLossFunction {
     ///
     /// # Parameters
     ///
-    /// * `predictions` - An `Arc<[f64]>` containing the predicted values from the model.
-    /// * `targets` - An `Arc<[f64]>` containing the actual target values.
+    /// * `predictions` - An `Arc<[F]>` containing the predicted values from the model.
+    /// * `targets` - An `Arc<[F]>` containing the actual target values.
     ///
     /// # Returns
     ///
-    /// A `Result<f64, anyhow::Error>`, where:
+    /// A `Result<F, QmachinaError>`, where:
     ///   - The `Ok` variant contains the computed Huber loss.
     ///   - The `Err` variant encapsulates errors that occur during computation, such as
     ///     mismatched lengths of the predictions and targets arrays.
     ///
-    fn compute(&self, predictions: Arc<[f64]>, targets: Arc<[f64]>) -> Result<f64> {
-        if predictions.len() != targets.len() {
-            return Err(anyhow!("Predictions and targets must have the same length"));
-        }
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
 
-        let loss = predictions.iter()
+impl<F: Float> ElementwiseLoss<F> for HuberLossFunction<F> {
+    /// Computes the Huber loss of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let losses = predictions.iter()
             .zip(targets.iter())
-            .fold(0.0, |acc, (&p, &t)| {
+            .map(|(&p, &t)| {
                 let error = p - t;
                 if error.abs() <= self.delta {
-                    acc + 0.5 * error.powi(2)
+                    constant::<F>(0.5) * error.powi(2)
                 } else {
-                    acc + self.delta * (error.abs() - 0.5 * self.delta)
+                    self.delta * (error.abs() - constant::<F>(0.5) * self.delta)
                 }
             })
-            .div(predictions.len() as f64);
+            .collect();
 
-        Ok(loss)
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for HuberLossFunction<F> {
+    /// Computes the gradient of the Huber loss with respect to each prediction.
+    ///
+    /// The derivative of each term is the error itself when its magnitude is within `delta`,
+    /// and `delta` times the sign of the error otherwise, divided by the number of elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let error = p - t;
+                if error.abs() <= self.delta {
+                    error / n
+                } else {
+                    self.delta * error.signum() / n
+                }
+            })
+            .collect();
+
+        Ok(gradient)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::loss::numerical_gradient;
     use std::sync::Arc;
 
     /// Test Huber loss with small errors (within delta).
     #[test]
     fn huber_loss_small_errors() {
         let delta = 1.0;
-        let huber_loss = HuberLossFunction::new(delta);
+        let huber_loss: HuberLossFunction = HuberLossFunction::new(delta);
         let predictions = Arc::new([1.2, 0.9, 1.1]);
         let targets = Arc::new([1.0, 1.0, 1.0]);
         let loss = huber_loss.compute(predictions, targets).unwrap();
@@ -120,7 +159,7 @@ mod tests {
     #[test]
     fn huber_loss_large_errors() {
         let delta = 1.0;
-        let huber_loss = HuberLossFunction::new(delta);
+        let huber_loss: HuberLossFunction = HuberLossFunction::new(delta);
         let predictions = Arc::new([3.0, 0.0, 4.0]);
         let targets = Arc::new([1.0, 1.0, 1.0]);
         let loss = huber_loss.compute(predictions, targets).unwrap();
@@ -132,7 +171,7 @@ mod tests {
     #[test]
     fn huber_loss_varying_errors() {
         let delta = 1.0;
-        let huber_loss = HuberLossFunction::new(delta);
+        let huber_loss: HuberLossFunction = HuberLossFunction::new(delta);
         let predictions = Arc::new([1.5, 0.5, 2.0]);
         let targets = Arc::new([1.0, 1.0, 1.0]);
         let loss = huber_loss.compute(predictions, targets).unwrap();
@@ -144,10 +183,24 @@ mod tests {
     #[test]
     fn huber_loss_invalid_input() {
         let delta = 1.0;
-        let huber_loss = HuberLossFunction::new(delta);
+        let huber_loss: HuberLossFunction = HuberLossFunction::new(delta);
         let predictions = Arc::new([1.5, 0.5]);
         let targets = Arc::new([1.0, 1.0, 1.0]);
         let result = huber_loss.compute(predictions, targets);
         assert!(result.is_err());
     }
+
+    /// Test the Huber gradient against finite differences on both sides of delta.
+    #[test]
+    fn huber_gradient_matches_finite_differences() {
+        let huber_loss = HuberLossFunction::new(1.0);
+        let predictions = [1.2, 0.9, 4.0, -2.5];
+        let targets = [1.0, 1.0, 1.0, 0.0];
+        let gradient = huber_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&huber_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
 }
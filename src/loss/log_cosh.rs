@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::{constant, count, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
+
+/// Represents the Log-Cosh Loss function for regression models.
+///
+/// The Log-Cosh Loss behaves like half the squared error for small errors and like the absolute
+/// error minus `log(2)` for large ones, so it is robust to outliers like the Huber Loss while
+/// being twice differentiable everywhere and free of any threshold parameter.
+///
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`.
+///
+/// # Mathematical Background
+///
+/// \[
+/// L = \frac{1}{n} \sum_{i=1}^{n} \log(\cosh(prediction_i - target_i))
+/// \]
+///
+/// Each term is evaluated as `|e| + log(1 + e^{-2|e|}) - log(2)`, which does not overflow for
+/// large errors as `cosh` does.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::log_cosh::LogCoshLossFunction;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.0, 1000.0]);
+/// let targets: Arc<[f64]> = Arc::new([0.0, 0.0]);
+///
+/// let loss = LogCoshLossFunction.compute(predictions, targets).unwrap();
+/// assert!((loss - (1000.0 - 2.0_f64.ln()) / 2.0).abs() < 1e-9);
+/// ```
+pub struct LogCoshLossFunction;
+
+impl<F: Float> LossFunction<F> for LogCoshLossFunction {
+    /// Computes the Log-Cosh Loss between predictions and targets, averaged over all elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for LogCoshLossFunction {
+    /// Computes the Log-Cosh Loss of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let ln_2 = constant::<F>(std::f64::consts::LN_2);
+        let losses = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let error = (p - t).abs();
+                error + (constant::<F>(-2.0) * error).exp().ln_1p() - ln_2
+            })
+            .collect();
+
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for LogCoshLossFunction {
+    /// Computes the gradient of the Log-Cosh Loss with respect to each prediction,
+    /// `tanh(p - t) / n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| (p - t).tanh() / n)
+            .collect();
+
+        Ok(gradient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::numerical_gradient;
+
+    /// Test Log-Cosh with perfect prediction.
+    /// Expected result is zero loss.
+    #[test]
+    fn log_cosh_perfect_prediction() {
+        let loss: f64 = LogCoshLossFunction.compute(Arc::new([1.0, 2.0]), Arc::new([1.0, 2.0])).unwrap();
+        assert!(loss.abs() < 1e-15);
+    }
+
+    /// Test Log-Cosh against the direct definition.
+    #[test]
+    fn log_cosh_matches_definition() {
+        let losses = LogCoshLossFunction.elementwise(Arc::new([0.5, -2.0, 3.0]), Arc::new([0.0, 0.0, 1.0])).unwrap();
+        for (loss, error) in losses.iter().zip([0.5_f64, -2.0, 2.0]) {
+            assert!((loss - error.cosh().ln()).abs() < 1e-12);
+        }
+    }
+
+    /// Test Log-Cosh with predictions and targets of different lengths.
+    /// Expected result is an error.
+    #[test]
+    fn log_cosh_mismatched_lengths() {
+        let result = LogCoshLossFunction.compute(Arc::new([1.0, 2.0]), Arc::new([1.0]));
+        assert!(result.is_err());
+    }
+
+    /// Test the Log-Cosh gradient against finite differences.
+    #[test]
+    fn log_cosh_gradient_matches_finite_differences() {
+        let predictions = [2.5, 0.0, 2.1, 1.8];
+        let targets = [3.0, -0.5, 2.0, 2.0];
+        let gradient = LogCoshLossFunction.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&LogCoshLossFunction, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+}
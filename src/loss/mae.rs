@@ -1,8 +1,8 @@
-use std::ops::Div;
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
 
-use super::LossFunction;
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::{count, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
 
 /// Represents the Mean Absolute Error (MAE) loss function for regression models.
 ///
@@ -10,7 +10,7 @@ use super::LossFunction;
 /// It measures the average of the absolute errors—that is, the average 
 /// absolute difference between the estimated values (predictions) and the actual value (targets).
 ///
-/// This struct implements the `LossFunction<f64>` trait, enabling its use in various
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`, enabling its use in various
 /// machine learning models where floating-point precision is required.
 ///
 /// # Mathematical Background
@@ -49,25 +49,59 @@ use super::LossFunction;
 /// It's used in regression tasks where the target variable is continuous.
 pub struct MeanAbsoluteErrorLossFunction;
 
-impl LossFunction<f64> for MeanAbsoluteErrorLossFunction {
-    fn compute(&self, predictions: Arc<[f64]>, targets: Arc<[f64]>) -> Result<f64> {
-        if predictions.len() != targets.len() {
-            return Err(anyhow!("Predictions and targets must have the same length"));
-        }
+impl<F: Float> LossFunction<F> for MeanAbsoluteErrorLossFunction {
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for MeanAbsoluteErrorLossFunction {
+    /// Computes the absolute error `|p - t|` of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let losses = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| (p - t).abs())
+            .collect();
+
+        Ok(losses)
+    }
+}
 
-        let mae = predictions.iter()
+impl<F: Float> DifferentiableLoss<F> for MeanAbsoluteErrorLossFunction {
+    /// Computes the gradient of the MAE with respect to each prediction, `sign(p - t) / n`.
+    ///
+    /// The absolute value is not differentiable where a prediction equals its target, so the
+    /// subgradient 0 is used there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
             .zip(targets.iter())
-            .map(|(p, t)| (p - t).abs())
-            .sum::<f64>()
-            .div(predictions.len() as f64);
+            .map(|(&p, &t)| {
+                let error = p - t;
+                if error == F::zero() { F::zero() } else { error.signum() / n }
+            })
+            .collect();
 
-        Ok(mae)
+        Ok(gradient)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::loss::numerical_gradient;
     use std::sync::Arc;
 
     /// Test MAE with perfect prediction.
@@ -123,7 +157,28 @@ mod tests {
         let predictions = Arc::new([1.5, 2.5, 3.5]);
         let targets = Arc::new([1.0, 3.0, 2.0]);
         let loss = mae_loss.compute(predictions, targets).unwrap();
-        let expected_loss = ((0.5 + 0.5 + 1.5) / 3.0) as f64;
+        let expected_loss = (0.5 + 0.5 + 1.5) / 3.0;
         assert_eq!(loss, expected_loss);
     }
+
+    /// Test the MAE gradient against finite differences, away from the kink at zero error.
+    #[test]
+    fn mae_gradient_matches_finite_differences() {
+        let predictions = [2.5, 0.0, 2.1, 1.8];
+        let targets = [3.0, -0.5, 2.0, 2.0];
+        let gradient = MeanAbsoluteErrorLossFunction.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&MeanAbsoluteErrorLossFunction, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test the MAE subgradient on perfect predictions.
+    /// Expected result is a zero gradient.
+    #[test]
+    fn mae_gradient_perfect_prediction() {
+        let gradient = MeanAbsoluteErrorLossFunction.gradient(Arc::new([1.0, 2.0]), Arc::new([1.0, 2.0])).unwrap();
+        assert_eq!(gradient, vec![0.0, 0.0]);
+    }
 }
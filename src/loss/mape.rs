@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, sign, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
+
+/// Represents the Mean Absolute Percentage Error (MAPE) loss function for regression models.
+///
+/// MAPE measures the error relative to the size of each target, which makes it comparable
+/// across series of different scales, such as the prices of different assets. It is returned
+/// as a fraction, so a value of 0.05 means an average error of 5%.
+///
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`.
+///
+/// # Mathematical Background
+///
+/// \[
+/// MAPE = \frac{1}{n} \sum_{i=1}^{n} \frac{|prediction_i - target_i|}{|target_i|}
+/// \]
+///
+/// MAPE is undefined for targets of zero and penalises over-predictions more than
+/// under-predictions, see `SymmetricMeanAbsolutePercentageErrorLossFunction` for a bounded
+/// alternative.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::mape::MeanAbsolutePercentageErrorLossFunction;
+///
+/// let predictions: Arc<[f64]> = Arc::new([105.0, 48.0]);
+/// let targets: Arc<[f64]> = Arc::new([100.0, 50.0]);
+///
+/// let loss = MeanAbsolutePercentageErrorLossFunction.compute(predictions, targets).unwrap();
+/// assert!((loss - 0.045).abs() < 1e-12);
+/// ```
+pub struct MeanAbsolutePercentageErrorLossFunction;
+
+impl<F: Float> LossFunction<F> for MeanAbsolutePercentageErrorLossFunction {
+    /// Computes the MAPE between predictions and targets, as a fraction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths, or if a target
+    /// is zero.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for MeanAbsolutePercentageErrorLossFunction {
+    /// Computes the absolute percentage error `|p - t| / |t|` of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        predictions.iter()
+            .zip(targets.iter())
+            .enumerate()
+            .map(|(index, (&p, &t))| {
+                if t == F::zero() {
+                    return Err(QmachinaError::InvalidValue { index });
+                }
+                Ok((p - t).abs() / t.abs())
+            })
+            .collect()
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for MeanAbsolutePercentageErrorLossFunction {
+    /// Computes the gradient of the MAPE with respect to each prediction,
+    /// `sign(p - t) / (|t| * n)`.
+    ///
+    /// The subgradient 0 is used where a prediction equals its target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        predictions.iter()
+            .zip(targets.iter())
+            .enumerate()
+            .map(|(index, (&p, &t))| {
+                if t == F::zero() {
+                    return Err(QmachinaError::InvalidValue { index });
+                }
+                Ok(sign(p - t) / (t.abs() * n))
+            })
+            .collect()
+    }
+}
+
+/// Represents the Symmetric Mean Absolute Percentage Error (sMAPE) loss function.
+///
+/// sMAPE scales each error by the average magnitude of the prediction and the target instead
+/// of the target alone. It is bounded between 0 and 2, treats over- and under-predictions
+/// alike, and remains defined for targets of zero as long as the prediction is not also zero.
+///
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`.
+///
+/// # Mathematical Background
+///
+/// \[
+/// sMAPE = \frac{1}{n} \sum_{i=1}^{n} \frac{2 \cdot |prediction_i - target_i|}{|prediction_i| + |target_i|}
+/// \]
+///
+/// Terms where both the prediction and the target are zero are perfect predictions and
+/// contribute 0.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::mape::SymmetricMeanAbsolutePercentageErrorLossFunction;
+///
+/// let predictions: Arc<[f64]> = Arc::new([110.0, 0.0]);
+/// let targets: Arc<[f64]> = Arc::new([90.0, 0.0]);
+///
+/// let loss = SymmetricMeanAbsolutePercentageErrorLossFunction.compute(predictions, targets).unwrap();
+/// assert!((loss - 0.1).abs() < 1e-12);
+/// ```
+pub struct SymmetricMeanAbsolutePercentageErrorLossFunction;
+
+impl<F: Float> LossFunction<F> for SymmetricMeanAbsolutePercentageErrorLossFunction {
+    /// Computes the sMAPE between predictions and targets, as a fraction between 0 and 2.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for SymmetricMeanAbsolutePercentageErrorLossFunction {
+    /// Computes the symmetric absolute percentage error of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let losses = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let scale = p.abs() + t.abs();
+                if scale == F::zero() { F::zero() } else { constant::<F>(2.0) * (p - t).abs() / scale }
+            })
+            .collect();
+
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for SymmetricMeanAbsolutePercentageErrorLossFunction {
+    /// Computes the gradient of the sMAPE with respect to each prediction,
+    /// `2 * (sign(p - t) * (|p| + |t|) - |p - t| * sign(p)) / ((|p| + |t|)^2 * n)`.
+    ///
+    /// The subgradient 0 is used for the absolute values at zero, and terms where both the
+    /// prediction and the target are zero have a zero gradient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let scale = p.abs() + t.abs();
+                if scale == F::zero() {
+                    return F::zero();
+                }
+                let numerator = sign(p - t) * scale - (p - t).abs() * sign(p);
+                constant::<F>(2.0) * numerator / (scale * scale * n)
+            })
+            .collect();
+
+        Ok(gradient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::numerical_gradient;
+
+    /// Test MAPE with perfect prediction.
+    /// Expected result is zero loss.
+    #[test]
+    fn mape_perfect_prediction() {
+        let loss: f64 = MeanAbsolutePercentageErrorLossFunction.compute(Arc::new([1.0, -2.0]), Arc::new([1.0, -2.0])).unwrap();
+        assert_eq!(loss, 0.0);
+    }
+
+    /// Test MAPE with a target of zero.
+    /// Expected result is an error pointing at the target.
+    #[test]
+    fn mape_zero_target() {
+        let result: Result<f64> = MeanAbsolutePercentageErrorLossFunction.compute(Arc::new([1.0, 1.0]), Arc::new([1.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::InvalidValue { index: 1 })));
+    }
+
+    /// Test the MAPE gradient against finite differences.
+    #[test]
+    fn mape_gradient_matches_finite_differences() {
+        let predictions = [2.5, 0.0, 2.1, 1.8];
+        let targets = [3.0, -0.5, 2.0, 2.0];
+        let gradient = MeanAbsolutePercentageErrorLossFunction.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&MeanAbsolutePercentageErrorLossFunction, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test sMAPE bounds: opposite signs give the maximum term of 2.
+    #[test]
+    fn smape_bounds() {
+        let losses = SymmetricMeanAbsolutePercentageErrorLossFunction.elementwise(Arc::new([1.0, 0.0, 3.0]), Arc::new([-1.0, 0.0, 3.0])).unwrap();
+        assert_eq!(losses, vec![2.0, 0.0, 0.0]);
+    }
+
+    /// Test the sMAPE gradient against finite differences.
+    #[test]
+    fn smape_gradient_matches_finite_differences() {
+        let predictions = [2.5, 0.3, 2.1, -1.8];
+        let targets = [3.0, -0.5, 2.0, 2.0];
+        let gradient = SymmetricMeanAbsolutePercentageErrorLossFunction.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&SymmetricMeanAbsolutePercentageErrorLossFunction, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+}
@@ -1,13 +1,21 @@
 //! This module contains implementations for popular Loss Functions
 
 use std::sync::Arc;
-use anyhow::Result;
+
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use crate::tensor::{ensure_same_shape, Tensor};
 
 pub mod mse;
 pub mod mae;
 pub mod bce;
 pub mod cce;
 pub mod huber;
+pub mod quantile;
+pub mod log_cosh;
+pub mod sharpe;
+pub mod directional;
+pub mod mape;
 
 /// The `LossFunction` trait defines a common interface for loss functions
 /// in machine learning algorithms. It is designed to compute a loss metric
@@ -18,17 +26,16 @@ pub mod huber;
 /// both predictions and targets facilitates safe, concurrent access to these
 /// arrays, making the trait suitable for use in multi-threaded contexts.
 ///
-/// The method `compute` returns a `Result<T, anyhow::Error>`, providing a 
-/// convenient way to handle errors that might occur during the computation
-/// of the loss. This approach leverages the `anyhow` crate for simplified
-/// error handling.
+/// The method `compute` returns a `Result<T, QmachinaError>`, so callers can
+/// tell apart the reasons a loss could not be computed, such as
+/// `QmachinaError::LengthMismatch` or `QmachinaError::InvalidProbability`.
 /// 
 /// # Type Parameters
 ///
 /// - `T`: The type of the elements in the prediction and target arrays. This type
-///        should be a numeric type (like `f32` or `f64`) that supports the operations
-///        necessary for computing the loss. It must also implement the `Clone` trait
-///        to enable efficient sharing of data.
+///   should be a numeric type (like `f32` or `f64`) that supports the operations
+///   necessary for computing the loss. It must also implement the `Clone` trait
+///   to enable efficient sharing of data.
 /// 
 /// # Example
 ///
@@ -36,7 +43,7 @@ pub mod huber;
 ///
 /// ```
 /// use std::sync::Arc;
-/// use anyhow::Result;
+/// use qmachina::{QmachinaError, Result};
 /// use qmachina::loss::LossFunction;
 ///
 /// struct MeanSquaredError;
@@ -44,7 +51,7 @@ pub mod huber;
 /// impl LossFunction<f64> for MeanSquaredError {
 ///     fn compute(&self, predictions: Arc<[f64]>, targets: Arc<[f64]>) -> Result<f64> {
 ///         if predictions.len() != targets.len() {
-///             return Err(anyhow::anyhow!("Predictions and targets must have the same length"));
+///             return Err(QmachinaError::LengthMismatch { expected: predictions.len(), got: targets.len() });
 ///         }
 ///         let mse = predictions.iter()
 ///             .zip(targets.iter())
@@ -57,7 +64,7 @@ pub mod huber;
 ///
 /// # Errors
 ///
-/// This trait method may return an `Err` variant, encapsulated in `anyhow::Error`,
+/// This trait method may return an `Err` variant, encapsulated in `QmachinaError`,
 /// to indicate various failure conditions, such as mismatched lengths of prediction
 /// and target arrays.
 ///
@@ -75,7 +82,618 @@ pub trait LossFunction<T> {
     ///
     /// # Returns
     ///
-    /// A `Result<T, anyhow::Error>`, where the `Ok` variant contains the computed loss
+    /// A `Result<T, QmachinaError>`, where the `Ok` variant contains the computed loss
     /// value and the `Err` variant encapsulates any errors that occurred during the computation.
     fn compute(&self, predictions: Arc<[T]>, targets: Arc<[T]>) -> Result<T>;
+
+    /// Computes the loss of predictions and targets stored in tensors of the same shape, as
+    /// `compute` does with their values in row-major order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes differ, and an error under the same
+    /// conditions as `compute` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::loss::LossFunction;
+    /// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+    /// use qmachina::tensor::Tensor;
+    ///
+    /// let predictions = Tensor::from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+    /// let targets = Tensor::from_rows(&[vec![1.0, 2.0], vec![3.0, 2.0]]).unwrap();
+    /// assert_eq!(MeanSquaredErrorLossFunction.compute_tensor(&predictions, &targets).unwrap(), 1.0);
+    /// ```
+    fn compute_tensor(&self, predictions: &Tensor<T>, targets: &Tensor<T>) -> Result<T> where T: Float {
+        ensure_same_shape(predictions, targets)?;
+        self.compute(predictions.as_slice().into(), targets.as_slice().into())
+    }
+}
+
+/// The `DifferentiableLoss` trait extends `LossFunction` with the analytic gradient of the loss
+/// with respect to each prediction, which is what drives gradient-based training.
+///
+/// The gradient is the one of the value returned by `compute`, so it includes the `1 / n`
+/// factor of losses averaged over their elements.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::DifferentiableLoss;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+///
+/// let predictions: Arc<[f64]> = Arc::new([1.0, 2.0]);
+/// let targets: Arc<[f64]> = Arc::new([0.0, 2.0]);
+///
+/// let gradient = MeanSquaredErrorLossFunction.gradient(predictions, targets).unwrap();
+/// assert_eq!(gradient, vec![1.0, 0.0]);
+/// ```
+pub trait DifferentiableLoss<T>: LossFunction<T> {
+    /// Computes the gradient of the loss with respect to each prediction.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[T]>` containing predicted values from the model.
+    /// * `targets` - An `Arc<[T]>` containing the actual target values to compare against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<T>, QmachinaError>`, where the `Ok` variant contains one partial derivative
+    /// per prediction and the `Err` variant encapsulates any errors that occurred during the
+    /// computation, under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[T]>, targets: Arc<[T]>) -> Result<Vec<T>>;
+
+    /// Computes the gradient of `compute_tensor` with respect to each prediction, in a tensor of
+    /// the shape of the predictions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_tensor`.
+    fn gradient_tensor(&self, predictions: &Tensor<T>, targets: &Tensor<T>) -> Result<Tensor<T>> where T: Float {
+        ensure_same_shape(predictions, targets)?;
+        let gradient = self.gradient(predictions.as_slice().into(), targets.as_slice().into())?;
+        Tensor::new(predictions.shape(), gradient)
+    }
+}
+
+/// How the per-sample losses of an `ElementwiseLoss` are reduced by `compute_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// The (weighted) average of the per-sample losses, `sum(w * l) / sum(w)`. Without weights,
+    /// this is the value returned by `compute`.
+    #[default]
+    Mean,
+    /// The (weighted) sum of the per-sample losses, `sum(w * l)`.
+    Sum,
+    /// No reduction, the (weighted) per-sample losses `w * l` are returned.
+    None
+}
+
+impl Reduction {
+    /// Reduces per-sample losses, optionally weighted.
+    ///
+    /// # Parameters
+    ///
+    /// * `losses` - The per-sample losses.
+    /// * `weights` - The optional non-negative weight of each sample, such as larger weights for
+    ///   recent observations.
+    ///
+    /// # Returns
+    ///
+    /// `LossOutput::Scalar` for `Mean` and `Sum`, and `LossOutput::Elementwise` for `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the weights do not have one value per loss, if a weight is negative,
+    /// NaN or infinite, or if the weights of a `Mean` reduction sum to zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::loss::{LossOutput, Reduction};
+    ///
+    /// let losses = vec![1.0, 2.0, 3.0];
+    /// let weights = [0.0, 1.0, 3.0];
+    ///
+    /// assert_eq!(Reduction::Mean.apply(losses.clone(), None).unwrap(), LossOutput::Scalar(2.0));
+    /// assert_eq!(Reduction::Mean.apply(losses.clone(), Some(&weights)).unwrap(), LossOutput::Scalar(2.75));
+    /// assert_eq!(Reduction::None.apply(losses, Some(&weights)).unwrap(), LossOutput::Elementwise(vec![0.0, 2.0, 9.0]));
+    /// ```
+    pub fn apply<F: Float>(self, losses: Vec<F>, weights: Option<&[F]>) -> Result<LossOutput<F>> {
+        let (losses, total_weight) = match weights {
+            Some(weights) => {
+                ensure_valid_weights(losses.len(), weights)?;
+
+                let weighted = losses.iter().zip(weights).map(|(&loss, &weight)| loss * weight).collect();
+                (weighted, weights.iter().fold(F::zero(), |sum, &weight| sum + weight))
+            }
+            None => {
+                let total_weight = count(losses.len());
+                (losses, total_weight)
+            }
+        };
+
+        match self {
+            Reduction::Mean => {
+                if total_weight == F::zero() {
+                    return Err(QmachinaError::UndefinedResult("The mean of losses without any weight is undefined."));
+                }
+                Ok(LossOutput::Scalar(sum(&losses) / total_weight))
+            }
+            Reduction::Sum => Ok(LossOutput::Scalar(sum(&losses))),
+            Reduction::None => Ok(LossOutput::Elementwise(losses))
+        }
+    }
+
+    /// Returns the derivative of the reduced loss with respect to each per-sample loss,
+    /// `w / sum(w)` for `Mean` and `w` for `Sum`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `apply`, and for `None`, which does not
+    /// reduce the losses to a single value.
+    pub(crate) fn scales<F: Float>(self, len: usize, weights: Option<&[F]>) -> Result<Vec<F>> {
+        let weights = match weights {
+            Some(weights) => {
+                ensure_valid_weights(len, weights)?;
+                weights.to_vec()
+            }
+            None => vec![F::one(); len]
+        };
+
+        match self {
+            Reduction::Mean => {
+                let total_weight = sum(&weights);
+                if total_weight == F::zero() {
+                    return Err(QmachinaError::UndefinedResult("The mean of losses without any weight is undefined."));
+                }
+                Ok(weights.into_iter().map(|weight| weight / total_weight).collect())
+            }
+            Reduction::Sum => Ok(weights),
+            Reduction::None => Err(QmachinaError::InvalidParameter("The gradient needs the losses reduced to a single value."))
+        }
+    }
+}
+
+/// Checks that there is one finite, non-negative weight per loss.
+fn ensure_valid_weights<F: Float>(len: usize, weights: &[F]) -> Result<()> {
+    ensure_same_length(len, weights.len())?;
+    ensure_finite(weights)?;
+    match weights.iter().position(|&weight| weight < F::zero()) {
+        Some(index) => Err(QmachinaError::InvalidValue { index }),
+        None => Ok(())
+    }
+}
+
+/// The value of a loss after a `Reduction`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LossOutput<F> {
+    /// A single value, from the `Mean` and `Sum` reductions.
+    Scalar(F),
+    /// One value per sample, from the `None` reduction.
+    Elementwise(Vec<F>)
+}
+
+impl<F: Copy> LossOutput<F> {
+    /// Returns the reduced value, or `None` for elementwise losses.
+    pub fn scalar(&self) -> Option<F> {
+        match self {
+            LossOutput::Scalar(value) => Some(*value),
+            LossOutput::Elementwise(_) => None
+        }
+    }
+
+    /// Returns the per-sample losses, or `None` for reduced losses.
+    pub fn elementwise(self) -> Option<Vec<F>> {
+        match self {
+            LossOutput::Scalar(_) => None,
+            LossOutput::Elementwise(values) => Some(values)
+        }
+    }
+}
+
+/// The `ElementwiseLoss` trait extends `LossFunction` for losses made of one term per sample,
+/// whose average is the value returned by `compute`.
+///
+/// Exposing the terms allows choosing how they are reduced and weighting the samples, for
+/// instance to give recent observations more importance in financial regressions.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::{ElementwiseLoss, LossOutput, Reduction};
+/// use qmachina::loss::mae::MeanAbsoluteErrorLossFunction;
+///
+/// let predictions: Arc<[f64]> = Arc::new([1.0, 2.0, 3.0]);
+/// let targets: Arc<[f64]> = Arc::new([2.0, 2.0, 5.0]);
+/// let weights: Arc<[f64]> = Arc::new([1.0, 1.0, 2.0]);
+///
+/// let losses = MeanAbsoluteErrorLossFunction.elementwise(predictions.clone(), targets.clone()).unwrap();
+/// assert_eq!(losses, vec![1.0, 0.0, 2.0]);
+///
+/// let loss = MeanAbsoluteErrorLossFunction
+///     .compute_with(predictions, targets, Some(weights), Reduction::Sum)
+///     .unwrap();
+/// assert_eq!(loss, LossOutput::Scalar(5.0));
+/// ```
+pub trait ElementwiseLoss<F: Float>: LossFunction<F> {
+    /// Computes the loss of each sample, without any reduction.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing predicted values from the model.
+    /// * `targets` - An `Arc<[F]>` containing the actual target values to compare against.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<F>, QmachinaError>` with one loss per sample, under the same error
+    /// conditions as `compute`.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>>;
+
+    /// Computes the loss with the given sample weights and reduction.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing predicted values from the model.
+    /// * `targets` - An `Arc<[F]>` containing the actual target values to compare against.
+    /// * `weights` - The optional non-negative weight of each sample.
+    /// * `reduction` - How the weighted per-sample losses are reduced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `elementwise` and `Reduction::apply`.
+    fn compute_with(
+        &self,
+        predictions: Arc<[F]>,
+        targets: Arc<[F]>,
+        weights: Option<Arc<[F]>>,
+        reduction: Reduction
+    ) -> Result<LossOutput<F>> {
+        reduction.apply(self.elementwise(predictions, targets)?, weights.as_deref())
+    }
+
+    /// Computes the gradient of `compute_with` with respect to each prediction, so that the
+    /// sample weights and the reduction also drive training.
+    ///
+    /// The default implementation scales the gradient of `compute`, which holds for losses
+    /// whose term of a sample only depends on its own prediction. Losses with coupled terms,
+    /// such as cross-entropies over logits, override it.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing predicted values from the model.
+    /// * `targets` - An `Arc<[F]>` containing the actual target values to compare against.
+    /// * `weights` - The optional non-negative weight of each sample.
+    /// * `reduction` - How the weighted per-sample losses are reduced, `Mean` or `Sum`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_with`, and for
+    /// `Reduction::None`, whose value is not a single loss.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use qmachina::loss::{ElementwiseLoss, Reduction};
+    /// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+    ///
+    /// let predictions: Arc<[f64]> = Arc::new([1.0, 2.0]);
+    /// let targets: Arc<[f64]> = Arc::new([0.0, 0.0]);
+    /// let weights: Arc<[f64]> = Arc::new([1.0, 3.0]);
+    ///
+    /// let gradient = MeanSquaredErrorLossFunction
+    ///     .gradient_with(predictions, targets, Some(weights), Reduction::Sum)
+    ///     .unwrap();
+    /// assert_eq!(gradient, vec![2.0, 12.0]);
+    /// ```
+    fn gradient_with(
+        &self,
+        predictions: Arc<[F]>,
+        targets: Arc<[F]>,
+        weights: Option<Arc<[F]>>,
+        reduction: Reduction
+    ) -> Result<Vec<F>> where Self: DifferentiableLoss<F> {
+        let scales = reduction.scales(predictions.len(), weights.as_deref())?;
+        let n = count::<F>(predictions.len());
+
+        Ok(self.gradient(predictions, targets)?
+            .into_iter()
+            .zip(scales)
+            .map(|(gradient, scale)| gradient * n * scale)
+            .collect())
+    }
+}
+
+/// The `BatchLossFunction` trait computes a loss over several samples at once, given as one row
+/// of predictions and one row of targets per sample, such as the class probabilities of each
+/// observation of a multi-class classifier.
+///
+/// Each implementor documents how a row is reduced to the loss of its sample. The loss of the
+/// batch is the average of the per-sample losses.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::loss::BatchLossFunction;
+/// use qmachina::loss::cce::CategoricalCrossEntropyLossFunction;
+///
+/// let loss_function = CategoricalCrossEntropyLossFunction;
+/// let predictions = vec![vec![0.5, 0.5, 0.0], vec![0.25, 0.25, 0.5]];
+/// let targets = vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]];
+///
+/// let loss: f64 = loss_function.compute_batch(&predictions, &targets).unwrap();
+/// assert!((loss - 2.0_f64.ln()).abs() < 1e-12);
+/// ```
+pub trait BatchLossFunction<F: Float> {
+    /// Computes the loss averaged over the samples of a batch.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - One row of predicted values per sample.
+    /// * `targets` - One row of target values per sample, each as long as its row of predictions.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<F, QmachinaError>` containing the average of the per-sample losses.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for a batch without samples, and
+    /// `QmachinaError::LengthMismatch` if the numbers of rows, or the lengths of paired rows,
+    /// differ. Errors about a value report its index in the flattened batch.
+    fn compute_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<F>;
+
+    /// Computes the gradient of `compute_batch` with respect to each prediction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<Vec<F>>, QmachinaError>` with one row of partial derivatives per sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch`.
+    fn gradient_batch(&self, predictions: &[Vec<F>], targets: &[Vec<F>]) -> Result<Vec<Vec<F>>>;
+
+    /// Computes the loss of a batch stored in 2-D tensors of the same shape, one row per sample,
+    /// as `compute_batch` does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes differ,
+    /// `QmachinaError::InvalidParameter` if the tensors are not 2-D, and an error under the same
+    /// conditions as `compute_batch` otherwise.
+    fn compute_batch_tensor(&self, predictions: &Tensor<F>, targets: &Tensor<F>) -> Result<F> {
+        ensure_same_shape(predictions, targets)?;
+        self.compute_batch(&predictions.to_rows()?, &targets.to_rows()?)
+    }
+
+    /// Computes the gradient of `compute_batch_tensor` with respect to each prediction, in a
+    /// tensor of the shape of the predictions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_batch_tensor`.
+    fn gradient_batch_tensor(&self, predictions: &Tensor<F>, targets: &Tensor<F>) -> Result<Tensor<F>> {
+        ensure_same_shape(predictions, targets)?;
+        Tensor::from_rows(&self.gradient_batch(&predictions.to_rows()?, &targets.to_rows()?)?)
+    }
+}
+
+/// Applies `row` to each sample of a batch, checking the shape of the batch and shifting the
+/// indices of the errors so that they point into the flattened batch.
+pub(crate) fn map_rows<F: Float, T>(
+    predictions: &[Vec<F>],
+    targets: &[Vec<F>],
+    mut row: impl FnMut(&[F], &[F]) -> Result<T>
+) -> Result<Vec<T>> {
+    if predictions.is_empty() {
+        return Err(QmachinaError::EmptyInput);
+    }
+    ensure_same_length(predictions.len(), targets.len())?;
+
+    let mut offset = 0;
+    predictions.iter()
+        .zip(targets)
+        .map(|(predictions, targets)| {
+            let value = row(predictions, targets).map_err(|error| error.offset(offset))?;
+            offset += predictions.len();
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Sums a slice of values.
+pub(crate) fn sum<F: Float>(values: &[F]) -> F {
+    values.iter().fold(F::zero(), |sum, &value| sum + value)
+}
+
+/// Averages a slice of values, as `compute` does with the terms of an `ElementwiseLoss`.
+pub(crate) fn mean<F: Float>(values: &[F]) -> F {
+    sum(values) / count(values.len())
+}
+
+/// Approximates the gradient of a loss with central finite differences.
+#[cfg(test)]
+pub(crate) fn numerical_gradient<L: LossFunction<f64>>(loss: &L, predictions: &[f64], targets: &[f64]) -> Vec<f64> {
+    let step = 1e-6;
+    let targets: Arc<[f64]> = targets.into();
+
+    (0..predictions.len())
+        .map(|index| {
+            let mut forward = predictions.to_vec();
+            let mut backward = predictions.to_vec();
+            forward[index] += step;
+            backward[index] -= step;
+
+            let forward = loss.compute(forward.into(), targets.clone()).unwrap();
+            let backward = loss.compute(backward.into(), targets.clone()).unwrap();
+            (forward - backward) / (2.0 * step)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::bce::BinaryCrossEntropyLossFunction;
+    use crate::loss::cce::{CategoricalCrossEntropyLossFunction, SoftmaxCrossEntropyLossFunction};
+    use crate::loss::directional::DirectionalLossFunction;
+    use crate::loss::huber::HuberLossFunction;
+    use crate::loss::log_cosh::LogCoshLossFunction;
+    use crate::loss::mae::MeanAbsoluteErrorLossFunction;
+    use crate::loss::mape::{MeanAbsolutePercentageErrorLossFunction, SymmetricMeanAbsolutePercentageErrorLossFunction};
+    use crate::loss::mse::MeanSquaredErrorLossFunction;
+    use crate::loss::quantile::QuantileLossFunction;
+
+    fn assert_reductions_match_compute<L: ElementwiseLoss<f64>>(loss: &L, predictions: &[f64], targets: &[f64]) {
+        let predictions: Arc<[f64]> = predictions.into();
+        let targets: Arc<[f64]> = targets.into();
+        let n = predictions.len() as f64;
+
+        let expected = loss.compute(predictions.clone(), targets.clone()).unwrap();
+        let mean = loss.compute_with(predictions.clone(), targets.clone(), None, Reduction::Mean).unwrap();
+        let sum = loss.compute_with(predictions.clone(), targets.clone(), None, Reduction::Sum).unwrap();
+        let elementwise = loss.compute_with(predictions, targets, None, Reduction::None).unwrap();
+
+        assert!((mean.scalar().unwrap() - expected).abs() < 1e-12, "Mean reduction should match compute");
+        assert!((sum.scalar().unwrap() - expected * n).abs() < 1e-12, "Sum reduction should be n times the mean");
+        assert_eq!(elementwise.elementwise().unwrap().len(), n as usize);
+    }
+
+    /// Checks `gradient_with` against central finite differences of `compute_with`, for a sum
+    /// without weights and for a weighted mean.
+    fn assert_gradient_with_matches<L>(loss: &L, predictions: &[f64], targets: &[f64])
+    where
+        L: ElementwiseLoss<f64> + DifferentiableLoss<f64>
+    {
+        let targets: Arc<[f64]> = targets.into();
+        let weights: Arc<[f64]> = Arc::new([0.5, 2.0, 1.0]);
+        let step = 1e-6;
+
+        for (weights, reduction) in [(None, Reduction::Sum), (Some(weights), Reduction::Mean)] {
+            let value = |predictions: Vec<f64>| {
+                loss.compute_with(predictions.into(), targets.clone(), weights.clone(), reduction).unwrap().scalar().unwrap()
+            };
+            let gradient = loss.gradient_with(predictions.into(), targets.clone(), weights.clone(), reduction).unwrap();
+
+            for (index, g) in gradient.iter().enumerate() {
+                let mut forward = predictions.to_vec();
+                let mut backward = predictions.to_vec();
+                forward[index] += step;
+                backward[index] -= step;
+                let expected = (value(forward) - value(backward)) / (2.0 * step);
+                assert!((g - expected).abs() < 1e-6, "{:?}: expected {}, got {}", reduction, expected, g);
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_with_matches_finite_differences_for_every_loss() {
+        let predictions = [0.2, 0.7, 0.1];
+        let targets = [0.0, 1.0, 0.0];
+
+        assert_gradient_with_matches(&MeanSquaredErrorLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&MeanAbsoluteErrorLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&HuberLossFunction::new(0.5), &predictions, &targets);
+        assert_gradient_with_matches(&BinaryCrossEntropyLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&CategoricalCrossEntropyLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&SoftmaxCrossEntropyLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&BinaryCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.1), &predictions, &targets);
+        assert_gradient_with_matches(&CategoricalCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.1), &predictions, &targets);
+        assert_gradient_with_matches(&QuantileLossFunction::new(0.9).unwrap(), &predictions, &targets);
+        assert_gradient_with_matches(&LogCoshLossFunction, &predictions, &targets);
+        assert_gradient_with_matches(&DirectionalLossFunction::new(10.0).unwrap(), &predictions, &[1.0, -1.0, 0.0]);
+        assert_gradient_with_matches(&MeanAbsolutePercentageErrorLossFunction, &predictions, &[0.1, 0.5, 0.3]);
+        assert_gradient_with_matches(&SymmetricMeanAbsolutePercentageErrorLossFunction, &predictions, &targets);
+    }
+
+    #[test]
+    fn gradient_with_matches_gradient_and_rejects_unreduced_losses() {
+        let predictions: Arc<[f64]> = Arc::new([0.2, 0.7, 0.1]);
+        let targets: Arc<[f64]> = Arc::new([0.0, 1.0, 0.0]);
+        let loss = HuberLossFunction::new(0.5);
+
+        let gradient = loss.gradient(predictions.clone(), targets.clone()).unwrap();
+        let mean = loss.gradient_with(predictions.clone(), targets.clone(), None, Reduction::Mean).unwrap();
+        for (g, m) in gradient.iter().zip(mean) {
+            assert!((g - m).abs() < 1e-15);
+        }
+
+        let result = loss.gradient_with(predictions.clone(), targets.clone(), None, Reduction::None);
+        assert!(matches!(result, Err(QmachinaError::InvalidParameter(_))));
+        let result = loss.gradient_with(predictions, targets, Some(Arc::new([1.0, -1.0, 0.0])), Reduction::Sum);
+        assert!(matches!(result, Err(QmachinaError::InvalidValue { index: 1 })));
+    }
+
+    #[test]
+    fn reductions_match_compute_for_every_loss() {
+        let predictions = [0.2, 0.7, 0.1];
+        let targets = [0.0, 1.0, 0.0];
+
+        assert_reductions_match_compute(&MeanSquaredErrorLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&MeanAbsoluteErrorLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&HuberLossFunction::new(0.5), &predictions, &targets);
+        assert_reductions_match_compute(&BinaryCrossEntropyLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&CategoricalCrossEntropyLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&SoftmaxCrossEntropyLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&BinaryCrossEntropyLossFunction.with_logits(true).with_label_smoothing(0.1), &predictions, &targets);
+        assert_reductions_match_compute(&CategoricalCrossEntropyLossFunction.with_epsilon(1e-7), &predictions, &targets);
+        assert_reductions_match_compute(&QuantileLossFunction::new(0.9).unwrap(), &predictions, &targets);
+        assert_reductions_match_compute(&LogCoshLossFunction, &predictions, &targets);
+        assert_reductions_match_compute(&DirectionalLossFunction::new(10.0).unwrap(), &predictions, &[1.0, -1.0, 0.0]);
+        assert_reductions_match_compute(&MeanAbsolutePercentageErrorLossFunction, &predictions, &[0.1, 0.5, 0.3]);
+        assert_reductions_match_compute(&SymmetricMeanAbsolutePercentageErrorLossFunction, &predictions, &targets);
+    }
+
+    #[test]
+    fn reduction_rejects_invalid_weights() {
+        let losses = vec![1.0, 2.0];
+
+        assert!(matches!(Reduction::Mean.apply(losses.clone(), Some(&[1.0])), Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })));
+        assert!(matches!(Reduction::Sum.apply(losses.clone(), Some(&[1.0, -1.0])), Err(QmachinaError::InvalidValue { index: 1 })));
+        assert!(matches!(Reduction::None.apply(losses.clone(), Some(&[f64::NAN, 1.0])), Err(QmachinaError::InvalidValue { index: 0 })));
+        assert!(matches!(Reduction::Mean.apply(losses, Some(&[0.0, 0.0])), Err(QmachinaError::UndefinedResult(_))));
+    }
+
+    #[test]
+    fn zero_weights_are_allowed_for_sums() {
+        assert_eq!(Reduction::Sum.apply(vec![1.0, 2.0], Some(&[0.0, 0.0])).unwrap(), LossOutput::Scalar(0.0));
+    }
+
+    #[test]
+    fn tensor_adapters_match_slices() {
+        let predictions = Tensor::new(&[2, 2], vec![0.2, 0.7, 0.1, 0.9]).unwrap();
+        let targets = Tensor::new(&[2, 2], vec![0.0, 1.0, 0.0, 1.0]).unwrap();
+        let loss = HuberLossFunction::new(0.5);
+
+        let expected = loss.compute(predictions.as_slice().into(), targets.as_slice().into()).unwrap();
+        assert_eq!(loss.compute_tensor(&predictions, &targets).unwrap(), expected);
+
+        let gradient = loss.gradient_tensor(&predictions, &targets).unwrap();
+        assert_eq!(gradient.shape(), &[2, 2]);
+        assert_eq!(gradient.into_vec(), loss.gradient(predictions.as_slice().into(), targets.as_slice().into()).unwrap());
+
+        let flat = Tensor::from_vec(vec![0.0, 1.0, 0.0, 1.0]);
+        assert!(matches!(loss.compute_tensor(&predictions, &flat), Err(QmachinaError::ShapeMismatch { .. })));
+    }
+
+    #[test]
+    fn batch_tensor_adapters_match_rows() {
+        let rows = vec![vec![0.5, -1.0, 2.0], vec![0.0, 3.0, 1.0]];
+        let targets = vec![vec![0.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]];
+        let loss = CategoricalCrossEntropyLossFunction.with_logits(true);
+
+        let predictions_tensor = Tensor::from_rows(&rows).unwrap();
+        let targets_tensor = Tensor::from_rows(&targets).unwrap();
+
+        assert_eq!(loss.compute_batch_tensor(&predictions_tensor, &targets_tensor).unwrap(), loss.compute_batch(&rows, &targets).unwrap());
+        assert_eq!(loss.gradient_batch_tensor(&predictions_tensor, &targets_tensor).unwrap().to_rows().unwrap(), loss.gradient_batch(&rows, &targets).unwrap());
+
+        let flat = Tensor::from_vec(vec![0.5, -1.0, 2.0]);
+        assert!(matches!(loss.compute_batch_tensor(&flat, &flat), Err(QmachinaError::InvalidParameter(_))));
+    }
 }
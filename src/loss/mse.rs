@@ -1,8 +1,6 @@
-use std::ops::Div;
-
-use super::LossFunction;
-
-use anyhow::{Result, anyhow};
+use crate::error::{ensure_same_length, Result};
+use crate::numeric::{constant, count, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
 
 /// Represents the Mean Squared Error (MSE) loss function for regression models.
 ///
@@ -10,7 +8,7 @@ use anyhow::{Result, anyhow};
 /// It measures the average of the squares of the errors—that is, the average squared 
 /// difference between the estimated values (predictions) and the actual value (targets).
 ///
-/// This struct implements the `LossFunction<f64>` trait, enabling its use in various
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`, enabling its use in various
 /// machine learning models where floating-point precision is required.
 ///
 /// # Mathematical Background
@@ -49,7 +47,7 @@ use anyhow::{Result, anyhow};
 /// It's primarily used in regression tasks where the target variable is continuous.
 pub struct MeanSquaredErrorLossFunction;
 
-impl LossFunction<f64> for MeanSquaredErrorLossFunction {
+impl<F: Float> LossFunction<F> for MeanSquaredErrorLossFunction {
     /// Computes the Mean Squared Error (MSE) between predictions and targets.
     ///
     /// This method calculates the MSE, a common loss function in regression,
@@ -59,12 +57,12 @@ impl LossFunction<f64> for MeanSquaredErrorLossFunction {
     ///
     /// # Parameters
     ///
-    /// * `predictions` - An `Arc<[f64]>` representing the predicted values from the model.
-    /// * `targets` - An `Arc<[f64]>` representing the actual target values.
+    /// * `predictions` - An `Arc<[F]>` representing the predicted values from the model.
+    /// * `targets` - An `Arc<[F]>` representing the actual target values.
     ///
     /// # Returns
     ///
-    /// A `Result<f64, anyhow::Error>`, where the `Ok` variant contains the computed MSE,
+    /// A `Result<F, QmachinaError>`, where the `Ok` variant contains the computed MSE,
     /// and the `Err` variant encapsulates errors, primarily when the lengths of predictions
     /// and targets arrays do not match.
     ///
@@ -72,24 +70,52 @@ impl LossFunction<f64> for MeanSquaredErrorLossFunction {
     ///
     /// This method returns an error if `predictions` and `targets` have different lengths,
     /// as it's not possible to compute MSE for mismatched data sets.
-    fn compute(&self, predictions: std::sync::Arc<[f64]>, targets: std::sync::Arc<[f64]>) -> Result<f64> {
-        if predictions.len() != targets.len() {
-            return Err(anyhow!("Predictions and targets must have the same length"));
-        }
+    fn compute(&self, predictions: std::sync::Arc<[F]>, targets: std::sync::Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for MeanSquaredErrorLossFunction {
+    /// Computes the squared error `(p - t)^2` of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: std::sync::Arc<[F]>, targets: std::sync::Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
 
-        let mse = predictions.iter()
+        let losses = predictions.iter()
             .zip(targets.iter())
-            .map(|(p, t)| (p - t).powi(2))
-            .sum::<f64>()
-            .div(predictions.len() as f64);
+            .map(|(&p, &t)| (p - t).powi(2))
+            .collect();
 
-        Ok(mse)
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for MeanSquaredErrorLossFunction {
+    /// Computes the gradient of the MSE with respect to each prediction, `2 * (p - t) / n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: std::sync::Arc<[F]>, targets: std::sync::Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| constant::<F>(2.0) * (p - t) / n)
+            .collect();
+
+        Ok(gradient)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::loss::{numerical_gradient, LossOutput, Reduction};
     use std::sync::Arc;
 
     /// Test MSE with perfect prediction.
@@ -145,7 +171,45 @@ mod tests {
         let predictions = Arc::new([1.5, 2.5, 3.5]);
         let targets = Arc::new([1.0, 3.0, 2.0]);
         let loss = mse_loss.compute(predictions, targets).unwrap();
-        let expected_loss = ((0.5f64.powi(2) + 0.5f64.powi(2) + 1.5f64.powi(2)) / 3.0) as f64;
+        let expected_loss = (0.5f64.powi(2) + 0.5f64.powi(2) + 1.5f64.powi(2)) / 3.0;
         assert_eq!(loss, expected_loss);
     }
+
+    /// Test the MSE gradient against finite differences.
+    #[test]
+    fn mse_gradient_matches_finite_differences() {
+        let predictions = [1.5, 2.5, 3.5, -0.7];
+        let targets = [1.0, 3.0, 2.0, 0.4];
+        let gradient = MeanSquaredErrorLossFunction.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&MeanSquaredErrorLossFunction, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+
+    /// Test the MSE gradient with mismatched lengths.
+    /// Expected result is an error.
+    #[test]
+    fn mse_gradient_mismatched_lengths() {
+        let result = MeanSquaredErrorLossFunction.gradient(Arc::new([1.0, 2.0]), Arc::new([1.0]));
+        assert!(result.is_err());
+    }
+
+    /// Test the MSE with sample weights favouring the most recent observation.
+    /// Expected result is the weighted average of the squared errors.
+    #[test]
+    fn mse_weighted_mean() {
+        let predictions: Arc<[f64]> = Arc::new([1.0, 2.0, 4.0]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 3.0, 2.0]);
+        let weights: Arc<[f64]> = Arc::new([1.0, 1.0, 2.0]);
+
+        let losses = MeanSquaredErrorLossFunction.elementwise(predictions.clone(), targets.clone()).unwrap();
+        assert_eq!(losses, vec![0.0, 1.0, 4.0]);
+
+        let loss = MeanSquaredErrorLossFunction
+            .compute_with(predictions, targets, Some(weights), Reduction::Mean)
+            .unwrap();
+        assert_eq!(loss, LossOutput::Scalar(9.0 / 4.0));
+    }
 }
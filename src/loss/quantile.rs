@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::{mean, DifferentiableLoss, ElementwiseLoss, LossFunction};
+
+/// Represents the Quantile (Pinball) Loss function for quantile regression.
+///
+/// Minimising the Quantile Loss makes the predictions estimate the `q`-th quantile of the
+/// targets instead of their mean. Training one model per quantile, such as 0.05 and 0.95,
+/// yields forecasting intervals, for instance for the returns of an asset.
+///
+/// This struct implements the `LossFunction<F>` trait for `f32` and `f64`.
+///
+/// # Mathematical Background
+///
+/// With the error `e = target - prediction`, the Quantile Loss is defined as:
+///
+/// \[
+/// L_q(e) = \max(q \cdot e, (q - 1) \cdot e)
+/// \]
+///
+/// Under-predictions are penalised with a slope of `q` and over-predictions with a slope of
+/// `1 - q`. For `q = 0.5`, it is half the Mean Absolute Error.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::quantile::QuantileLossFunction;
+///
+/// let quantile_loss = QuantileLossFunction::new(0.9).unwrap();
+/// let predictions: Arc<[f64]> = Arc::new([1.0, 3.0]);
+/// let targets: Arc<[f64]> = Arc::new([2.0, 2.0]);
+///
+/// // The under-prediction costs 0.9, the over-prediction only 0.1.
+/// let loss = quantile_loss.compute(predictions, targets).unwrap();
+/// assert!((loss - 0.5).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct QuantileLossFunction<F = f64> {
+    quantile: F
+}
+
+impl<F: Float> QuantileLossFunction<F> {
+    /// Creates a new instance of the Quantile Loss Function for the given quantile.
+    ///
+    /// # Parameters
+    ///
+    /// * `quantile` - The quantile `q` to estimate, strictly between 0 and 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the quantile is not strictly between 0 and 1.
+    pub fn new(quantile: F) -> Result<Self> {
+        if !(quantile > F::zero() && quantile < F::one()) {
+            return Err(QmachinaError::InvalidParameter("The quantile must be strictly between 0 and 1."));
+        }
+
+        Ok(Self { quantile })
+    }
+
+    /// Returns the quantile estimated by this loss.
+    pub fn quantile(&self) -> F {
+        self.quantile
+    }
+}
+
+impl<F: Float> LossFunction<F> for QuantileLossFunction<F> {
+    /// Computes the Quantile Loss between predictions and targets, averaged over all elements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        Ok(mean(&self.elementwise(predictions, targets)?))
+    }
+}
+
+impl<F: Float> ElementwiseLoss<F> for QuantileLossFunction<F> {
+    /// Computes the Quantile Loss of each prediction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn elementwise(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let losses = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                let error = t - p;
+                (self.quantile * error).max((self.quantile - F::one()) * error)
+            })
+            .collect();
+
+        Ok(losses)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for QuantileLossFunction<F> {
+    /// Computes the gradient of the Quantile Loss with respect to each prediction, `-q / n` for
+    /// under-predictions and `(1 - q) / n` for over-predictions.
+    ///
+    /// The loss is not differentiable where a prediction equals its target, so the subgradient
+    /// 0 is used there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the predictions and targets have different lengths.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        ensure_same_length(predictions.len(), targets.len())?;
+
+        let n = count::<F>(predictions.len());
+        let gradient = predictions.iter()
+            .zip(targets.iter())
+            .map(|(&p, &t)| {
+                if p < t {
+                    -self.quantile / n
+                } else if p > t {
+                    (F::one() - self.quantile) / n
+                } else {
+                    F::zero()
+                }
+            })
+            .collect();
+
+        Ok(gradient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::mae::MeanAbsoluteErrorLossFunction;
+    use crate::loss::numerical_gradient;
+
+    /// Test the Quantile Loss with an asymmetric quantile.
+    /// Expected result is a loss weighting under-predictions by `q` and over-predictions by `1 - q`.
+    #[test]
+    fn quantile_asymmetric_penalties() {
+        let quantile_loss = QuantileLossFunction::new(0.25).unwrap();
+        let losses = quantile_loss.elementwise(Arc::new([1.0, 3.0, 2.0]), Arc::new([2.0, 2.0, 2.0])).unwrap();
+        assert_eq!(losses, vec![0.25, 0.75, 0.0]);
+    }
+
+    /// Test the median Quantile Loss.
+    /// Expected result is half the Mean Absolute Error.
+    #[test]
+    fn quantile_median_is_half_mae() {
+        let predictions: Arc<[f64]> = Arc::new([1.5, 2.5, 3.5]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 3.0, 2.0]);
+
+        let loss = QuantileLossFunction::new(0.5).unwrap().compute(predictions.clone(), targets.clone()).unwrap();
+        let mae = MeanAbsoluteErrorLossFunction.compute(predictions, targets).unwrap();
+        assert!((loss - mae / 2.0).abs() < 1e-12);
+    }
+
+    /// Test quantiles outside of `(0, 1)`.
+    /// Expected result is an error.
+    #[test]
+    fn quantile_invalid_quantile() {
+        assert!(matches!(QuantileLossFunction::new(0.0), Err(QmachinaError::InvalidParameter(_))));
+        assert!(QuantileLossFunction::new(1.0).is_err());
+        assert!(QuantileLossFunction::new(f64::NAN).is_err());
+    }
+
+    /// Test the Quantile Loss gradient against finite differences, away from the kink at zero error.
+    #[test]
+    fn quantile_gradient_matches_finite_differences() {
+        let quantile_loss = QuantileLossFunction::new(0.9).unwrap();
+        let predictions = [2.5, 0.0, 2.1, 1.8];
+        let targets = [3.0, -0.5, 2.0, 2.0];
+        let gradient = quantile_loss.gradient(Arc::new(predictions), Arc::new(targets)).unwrap();
+        let expected = numerical_gradient(&quantile_loss, &predictions, &targets);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-6, "Expected {}, got {}", e, g);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, ensure_sufficient, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::{DifferentiableLoss, LossFunction};
+
+/// Represents a loss equal to the negative Sharpe ratio of a strategy, for training
+/// position-sizing networks.
+///
+/// The predictions are the positions taken at each period and the targets are the returns of
+/// the traded asset over the same periods, so the strategy earns `r_i = position_i * return_i`.
+/// Minimising this loss maximises the risk-adjusted return of the strategy over the batch,
+/// rather than the accuracy of a return forecast.
+///
+/// Unlike the other losses, the Sharpe ratio is not a sum of per-sample terms: the gradient of
+/// every position depends on the mean and volatility of the whole batch.
+///
+/// # Mathematical Background
+///
+/// With the mean `\mu` and the population standard deviation `\sigma` of the strategy returns,
+/// and `A` periods per year:
+///
+/// \[
+/// L = -\sqrt{A} \cdot \frac{\mu}{\sigma}
+/// \]
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::loss::LossFunction;
+/// use qmachina::loss::sharpe::NegativeSharpeLossFunction;
+///
+/// let sharpe_loss = NegativeSharpeLossFunction::new().with_periods_per_year(252);
+/// let positions: Arc<[f64]> = Arc::new([1.0, 1.0, -1.0, 0.5]);
+/// let returns: Arc<[f64]> = Arc::new([0.01, 0.02, -0.01, 0.0]);
+///
+/// let loss = sharpe_loss.compute(positions, returns).unwrap();
+/// assert!(loss < 0.0, "A profitable strategy has a negative loss");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NegativeSharpeLossFunction {
+    periods_per_year: usize
+}
+
+impl NegativeSharpeLossFunction {
+    /// Creates a new instance of the Negative Sharpe Loss Function, without annualisation.
+    pub fn new() -> Self {
+        Self { periods_per_year: 1 }
+    }
+
+    /// Annualises the Sharpe ratio by `sqrt(periods_per_year)`, such as 252 for daily returns.
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn with_periods_per_year(mut self, periods_per_year: usize) -> Self {
+        self.periods_per_year = periods_per_year.max(1);
+        self
+    }
+
+    /// Returns the number of periods per year used to annualise the Sharpe ratio.
+    pub fn periods_per_year(&self) -> usize {
+        self.periods_per_year
+    }
+
+    /// Checks the inputs and returns the strategy returns with their mean and standard deviation.
+    fn statistics<F: Float>(positions: &[F], returns: &[F]) -> Result<(Vec<F>, F, F)> {
+        ensure_same_length(positions.len(), returns.len())?;
+        ensure_sufficient(2, positions.len())?;
+
+        let strategy: Vec<F> = positions.iter().zip(returns.iter()).map(|(&p, &r)| p * r).collect();
+        let n = count::<F>(strategy.len());
+        let mean = strategy.iter().fold(F::zero(), |sum, &r| sum + r) / n;
+        let variance = strategy.iter().fold(F::zero(), |sum, &r| sum + (r - mean).powi(2)) / n;
+
+        if variance == F::zero() {
+            return Err(QmachinaError::UndefinedResult("The Sharpe ratio is undefined without volatility."));
+        }
+
+        Ok((strategy, mean, variance.sqrt()))
+    }
+}
+
+impl Default for NegativeSharpeLossFunction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> LossFunction<F> for NegativeSharpeLossFunction {
+    /// Computes the negative (annualised) Sharpe ratio of the strategy.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the position taken at each period.
+    /// * `targets` - An `Arc<[F]>` containing the return of the asset at each period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the positions and returns have different lengths, if there are fewer
+    /// than two periods, or if the strategy returns have no volatility.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        let (_, mean, std_dev) = Self::statistics(&predictions, &targets)?;
+
+        Ok(-count::<F>(self.periods_per_year).sqrt() * mean / std_dev)
+    }
+}
+
+impl<F: Float> DifferentiableLoss<F> for NegativeSharpeLossFunction {
+    /// Computes the gradient of the loss with respect to each position,
+    /// `-sqrt(A) * return_i * (1 / (n * sigma) - mu * (r_i - mu) / (n * sigma^3))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute`.
+    fn gradient(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<Vec<F>> {
+        let (strategy, mean, std_dev) = Self::statistics(&predictions, &targets)?;
+        let n = count::<F>(strategy.len());
+        let scale = count::<F>(self.periods_per_year).sqrt();
+
+        Ok(strategy.iter()
+            .zip(targets.iter())
+            .map(|(&r, &asset_return)| {
+                let sharpe_gradient = F::one() / (n * std_dev) - mean * (r - mean) / (n * std_dev.powi(3));
+                -scale * asset_return * sharpe_gradient
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::numerical_gradient;
+
+    /// Test the loss against the Sharpe ratio computed by hand.
+    #[test]
+    fn sharpe_matches_definition() {
+        let positions: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 1.0]);
+        let returns: Arc<[f64]> = Arc::new([0.01, 0.03, -0.01, 0.01]);
+
+        // Mean 0.01, population standard deviation sqrt(0.0002).
+        let loss = NegativeSharpeLossFunction::new().compute(positions.clone(), returns.clone()).unwrap();
+        assert!((loss + 0.01 / 0.0002_f64.sqrt()).abs() < 1e-12);
+
+        let annualised = NegativeSharpeLossFunction::new().with_periods_per_year(4).compute(positions, returns).unwrap();
+        assert!((annualised - 2.0 * loss).abs() < 1e-12);
+    }
+
+    /// Test the loss when the strategy returns are constant, or there is a single period.
+    /// Expected result is an error.
+    #[test]
+    fn sharpe_undefined_cases() {
+        let sharpe_loss = NegativeSharpeLossFunction::new();
+        let flat: Result<f64> = sharpe_loss.compute(Arc::new([1.0, 1.0]), Arc::new([0.01, 0.01]));
+        assert!(matches!(flat, Err(QmachinaError::UndefinedResult(_))));
+
+        let single: Result<f64> = sharpe_loss.compute(Arc::new([1.0]), Arc::new([0.01]));
+        assert!(matches!(single, Err(QmachinaError::InsufficientData { required: 2, got: 1 })));
+    }
+
+    /// Test the gradient against finite differences.
+    #[test]
+    fn sharpe_gradient_matches_finite_differences() {
+        let sharpe_loss = NegativeSharpeLossFunction::new().with_periods_per_year(252);
+        let positions = [0.5, -0.2, 1.0, 0.8, -1.0];
+        let returns = [0.01, -0.02, 0.015, -0.005, 0.002];
+        let gradient = sharpe_loss.gradient(Arc::new(positions), Arc::new(returns)).unwrap();
+        let expected = numerical_gradient(&sharpe_loss, &positions, &returns);
+
+        for (g, e) in gradient.iter().zip(expected) {
+            assert!((g - e).abs() < 1e-4 * e.abs().max(1.0), "Expected {}, got {}", e, g);
+        }
+    }
+}
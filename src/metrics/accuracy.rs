@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::numeric::Float;
+use super::confusion_matrix::confusion_matrix;
+use super::{Metric, DEFAULT_THRESHOLD};
+
+/// Represents the accuracy of a binary classifier, the fraction of samples whose class is predicted correctly.
+///
+/// Accuracy is easy to interpret but misleading on imbalanced classes, where always predicting
+/// the majority class scores well. Precision, recall or the Matthews correlation are then more
+/// informative.
+///
+/// Predictions greater than or equal to the threshold, 0.5 by default, are classified as
+/// positive. This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::accuracy::AccuracyMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.3, 0.6, 0.1]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let accuracy = AccuracyMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+/// assert_eq!(accuracy, 0.6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccuracyMetric {
+    threshold: f64
+}
+
+impl AccuracyMetric {
+    /// Constructs a new `AccuracyMetric` with the default threshold of 0.5.
+    pub fn new() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD }
+    }
+
+    /// Sets the value from which a prediction is classified as positive.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The classification threshold. A NaN or infinite threshold is reported
+    ///   as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Returns the classification threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for AccuracyMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for AccuracyMetric {
+    /// Computes the accuracy of the predictions.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is
+    /// NaN or infinite, if a target is neither 0 nor 1, or if the threshold is invalid.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        confusion_matrix(&predictions, &targets, self.threshold)?.accuracy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the accuracy with the default and a custom threshold.
+    #[test]
+    fn accuracy_with_thresholds() {
+        let predictions: Arc<[f64]> = Arc::new([0.9, 0.55, 0.4, 0.7, 0.2]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+
+        assert_eq!(AccuracyMetric::new().compute(predictions.clone(), targets.clone()).unwrap(), 0.6);
+        assert_eq!(AccuracyMetric::new().with_threshold(0.75).compute(predictions, targets).unwrap(), 0.6);
+    }
+
+    /// Test the accuracy with invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn accuracy_invalid_inputs() {
+        let accuracy = AccuracyMetric::new();
+        assert!(matches!(accuracy.compute(Arc::new([0.5, 0.5]), Arc::new([1.0])), Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })));
+        assert!(matches!(accuracy.with_threshold(f64::INFINITY).compute(Arc::new([0.5]), Arc::new([1.0])), Err(QmachinaError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::{ensure_binary, Metric};
+
+/// Represents the Brier score of a probabilistic binary classifier, the mean squared difference
+/// between the predicted probabilities and the actual classes.
+///
+/// The Brier score measures both discrimination and calibration: it ranges from 0 for perfect
+/// predictions to 1 for confident, wrong ones, and always predicting 1/2 scores 0.25. Unlike the
+/// log-loss, it stays bounded when a prediction is certain and wrong.
+///
+/// \[
+/// BS = \frac{1}{n} \sum_{i=1}^{n} (p_i - y_i)^2
+/// \]
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::brier::BrierScoreMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.2, 0.5]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 1.0]);
+///
+/// let brier: f64 = BrierScoreMetric.compute(predictions, targets).unwrap();
+/// assert!((brier - 0.1).abs() < 1e-12);
+/// ```
+pub struct BrierScoreMetric;
+
+impl<F: Float> Metric<F> for BrierScoreMetric {
+    /// Computes the Brier score of the predicted probabilities.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities of the positive class.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is not
+    /// a probability, or if a target is neither 0 nor 1.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        ensure_same_length(predictions.len(), targets.len())?;
+        if predictions.is_empty() {
+            return Err(QmachinaError::EmptyInput);
+        }
+        if let Some(index) = predictions.iter().position(|p| !(F::zero()..=F::one()).contains(p)) {
+            return Err(QmachinaError::InvalidProbability { index });
+        }
+        ensure_binary(&targets)?;
+
+        let squared_errors = predictions.iter()
+            .zip(targets.iter())
+            .fold(F::zero(), |sum, (&p, &t)| sum + (p - t).powi(2));
+
+        Ok(squared_errors / count(predictions.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the Brier score of perfect, uninformative and inverted predictions.
+    #[test]
+    fn brier_score_bounds() {
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0]);
+
+        assert_eq!(BrierScoreMetric.compute(Arc::new([1.0, 0.0]), targets.clone()).unwrap(), 0.0);
+        assert_eq!(BrierScoreMetric.compute(Arc::new([0.5, 0.5]), targets.clone()).unwrap(), 0.25);
+        assert_eq!(BrierScoreMetric.compute(Arc::new([0.0, 1.0]), targets).unwrap(), 1.0);
+    }
+
+    /// Test the Brier score with invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn brier_score_invalid_inputs() {
+        assert!(matches!(BrierScoreMetric.compute(Arc::new([0.5, 1.2]), Arc::new([1.0, 0.0])), Err(QmachinaError::InvalidProbability { index: 1 })));
+        assert!(matches!(BrierScoreMetric.compute(Arc::new([0.5]), Arc::new([0.5])), Err(QmachinaError::InvalidValue { index: 0 })));
+        assert!(matches!(BrierScoreMetric.compute(Arc::<[f64]>::from(vec![]), Arc::from(vec![])), Err(QmachinaError::EmptyInput)));
+    }
+}
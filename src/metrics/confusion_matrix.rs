@@ -0,0 +1,242 @@
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, to_f64, Float};
+use super::{ensure_binary, ensure_threshold};
+
+/// Represents the confusion matrix of a binary classifier, the number of samples of each class
+/// predicted as each class.
+///
+/// A prediction greater than or equal to the threshold is classified as positive. Every metric
+/// comparing classes derives from these four counts:
+///
+/// |                 | Predicted positive | Predicted negative |
+/// |-----------------|--------------------|--------------------|
+/// | Actual positive | `true_positives`   | `false_negatives`  |
+/// | Actual negative | `false_positives`  | `true_negatives`   |
+///
+/// # Example
+///
+/// ```
+/// use qmachina::metrics::confusion_matrix::ConfusionMatrix;
+///
+/// let predictions = [0.9, 0.8, 0.3, 0.6, 0.1];
+/// let targets = [1.0, 1.0, 1.0, 0.0, 0.0];
+///
+/// let matrix = ConfusionMatrix::from_predictions(&predictions, &targets, 0.5).unwrap();
+/// assert_eq!((matrix.true_positives, matrix.false_negatives), (2, 1));
+/// assert_eq!((matrix.false_positives, matrix.true_negatives), (1, 1));
+/// assert_eq!(matrix.precision::<f64>().unwrap(), 2.0 / 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConfusionMatrix {
+    /// Positive samples predicted as positive.
+    pub true_positives: usize,
+    /// Negative samples predicted as positive.
+    pub false_positives: usize,
+    /// Negative samples predicted as negative.
+    pub true_negatives: usize,
+    /// Positive samples predicted as negative.
+    pub false_negatives: usize
+}
+
+impl ConfusionMatrix {
+    /// Counts the samples of each class predicted as each class.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - The predicted probabilities or scores.
+    /// * `targets` - The actual classes, `0` or `1`.
+    /// * `threshold` - The value from which a prediction is classified as positive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is NaN
+    /// or infinite, if a target is neither 0 nor 1, or if the threshold is NaN or infinite.
+    pub fn from_predictions<F: Float>(predictions: &[F], targets: &[F], threshold: F) -> Result<Self> {
+        ensure_same_length(predictions.len(), targets.len())?;
+        if predictions.is_empty() {
+            return Err(QmachinaError::EmptyInput);
+        }
+        ensure_finite(predictions)?;
+        ensure_binary(targets)?;
+        ensure_threshold(to_f64(threshold))?;
+
+        let mut matrix = Self::default();
+        for (&p, &t) in predictions.iter().zip(targets) {
+            match (p >= threshold, t == F::one()) {
+                (true, true) => matrix.true_positives += 1,
+                (true, false) => matrix.false_positives += 1,
+                (false, false) => matrix.true_negatives += 1,
+                (false, true) => matrix.false_negatives += 1
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Returns the number of samples.
+    pub fn total(&self) -> usize {
+        self.true_positives + self.false_positives + self.true_negatives + self.false_negatives
+    }
+
+    /// Returns the fraction of samples whose class is predicted correctly, `(TP + TN) / total`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedResult` for a matrix without samples.
+    pub fn accuracy<F: Float>(&self) -> Result<F> {
+        ratio(
+            self.true_positives + self.true_negatives,
+            self.total(),
+            "The accuracy is undefined without samples."
+        )
+    }
+
+    /// Returns the fraction of positive predictions that are correct, `TP / (TP + FP)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedResult` without positive predictions.
+    pub fn precision<F: Float>(&self) -> Result<F> {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_positives,
+            "The precision is undefined without positive predictions."
+        )
+    }
+
+    /// Returns the fraction of positive samples that are predicted as positive, `TP / (TP + FN)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedResult` without positive samples.
+    pub fn recall<F: Float>(&self) -> Result<F> {
+        ratio(
+            self.true_positives,
+            self.true_positives + self.false_negatives,
+            "The recall is undefined without positive samples."
+        )
+    }
+
+    /// Returns the harmonic mean of the precision and the recall, `2TP / (2TP + FP + FN)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedResult` without positive samples nor positive predictions.
+    pub fn f1_score<F: Float>(&self) -> Result<F> {
+        ratio(
+            2 * self.true_positives,
+            2 * self.true_positives + self.false_positives + self.false_negatives,
+            "The F1 score is undefined without positive samples nor positive predictions."
+        )
+    }
+
+    /// Returns the Matthews correlation coefficient, the correlation between the predicted and
+    /// the actual classes, `(TP * TN - FP * FN) / sqrt((TP + FP)(TP + FN)(TN + FP)(TN + FN))`.
+    ///
+    /// It ranges from -1 for inverted predictions to 1 for perfect predictions, 0 being no better
+    /// than chance, and remains informative when the classes are imbalanced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::UndefinedResult` when a row or a column of the matrix is empty,
+    /// such as when every sample is predicted as the same class.
+    pub fn matthews_correlation<F: Float>(&self) -> Result<F> {
+        let (tp, fp) = (count::<F>(self.true_positives), count::<F>(self.false_positives));
+        let (tn, fn_) = (count::<F>(self.true_negatives), count::<F>(self.false_negatives));
+
+        let denominator = (tp + fp) * (tp + fn_) * (tn + fp) * (tn + fn_);
+        if denominator == F::zero() {
+            return Err(QmachinaError::UndefinedResult(
+                "The Matthews correlation is undefined when a class is never predicted or never observed."
+            ));
+        }
+
+        Ok((tp * tn - fp * fn_) / denominator.sqrt())
+    }
+}
+
+/// Divides two counts, reporting a zero denominator as `QmachinaError::UndefinedResult`.
+fn ratio<F: Float>(numerator: usize, denominator: usize, undefined: &'static str) -> Result<F> {
+    if denominator == 0 {
+        return Err(QmachinaError::UndefinedResult(undefined));
+    }
+
+    Ok(count::<F>(numerator) / count::<F>(denominator))
+}
+
+/// Builds the confusion matrix of a metric configured with an `f64` threshold.
+pub(crate) fn confusion_matrix<F: Float>(predictions: &[F], targets: &[F], threshold: f64) -> Result<ConfusionMatrix> {
+    ensure_threshold(threshold)?;
+    ConfusionMatrix::from_predictions(predictions, targets, constant(threshold))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> ConfusionMatrix {
+        ConfusionMatrix { true_positives: 6, false_positives: 2, true_negatives: 9, false_negatives: 3 }
+    }
+
+    /// Test counting the samples with the default and a custom threshold.
+    #[test]
+    fn confusion_matrix_counts() {
+        let predictions = [0.9, 0.5, 0.4, 0.7, 0.2, 0.1];
+        let targets = [1.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+
+        let matrix = ConfusionMatrix::from_predictions(&predictions, &targets, 0.5).unwrap();
+        assert_eq!(matrix, ConfusionMatrix { true_positives: 2, false_positives: 1, true_negatives: 2, false_negatives: 1 });
+        assert_eq!(matrix.total(), 6);
+
+        let matrix = ConfusionMatrix::from_predictions(&predictions, &targets, 0.15).unwrap();
+        assert_eq!(matrix, ConfusionMatrix { true_positives: 3, false_positives: 2, true_negatives: 1, false_negatives: 0 });
+    }
+
+    /// Test the metrics derived from the counts.
+    #[test]
+    fn confusion_matrix_metrics() {
+        let matrix = matrix();
+
+        assert_eq!(matrix.accuracy::<f64>().unwrap(), 0.75);
+        assert_eq!(matrix.precision::<f64>().unwrap(), 0.75);
+        assert_eq!(matrix.recall::<f64>().unwrap(), 2.0 / 3.0);
+
+        let f1: f64 = matrix.f1_score().unwrap();
+        assert!((f1 - 2.0 * 0.75 * (2.0 / 3.0) / (0.75 + 2.0 / 3.0)).abs() < 1e-12);
+
+        let mcc: f64 = matrix.matthews_correlation().unwrap();
+        assert!((mcc - (54.0 - 6.0) / (8.0_f64 * 9.0 * 11.0 * 12.0).sqrt()).abs() < 1e-12);
+    }
+
+    /// Test the metrics when a class is missing.
+    /// Expected result is an error for the undefined ratios.
+    #[test]
+    fn confusion_matrix_undefined_metrics() {
+        let matrix = ConfusionMatrix { true_positives: 0, false_positives: 0, true_negatives: 4, false_negatives: 0 };
+
+        assert_eq!(matrix.accuracy::<f64>().unwrap(), 1.0);
+        assert!(matches!(matrix.precision::<f64>(), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(matrix.recall::<f64>(), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(matrix.f1_score::<f64>(), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(matrix.matthews_correlation::<f64>(), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(ConfusionMatrix::default().accuracy::<f64>(), Err(QmachinaError::UndefinedResult(_))));
+    }
+
+    /// Test counting invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn confusion_matrix_invalid_inputs() {
+        assert!(matches!(ConfusionMatrix::from_predictions(&[0.5], &[1.0, 0.0], 0.5), Err(QmachinaError::LengthMismatch { expected: 1, got: 2 })));
+        assert!(matches!(ConfusionMatrix::from_predictions::<f64>(&[], &[], 0.5), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(ConfusionMatrix::from_predictions(&[0.5, f64::NAN], &[1.0, 0.0], 0.5), Err(QmachinaError::InvalidValue { index: 1 })));
+        assert!(matches!(ConfusionMatrix::from_predictions(&[0.5, 0.2], &[1.0, 0.3], 0.5), Err(QmachinaError::InvalidValue { index: 1 })));
+        assert!(matches!(ConfusionMatrix::from_predictions(&[0.5], &[1.0], f64::NAN), Err(QmachinaError::InvalidParameter(_))));
+    }
+
+    /// Test counting single-precision predictions.
+    #[test]
+    fn confusion_matrix_f32() {
+        let matrix = ConfusionMatrix::from_predictions(&[0.7_f32, 0.2], &[1.0, 0.0], 0.5).unwrap();
+        assert_eq!(matrix.accuracy::<f32>().unwrap(), 1.0);
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::numeric::Float;
+use super::confusion_matrix::confusion_matrix;
+use super::{Metric, DEFAULT_THRESHOLD};
+
+/// Represents the F1 score of a binary classifier, the harmonic mean of its precision and recall.
+///
+/// The F1 score balances false positives and false negatives in a single value, and is only high
+/// when both the precision and the recall are.
+///
+/// Predictions greater than or equal to the threshold, 0.5 by default, are classified as
+/// positive. This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::f1::F1ScoreMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.3, 0.6, 0.1]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let f1 = F1ScoreMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+/// assert!((f1 - 2.0 / 3.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct F1ScoreMetric {
+    threshold: f64
+}
+
+impl F1ScoreMetric {
+    /// Constructs a new `F1ScoreMetric` with the default threshold of 0.5.
+    pub fn new() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD }
+    }
+
+    /// Sets the value from which a prediction is classified as positive.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The classification threshold. A NaN or infinite threshold is reported
+    ///   as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Returns the classification threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for F1ScoreMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for F1ScoreMetric {
+    /// Computes the F1 score of the predictions.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is
+    /// NaN or infinite, if a target is neither 0 nor 1, or if the threshold is invalid.
+    /// Without positive samples nor positive predictions, the F1 score is undefined and
+    /// `QmachinaError::UndefinedResult` is returned.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        confusion_matrix(&predictions, &targets, self.threshold)?.f1_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the F1 score against the harmonic mean of the precision and the recall.
+    #[test]
+    fn f1_is_harmonic_mean() {
+        let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.7, 0.4, 0.3, 0.1]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 1.0, 1.0, 1.0, 0.0]);
+
+        let f1: f64 = F1ScoreMetric::new().compute(predictions, targets).unwrap();
+        let (precision, recall) = (2.0 / 3.0, 0.5);
+        assert!((f1 - 2.0 * precision * recall / (precision + recall)).abs() < 1e-12);
+    }
+
+    /// Test the F1 score without positive samples nor positive predictions.
+    /// Expected result is an error.
+    #[test]
+    fn f1_without_positives() {
+        let result = F1ScoreMetric::new().compute(Arc::new([0.1, 0.2]), Arc::new([0.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedResult(_))));
+    }
+}
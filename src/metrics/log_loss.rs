@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::loss::LossFunction;
+use crate::loss::bce::BinaryCrossEntropyLossFunction;
+use crate::numeric::Float;
+use super::Metric;
+
+/// The margin by which predicted probabilities are clipped away from 0 and 1, unless configured
+/// otherwise. It is small enough to barely affect well-calibrated predictions while remaining
+/// representable next to 1 in single precision.
+pub const DEFAULT_EPSILON: f64 = 1e-7;
+
+/// Represents the log-loss of a probabilistic binary classifier, the average negative
+/// log-likelihood of the actual classes under the predicted probabilities.
+///
+/// The log-loss is the Binary Cross-Entropy used as a metric. To evaluate a classifier that may
+/// be certain, and wrong, the probabilities are clipped to `[epsilon, 1 - epsilon]`, so that such
+/// a prediction is heavily penalized instead of making the metric infinite.
+///
+/// This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::log_loss::LogLossMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.5, 1.0]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0]);
+///
+/// let log_loss: f64 = LogLossMetric::new().compute(predictions, targets).unwrap();
+/// assert!(log_loss.is_finite() && log_loss > 8.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogLossMetric {
+    epsilon: f64
+}
+
+impl LogLossMetric {
+    /// Constructs a new `LogLossMetric` clipping probabilities by `DEFAULT_EPSILON`.
+    pub fn new() -> Self {
+        Self { epsilon: DEFAULT_EPSILON }
+    }
+
+    /// Sets the margin by which probabilities are clipped away from 0 and 1.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The clipping margin, between 0 and 1/2 (exclusive). Other values are
+    ///   reported as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_epsilon(self, epsilon: f64) -> Self {
+        Self { epsilon }
+    }
+
+    /// Returns the clipping margin.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+impl Default for LogLossMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for LogLossMetric {
+    /// Computes the log-loss of the predicted probabilities.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities of the positive class.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`, or probabilities.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `BinaryCrossEntropyLossFunction::compute`
+    /// with clipping: mismatched lengths, predictions outside `[0, 1]` or an invalid epsilon.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        BinaryCrossEntropyLossFunction
+            .with_epsilon(self.epsilon)
+            .compute(predictions, targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the log-loss against the Binary Cross-Entropy of unclipped probabilities.
+    #[test]
+    fn log_loss_matches_bce() {
+        let predictions: Arc<[f64]> = Arc::new([0.8, 0.3, 0.6]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 0.0]);
+
+        let log_loss = LogLossMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+        let bce = BinaryCrossEntropyLossFunction.compute(predictions, targets).unwrap();
+        assert_eq!(log_loss, bce);
+    }
+
+    /// Test the log-loss of confident, wrong predictions.
+    /// Expected result is the negative logarithm of the clipping margin.
+    #[test]
+    fn log_loss_clips_probabilities() {
+        let log_loss: f32 = LogLossMetric::new().compute(Arc::new([0.0_f32, 1.0]), Arc::new([1.0, 0.0])).unwrap();
+        // 1 - 1e-7 rounds to the nearest single-precision value below 1, about 1 - 1.2e-7
+        assert!(log_loss.is_finite() && log_loss > 15.0);
+
+        let log_loss: f64 = LogLossMetric::new().with_epsilon(1e-3).compute(Arc::new([0.0]), Arc::new([1.0])).unwrap();
+        assert!((log_loss + (1e-3_f64).ln()).abs() < 1e-12);
+    }
+
+    /// Test the log-loss with invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn log_loss_invalid_inputs() {
+        let result = LogLossMetric::new().compute(Arc::new([1.5]), Arc::new([1.0]));
+        assert!(matches!(result, Err(QmachinaError::InvalidProbability { index: 0 })));
+
+        let result = LogLossMetric::new().with_epsilon(0.0).compute(Arc::new([0.5]), Arc::new([1.0]));
+        assert!(matches!(result, Err(QmachinaError::InvalidParameter(_))));
+    }
+}
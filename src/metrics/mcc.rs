@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::numeric::Float;
+use super::confusion_matrix::confusion_matrix;
+use super::{Metric, DEFAULT_THRESHOLD};
+
+/// Represents the Matthews correlation coefficient of a binary classifier, the correlation between its predicted and actual classes.
+///
+/// The coefficient ranges from -1 for inverted predictions to 1 for perfect predictions, 0 being no
+/// better than chance. Unlike the accuracy, it stays informative when the classes are imbalanced.
+///
+/// Predictions greater than or equal to the threshold, 0.5 by default, are classified as
+/// positive. This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::mcc::MatthewsCorrelationMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.3, 0.6, 0.1]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let mcc = MatthewsCorrelationMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+/// assert!((mcc - 1.0 / 6.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatthewsCorrelationMetric {
+    threshold: f64
+}
+
+impl MatthewsCorrelationMetric {
+    /// Constructs a new `MatthewsCorrelationMetric` with the default threshold of 0.5.
+    pub fn new() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD }
+    }
+
+    /// Sets the value from which a prediction is classified as positive.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The classification threshold. A NaN or infinite threshold is reported
+    ///   as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Returns the classification threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for MatthewsCorrelationMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for MatthewsCorrelationMetric {
+    /// Computes the Matthews correlation coefficient of the predictions.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is
+    /// NaN or infinite, if a target is neither 0 nor 1, or if the threshold is invalid.
+    /// When a class is never predicted or never observed, the coefficient is undefined and
+    /// `QmachinaError::UndefinedResult` is returned.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        confusion_matrix(&predictions, &targets, self.threshold)?.matthews_correlation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the coefficient of perfect, inverted and imbalanced predictions.
+    #[test]
+    fn mcc_ranges_from_inverted_to_perfect() {
+        let mcc = MatthewsCorrelationMetric::new();
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 1.0, 0.0]);
+
+        assert_eq!(mcc.compute(Arc::new([0.9, 0.1, 0.8, 0.2]), targets.clone()).unwrap(), 1.0);
+        assert_eq!(mcc.compute(Arc::new([0.1, 0.9, 0.2, 0.8]), targets).unwrap(), -1.0);
+
+        // Always predicting the majority class gives a high accuracy but an undefined coefficient
+        let result = mcc.compute(Arc::new([0.1, 0.1, 0.1, 0.1]), Arc::new([0.0, 0.0, 0.0, 1.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedResult(_))));
+    }
+}
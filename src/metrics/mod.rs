@@ -0,0 +1,102 @@
+//! This module contains evaluation metrics for binary classifiers.
+//!
+//! Metrics take the same layout as `LossFunction`: one prediction per sample, either a predicted
+//! probability or a score, and one target per sample, `1` for the positive class and `0` for the
+//! negative class. Unlike losses, metrics are not meant to be differentiated, but to report how
+//! well a trained classifier performs.
+//!
+//! Metrics comparing classes, such as accuracy or precision, first turn the predictions into
+//! classes with a threshold, and are all derived from a `ConfusionMatrix`. Ranking metrics, such
+//! as ROC-AUC, use the predictions as scores, and probabilistic metrics, such as the Brier score,
+//! use them as probabilities.
+
+use std::sync::Arc;
+
+use crate::error::{QmachinaError, Result};
+use crate::numeric::Float;
+
+pub mod confusion_matrix;
+pub mod accuracy;
+pub mod precision;
+pub mod recall;
+pub mod f1;
+pub mod mcc;
+pub mod roc_auc;
+pub mod pr_auc;
+pub mod log_loss;
+pub mod brier;
+
+/// The threshold above which a predicted probability is classified as positive, unless
+/// configured otherwise.
+pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// The `Metric` trait defines a common interface for evaluation metrics, computed from
+/// predictions and actual target values.
+///
+/// It mirrors `LossFunction`, so the predictions of a model can be passed to its loss and to its
+/// metrics alike.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::accuracy::AccuracyMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.2, 0.6, 0.4]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 0.0, 0.0]);
+///
+/// let accuracy = AccuracyMetric::new().compute(predictions, targets).unwrap();
+/// assert_eq!(accuracy, 0.75);
+/// ```
+pub trait Metric<T> {
+    /// Computes the metric based on the provided predictions and target values.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[T]>` containing predicted values from the model.
+    /// * `targets` - An `Arc<[T]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<T, QmachinaError>`, where the `Ok` variant contains the value of the metric
+    /// and the `Err` variant encapsulates any errors that occurred during the computation.
+    fn compute(&self, predictions: Arc<[T]>, targets: Arc<[T]>) -> Result<T>;
+}
+
+/// Checks that every target is a class, `0` or `1`.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InvalidValue` with the index of the first other target.
+pub(crate) fn ensure_binary<F: Float>(targets: &[F]) -> Result<()> {
+    match targets.iter().position(|&target| target != F::zero() && target != F::one()) {
+        Some(index) => Err(QmachinaError::InvalidValue { index }),
+        None => Ok(())
+    }
+}
+
+/// Checks that a threshold can be compared with predictions.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InvalidParameter` if the threshold is NaN or infinite.
+pub(crate) fn ensure_threshold(threshold: f64) -> Result<()> {
+    if !threshold.is_finite() {
+        return Err(QmachinaError::InvalidParameter("The threshold must be finite."));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_binary_reports_first_invalid_target() {
+        assert!(ensure_binary(&[0.0, 1.0, 1.0]).is_ok());
+        assert!(matches!(ensure_binary(&[0.0, 0.5, 2.0]), Err(QmachinaError::InvalidValue { index: 1 })));
+        assert!(matches!(ensure_binary(&[f64::NAN]), Err(QmachinaError::InvalidValue { index: 0 })));
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::{ensure_binary, Metric};
+
+/// Represents the area under the Precision-Recall curve (PR-AUC) of a binary classifier,
+/// computed as its average precision.
+///
+/// The PR curve plots the precision against the recall over every threshold. Unlike the ROC
+/// curve, it ignores the true negatives, which makes it the better summary when positives are
+/// rare, such as large market moves or fraudulent transactions. A random ranking scores the
+/// fraction of positive samples rather than 0.5.
+///
+/// # Mathematical Background
+///
+/// Going through the distinct scores from the highest, each taken as a threshold, the average
+/// precision weights the precision `P_k` at each threshold by the increase in recall `R_k`:
+///
+/// \[
+/// AP = \sum_{k} (R_k - R_{k-1}) \cdot P_k
+/// \]
+///
+/// This step-wise sum does not interpolate between the points of the curve, which would
+/// overestimate the area.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::pr_auc::PrAucMetric;
+///
+/// let scores: Arc<[f64]> = Arc::new([0.1, 0.4, 0.35, 0.8]);
+/// let targets: Arc<[f64]> = Arc::new([0.0, 0.0, 1.0, 1.0]);
+///
+/// let average_precision: f64 = PrAucMetric.compute(scores, targets).unwrap();
+/// assert!((average_precision - (0.5 * 1.0 + 0.5 * 2.0 / 3.0)).abs() < 1e-12);
+/// ```
+pub struct PrAucMetric;
+
+impl<F: Float> Metric<F> for PrAucMetric {
+    /// Computes the average precision of the scores.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores. Only
+    ///   their order matters.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs have different lengths, if a prediction is NaN or infinite,
+    /// or if a target is neither 0 nor 1. The area is undefined without positive samples, and
+    /// `QmachinaError::UndefinedResult` is then returned.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        ensure_same_length(predictions.len(), targets.len())?;
+        ensure_finite(&predictions)?;
+        ensure_binary(&targets)?;
+
+        let positives = targets.iter().filter(|&&t| t == F::one()).count();
+        if positives == 0 {
+            return Err(QmachinaError::UndefinedResult("The PR-AUC is undefined without positive samples."));
+        }
+
+        let mut order: Vec<usize> = (0..predictions.len()).collect();
+        order.sort_by(|&a, &b| predictions[b].partial_cmp(&predictions[a]).unwrap_or(Ordering::Equal));
+
+        // Lower the threshold one distinct score at a time, so that tied samples enter together
+        let mut area = F::zero();
+        let (mut true_positives, mut previous_recall) = (0, F::zero());
+        let mut start = 0;
+        while start < order.len() {
+            let mut end = start + 1;
+            while end < order.len() && predictions[order[end]] == predictions[order[start]] {
+                end += 1;
+            }
+
+            true_positives += order[start..end].iter().filter(|&&index| targets[index] == F::one()).count();
+            let precision = count::<F>(true_positives) / count::<F>(end);
+            let recall = count::<F>(true_positives) / count::<F>(positives);
+
+            area += (recall - previous_recall) * precision;
+            previous_recall = recall;
+            start = end;
+        }
+
+        Ok(area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the average precision of perfect and inverted rankings.
+    #[test]
+    fn pr_auc_bounds() {
+        let targets: Arc<[f64]> = Arc::new([0.0, 0.0, 1.0, 1.0]);
+
+        assert_eq!(PrAucMetric.compute(Arc::new([0.1, 0.2, 0.8, 0.9]), targets.clone()).unwrap(), 1.0);
+
+        let inverted: f64 = PrAucMetric.compute(Arc::new([0.9, 0.8, 0.2, 0.1]), targets).unwrap();
+        assert!((inverted - (0.5 / 3.0 + 0.5 * 0.5)).abs() < 1e-12);
+    }
+
+    /// Test the average precision with tied scores.
+    /// Expected result is the fraction of positives when every score is tied.
+    #[test]
+    fn pr_auc_with_ties() {
+        let targets: Arc<[f64]> = Arc::new([0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(PrAucMetric.compute(Arc::new([0.5, 0.5, 0.5, 0.5]), targets.clone()).unwrap(), 0.25);
+
+        let average_precision: f64 = PrAucMetric.compute(Arc::new([0.9, 0.7, 0.7, 0.1]), targets).unwrap();
+        assert!((average_precision - 1.0 / 3.0).abs() < 1e-12);
+    }
+
+    /// Test the average precision without positive samples or with invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn pr_auc_invalid_inputs() {
+        assert!(matches!(PrAucMetric.compute(Arc::new([0.1, 0.9]), Arc::new([0.0, 0.0])), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(PrAucMetric.compute(Arc::new([0.1, 0.9]), Arc::new([1.0])), Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })));
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::numeric::Float;
+use super::confusion_matrix::confusion_matrix;
+use super::{Metric, DEFAULT_THRESHOLD};
+
+/// Represents the precision of a binary classifier, the fraction of positive predictions that are correct.
+///
+/// Precision matters when false positives are costly, such as trading signals whose false alarms
+/// each open a losing position.
+///
+/// Predictions greater than or equal to the threshold, 0.5 by default, are classified as
+/// positive. This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::precision::PrecisionMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.3, 0.6, 0.1]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let precision = PrecisionMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+/// assert_eq!(precision, 2.0 / 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecisionMetric {
+    threshold: f64
+}
+
+impl PrecisionMetric {
+    /// Constructs a new `PrecisionMetric` with the default threshold of 0.5.
+    pub fn new() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD }
+    }
+
+    /// Sets the value from which a prediction is classified as positive.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The classification threshold. A NaN or infinite threshold is reported
+    ///   as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Returns the classification threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for PrecisionMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for PrecisionMetric {
+    /// Computes the precision of the predictions.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is
+    /// NaN or infinite, if a target is neither 0 nor 1, or if the threshold is invalid.
+    /// Without positive predictions, the precision is undefined and
+    /// `QmachinaError::UndefinedResult` is returned.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        confusion_matrix(&predictions, &targets, self.threshold)?.precision()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the precision of predictions with false positives.
+    #[test]
+    fn precision_with_false_positives() {
+        let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.7, 0.6, 0.1]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 0.0, 1.0, 0.0, 1.0]);
+
+        assert_eq!(PrecisionMetric::new().compute(predictions.clone(), targets.clone()).unwrap(), 0.5);
+        assert_eq!(PrecisionMetric::new().with_threshold(0.65).compute(predictions, targets).unwrap(), 2.0 / 3.0);
+    }
+
+    /// Test the precision without positive predictions.
+    /// Expected result is an error.
+    #[test]
+    fn precision_without_positive_predictions() {
+        let result = PrecisionMetric::new().compute(Arc::new([0.1, 0.2]), Arc::new([1.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedResult(_))));
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::numeric::Float;
+use super::confusion_matrix::confusion_matrix;
+use super::{Metric, DEFAULT_THRESHOLD};
+
+/// Represents the recall of a binary classifier, the fraction of positive samples that are predicted as positive.
+///
+/// Recall, also called sensitivity or true positive rate, matters when false negatives are
+/// costly, such as missed defaults in credit scoring.
+///
+/// Predictions greater than or equal to the threshold, 0.5 by default, are classified as
+/// positive. This struct implements the `Metric<F>` trait for `f32` and `f64`.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::recall::RecallMetric;
+///
+/// let predictions: Arc<[f64]> = Arc::new([0.9, 0.8, 0.3, 0.6, 0.1]);
+/// let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 0.0, 0.0]);
+///
+/// let recall = RecallMetric::new().compute(predictions.clone(), targets.clone()).unwrap();
+/// assert_eq!(recall, 2.0 / 3.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecallMetric {
+    threshold: f64
+}
+
+impl RecallMetric {
+    /// Constructs a new `RecallMetric` with the default threshold of 0.5.
+    pub fn new() -> Self {
+        Self { threshold: DEFAULT_THRESHOLD }
+    }
+
+    /// Sets the value from which a prediction is classified as positive.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` - The classification threshold. A NaN or infinite threshold is reported
+    ///   as `QmachinaError::InvalidParameter` when the metric is computed.
+    pub fn with_threshold(self, threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Returns the classification threshold.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+impl Default for RecallMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float> Metric<F> for RecallMetric {
+    /// Computes the recall of the predictions.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs are empty or have different lengths, if a prediction is
+    /// NaN or infinite, if a target is neither 0 nor 1, or if the threshold is invalid.
+    /// Without positive samples, the recall is undefined and `QmachinaError::UndefinedResult` is
+    /// returned.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        confusion_matrix(&predictions, &targets, self.threshold)?.recall()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+
+    /// Test the recall of predictions with false negatives.
+    #[test]
+    fn recall_with_false_negatives() {
+        let predictions: Arc<[f64]> = Arc::new([0.9, 0.4, 0.3, 0.6, 0.1]);
+        let targets: Arc<[f64]> = Arc::new([1.0, 1.0, 1.0, 1.0, 0.0]);
+
+        assert_eq!(RecallMetric::new().compute(predictions.clone(), targets.clone()).unwrap(), 0.5);
+        assert_eq!(RecallMetric::new().with_threshold(0.2).compute(predictions, targets).unwrap(), 1.0);
+    }
+
+    /// Test the recall without positive samples.
+    /// Expected result is an error.
+    #[test]
+    fn recall_without_positive_samples() {
+        let result = RecallMetric::new().compute(Arc::new([0.9, 0.2]), Arc::new([0.0, 0.0]));
+        assert!(matches!(result, Err(QmachinaError::UndefinedResult(_))));
+    }
+}
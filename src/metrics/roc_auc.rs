@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use crate::numeric::{constant, count, Float};
+use super::{ensure_binary, Metric};
+
+/// Represents the area under the Receiver Operating Characteristic curve (ROC-AUC) of a binary
+/// classifier.
+///
+/// The ROC curve plots the true positive rate against the false positive rate over every
+/// threshold, and its area is the probability that a random positive sample is scored above a
+/// random negative sample. It is 1 for a perfect ranking and 0.5 for a random one, independently
+/// of any threshold and of the class balance.
+///
+/// # Mathematical Background
+///
+/// The area is computed from the ranks of the scores, as the Mann-Whitney U statistic:
+///
+/// \[
+/// AUC = \frac{\sum_{i \in P} r_i - |P| (|P| + 1) / 2}{|P| \cdot |N|}
+/// \]
+///
+/// where `P` and `N` are the positive and negative samples and `r_i` is the rank of the score of
+/// sample `i`. Tied scores get their average rank, so that a tie between a positive and a negative
+/// sample counts for one half.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use qmachina::metrics::Metric;
+/// use qmachina::metrics::roc_auc::RocAucMetric;
+///
+/// let scores: Arc<[f64]> = Arc::new([0.1, 0.4, 0.35, 0.8]);
+/// let targets: Arc<[f64]> = Arc::new([0.0, 0.0, 1.0, 1.0]);
+///
+/// assert_eq!(RocAucMetric.compute(scores, targets).unwrap(), 0.75);
+/// ```
+pub struct RocAucMetric;
+
+impl<F: Float> Metric<F> for RocAucMetric {
+    /// Computes the ROC-AUC of the scores.
+    ///
+    /// # Parameters
+    ///
+    /// * `predictions` - An `Arc<[F]>` containing the predicted probabilities or scores. Only
+    ///   their order matters.
+    /// * `targets` - An `Arc<[F]>` containing the actual classes, `0` or `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inputs have different lengths, if a prediction is NaN or infinite,
+    /// or if a target is neither 0 nor 1. The area is undefined, and
+    /// `QmachinaError::UndefinedResult` is returned, unless both classes are present.
+    fn compute(&self, predictions: Arc<[F]>, targets: Arc<[F]>) -> Result<F> {
+        ensure_same_length(predictions.len(), targets.len())?;
+        ensure_finite(&predictions)?;
+        ensure_binary(&targets)?;
+
+        let positives = targets.iter().filter(|&&t| t == F::one()).count();
+        let negatives = targets.len() - positives;
+        if positives == 0 || negatives == 0 {
+            return Err(QmachinaError::UndefinedResult("The ROC-AUC is undefined unless both classes are present."));
+        }
+
+        let mut order: Vec<usize> = (0..predictions.len()).collect();
+        order.sort_by(|&a, &b| predictions[a].partial_cmp(&predictions[b]).unwrap_or(Ordering::Equal));
+
+        // Sum the ranks of the positive samples, giving tied scores their average rank
+        let mut rank_sum = F::zero();
+        let mut start = 0;
+        while start < order.len() {
+            let mut end = start + 1;
+            while end < order.len() && predictions[order[end]] == predictions[order[start]] {
+                end += 1;
+            }
+
+            let average_rank = count::<F>(start + end + 1) / constant(2.0);
+            let tied_positives = order[start..end].iter().filter(|&&index| targets[index] == F::one()).count();
+            rank_sum += average_rank * count::<F>(tied_positives);
+            start = end;
+        }
+
+        let (positives, negatives) = (count::<F>(positives), count::<F>(negatives));
+        Ok((rank_sum - positives * (positives + F::one()) / constant(2.0)) / (positives * negatives))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares every positive score with every negative score.
+    fn pairwise_auc(scores: &[f64], targets: &[f64]) -> f64 {
+        let of_class = |class: f64| scores.iter().zip(targets).filter(move |(_, &t)| t == class).map(|(&s, _)| s);
+
+        let mut total = 0.0;
+        let mut pairs = 0.0;
+        for positive in of_class(1.0) {
+            for negative in of_class(0.0) {
+                total += if positive > negative { 1.0 } else if positive == negative { 0.5 } else { 0.0 };
+                pairs += 1.0;
+            }
+        }
+        total / pairs
+    }
+
+    /// Test the ROC-AUC of perfect, inverted and uninformative scores.
+    #[test]
+    fn roc_auc_bounds() {
+        let targets: Arc<[f64]> = Arc::new([0.0, 0.0, 1.0, 1.0]);
+
+        assert_eq!(RocAucMetric.compute(Arc::new([0.1, 0.2, 0.8, 0.9]), targets.clone()).unwrap(), 1.0);
+        assert_eq!(RocAucMetric.compute(Arc::new([0.9, 0.8, 0.2, 0.1]), targets.clone()).unwrap(), 0.0);
+        assert_eq!(RocAucMetric.compute(Arc::new([0.5, 0.5, 0.5, 0.5]), targets).unwrap(), 0.5);
+    }
+
+    /// Test the ROC-AUC with tied scores against the pairwise comparison of the samples.
+    #[test]
+    fn roc_auc_matches_pairwise_comparison() {
+        let scores = [0.3, -1.2, 0.3, 2.5, 0.7, 0.3, -0.4, 0.7];
+        let targets = [1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0];
+
+        let auc = RocAucMetric.compute(Arc::new(scores), Arc::new(targets)).unwrap();
+        assert!((auc - pairwise_auc(&scores, &targets)).abs() < 1e-12);
+    }
+
+    /// Test the ROC-AUC with a single class or invalid inputs.
+    /// Expected result is an error.
+    #[test]
+    fn roc_auc_invalid_inputs() {
+        assert!(matches!(RocAucMetric.compute(Arc::new([0.1, 0.9]), Arc::new([1.0, 1.0])), Err(QmachinaError::UndefinedResult(_))));
+        assert!(matches!(RocAucMetric.compute(Arc::new([0.1, f64::NAN]), Arc::new([0.0, 1.0])), Err(QmachinaError::InvalidValue { index: 1 })));
+        assert!(matches!(RocAucMetric.compute(Arc::new([0.1, 0.9]), Arc::new([0.0, 2.0])), Err(QmachinaError::InvalidValue { index: 1 })));
+    }
+}
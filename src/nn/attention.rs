@@ -0,0 +1,499 @@
+//! This module contains the scaled dot-product attention and the multi-head self-attention layer
+//! built on it.
+use crate::activation::ActivationFunction;
+use crate::activation::identity::IdentityActivationFunction;
+use crate::activation::softmax::SoftmaxActivationFunction;
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use super::conv::Conv1d;
+use super::Layer;
+
+/// The seed used to initialise the weights when none is given.
+const DEFAULT_SEED: u64 = 0x0A77_E4D5;
+
+/// The gradients of the queries, keys and values of an attention, one row per time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttentionGradients {
+    /// The gradient with respect to each query.
+    pub queries: Vec<Vec<f64>>,
+    /// The gradient with respect to each key.
+    pub keys: Vec<Vec<f64>>,
+    /// The gradient with respect to each value.
+    pub values: Vec<Vec<f64>>
+}
+
+/// Represents the scaled dot-product attention of Vaswani et al. (2017),
+/// `softmax(Q * K^T / sqrt(d_k)) * V`.
+///
+/// Each query is compared with every key through their dot product, scaled by the square root
+/// of their size `d_k`. `SoftmaxActivationFunction` turns the scores of a query into weights,
+/// with which the values are averaged. With a causal mask, a query only attends to the keys up
+/// to its own time step, so that a forecaster cannot look ahead.
+///
+/// The attention caches its inputs and weights during `forward` so that `backward` can compute
+/// the gradients of the queries, keys and values. It has no parameters of its own.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::attention::ScaledDotProductAttention;
+///
+/// let mut attention = ScaledDotProductAttention::new().with_causal_mask(true);
+/// let queries = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+/// let values = vec![vec![2.0], vec![4.0]];
+///
+/// let output = attention.forward(&queries, &queries, &values).unwrap();
+/// // The first time step can only attend to itself
+/// assert_eq!(output[0], vec![2.0]);
+/// assert_eq!(attention.weights()[0], vec![1.0, 0.0]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScaledDotProductAttention {
+    causal: bool,
+    queries: Vec<Vec<f64>>,
+    keys: Vec<Vec<f64>>,
+    values: Vec<Vec<f64>>,
+    scores: Vec<Vec<f64>>,
+    weights: Vec<Vec<f64>>
+}
+
+impl ScaledDotProductAttention {
+    /// Constructs a new `ScaledDotProductAttention` without a mask.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `ScaledDotProductAttention` with or without a causal mask.
+    pub fn with_causal_mask(mut self, causal: bool) -> Self {
+        self.causal = causal;
+        self
+    }
+
+    /// Returns the attention weights of the last forward pass, one row per query and one column
+    /// per key. Each row sums to one.
+    pub fn weights(&self) -> &[Vec<f64>] {
+        &self.weights
+    }
+
+    /// Returns the number of keys a query attends to.
+    fn visible(&self, query: usize) -> usize {
+        if self.causal { query + 1 } else { self.keys.len() }
+    }
+
+    /// Attends from each query to the keys and averages the values with the attention weights.
+    ///
+    /// # Parameters
+    ///
+    /// * `queries` - One query of `d_k` values per time step.
+    /// * `keys` - One key of `d_k` values per time step.
+    /// * `values` - One value per key, all of the same size.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Vec<f64>>)` containing one output, of the size of the values, per query.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` if there are no queries or no keys, and
+    /// `QmachinaError::LengthMismatch` if the keys and the values differ in number, if the rows
+    /// differ in size or if a causal attention is not given as many queries as keys.
+    pub fn forward(&mut self, queries: &[Vec<f64>], keys: &[Vec<f64>], values: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+        if queries.is_empty() || keys.is_empty() {
+            return Err(QmachinaError::EmptyInput);
+        }
+        ensure_same_length(keys.len(), values.len())?;
+        if self.causal {
+            ensure_same_length(keys.len(), queries.len())?;
+        }
+        let size = queries[0].len();
+        for row in queries.iter().chain(keys) {
+            ensure_same_length(size, row.len())?;
+        }
+        for row in values {
+            ensure_same_length(values[0].len(), row.len())?;
+        }
+
+        self.queries = queries.to_vec();
+        self.keys = keys.to_vec();
+        self.values = values.to_vec();
+
+        let scale = (size.max(1) as f64).sqrt();
+        self.scores = (0..queries.len())
+            .map(|query| {
+                self.keys[..self.visible(query)].iter()
+                    .map(|key| queries[query].iter().zip(key).map(|(q, k)| q * k).sum::<f64>() / scale)
+                    .collect()
+            })
+            .collect();
+        self.weights = self.scores.iter()
+            .map(|scores| {
+                let mut weights = SoftmaxActivationFunction.activate(scores);
+                weights.resize(keys.len(), 0.0);
+                weights
+            })
+            .collect();
+
+        Ok(self.weights.iter()
+            .map(|weights| {
+                let mut output = vec![0.0; values[0].len()];
+                for (weight, value) in weights.iter().zip(values) {
+                    for (output, value) in output.iter_mut().zip(value) {
+                        *output += weight * value;
+                    }
+                }
+                output
+            })
+            .collect())
+    }
+
+    /// Backpropagates the gradient of the outputs through the averaging of the values and the
+    /// Softmax of the scores.
+    ///
+    /// # Parameters
+    ///
+    /// * `output_gradient` - The gradient of the loss with respect to each output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient does not match the
+    /// outputs of the last forward pass.
+    pub fn backward(&mut self, output_gradient: &[Vec<f64>]) -> Result<AttentionGradients> {
+        if self.weights.is_empty() {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.queries.len(), output_gradient.len())?;
+
+        let scale = (self.queries[0].len().max(1) as f64).sqrt();
+        let mut gradients = AttentionGradients {
+            queries: vec![vec![0.0; self.queries[0].len()]; self.queries.len()],
+            keys: vec![vec![0.0; self.keys[0].len()]; self.keys.len()],
+            values: vec![vec![0.0; self.values[0].len()]; self.values.len()]
+        };
+
+        for (query, gradient) in output_gradient.iter().enumerate() {
+            ensure_same_length(self.values[0].len(), gradient.len())?;
+            let visible = self.visible(query);
+
+            let mut weight_gradient = Vec::with_capacity(visible);
+            for (key, value) in self.values[..visible].iter().enumerate() {
+                weight_gradient.push(gradient.iter().zip(value).map(|(g, v)| g * v).sum::<f64>());
+                for (value_gradient, g) in gradients.values[key].iter_mut().zip(gradient) {
+                    *value_gradient += self.weights[query][key] * g;
+                }
+            }
+
+            let score_gradient = SoftmaxActivationFunction.jacobian_vector_product(&self.scores[query], &weight_gradient)?;
+            for (key, score_gradient) in score_gradient.iter().enumerate() {
+                let delta = score_gradient / scale;
+                for (query_gradient, k) in gradients.queries[query].iter_mut().zip(&self.keys[key]) {
+                    *query_gradient += delta * k;
+                }
+                for (key_gradient, q) in gradients.keys[key].iter_mut().zip(&self.queries[query]) {
+                    *key_gradient += delta * q;
+                }
+            }
+        }
+
+        Ok(gradients)
+    }
+}
+
+/// Represents a multi-head self-attention layer over a sequence.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `model_size`
+/// features. Position-wise projections, convolutions of kernel size 1, map every time step to a
+/// query, a key and a value, which are split into `heads` slices of `model_size / heads`
+/// features. Each head runs a `ScaledDotProductAttention` over its slices, letting the heads
+/// attend to different time steps, and the concatenated outputs are projected back to
+/// `model_size` features per time step.
+///
+/// The projections are initialised from the Xavier uniform distribution, while their biases
+/// start at zero. The number of heads must divide the model size.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::attention::MultiHeadAttention;
+///
+/// // Five time steps of four features, attended by two heads
+/// let mut attention = MultiHeadAttention::new(4, 2).with_causal_mask(true);
+/// let input: Vec<f64> = (0..20).map(|index| (index as f64).sin()).collect();
+///
+/// let output = attention.forward(&input).unwrap();
+/// assert_eq!(output.len(), 20);
+/// assert_eq!(attention.attention()[1].weights()[0], vec![1.0, 0.0, 0.0, 0.0, 0.0]);
+/// ```
+pub struct MultiHeadAttention {
+    model_size: usize,
+    query: Conv1d,
+    key: Conv1d,
+    value: Conv1d,
+    output: Conv1d,
+    attention: Vec<ScaledDotProductAttention>
+}
+
+impl MultiHeadAttention {
+    /// Constructs a new `MultiHeadAttention` layer without a mask.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_size` - The number of features of each time step.
+    /// * `heads` - The number of attention heads, which must divide the model size.
+    pub fn new(model_size: usize, heads: usize) -> Self {
+        let projection = || Conv1d::new(model_size, model_size, 1, Box::new(IdentityActivationFunction));
+
+        Self {
+            model_size,
+            query: projection(),
+            key: projection(),
+            value: projection(),
+            output: projection(),
+            attention: vec![ScaledDotProductAttention::new(); heads]
+        }.with_seed(DEFAULT_SEED)
+    }
+
+    /// Returns the `MultiHeadAttention` layer with the weights of its projections re-initialised
+    /// from seeds derived from the given one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the `MultiHeadAttention` layer with or without a causal mask in every head.
+    pub fn with_causal_mask(mut self, causal: bool) -> Self {
+        self.attention = self.attention.into_iter().map(|head| head.with_causal_mask(causal)).collect();
+        self
+    }
+
+    /// Returns the number of features of each time step.
+    pub fn model_size(&self) -> usize {
+        self.model_size
+    }
+
+    /// Returns the number of attention heads.
+    pub fn heads(&self) -> usize {
+        self.attention.len()
+    }
+
+    /// Returns the attention of each head, whose weights are those of the last forward pass.
+    pub fn attention(&self) -> &[ScaledDotProductAttention] {
+        &self.attention
+    }
+
+    /// Returns the number of features of each head.
+    fn head_size(&self) -> Result<usize> {
+        if self.attention.is_empty() || !self.model_size.is_multiple_of(self.attention.len()) {
+            return Err(QmachinaError::InvalidParameter("The number of heads must be positive and divide the model size."));
+        }
+
+        Ok(self.model_size / self.attention.len())
+    }
+
+    /// Returns the slices of a head in a flattened sequence, one row per time step.
+    fn split(&self, sequence: &[f64], head: usize, head_size: usize) -> Vec<Vec<f64>> {
+        sequence.chunks(self.model_size)
+            .map(|step| step[head * head_size..(head + 1) * head_size].to_vec())
+            .collect()
+    }
+
+    /// Writes the rows of a head into their slices of a flattened sequence.
+    fn merge(&self, sequence: &mut [f64], rows: &[Vec<f64>], head: usize, head_size: usize) {
+        for (step, row) in sequence.chunks_mut(self.model_size).zip(rows) {
+            step[head * head_size..(head + 1) * head_size].copy_from_slice(row);
+        }
+    }
+}
+
+impl Layer for MultiHeadAttention {
+    /// Attends from every time step of a sample to its time steps, in every head.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps, or if the
+    /// number of heads does not divide the model size.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let head_size = self.head_size()?;
+        let queries = self.query.forward(input)?;
+        let keys = self.key.forward(input)?;
+        let values = self.value.forward(input)?;
+
+        let mut heads = vec![0.0; input.len()];
+        for head in 0..self.attention.len() {
+            let (queries, keys, values) = (
+                self.split(&queries, head, head_size),
+                self.split(&keys, head, head_size),
+                self.split(&values, head, head_size)
+            );
+            let output = self.attention[head].forward(&queries, &keys, &values)?;
+            self.merge(&mut heads, &output, head, head_size);
+        }
+
+        self.output.forward(&heads)
+    }
+
+    /// Backpropagates through the output projection, the attention of every head and the
+    /// query, key and value projections, accumulating the gradients of their weights.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        let heads_gradient = self.output.backward(output_gradient)?;
+        let head_size = self.head_size()?;
+
+        let mut query_gradient = vec![0.0; heads_gradient.len()];
+        let mut key_gradient = vec![0.0; heads_gradient.len()];
+        let mut value_gradient = vec![0.0; heads_gradient.len()];
+        for head in 0..self.attention.len() {
+            let output_gradient = self.split(&heads_gradient, head, head_size);
+            let gradients = self.attention[head].backward(&output_gradient)?;
+            self.merge(&mut query_gradient, &gradients.queries, head, head_size);
+            self.merge(&mut key_gradient, &gradients.keys, head, head_size);
+            self.merge(&mut value_gradient, &gradients.values, head, head_size);
+        }
+
+        let mut input_gradient = self.query.backward(&query_gradient)?;
+        for gradient in [self.key.backward(&key_gradient)?, self.value.backward(&value_gradient)?] {
+            for (input_gradient, gradient) in input_gradient.iter_mut().zip(gradient) {
+                *input_gradient += gradient;
+            }
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the weights and biases of the query, key, value and output projections, each with
+    /// its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        let mut parameters = self.query.parameters_mut();
+        parameters.extend(self.key.parameters_mut());
+        parameters.extend(self.value.parameters_mut());
+        parameters.extend(self.output.parameters_mut());
+        parameters
+    }
+
+    /// Clears the accumulated gradients of the projections.
+    fn zero_grad(&mut self) {
+        self.query.zero_grad();
+        self.key.zero_grad();
+        self.value.zero_grad();
+        self.output.zero_grad();
+    }
+
+    /// Re-initialises the weights of the projections from seeds derived from the given one.
+    fn reseed(&mut self, seed: u64) {
+        self.query.reseed(seed);
+        self.key.reseed(seed.wrapping_add(1));
+        self.value.reseed(seed.wrapping_add(2));
+        self.output.reseed(seed.wrapping_add(3));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+
+    /// Six time steps of four features.
+    fn input() -> Vec<f64> {
+        (0..24).map(|index| ((index * 7 % 17) as f64 - 8.5) / 6.0).collect()
+    }
+
+    /// Runs a `ScaledDotProductAttention` as a layer, reading three queries, three keys and
+    /// three values of two features from the input, in that order.
+    struct Separate(ScaledDotProductAttention);
+
+    impl Layer for Separate {
+        fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+            let rows: Vec<Vec<f64>> = input.chunks(2).map(<[f64]>::to_vec).collect();
+            Ok(self.0.forward(&rows[..3], &rows[3..6], &rows[6..])?.concat())
+        }
+
+        fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+            let rows: Vec<Vec<f64>> = output_gradient.chunks(2).map(<[f64]>::to_vec).collect();
+            let gradients = self.0.backward(&rows)?;
+            Ok([gradients.queries.concat(), gradients.keys.concat(), gradients.values.concat()].concat())
+        }
+
+        fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+            Vec::new()
+        }
+
+        fn zero_grad(&mut self) {}
+    }
+
+    /// Test the attention against a manual computation.
+    #[test]
+    fn forward_matches_manual_computation() {
+        let mut attention = ScaledDotProductAttention::new();
+        let queries = vec![vec![0.0, 0.0], vec![2.0, 0.0]];
+        let keys = vec![vec![1.0, 0.0], vec![-1.0, 0.0]];
+        let values = vec![vec![1.0, 2.0], vec![3.0, 6.0]];
+        let output = attention.forward(&queries, &keys, &values).unwrap();
+
+        // A zero query weighs every key equally
+        assert_eq!(output[0], vec![2.0, 4.0]);
+
+        // Scores of +-2 / sqrt(2)
+        let weight = 1.0 / (1.0 + (-4.0 / 2f64.sqrt()).exp());
+        assert!((attention.weights()[1][0] - weight).abs() < 1e-12);
+        assert!((output[1][0] - (weight + 3.0 * (1.0 - weight))).abs() < 1e-12);
+        assert!(attention.weights().iter().all(|row| (row.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+    }
+
+    /// Test that the causal mask hides the future time steps.
+    /// Expected result is zero weights above the diagonal and outputs that do not depend on the
+    /// later time steps.
+    #[test]
+    fn causal_mask_ignores_the_future() {
+        let mut attention = MultiHeadAttention::new(4, 2).with_seed(3).with_causal_mask(true);
+        let output = attention.forward(&input()).unwrap();
+        for head in attention.attention() {
+            for (query, weights) in head.weights().iter().enumerate() {
+                assert!(weights[query + 1..].iter().all(|&weight| weight == 0.0));
+            }
+        }
+
+        let mut changed = input();
+        changed[12..].fill(5.0);
+        assert_eq!(attention.forward(&changed).unwrap()[..12], output[..12]);
+
+        let mut unmasked = MultiHeadAttention::new(4, 2).with_seed(3);
+        assert_ne!(unmasked.forward(&changed).unwrap()[..12], unmasked.forward(&input()).unwrap()[..12]);
+    }
+
+    /// Test the gradients against finite differences, with and without a causal mask.
+    #[test]
+    fn backward_matches_finite_differences() {
+        let rows = &input()[..18];
+        assert_gradients_match(|| Separate(ScaledDotProductAttention::new()), rows);
+        assert_gradients_match(|| Separate(ScaledDotProductAttention::new().with_causal_mask(true)), rows);
+        assert_gradients_match(|| MultiHeadAttention::new(4, 2).with_seed(5), &input());
+        assert_gradients_match(|| MultiHeadAttention::new(4, 1).with_seed(6).with_causal_mask(true), &input());
+    }
+
+    /// Test invalid configurations, inputs and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut attention = ScaledDotProductAttention::new();
+        let rows = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        assert!(matches!(attention.backward(&rows), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(attention.forward(&[], &rows, &rows), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(attention.forward(&rows, &rows, &rows[..1]), Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })));
+        assert!(matches!(attention.forward(&[vec![1.0]], &rows, &rows), Err(QmachinaError::LengthMismatch { expected: 1, got: 2 })));
+        assert!(matches!(
+            ScaledDotProductAttention::new().with_causal_mask(true).forward(&rows[..1], &rows, &rows),
+            Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })
+        ));
+
+        attention.forward(&rows, &rows, &rows).unwrap();
+        assert!(matches!(attention.backward(&rows[..1]), Err(QmachinaError::LengthMismatch { expected: 2, got: 1 })));
+
+        assert!(matches!(MultiHeadAttention::new(4, 3).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(MultiHeadAttention::new(4, 0).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(MultiHeadAttention::new(4, 2).forward(&input()[..6]), Err(QmachinaError::LengthMismatch { expected: 8, got: 6 })));
+        assert!(matches!(MultiHeadAttention::new(4, 2).backward(&input()), Err(QmachinaError::InvalidState(_))));
+    }
+}
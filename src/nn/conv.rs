@@ -0,0 +1,420 @@
+//! This module contains the one-dimensional convolution layer over sequences.
+use crate::activation::{ActivationFunction, BatchActivationFunction};
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use super::{time_steps, xavier_uniform, Layer};
+
+/// The seed used to initialise the weights when none is given.
+const DEFAULT_SEED: u64 = 0x0C0F_F1D1;
+
+/// The zeros added around a sequence before a convolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    /// No padding: the kernel only visits windows inside the sequence.
+    #[default]
+    Valid,
+    /// The same number of zeros before and after the sequence.
+    Zeros(usize),
+    /// Zeros before the sequence only, `dilation * (kernel_size - 1)` of them, so that the output
+    /// at a time step only depends on the inputs up to that time step. With a stride of 1, the
+    /// output has the length of the input.
+    Causal
+}
+
+/// Represents a one-dimensional convolution over a sequence, `y_t = f(b + sum_k W_k x_{t * s + k * d})`.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `in_channels`
+/// features, such as the rows of `feature_windows`. The layer slides `out_channels` kernels of
+/// `kernel_size` taps over the time steps, every `stride` time steps, with `dilation` time steps
+/// between the taps, and outputs the sequence of the results in the same layout. The number of
+/// time steps is read from the length of each input, so a layer accepts sequences of any length
+/// that fits the kernel.
+///
+/// The weights are stored row-major as `[out_channels][kernel_size][in_channels]` and are
+/// initialised from the Xavier uniform distribution, while the biases start at zero. The
+/// activation `f` is applied element-wise and its `derivate` is used during backpropagation.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::conv::{Conv1d, Padding};
+///
+/// // A causal difference of consecutive values, y_t = x_t - x_{t-1}
+/// let mut conv = Conv1d::from_weights(1, 1, 2, vec![-1.0, 1.0], vec![0.0], Box::new(IdentityActivationFunction))
+///     .unwrap()
+///     .with_padding(Padding::Causal);
+///
+/// assert_eq!(conv.forward(&[1.0, 3.0, 6.0, 10.0]).unwrap(), vec![1.0, 2.0, 3.0, 4.0]);
+/// ```
+pub struct Conv1d {
+    in_channels: usize,
+    out_channels: usize,
+    kernel_size: usize,
+    stride: usize,
+    dilation: usize,
+    padding: Padding,
+    weights: Vec<f64>,
+    bias: Vec<f64>,
+    activation: Box<dyn ActivationFunction<f64, f64>>,
+    weight_gradients: Vec<f64>,
+    bias_gradients: Vec<f64>,
+    input: Option<Vec<f64>>,
+    pre_activation: Vec<f64>
+}
+
+impl Conv1d {
+    /// Constructs a new `Conv1d` layer with Xavier-initialised weights and zero biases, a stride
+    /// and a dilation of 1 and no padding.
+    ///
+    /// # Parameters
+    ///
+    /// * `in_channels` - The number of features of each input time step.
+    /// * `out_channels` - The number of kernels, which is the number of features of each output
+    ///   time step.
+    /// * `kernel_size` - The number of time steps each kernel spans.
+    /// * `activation` - The activation function applied to each output.
+    pub fn new(
+        in_channels: usize,
+        out_channels: usize,
+        kernel_size: usize,
+        activation: Box<dyn ActivationFunction<f64, f64>>
+    ) -> Self {
+        let count = out_channels * kernel_size * in_channels;
+        let weights = xavier_uniform(kernel_size * in_channels, kernel_size * out_channels, count, DEFAULT_SEED);
+        Self::with_parameters(in_channels, out_channels, kernel_size, weights, vec![0.0; out_channels], activation)
+    }
+
+    /// Builds the layer around its weights and biases, with cleared gradients, a stride and a
+    /// dilation of 1 and no padding.
+    fn with_parameters(
+        in_channels: usize,
+        out_channels: usize,
+        kernel_size: usize,
+        weights: Vec<f64>,
+        bias: Vec<f64>,
+        activation: Box<dyn ActivationFunction<f64, f64>>
+    ) -> Self {
+        let count = weights.len();
+        Self {
+            in_channels,
+            out_channels,
+            kernel_size,
+            stride: 1,
+            dilation: 1,
+            padding: Padding::Valid,
+            weights,
+            bias,
+            activation,
+            weight_gradients: vec![0.0; count],
+            bias_gradients: vec![0.0; out_channels],
+            input: None,
+            pre_activation: Vec::new()
+        }
+    }
+
+    /// Constructs a new `Conv1d` layer with the given weights and biases.
+    ///
+    /// # Parameters
+    ///
+    /// * `in_channels` - The number of features of each input time step.
+    /// * `out_channels` - The number of kernels.
+    /// * `kernel_size` - The number of time steps each kernel spans.
+    /// * `weights` - The row-major weights, `[out_channels][kernel_size][in_channels]`.
+    /// * `bias` - One bias per kernel.
+    /// * `activation` - The activation function applied to each output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of weights or biases does not match the sizes, or if they
+    /// contain invalid values (NaN or infinite).
+    pub fn from_weights(
+        in_channels: usize,
+        out_channels: usize,
+        kernel_size: usize,
+        weights: Vec<f64>,
+        bias: Vec<f64>,
+        activation: Box<dyn ActivationFunction<f64, f64>>
+    ) -> Result<Self> {
+        ensure_same_length(out_channels * kernel_size * in_channels, weights.len())?;
+        ensure_same_length(out_channels, bias.len())?;
+        ensure_finite(&weights)?;
+        ensure_finite(&bias)?;
+
+        Ok(Self::with_parameters(in_channels, out_channels, kernel_size, weights, bias, activation))
+    }
+
+    /// Returns the `Conv1d` layer moving its kernels by `stride` time steps, which must be
+    /// positive.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Returns the `Conv1d` layer with `dilation` time steps between the taps of its kernels,
+    /// which must be positive. A dilation of 1 makes the taps contiguous.
+    pub fn with_dilation(mut self, dilation: usize) -> Self {
+        self.dilation = dilation;
+        self
+    }
+
+    /// Returns the `Conv1d` layer padding its input with zeros as given.
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the `Conv1d` layer with its weights re-initialised from the given seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the Xavier initialisation.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the number of features of each input time step.
+    pub fn in_channels(&self) -> usize {
+        self.in_channels
+    }
+
+    /// Returns the number of features of each output time step.
+    pub fn out_channels(&self) -> usize {
+        self.out_channels
+    }
+
+    /// Returns the number of time steps each kernel spans.
+    pub fn kernel_size(&self) -> usize {
+        self.kernel_size
+    }
+
+    /// Returns the row-major weights, `[out_channels][kernel_size][in_channels]`.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the biases.
+    pub fn bias(&self) -> &[f64] {
+        &self.bias
+    }
+
+    /// Returns the number of output time steps for an input of `input_length` time steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the kernel size, the stride or the dilation
+    /// is zero, and `QmachinaError::InsufficientData` if the padded input is shorter than the
+    /// span of a kernel.
+    pub fn output_length(&self, input_length: usize) -> Result<usize> {
+        if self.kernel_size == 0 || self.stride == 0 || self.dilation == 0 {
+            return Err(QmachinaError::InvalidParameter("The kernel size, the stride and the dilation must be positive."));
+        }
+
+        let (before, after) = self.padding_sizes();
+        let span = self.dilation * (self.kernel_size - 1) + 1;
+        let padded = input_length + before + after;
+        if padded < span {
+            return Err(QmachinaError::InsufficientData { required: span - before - after, got: input_length });
+        }
+
+        Ok((padded - span) / self.stride + 1)
+    }
+
+    /// Returns the number of zeros added before and after the sequence.
+    fn padding_sizes(&self) -> (usize, usize) {
+        match self.padding {
+            Padding::Valid => (0, 0),
+            Padding::Zeros(size) => (size, size),
+            Padding::Causal => (self.dilation * self.kernel_size.saturating_sub(1), 0)
+        }
+    }
+
+    /// Returns each tap of the kernel at an output time step along with the input time step it
+    /// reads, skipping the taps over the padding.
+    fn taps(&self, output_step: usize, input_length: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (before, _) = self.padding_sizes();
+        let start = output_step * self.stride;
+        let dilation = self.dilation;
+
+        (0..self.kernel_size).filter_map(move |tap| {
+            let position = (start + tap * dilation).checked_sub(before)?;
+            (position < input_length).then_some((tap, position))
+        })
+    }
+}
+
+impl Layer for Conv1d {
+    /// Slides the kernels over the time steps of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps, if the
+    /// sequence is shorter than a kernel, or if the kernel size, the stride or the dilation is
+    /// zero.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let input_length = time_steps(input, self.in_channels)?;
+        let output_length = self.output_length(input_length)?;
+        let window = self.kernel_size * self.in_channels;
+
+        self.pre_activation.clear();
+        for step in 0..output_length {
+            for channel in 0..self.out_channels {
+                let kernel = &self.weights[channel * window..(channel + 1) * window];
+                let value = self.taps(step, input_length)
+                    .map(|(tap, position)| {
+                        let weights = &kernel[tap * self.in_channels..(tap + 1) * self.in_channels];
+                        let features = &input[position * self.in_channels..(position + 1) * self.in_channels];
+                        weights.iter().zip(features).map(|(w, x)| w * x).sum::<f64>()
+                    })
+                    .sum::<f64>();
+                self.pre_activation.push(value + self.bias[channel]);
+            }
+        }
+        self.input = Some(input.to_vec());
+
+        Ok(self.activation.activate_batch(&self.pre_activation))
+    }
+
+    /// Backpropagates through the activation and the convolution, accumulating the gradients of
+    /// the weights and biases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        let input = self.input.as_ref().ok_or(QmachinaError::InvalidState("Backward pass requires a forward pass first."))?;
+        ensure_same_length(self.pre_activation.len(), output_gradient.len())?;
+
+        let input_length = input.len() / self.in_channels;
+        let window = self.kernel_size * self.in_channels;
+        let derivatives = self.activation.derivate_batch(&self.pre_activation);
+        let mut input_gradient = vec![0.0; input.len()];
+
+        for (index, (&gradient, derivative)) in output_gradient.iter().zip(derivatives).enumerate() {
+            let (step, channel) = (index / self.out_channels, index % self.out_channels);
+            let delta = gradient * derivative;
+            self.bias_gradients[channel] += delta;
+
+            for (tap, position) in self.taps(step, input_length) {
+                let weights = channel * window + tap * self.in_channels..channel * window + (tap + 1) * self.in_channels;
+                let features = position * self.in_channels..(position + 1) * self.in_channels;
+                for ((weight_gradient, weight), (x, input_gradient)) in self.weight_gradients[weights.clone()].iter_mut()
+                    .zip(&self.weights[weights])
+                    .zip(input[features.clone()].iter().zip(&mut input_gradient[features]))
+                {
+                    *weight_gradient += delta * x;
+                    *input_gradient += delta * weight;
+                }
+            }
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the weights and the biases, each with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        vec![
+            (&mut self.weights, &self.weight_gradients),
+            (&mut self.bias, &self.bias_gradients)
+        ]
+    }
+
+    /// Clears the accumulated weight and bias gradients.
+    fn zero_grad(&mut self) {
+        self.weight_gradients.fill(0.0);
+        self.bias_gradients.fill(0.0);
+    }
+
+    /// Re-initialises the weights from the given seed, keeping the biases.
+    fn reseed(&mut self, seed: u64) {
+        let fan_in = self.kernel_size * self.in_channels;
+        self.weights = xavier_uniform(fan_in, self.kernel_size * self.out_channels, self.weights.len(), seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::identity::IdentityActivationFunction;
+    use crate::activation::tanh::TanhActivationFunction;
+    use crate::nn::assert_gradients_match;
+
+    /// Ten time steps of two channels.
+    fn input() -> Vec<f64> {
+        (0..20).map(|index| ((index * 7 % 11) as f64 - 5.0) / 4.0).collect()
+    }
+
+    fn layer() -> Conv1d {
+        Conv1d::new(2, 3, 3, Box::new(TanhActivationFunction)).with_seed(5)
+    }
+
+    /// Test the output of a convolution against a manual computation.
+    #[test]
+    fn forward_matches_manual_computation() {
+        // Two channels summed over a kernel of two taps, every other time step
+        let mut conv = Conv1d::from_weights(2, 1, 2, vec![1.0, 10.0, 100.0, 1000.0], vec![0.5], Box::new(IdentityActivationFunction))
+            .unwrap()
+            .with_stride(2);
+        let output = conv.forward(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]).unwrap();
+        assert_eq!(output, vec![4321.5, 8765.5]);
+    }
+
+    /// Test the length of the output with each padding, stride and dilation.
+    #[test]
+    fn output_lengths() {
+        let conv = || Conv1d::new(1, 1, 3, Box::new(IdentityActivationFunction));
+
+        assert_eq!(conv().output_length(10).unwrap(), 8);
+        assert_eq!(conv().with_padding(Padding::Zeros(1)).output_length(10).unwrap(), 10);
+        assert_eq!(conv().with_padding(Padding::Causal).output_length(10).unwrap(), 10);
+        assert_eq!(conv().with_stride(3).output_length(10).unwrap(), 3);
+        assert_eq!(conv().with_dilation(2).output_length(10).unwrap(), 6);
+        assert_eq!(conv().with_dilation(4).with_padding(Padding::Causal).output_length(10).unwrap(), 10);
+    }
+
+    /// Test that a causal convolution does not see the future.
+    /// Expected result is the same output up to a time step whatever the later inputs.
+    #[test]
+    fn causal_padding_ignores_the_future() {
+        let mut conv = layer().with_padding(Padding::Causal).with_dilation(2);
+        let output = conv.forward(&input()).unwrap();
+        assert_eq!(output.len(), 30);
+
+        let mut changed = input();
+        changed[12..].fill(100.0);
+        let changed_output = conv.forward(&changed).unwrap();
+
+        // The first six time steps only depend on the first six inputs
+        assert_eq!(output[..18], changed_output[..18]);
+        assert_ne!(output[18..21], changed_output[18..21]);
+    }
+
+    /// Test the gradients against finite differences for each padding, stride and dilation.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(layer, &input());
+        assert_gradients_match(|| layer().with_padding(Padding::Zeros(2)).with_stride(2), &input());
+        assert_gradients_match(|| layer().with_padding(Padding::Causal).with_dilation(3), &input());
+        assert_gradients_match(|| layer().with_dilation(2).with_stride(3), &input());
+    }
+
+    /// Test invalid configurations, inputs and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut conv = layer();
+        assert!(matches!(conv.backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(conv.forward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 4, got: 3 })));
+        assert!(matches!(conv.forward(&[1.0; 4]), Err(QmachinaError::InsufficientData { required: 3, got: 2 })));
+        assert!(matches!(layer().with_stride(0).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(layer().with_dilation(0).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+
+        conv.forward(&input()).unwrap();
+        assert!(matches!(conv.backward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 24, got: 3 })));
+
+        assert!(Conv1d::from_weights(2, 1, 2, vec![1.0; 3], vec![0.0], Box::new(IdentityActivationFunction)).is_err());
+        assert!(Conv1d::from_weights(1, 1, 1, vec![f64::NAN], vec![0.0], Box::new(IdentityActivationFunction)).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! This module contains the definition and implementation of the fully connected (dense) layer.
+use crate::activation::{ActivationFunction, BatchActivationFunction};
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use super::{xavier_uniform, Layer};
+
+/// The seed used to initialise the weights when none is given.
+const DEFAULT_SEED: u64 = 0x5EED_DE45;
+
+/// Represents a fully connected layer, `y = f(W * x + b)`.
+///
+/// The weights are stored row-major, one row of `input_size` weights per output, and are
+/// initialised from the Xavier uniform distribution while the biases start at zero. The
+/// activation `f` is applied element-wise and its `derivate` is used during backpropagation.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::activation::relu::ReLUActivationFunction;
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::dense::Dense;
+///
+/// let mut layer = Dense::new(3, 2, Box::new(ReLUActivationFunction)).with_seed(7);
+/// let output = layer.forward(&[0.5, -1.0, 2.0]).unwrap();
+/// assert_eq!(output.len(), 2);
+/// assert!(output.iter().all(|&value| value >= 0.0));
+/// ```
+pub struct Dense {
+    input_size: usize,
+    output_size: usize,
+    weights: Vec<f64>,
+    bias: Vec<f64>,
+    activation: Box<dyn ActivationFunction<f64, f64>>,
+    weight_gradients: Vec<f64>,
+    bias_gradients: Vec<f64>,
+    input: Option<Vec<f64>>,
+    pre_activation: Vec<f64>
+}
+
+impl Dense {
+    /// Constructs a new `Dense` layer with Xavier-initialised weights and zero biases.
+    ///
+    /// Layers of the same shape start from the same weights; `with_seed`, or
+    /// `Sequential::with_seed` for a whole model, gives each of them its own.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of inputs of the layer.
+    /// * `output_size` - The number of outputs (neurons) of the layer.
+    /// * `activation` - The activation function applied to each output.
+    pub fn new(input_size: usize, output_size: usize, activation: Box<dyn ActivationFunction<f64, f64>>) -> Self {
+        let weights = xavier_uniform(input_size, output_size, input_size * output_size, DEFAULT_SEED);
+        Self::with_parameters(input_size, output_size, weights, vec![0.0; output_size], activation)
+    }
+
+    /// Builds the layer around its weights and biases, with cleared gradients.
+    fn with_parameters(
+        input_size: usize,
+        output_size: usize,
+        weights: Vec<f64>,
+        bias: Vec<f64>,
+        activation: Box<dyn ActivationFunction<f64, f64>>
+    ) -> Self {
+        Self {
+            input_size,
+            output_size,
+            weights,
+            bias,
+            activation,
+            weight_gradients: vec![0.0; input_size * output_size],
+            bias_gradients: vec![0.0; output_size],
+            input: None,
+            pre_activation: Vec::with_capacity(output_size)
+        }
+    }
+
+    /// Constructs a new `Dense` layer with the given weights and biases.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of inputs of the layer.
+    /// * `output_size` - The number of outputs (neurons) of the layer.
+    /// * `weights` - The row-major weights, `output_size` rows of `input_size` values.
+    /// * `bias` - One bias per output.
+    /// * `activation` - The activation function applied to each output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of weights or biases does not match the layer sizes, or if
+    /// they contain invalid values (NaN or infinite).
+    pub fn from_weights(
+        input_size: usize,
+        output_size: usize,
+        weights: Vec<f64>,
+        bias: Vec<f64>,
+        activation: Box<dyn ActivationFunction<f64, f64>>
+    ) -> Result<Self> {
+        ensure_same_length(input_size * output_size, weights.len())?;
+        ensure_same_length(output_size, bias.len())?;
+        ensure_finite(&weights)?;
+        ensure_finite(&bias)?;
+
+        Ok(Self::with_parameters(input_size, output_size, weights, bias, activation))
+    }
+
+    /// Returns the `Dense` layer with its weights re-initialised from the given seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the Xavier initialisation.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the number of inputs of the layer.
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    /// Returns the number of outputs of the layer.
+    pub fn output_size(&self) -> usize {
+        self.output_size
+    }
+
+    /// Returns the row-major weights of the layer.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the biases of the layer.
+    pub fn bias(&self) -> &[f64] {
+        &self.bias
+    }
+}
+
+impl Layer for Dense {
+    /// Computes `f(W * x + b)` for a single sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input length does not match the input size of the layer.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        ensure_same_length(self.input_size, input.len())?;
+
+        self.pre_activation.clear();
+        self.pre_activation.extend(self.bias.iter().enumerate().map(|(neuron, bias)| {
+            let row = &self.weights[neuron * self.input_size..(neuron + 1) * self.input_size];
+            row.iter().zip(input).map(|(w, x)| w * x).sum::<f64>() + bias
+        }));
+        self.input = Some(input.to_vec());
+
+        Ok(self.activation.activate_batch(&self.pre_activation))
+    }
+
+    /// Backpropagates through the activation and the affine transform, accumulating
+    /// `delta * x^T` into the weight gradients and `delta` into the bias gradients, where
+    /// `delta = output_gradient * f'(W * x + b)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output size of the layer.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        let input = self.input.as_ref().ok_or(QmachinaError::InvalidState("Backward pass requires a forward pass first."))?;
+        ensure_same_length(self.output_size, output_gradient.len())?;
+
+        let derivatives = self.activation.derivate_batch(&self.pre_activation);
+        let mut input_gradient = vec![0.0; self.input_size];
+        for (neuron, (&gradient, derivative)) in output_gradient.iter().zip(derivatives).enumerate() {
+            let delta = gradient * derivative;
+            let row = neuron * self.input_size..(neuron + 1) * self.input_size;
+
+            self.bias_gradients[neuron] += delta;
+            for ((weight_gradient, weight), (x, input_gradient)) in self.weight_gradients[row.clone()].iter_mut()
+                .zip(self.weights[row].iter())
+                .zip(input.iter().zip(input_gradient.iter_mut()))
+            {
+                *weight_gradient += delta * x;
+                *input_gradient += delta * weight;
+            }
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the weights and the biases, each with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        vec![
+            (&mut self.weights, &self.weight_gradients),
+            (&mut self.bias, &self.bias_gradients)
+        ]
+    }
+
+    /// Clears the accumulated weight and bias gradients.
+    fn zero_grad(&mut self) {
+        self.weight_gradients.fill(0.0);
+        self.bias_gradients.fill(0.0);
+    }
+
+    /// Re-initialises the weights from the given seed, keeping the biases.
+    fn reseed(&mut self, seed: u64) {
+        self.weights = xavier_uniform(self.input_size, self.output_size, self.weights.len(), seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::identity::IdentityActivationFunction;
+    use crate::activation::sigmoid::SigmoidActivationFunction;
+    use crate::activation::tanh::TanhActivationFunction;
+
+    fn layer() -> Dense {
+        Dense::from_weights(
+            3, 2,
+            vec![0.2, -0.5, 0.1, 0.7, 0.3, -0.4],
+            vec![0.05, -0.1],
+            Box::new(TanhActivationFunction)
+        ).unwrap()
+    }
+
+    /// The sum of the outputs, whose gradient with respect to each output is 1.
+    fn objective(layer: &mut Dense, input: &[f64]) -> f64 {
+        layer.forward(input).unwrap().iter().sum()
+    }
+
+    #[test]
+    fn forward_matches_manual_computation() {
+        let mut dense = Dense::from_weights(2, 2, vec![1.0, 2.0, -1.0, 0.5], vec![0.5, 0.0], Box::new(IdentityActivationFunction)).unwrap();
+        assert_eq!(dense.forward(&[1.0, 2.0]).unwrap(), vec![5.5, 0.0]);
+
+        let mut dense = Dense::from_weights(1, 1, vec![2.0], vec![-2.0], Box::new(SigmoidActivationFunction)).unwrap();
+        assert_eq!(dense.forward(&[1.0]).unwrap(), vec![0.5]);
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let input = [0.3, -1.2, 0.8];
+        let step = 1e-6;
+
+        let mut dense = layer();
+        dense.forward(&input).unwrap();
+        let input_gradient = dense.backward(&[1.0, 1.0]).unwrap();
+
+        for index in 0..input.len() {
+            let mut forward = input;
+            let mut backward = input;
+            forward[index] += step;
+            backward[index] -= step;
+
+            let expected = (objective(&mut layer(), &forward) - objective(&mut layer(), &backward)) / (2.0 * step);
+            assert!((input_gradient[index] - expected).abs() < 1e-6, "Input gradient {} mismatch", index);
+        }
+
+        for index in 0..6 {
+            let perturbed = |delta: f64| {
+                let mut weights = layer().weights().to_vec();
+                weights[index] += delta;
+                let mut dense = Dense::from_weights(3, 2, weights, vec![0.05, -0.1], Box::new(TanhActivationFunction)).unwrap();
+                objective(&mut dense, &input)
+            };
+
+            let expected = (perturbed(step) - perturbed(-step)) / (2.0 * step);
+            assert!((dense.weight_gradients[index] - expected).abs() < 1e-6, "Weight gradient {} mismatch", index);
+        }
+    }
+
+    #[test]
+    fn gradients_accumulate_until_zeroed() {
+        let mut dense = layer();
+        dense.forward(&[1.0, 0.0, -1.0]).unwrap();
+        dense.backward(&[1.0, 0.0]).unwrap();
+        let once = dense.bias_gradients.clone();
+        dense.backward(&[1.0, 0.0]).unwrap();
+
+        assert_eq!(dense.bias_gradients[0], 2.0 * once[0]);
+
+        dense.zero_grad();
+        assert!(dense.parameters_mut().iter().all(|(_, gradients)| gradients.iter().all(|&g| g == 0.0)));
+    }
+
+    #[test]
+    fn mismatched_sizes_fail() {
+        let mut dense = layer();
+        assert!(dense.backward(&[1.0, 1.0]).is_err(), "Backward before forward should fail");
+        assert!(dense.forward(&[1.0, 2.0]).is_err());
+
+        dense.forward(&[1.0, 2.0, 3.0]).unwrap();
+        assert!(dense.backward(&[1.0]).is_err());
+
+        assert!(Dense::from_weights(2, 2, vec![1.0; 3], vec![0.0; 2], Box::new(IdentityActivationFunction)).is_err());
+        assert!(Dense::from_weights(2, 2, vec![1.0; 4], vec![0.0; 1], Box::new(IdentityActivationFunction)).is_err());
+        assert!(Dense::from_weights(1, 1, vec![f64::NAN], vec![0.0], Box::new(IdentityActivationFunction)).is_err());
+    }
+
+    #[test]
+    fn seed_controls_initialisation() {
+        let first = Dense::new(4, 3, Box::new(IdentityActivationFunction)).with_seed(1);
+        let second = Dense::new(4, 3, Box::new(IdentityActivationFunction)).with_seed(1);
+        let third = Dense::new(4, 3, Box::new(IdentityActivationFunction)).with_seed(2);
+
+        assert_eq!(first.weights(), second.weights());
+        assert_ne!(first.weights(), third.weights());
+        assert_eq!(first.bias(), &[0.0; 3]);
+    }
+}
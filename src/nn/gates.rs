@@ -0,0 +1,166 @@
+//! This module contains the weights shared by the gates of the recurrent cells.
+use std::ops::Range;
+
+use crate::error::{ensure_finite, ensure_same_length, Result};
+use super::xavier_uniform;
+
+/// The parameters of the gates of a recurrent cell, or their gradients.
+///
+/// Each gate has a block of `hidden_size` rows in the input-to-hidden weights, the
+/// hidden-to-hidden weights and the biases, stored row-major one gate after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Gates {
+    pub(super) input_size: usize,
+    pub(super) hidden_size: usize,
+    pub(super) input_weights: Vec<f64>,
+    pub(super) recurrent_weights: Vec<f64>,
+    pub(super) bias: Vec<f64>
+}
+
+impl Gates {
+    /// Constructs `count` gates with Xavier-initialised weights and zero biases.
+    pub(super) fn new(count: usize, input_size: usize, hidden_size: usize, seed: u64) -> Self {
+        let rows = count * hidden_size;
+        Self {
+            input_size,
+            hidden_size,
+            input_weights: xavier_uniform(input_size, hidden_size, rows * input_size, seed),
+            recurrent_weights: xavier_uniform(hidden_size, hidden_size, rows * hidden_size, seed.rotate_left(32)),
+            bias: vec![0.0; rows]
+        }
+    }
+
+    /// Constructs `count` gates whose parameters are all zero, to accumulate gradients.
+    pub(super) fn zeros(count: usize, input_size: usize, hidden_size: usize) -> Self {
+        let rows = count * hidden_size;
+        Self {
+            input_size,
+            hidden_size,
+            input_weights: vec![0.0; rows * input_size],
+            recurrent_weights: vec![0.0; rows * hidden_size],
+            bias: vec![0.0; rows]
+        }
+    }
+
+    /// Constructs `count` gates from their parameters, checking their sizes and values.
+    pub(super) fn from_weights(
+        count: usize,
+        input_size: usize,
+        hidden_size: usize,
+        input_weights: Vec<f64>,
+        recurrent_weights: Vec<f64>,
+        bias: Vec<f64>
+    ) -> Result<Self> {
+        let rows = count * hidden_size;
+        ensure_same_length(rows * input_size, input_weights.len())?;
+        ensure_same_length(rows * hidden_size, recurrent_weights.len())?;
+        ensure_same_length(rows, bias.len())?;
+        ensure_finite(&input_weights)?;
+        ensure_finite(&recurrent_weights)?;
+        ensure_finite(&bias)?;
+
+        Ok(Self { input_size, hidden_size, input_weights, recurrent_weights, bias })
+    }
+
+    /// Computes `W * x + b` for every gate.
+    pub(super) fn project_input(&self, input: &[f64]) -> Vec<f64> {
+        let mut output = self.bias.clone();
+        multiply_add(&self.input_weights, input, &mut output);
+        output
+    }
+
+    /// Computes `U * h` for the given gates.
+    pub(super) fn project_hidden(&self, gates: Range<usize>, hidden: &[f64]) -> Vec<f64> {
+        let mut output = vec![0.0; gates.len() * self.hidden_size];
+        multiply_add(&self.recurrent_weights[self.recurrent_rows(gates)], hidden, &mut output);
+        output
+    }
+
+    /// Computes `W^T * delta`, the gradient with respect to the input of the pre-activations of
+    /// every gate.
+    pub(super) fn input_gradient(&self, delta: &[f64]) -> Vec<f64> {
+        let mut gradient = vec![0.0; self.input_size];
+        multiply_transposed_add(&self.input_weights, delta, &mut gradient);
+        gradient
+    }
+
+    /// Computes `U^T * delta` for the given gates, the gradient with respect to the hidden state
+    /// of their pre-activations.
+    pub(super) fn hidden_gradient(&self, gates: Range<usize>, delta: &[f64]) -> Vec<f64> {
+        let mut gradient = vec![0.0; self.hidden_size];
+        multiply_transposed_add(&self.recurrent_weights[self.recurrent_rows(gates)], delta, &mut gradient);
+        gradient
+    }
+
+    /// Accumulates `delta * x^T` into the input weights and `delta` into the biases of every
+    /// gate.
+    pub(super) fn accumulate_input(&mut self, delta: &[f64], input: &[f64]) {
+        add_outer(&mut self.input_weights, delta, input);
+        for (bias, &delta) in self.bias.iter_mut().zip(delta) {
+            *bias += delta;
+        }
+    }
+
+    /// Accumulates `delta * h^T` into the hidden-to-hidden weights of the given gates.
+    pub(super) fn accumulate_hidden(&mut self, gates: Range<usize>, delta: &[f64], hidden: &[f64]) {
+        let rows = self.recurrent_rows(gates);
+        add_outer(&mut self.recurrent_weights[rows], delta, hidden);
+    }
+
+    /// Returns each parameter group along with its gradient in `gradients`.
+    pub(super) fn parameters_mut<'a>(&'a mut self, gradients: &'a Gates) -> Vec<(&'a mut [f64], &'a [f64])> {
+        vec![
+            (&mut self.input_weights, &gradients.input_weights),
+            (&mut self.recurrent_weights, &gradients.recurrent_weights),
+            (&mut self.bias, &gradients.bias)
+        ]
+    }
+
+    /// Sets every parameter to zero.
+    pub(super) fn fill_zero(&mut self) {
+        self.input_weights.fill(0.0);
+        self.recurrent_weights.fill(0.0);
+        self.bias.fill(0.0);
+    }
+
+    /// Returns the range of the hidden-to-hidden weights of the given gates.
+    fn recurrent_rows(&self, gates: Range<usize>) -> Range<usize> {
+        let block = self.hidden_size * self.hidden_size;
+        gates.start * block..gates.end * block
+    }
+}
+
+/// Adds the product of a row-major matrix, of `output.len()` rows, with a vector to `output`.
+fn multiply_add(weights: &[f64], input: &[f64], output: &mut [f64]) {
+    if input.is_empty() {
+        return;
+    }
+    for (value, row) in output.iter_mut().zip(weights.chunks(input.len())) {
+        *value += row.iter().zip(input).map(|(w, x)| w * x).sum::<f64>();
+    }
+}
+
+/// Adds the product of the transpose of a row-major matrix, of `delta.len()` rows, with `delta`
+/// to `output`.
+fn multiply_transposed_add(weights: &[f64], delta: &[f64], output: &mut [f64]) {
+    if output.is_empty() {
+        return;
+    }
+    for (&delta, row) in delta.iter().zip(weights.chunks(output.len())) {
+        for (value, weight) in output.iter_mut().zip(row) {
+            *value += delta * weight;
+        }
+    }
+}
+
+/// Adds the outer product `delta * x^T` to a row-major matrix.
+fn add_outer(matrix: &mut [f64], delta: &[f64], input: &[f64]) {
+    if input.is_empty() {
+        return;
+    }
+    for (&delta, row) in delta.iter().zip(matrix.chunks_mut(input.len())) {
+        for (value, x) in row.iter_mut().zip(input) {
+            *value += delta * x;
+        }
+    }
+}
@@ -0,0 +1,461 @@
+//! This module contains the Gated Recurrent Unit (GRU) cell and the recurrent layer built on it.
+use crate::activation::ActivationFunction;
+use crate::activation::sigmoid::SigmoidActivationFunction;
+use crate::activation::tanh::TanhActivationFunction;
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use super::gates::Gates;
+use super::{time_steps, Layer};
+
+/// The seed used to initialise the weights when none is given.
+const DEFAULT_SEED: u64 = 0x0062_CE11;
+
+/// The number of gates of the cell: reset, update and candidate.
+const GATES: usize = 3;
+
+/// Represents a Gated Recurrent Unit cell, which updates its hidden state with one time step of
+/// a sequence.
+///
+/// The GRU merges the memory and the hidden state of an LSTM into a single state, which makes
+/// it lighter to train on short financial series:
+///
+/// \[
+/// \begin{aligned}
+/// r &= \sigma(W_r x + U_r h + b_r), \quad z = \sigma(W_z x + U_z h + b_z) \\
+/// n &= \tanh(W_n x + U_n (r \odot h) + b_n) \\
+/// h' &= (1 - z) \odot n + z \odot h
+/// \end{aligned}
+/// \]
+///
+/// The reset and update gates use `SigmoidActivationFunction` and the candidate uses
+/// `TanhActivationFunction`. The weights of the three gates are stored one block of
+/// `hidden_size` rows after the other, in the order reset, update and candidate. They are
+/// initialised from the Xavier uniform distribution and the biases start at zero.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::gru::GruCell;
+///
+/// let cell = GruCell::new(1, 4).with_seed(3);
+/// let mut hidden = vec![0.0; 4];
+/// for value in [0.01, -0.02, 0.015] {
+///     hidden = cell.step(&[value], &hidden).unwrap();
+/// }
+/// assert!(hidden.iter().all(|h| h.abs() < 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GruCell {
+    gates: Gates
+}
+
+/// The values of a time step cached for backpropagation through time.
+struct GruStep {
+    input: Vec<f64>,
+    previous: Vec<f64>,
+    reset_previous: Vec<f64>,
+    pre_activations: Vec<f64>,
+    hidden: Vec<f64>
+}
+
+impl GruCell {
+    /// Constructs a new `GruCell` with Xavier-initialised weights.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden state.
+    pub fn new(input_size: usize, hidden_size: usize) -> Self {
+        Self { gates: Gates::new(GATES, input_size, hidden_size, DEFAULT_SEED) }
+    }
+
+    /// Constructs a new `GruCell` with the given weights and biases.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden state.
+    /// * `input_weights` - The row-major input-to-hidden weights, `3 * hidden_size` rows of
+    ///   `input_size` values.
+    /// * `recurrent_weights` - The row-major hidden-to-hidden weights, `3 * hidden_size` rows of
+    ///   `hidden_size` values.
+    /// * `bias` - One bias per row, `3 * hidden_size` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of weights or biases does not match the sizes, or if they
+    /// contain invalid values (NaN or infinite).
+    pub fn from_weights(
+        input_size: usize,
+        hidden_size: usize,
+        input_weights: Vec<f64>,
+        recurrent_weights: Vec<f64>,
+        bias: Vec<f64>
+    ) -> Result<Self> {
+        let gates = Gates::from_weights(GATES, input_size, hidden_size, input_weights, recurrent_weights, bias)?;
+        Ok(Self { gates })
+    }
+
+    /// Returns the `GruCell` with its weights re-initialised from the given seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the Xavier initialisation.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { gates: Gates::new(GATES, self.input_size(), self.hidden_size(), seed) }
+    }
+
+    /// Returns the number of features of each time step.
+    pub fn input_size(&self) -> usize {
+        self.gates.input_size
+    }
+
+    /// Returns the number of values of the hidden state.
+    pub fn hidden_size(&self) -> usize {
+        self.gates.hidden_size
+    }
+
+    /// Returns the row-major input-to-hidden weights.
+    pub fn input_weights(&self) -> &[f64] {
+        &self.gates.input_weights
+    }
+
+    /// Returns the row-major hidden-to-hidden weights.
+    pub fn recurrent_weights(&self) -> &[f64] {
+        &self.gates.recurrent_weights
+    }
+
+    /// Returns the biases.
+    pub fn bias(&self) -> &[f64] {
+        &self.gates.bias
+    }
+
+    /// Computes the hidden state of the cell after one time step.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The features of the time step.
+    /// * `hidden` - The hidden state after the previous time step, zeros for the first one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input or the hidden state do not match the sizes of the cell.
+    pub fn step(&self, input: &[f64], hidden: &[f64]) -> Result<Vec<f64>> {
+        ensure_same_length(self.input_size(), input.len())?;
+        ensure_same_length(self.hidden_size(), hidden.len())?;
+
+        Ok(self.forward(input, hidden).hidden)
+    }
+
+    /// Computes one time step, keeping the values needed by `backward`.
+    fn forward(&self, input: &[f64], previous: &[f64]) -> GruStep {
+        let size = self.hidden_size();
+        let mut pre_activations = self.gates.project_input(input);
+        for (value, recurrent) in pre_activations.iter_mut().zip(self.gates.project_hidden(0..2, previous)) {
+            *value += recurrent;
+        }
+
+        let reset_previous: Vec<f64> = (0..size)
+            .map(|unit| SigmoidActivationFunction.activate(pre_activations[unit]) * previous[unit])
+            .collect();
+        for (value, recurrent) in pre_activations[2 * size..].iter_mut().zip(self.gates.project_hidden(2..3, &reset_previous)) {
+            *value += recurrent;
+        }
+
+        let hidden = (0..size)
+            .map(|unit| {
+                let update = SigmoidActivationFunction.activate(pre_activations[size + unit]);
+                let candidate = TanhActivationFunction.activate(pre_activations[2 * size + unit]);
+                (1.0 - update) * candidate + update * previous[unit]
+            })
+            .collect();
+
+        GruStep { input: input.to_vec(), previous: previous.to_vec(), reset_previous, pre_activations, hidden }
+    }
+
+    /// Backpropagates the gradient of the hidden state after a time step, accumulating the
+    /// gradients of the parameters and returning those of the input and of the previous hidden
+    /// state.
+    fn backward(&self, step: &GruStep, hidden_gradient: &[f64], gradients: &mut Gates) -> (Vec<f64>, Vec<f64>) {
+        let size = self.hidden_size();
+        let z = &step.pre_activations;
+        let mut delta = vec![0.0; GATES * size];
+        let mut previous_gradient = Vec::with_capacity(size);
+
+        for unit in 0..size {
+            let update = SigmoidActivationFunction.activate(z[size + unit]);
+            let candidate = TanhActivationFunction.activate(z[2 * size + unit]);
+            let dh = hidden_gradient[unit];
+
+            delta[size + unit] = dh * (step.previous[unit] - candidate) * SigmoidActivationFunction.derivate(z[size + unit]);
+            delta[2 * size + unit] = dh * (1.0 - update) * TanhActivationFunction.derivate(z[2 * size + unit]);
+            previous_gradient.push(dh * update);
+        }
+
+        // The candidate sees the previous state through the reset gate
+        let reset_previous_gradient = self.gates.hidden_gradient(2..3, &delta[2 * size..]);
+        for unit in 0..size {
+            let reset = SigmoidActivationFunction.activate(z[unit]);
+            delta[unit] = reset_previous_gradient[unit] * step.previous[unit] * SigmoidActivationFunction.derivate(z[unit]);
+            previous_gradient[unit] += reset_previous_gradient[unit] * reset;
+        }
+
+        gradients.accumulate_input(&delta, &step.input);
+        gradients.accumulate_hidden(0..2, &delta[..2 * size], &step.previous);
+        gradients.accumulate_hidden(2..3, &delta[2 * size..], &step.reset_previous);
+
+        for (gradient, recurrent) in previous_gradient.iter_mut().zip(self.gates.hidden_gradient(0..2, &delta[..2 * size])) {
+            *gradient += recurrent;
+        }
+        (self.gates.input_gradient(&delta), previous_gradient)
+    }
+}
+
+/// Represents a recurrent layer that runs a `GruCell` over a sequence, trained with
+/// backpropagation through time.
+///
+/// The input of a sample is a window of time steps flattened in time order, such as the rows of
+/// `feature_windows`, so its length must be a multiple of the input size of the cell. The hidden
+/// state starts at zero for each sample. The output is the hidden state after the last time
+/// step, or the hidden states of every time step concatenated when returning sequences, to stack
+/// recurrent layers.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::sigmoid::SigmoidActivationFunction;
+/// use qmachina::loss::bce::BinaryCrossEntropyLossFunction;
+/// use qmachina::nn::dense::Dense;
+/// use qmachina::nn::gru::Gru;
+/// use qmachina::nn::sequential::Sequential;
+///
+/// // Windows of 4 returns, predicting the direction of the next one
+/// let mut model = Sequential::new()
+///     .with_layer(Gru::new(1, 6))
+///     .with_layer(Dense::new(6, 1, Box::new(SigmoidActivationFunction)));
+///
+/// let inputs = vec![vec![0.01, 0.02, 0.01, 0.03], vec![-0.02, -0.01, -0.03, -0.01]];
+/// let targets = vec![vec![1.0], vec![0.0]];
+///
+/// let loss = BinaryCrossEntropyLossFunction;
+/// let first = model.train_step(&inputs, &targets, &loss, 0.5).unwrap();
+/// let second = model.train_step(&inputs, &targets, &loss, 0.5).unwrap();
+/// assert!(second < first);
+/// ```
+pub struct Gru {
+    cell: GruCell,
+    gradients: Gates,
+    return_sequences: bool,
+    steps: Vec<GruStep>
+}
+
+impl Gru {
+    /// Constructs a new `Gru` layer with a Xavier-initialised cell, returning the last hidden
+    /// state.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden state.
+    pub fn new(input_size: usize, hidden_size: usize) -> Self {
+        Self::from_cell(GruCell::new(input_size, hidden_size))
+    }
+
+    /// Constructs a new `Gru` layer running the given cell, returning the last hidden state.
+    pub fn from_cell(cell: GruCell) -> Self {
+        Self {
+            gradients: Gates::zeros(GATES, cell.input_size(), cell.hidden_size()),
+            cell,
+            return_sequences: false,
+            steps: Vec::new()
+        }
+    }
+
+    /// Returns the `Gru` layer with its weights re-initialised from the given seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the `Gru` layer outputting the hidden states of every time step when
+    /// `return_sequences` is `true`, or only the last one otherwise.
+    pub fn with_return_sequences(mut self, return_sequences: bool) -> Self {
+        self.return_sequences = return_sequences;
+        self
+    }
+
+    /// Returns the cell run by the layer.
+    pub fn cell(&self) -> &GruCell {
+        &self.cell
+    }
+}
+
+impl Layer for Gru {
+    /// Runs the cell over the time steps of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for an empty input and
+    /// `QmachinaError::LengthMismatch` if it is not a whole number of time steps.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        time_steps(input, self.cell.input_size())?;
+
+        self.steps.clear();
+        let mut hidden = vec![0.0; self.cell.hidden_size()];
+        let mut output = Vec::new();
+        for features in input.chunks(self.cell.input_size()) {
+            let step = self.cell.forward(features, &hidden);
+            if self.return_sequences {
+                output.extend_from_slice(&step.hidden);
+            }
+            hidden.clone_from(&step.hidden);
+            self.steps.push(step);
+        }
+
+        if !self.return_sequences {
+            output = hidden;
+        }
+        Ok(output)
+    }
+
+    /// Backpropagates through time, from the last time step to the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient does not match the
+    /// output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.steps.is_empty() {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        let size = self.cell.hidden_size();
+        let length = self.steps.len();
+        ensure_same_length(if self.return_sequences { length * size } else { size }, output_gradient.len())?;
+
+        let mut next = vec![0.0; size];
+        let mut input_gradient = vec![0.0; length * self.cell.input_size()];
+        for (index, step) in self.steps.iter().enumerate().rev() {
+            let output = match self.return_sequences {
+                true => &output_gradient[index * size..(index + 1) * size],
+                false if index == length - 1 => output_gradient,
+                false => &[]
+            };
+            for (hidden, &gradient) in next.iter_mut().zip(output) {
+                *hidden += gradient;
+            }
+
+            let (gradient, previous) = self.cell.backward(step, &next, &mut self.gradients);
+            input_gradient[index * gradient.len()..(index + 1) * gradient.len()].copy_from_slice(&gradient);
+            next = previous;
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the input-to-hidden weights, the hidden-to-hidden weights and the biases, each
+    /// with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        self.cell.gates.parameters_mut(&self.gradients)
+    }
+
+    /// Clears the accumulated gradients of the parameters.
+    fn zero_grad(&mut self) {
+        self.gradients.fill_zero();
+    }
+
+    /// Re-initialises the weights of the cell from the given seed, as `GruCell::with_seed` does.
+    fn reseed(&mut self, seed: u64) {
+        self.cell = GruCell { gates: Gates::new(GATES, self.cell.input_size(), self.cell.hidden_size(), seed) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::bce::BinaryCrossEntropyLossFunction;
+    use crate::nn::assert_gradients_match;
+    use crate::nn::dense::Dense;
+    use crate::nn::lstm::Lstm;
+    use crate::nn::sequential::Sequential;
+
+    const INPUT: [f64; 6] = [0.3, -1.2, 0.8, 0.1, -0.4, 0.9];
+
+    fn layer() -> Gru {
+        Gru::new(2, 3).with_seed(11)
+    }
+
+    /// Test one step of the cell against the equations of the cell.
+    #[test]
+    fn step_matches_manual_computation() {
+        let cell = GruCell::from_weights(1, 1, vec![1.0, 1.0, 1.0], vec![1.0, -1.0, 2.0], vec![0.0, 0.0, 0.5]).unwrap();
+        let hidden = cell.step(&[0.5], &[0.25]).unwrap();
+
+        let sigmoid = |x: f64| 1.0 / (1.0 + (-x).exp());
+        let (reset, update) = (sigmoid(0.75), sigmoid(0.25));
+        let candidate = (0.5 + 2.0 * reset * 0.25 + 0.5_f64).tanh();
+        assert!((hidden[0] - ((1.0 - update) * candidate + update * 0.25)).abs() < 1e-12);
+    }
+
+    /// Test that the layer returns the state of a cell stepped over the sequence.
+    #[test]
+    fn forward_matches_cell_steps() {
+        let mut gru = layer();
+        let mut hidden = vec![0.0; 3];
+        let mut hidden_states = Vec::new();
+        for features in INPUT.chunks(2) {
+            hidden = gru.cell().step(features, &hidden).unwrap();
+            hidden_states.extend_from_slice(&hidden);
+        }
+
+        assert_eq!(gru.forward(&INPUT).unwrap(), hidden);
+
+        let mut gru = layer().with_return_sequences(true);
+        assert_eq!(gru.forward(&INPUT).unwrap(), hidden_states);
+    }
+
+    /// Test the gradients of backpropagation through time against finite differences, returning
+    /// the last state or the whole sequence.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(layer, &INPUT);
+        assert_gradients_match(|| layer().with_return_sequences(true), &INPUT);
+    }
+
+    /// Test that stacked recurrent layers learn to tell rising windows from falling ones.
+    #[test]
+    fn learns_the_direction_of_a_window() {
+        let mut model = Sequential::new()
+            .with_layer(Lstm::new(1, 4).with_seed(1).with_return_sequences(true))
+            .with_layer(Gru::new(4, 4).with_seed(2))
+            .with_layer(Dense::new(4, 1, Box::new(SigmoidActivationFunction)).with_seed(3));
+
+        let inputs = vec![
+            vec![0.1, 0.2, 0.3, 0.4], vec![0.0, 0.1, 0.3, 0.5], vec![-0.2, 0.0, 0.1, 0.2],
+            vec![0.4, 0.3, 0.2, 0.1], vec![0.5, 0.3, 0.1, 0.0], vec![0.2, 0.1, 0.0, -0.2]
+        ];
+        let targets = vec![vec![1.0], vec![1.0], vec![1.0], vec![0.0], vec![0.0], vec![0.0]];
+        let loss = BinaryCrossEntropyLossFunction;
+
+        let first = model.train_step(&inputs, &targets, &loss, 1.0).unwrap();
+        let mut last = first;
+        for _ in 0..300 {
+            last = model.train_step(&inputs, &targets, &loss, 1.0).unwrap();
+        }
+        assert!(last < 0.1 * first, "Loss went from {first} to {last}");
+    }
+
+    /// Test invalid inputs, weights and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut gru = layer().with_return_sequences(true);
+        assert!(matches!(gru.backward(&[1.0; 9]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(gru.forward(&[]), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(gru.forward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 4, got: 3 })));
+
+        gru.forward(&INPUT).unwrap();
+        assert!(matches!(gru.backward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 9, got: 3 })));
+
+        assert!(GruCell::from_weights(1, 1, vec![1.0; 3], vec![1.0; 3], vec![0.0; 2]).is_err());
+        assert!(matches!(layer().cell().step(&[1.0, 2.0], &[0.0]), Err(QmachinaError::LengthMismatch { expected: 3, got: 1 })));
+    }
+}
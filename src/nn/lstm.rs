@@ -0,0 +1,470 @@
+//! This module contains the Long Short-Term Memory (LSTM) cell and the recurrent layer built on
+//! it.
+use crate::activation::ActivationFunction;
+use crate::activation::sigmoid::SigmoidActivationFunction;
+use crate::activation::tanh::TanhActivationFunction;
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use super::gates::Gates;
+use super::{time_steps, Layer};
+
+/// The seed used to initialise the weights when none is given.
+const DEFAULT_SEED: u64 = 0x0015_7A7E;
+
+/// The number of gates of the cell: input, forget, candidate and output.
+const GATES: usize = 4;
+
+/// Represents the state carried by an LSTM from one time step to the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LstmState {
+    /// The hidden state `h`, which is also the output of the cell.
+    pub hidden: Vec<f64>,
+    /// The cell state `c`, the long-term memory of the cell.
+    pub cell: Vec<f64>
+}
+
+impl LstmState {
+    /// Constructs the initial state of a cell, where both states are zero.
+    pub fn zeros(hidden_size: usize) -> Self {
+        Self { hidden: vec![0.0; hidden_size], cell: vec![0.0; hidden_size] }
+    }
+}
+
+/// Represents a Long Short-Term Memory cell, which updates its state with one time step of a
+/// sequence.
+///
+/// The cell keeps a long-term memory `c` that gates decide to forget, update and expose:
+///
+/// \[
+/// \begin{aligned}
+/// i &= \sigma(W_i x + U_i h + b_i), \quad f = \sigma(W_f x + U_f h + b_f), \quad o = \sigma(W_o x + U_o h + b_o) \\
+/// g &= \tanh(W_g x + U_g h + b_g) \\
+/// c' &= f \odot c + i \odot g, \quad h' = o \odot \tanh(c')
+/// \end{aligned}
+/// \]
+///
+/// The gates use `SigmoidActivationFunction` and the candidate and output use
+/// `TanhActivationFunction`. The weights of the four gates are stored one block of
+/// `hidden_size` rows after the other, in the order input, forget, candidate and output. They
+/// are initialised from the Xavier uniform distribution, and the biases start at zero except
+/// for the forget gate, which starts at one so that the cell remembers by default.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::lstm::{LstmCell, LstmState};
+///
+/// // Feed returns one at a time, carrying the state between them
+/// let cell = LstmCell::new(1, 4).with_seed(3);
+/// let mut state = LstmState::zeros(4);
+/// for value in [0.01, -0.02, 0.015] {
+///     state = cell.step(&[value], &state).unwrap();
+/// }
+/// assert!(state.hidden.iter().all(|h| h.abs() < 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LstmCell {
+    gates: Gates
+}
+
+/// The values of a time step cached for backpropagation through time.
+struct LstmStep {
+    input: Vec<f64>,
+    previous: LstmState,
+    pre_activations: Vec<f64>,
+    state: LstmState
+}
+
+impl LstmCell {
+    /// Constructs a new `LstmCell` with Xavier-initialised weights.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden and cell states.
+    pub fn new(input_size: usize, hidden_size: usize) -> Self {
+        Self { gates: Gates::new(GATES, input_size, hidden_size, DEFAULT_SEED) }.with_forget_bias()
+    }
+
+    /// Constructs a new `LstmCell` with the given weights and biases.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden and cell states.
+    /// * `input_weights` - The row-major input-to-hidden weights, `4 * hidden_size` rows of
+    ///   `input_size` values.
+    /// * `recurrent_weights` - The row-major hidden-to-hidden weights, `4 * hidden_size` rows of
+    ///   `hidden_size` values.
+    /// * `bias` - One bias per row, `4 * hidden_size` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of weights or biases does not match the sizes, or if they
+    /// contain invalid values (NaN or infinite).
+    pub fn from_weights(
+        input_size: usize,
+        hidden_size: usize,
+        input_weights: Vec<f64>,
+        recurrent_weights: Vec<f64>,
+        bias: Vec<f64>
+    ) -> Result<Self> {
+        let gates = Gates::from_weights(GATES, input_size, hidden_size, input_weights, recurrent_weights, bias)?;
+        Ok(Self { gates })
+    }
+
+    /// Returns the `LstmCell` with its weights re-initialised from the given seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the Xavier initialisation.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { gates: Gates::new(GATES, self.input_size(), self.hidden_size(), seed) }.with_forget_bias()
+    }
+
+    /// Returns the number of features of each time step.
+    pub fn input_size(&self) -> usize {
+        self.gates.input_size
+    }
+
+    /// Returns the number of values of the hidden and cell states.
+    pub fn hidden_size(&self) -> usize {
+        self.gates.hidden_size
+    }
+
+    /// Returns the row-major input-to-hidden weights.
+    pub fn input_weights(&self) -> &[f64] {
+        &self.gates.input_weights
+    }
+
+    /// Returns the row-major hidden-to-hidden weights.
+    pub fn recurrent_weights(&self) -> &[f64] {
+        &self.gates.recurrent_weights
+    }
+
+    /// Returns the biases.
+    pub fn bias(&self) -> &[f64] {
+        &self.gates.bias
+    }
+
+    /// Computes the state of the cell after one time step.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The features of the time step.
+    /// * `state` - The state after the previous time step, `LstmState::zeros` for the first one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input or the state do not match the sizes of the cell.
+    pub fn step(&self, input: &[f64], state: &LstmState) -> Result<LstmState> {
+        ensure_same_length(self.input_size(), input.len())?;
+        ensure_same_length(self.hidden_size(), state.hidden.len())?;
+        ensure_same_length(self.hidden_size(), state.cell.len())?;
+
+        Ok(self.forward(input, state).state)
+    }
+
+    /// Sets the biases of the forget gate to one.
+    fn with_forget_bias(mut self) -> Self {
+        let hidden_size = self.hidden_size();
+        self.gates.bias[hidden_size..2 * hidden_size].fill(1.0);
+        self
+    }
+
+    /// Computes one time step, keeping the values needed by `backward`.
+    fn forward(&self, input: &[f64], previous: &LstmState) -> LstmStep {
+        let size = self.hidden_size();
+        let mut pre_activations = self.gates.project_input(input);
+        for (value, recurrent) in pre_activations.iter_mut().zip(self.gates.project_hidden(0..GATES, &previous.hidden)) {
+            *value += recurrent;
+        }
+
+        let mut state = LstmState { hidden: Vec::with_capacity(size), cell: Vec::with_capacity(size) };
+        for unit in 0..size {
+            let [i, f, g, o] = self.activations(&pre_activations, unit);
+            let cell = f * previous.cell[unit] + i * g;
+            state.cell.push(cell);
+            state.hidden.push(o * TanhActivationFunction.activate(cell));
+        }
+
+        LstmStep { input: input.to_vec(), previous: previous.clone(), pre_activations, state }
+    }
+
+    /// Backpropagates the gradients of the hidden and cell states after a time step, accumulating
+    /// the gradients of the parameters and returning those of the input and of the previous
+    /// state.
+    fn backward(&self, step: &LstmStep, hidden_gradient: &[f64], cell_gradient: &[f64], gradients: &mut Gates) -> (Vec<f64>, LstmState) {
+        let size = self.hidden_size();
+        let z = &step.pre_activations;
+        let mut delta = vec![0.0; GATES * size];
+        let mut previous_cell = Vec::with_capacity(size);
+
+        for unit in 0..size {
+            let [i, f, g, o] = self.activations(z, unit);
+            let cell = step.state.cell[unit];
+            let dh = hidden_gradient[unit];
+            let dc = cell_gradient[unit] + dh * o * TanhActivationFunction.derivate(cell);
+
+            delta[unit] = dc * g * SigmoidActivationFunction.derivate(z[unit]);
+            delta[size + unit] = dc * step.previous.cell[unit] * SigmoidActivationFunction.derivate(z[size + unit]);
+            delta[2 * size + unit] = dc * i * TanhActivationFunction.derivate(z[2 * size + unit]);
+            delta[3 * size + unit] = dh * TanhActivationFunction.activate(cell) * SigmoidActivationFunction.derivate(z[3 * size + unit]);
+            previous_cell.push(dc * f);
+        }
+
+        gradients.accumulate_input(&delta, &step.input);
+        gradients.accumulate_hidden(0..GATES, &delta, &step.previous.hidden);
+
+        let previous = LstmState { hidden: self.gates.hidden_gradient(0..GATES, &delta), cell: previous_cell };
+        (self.gates.input_gradient(&delta), previous)
+    }
+
+    /// Returns the input, forget, candidate and output activations of a unit.
+    fn activations(&self, pre_activations: &[f64], unit: usize) -> [f64; GATES] {
+        let size = self.hidden_size();
+        [
+            SigmoidActivationFunction.activate(pre_activations[unit]),
+            SigmoidActivationFunction.activate(pre_activations[size + unit]),
+            TanhActivationFunction.activate(pre_activations[2 * size + unit]),
+            SigmoidActivationFunction.activate(pre_activations[3 * size + unit])
+        ]
+    }
+}
+
+/// Represents a recurrent layer that runs an `LstmCell` over a sequence, trained with
+/// backpropagation through time.
+///
+/// The input of a sample is a window of time steps flattened in time order, such as the rows of
+/// `feature_windows`, so its length must be a multiple of the input size of the cell. The state
+/// starts at zero for each sample. The output is the hidden state after the last time step, or
+/// the hidden states of every time step concatenated when returning sequences, to stack
+/// recurrent layers.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+/// use qmachina::nn::dense::Dense;
+/// use qmachina::nn::lstm::Lstm;
+/// use qmachina::nn::sequential::Sequential;
+///
+/// // Windows of 3 time steps of 2 features, predicting the next value
+/// let mut model = Sequential::new()
+///     .with_layer(Lstm::new(2, 8))
+///     .with_layer(Dense::new(8, 1, Box::new(IdentityActivationFunction)));
+///
+/// let inputs = vec![vec![0.1, 0.5, 0.2, 0.4, 0.3, 0.3], vec![0.3, 0.3, 0.2, 0.4, 0.1, 0.5]];
+/// let targets = vec![vec![0.4], vec![0.0]];
+///
+/// let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+/// let second = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+/// assert!(second < first);
+/// ```
+pub struct Lstm {
+    cell: LstmCell,
+    gradients: Gates,
+    return_sequences: bool,
+    steps: Vec<LstmStep>
+}
+
+impl Lstm {
+    /// Constructs a new `Lstm` layer with a Xavier-initialised cell, returning the last hidden
+    /// state.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_size` - The number of features of each time step.
+    /// * `hidden_size` - The number of values of the hidden state.
+    pub fn new(input_size: usize, hidden_size: usize) -> Self {
+        Self::from_cell(LstmCell::new(input_size, hidden_size))
+    }
+
+    /// Constructs a new `Lstm` layer running the given cell, returning the last hidden state.
+    pub fn from_cell(cell: LstmCell) -> Self {
+        Self {
+            gradients: Gates::zeros(GATES, cell.input_size(), cell.hidden_size()),
+            cell,
+            return_sequences: false,
+            steps: Vec::new()
+        }
+    }
+
+    /// Returns the `Lstm` layer with its weights re-initialised from the given seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the `Lstm` layer outputting the hidden states of every time step when
+    /// `return_sequences` is `true`, or only the last one otherwise.
+    pub fn with_return_sequences(mut self, return_sequences: bool) -> Self {
+        self.return_sequences = return_sequences;
+        self
+    }
+
+    /// Returns the cell run by the layer.
+    pub fn cell(&self) -> &LstmCell {
+        &self.cell
+    }
+}
+
+impl Layer for Lstm {
+    /// Runs the cell over the time steps of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for an empty input and
+    /// `QmachinaError::LengthMismatch` if it is not a whole number of time steps.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        time_steps(input, self.cell.input_size())?;
+
+        self.steps.clear();
+        let mut state = LstmState::zeros(self.cell.hidden_size());
+        let mut output = Vec::new();
+        for features in input.chunks(self.cell.input_size()) {
+            let step = self.cell.forward(features, &state);
+            if self.return_sequences {
+                output.extend_from_slice(&step.state.hidden);
+            }
+            state = step.state.clone();
+            self.steps.push(step);
+        }
+
+        if !self.return_sequences {
+            output = state.hidden;
+        }
+        Ok(output)
+    }
+
+    /// Backpropagates through time, from the last time step to the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient does not match the
+    /// output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.steps.is_empty() {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        let size = self.cell.hidden_size();
+        let length = self.steps.len();
+        ensure_same_length(if self.return_sequences { length * size } else { size }, output_gradient.len())?;
+
+        let mut next = LstmState::zeros(size);
+        let mut input_gradient = vec![0.0; length * self.cell.input_size()];
+        for (index, step) in self.steps.iter().enumerate().rev() {
+            let output = match self.return_sequences {
+                true => &output_gradient[index * size..(index + 1) * size],
+                false if index == length - 1 => output_gradient,
+                false => &[]
+            };
+            for (hidden, &gradient) in next.hidden.iter_mut().zip(output) {
+                *hidden += gradient;
+            }
+
+            let (gradient, previous) = self.cell.backward(step, &next.hidden, &next.cell, &mut self.gradients);
+            input_gradient[index * gradient.len()..(index + 1) * gradient.len()].copy_from_slice(&gradient);
+            next = previous;
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the input-to-hidden weights, the hidden-to-hidden weights and the biases, each
+    /// with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        self.cell.gates.parameters_mut(&self.gradients)
+    }
+
+    /// Clears the accumulated gradients of the parameters.
+    fn zero_grad(&mut self) {
+        self.gradients.fill_zero();
+    }
+
+    /// Re-initialises the weights of the cell from the given seed, as `LstmCell::with_seed`
+    /// does.
+    fn reseed(&mut self, seed: u64) {
+        let gates = Gates::new(GATES, self.cell.input_size(), self.cell.hidden_size(), seed);
+        self.cell = LstmCell { gates }.with_forget_bias();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+
+    const INPUT: [f64; 6] = [0.3, -1.2, 0.8, 0.1, -0.4, 0.9];
+
+    fn layer() -> Lstm {
+        Lstm::new(2, 3).with_seed(11)
+    }
+
+    /// Test one step of the cell against the equations of the cell.
+    #[test]
+    fn step_matches_manual_computation() {
+        // With one unit and unit weights, every gate sees x + h + b
+        let cell = LstmCell::from_weights(1, 1, vec![1.0; 4], vec![1.0; 4], vec![0.0, 1.0, 0.0, 0.0]).unwrap();
+        let state = cell.step(&[0.5], &LstmState { hidden: vec![0.25], cell: vec![-1.0] }).unwrap();
+
+        let sigmoid = |x: f64| 1.0 / (1.0 + (-x).exp());
+        let expected_cell = -sigmoid(1.75) + sigmoid(0.75) * 0.75_f64.tanh();
+        assert!((state.cell[0] - expected_cell).abs() < 1e-12);
+        assert!((state.hidden[0] - sigmoid(0.75) * expected_cell.tanh()).abs() < 1e-12);
+    }
+
+    /// Test that the layer returns the state of a cell stepped over the sequence.
+    #[test]
+    fn forward_matches_cell_steps() {
+        let mut lstm = layer();
+        let mut state = LstmState::zeros(3);
+        let mut hidden_states = Vec::new();
+        for features in INPUT.chunks(2) {
+            state = lstm.cell().step(features, &state).unwrap();
+            hidden_states.extend_from_slice(&state.hidden);
+        }
+
+        assert_eq!(lstm.forward(&INPUT).unwrap(), state.hidden);
+
+        let mut lstm = layer().with_return_sequences(true);
+        assert_eq!(lstm.forward(&INPUT).unwrap(), hidden_states);
+    }
+
+    /// Test the gradients of backpropagation through time against finite differences, returning
+    /// the last state or the whole sequence.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(layer, &INPUT);
+        assert_gradients_match(|| layer().with_return_sequences(true), &INPUT);
+    }
+
+    /// Test the forget gate bias and the reproducibility of the initialisation.
+    #[test]
+    fn initialisation() {
+        let cell = LstmCell::new(2, 3);
+
+        assert_eq!(cell.bias(), &[0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(cell.input_weights().len(), 24);
+        assert_eq!(cell.recurrent_weights().len(), 36);
+        assert_eq!(cell.clone().with_seed(5), LstmCell::new(2, 3).with_seed(5));
+        assert_ne!(cell.with_seed(5).input_weights(), LstmCell::new(2, 3).with_seed(6).input_weights());
+    }
+
+    /// Test invalid inputs, weights and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut lstm = layer();
+        assert!(matches!(lstm.backward(&[1.0; 3]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(lstm.forward(&[]), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(lstm.forward(&[1.0; 5]), Err(QmachinaError::LengthMismatch { expected: 6, got: 5 })));
+
+        lstm.forward(&INPUT).unwrap();
+        assert!(matches!(lstm.backward(&[1.0; 9]), Err(QmachinaError::LengthMismatch { expected: 3, got: 9 })));
+
+        assert!(LstmCell::from_weights(1, 1, vec![1.0; 3], vec![1.0; 4], vec![0.0; 4]).is_err());
+        assert!(LstmCell::from_weights(1, 1, vec![1.0; 4], vec![f64::NAN; 4], vec![0.0; 4]).is_err());
+        assert!(matches!(layer().cell().step(&[1.0, 2.0], &LstmState::zeros(2)), Err(QmachinaError::LengthMismatch { .. })));
+    }
+}
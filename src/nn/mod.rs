@@ -0,0 +1,271 @@
+//! This module contains building blocks for small neural networks.
+//!
+//! Layers are composed into a `Sequential` model, which runs the forward pass, backpropagates
+//! the gradient of a `DifferentiableLoss` through the layers and updates their parameters.
+//! Recurrent, convolutional, pooling and attention layers consume windows of time steps
+//! flattened into a single sample, as built by `feature_windows` from indicator series.
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+
+pub mod attention;
+pub mod conv;
+pub mod dense;
+pub mod gru;
+pub mod lstm;
+pub mod norm;
+pub mod pool;
+pub mod positional;
+pub mod sequential;
+pub mod tcn;
+pub mod transformer;
+
+mod gates;
+
+/// The `Layer` trait defines the interface of a trainable layer in a neural network.
+///
+/// A layer caches what it needs during `forward` so that `backward` can compute the gradients
+/// of its parameters. Parameter gradients are accumulated across calls to `backward`, which lets
+/// a model sum them over a mini-batch before updating, and are cleared with `zero_grad`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::dense::Dense;
+///
+/// let mut layer = Dense::from_weights(2, 1, vec![1.0, -1.0], vec![0.5], Box::new(IdentityActivationFunction)).unwrap();
+/// assert_eq!(layer.forward(&[3.0, 1.0]).unwrap(), vec![2.5]);
+///
+/// let input_gradient = layer.backward(&[1.0]).unwrap();
+/// assert_eq!(input_gradient, vec![1.0, -1.0]);
+/// ```
+pub trait Layer {
+    /// Computes the output of the layer for a single sample, caching the values needed by
+    /// `backward`.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The input values of the sample.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<f64>)` containing the output of the layer, or an error if the input does
+    /// not match the layer.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>>;
+
+    /// Backpropagates the gradient of the loss through the layer for the last sample passed to
+    /// `forward`, accumulating the gradients of the parameters.
+    ///
+    /// # Parameters
+    ///
+    /// * `output_gradient` - The gradient of the loss with respect to the output of the layer.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<f64>)` containing the gradient of the loss with respect to the input of the
+    /// layer, or an error if no forward pass was run or the gradient does not match the layer.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>>;
+
+    /// Returns each parameter group of the layer along with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])>;
+
+    /// Clears the accumulated gradients of the parameters.
+    fn zero_grad(&mut self);
+
+    /// Re-initialises the parameters of the layer from the given seed, as its `with_seed` does.
+    /// Layers without randomly initialised parameters keep them unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the initialisation.
+    fn reseed(&mut self, _seed: u64) {}
+}
+
+/// Generates `count` weights from the Xavier (Glorot) uniform distribution,
+/// `U(-sqrt(6 / (fan_in + fan_out)), sqrt(6 / (fan_in + fan_out)))`, using a xorshift generator
+/// so that initialisation is reproducible for a given seed.
+pub(crate) fn xavier_uniform(fan_in: usize, fan_out: usize, count: usize, seed: u64) -> Vec<f64> {
+    let limit = (6.0 / (fan_in + fan_out).max(1) as f64).sqrt();
+    // A zero state would make the generator stuck at zero.
+    let mut state = if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed };
+
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            let unit = (state >> 11) as f64 / (1u64 << 53) as f64;
+            (2.0 * unit - 1.0) * limit
+        })
+        .collect()
+}
+
+/// Builds, at every time step, the window of the features over the last `length` time steps,
+/// the input expected by the recurrent layers.
+///
+/// The features are series over the same time steps, such as the outputs of
+/// `Indicator::compute_series` for several indicators, where `None` marks a time step without a
+/// value. A window holds its time steps in time order, the features of a time step being
+/// contiguous. The window ending at a time step is `None` unless every feature has a value over
+/// the whole window, so that the windows stay aligned with the series.
+///
+/// # Parameters
+///
+/// * `features` - One series per feature, all of the same length.
+/// * `length` - The number of time steps of each window.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InvalidParameter` if the length is zero,
+/// `QmachinaError::EmptyInput` without features and `QmachinaError::LengthMismatch` if the series
+/// have different lengths.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::feature_windows;
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::sma::SimpleMovingAverage;
+///
+/// let prices = [10.0, 11.0, 12.0, 11.0, 13.0];
+/// let returns: Vec<Option<f64>> = std::iter::once(None)
+///     .chain(prices.windows(2).map(|pair| Some(pair[1] / pair[0] - 1.0)))
+///     .collect();
+/// let sma = SimpleMovingAverage::new(2).compute_series(&prices).unwrap();
+///
+/// let windows = feature_windows(&[returns, sma], 2).unwrap();
+/// assert_eq!(windows.len(), 5);
+/// assert!(windows[..2].iter().all(Option::is_none));
+/// assert_eq!(windows[2].as_ref().unwrap().len(), 4);
+/// ```
+pub fn feature_windows(features: &[Vec<Option<f64>>], length: usize) -> Result<Vec<Option<Vec<f64>>>> {
+    if length == 0 {
+        return Err(QmachinaError::InvalidParameter("The length of the windows must be positive."));
+    }
+    let Some(first) = features.first() else {
+        return Err(QmachinaError::EmptyInput);
+    };
+    for feature in features {
+        ensure_same_length(first.len(), feature.len())?;
+    }
+
+    let time_steps: Vec<Option<Vec<f64>>> = (0..first.len())
+        .map(|time| features.iter().map(|feature| feature[time]).collect())
+        .collect();
+
+    Ok((0..time_steps.len())
+        .map(|end| {
+            let start = (end + 1).checked_sub(length)?;
+            time_steps[start..=end].iter().try_fold(Vec::with_capacity(length * features.len()), |mut window, step| {
+                window.extend(step.as_ref()?);
+                Some(window)
+            })
+        })
+        .collect())
+}
+
+/// Checks that the input of a recurrent layer is a whole, non-zero number of time steps of
+/// `input_size` features, returning the number of time steps.
+pub(crate) fn time_steps(input: &[f64], input_size: usize) -> Result<usize> {
+    if input.is_empty() {
+        return Err(QmachinaError::EmptyInput);
+    }
+    if input_size == 0 {
+        return Err(QmachinaError::InvalidParameter("A recurrent layer requires at least one input feature."));
+    }
+    ensure_same_length(input.len().next_multiple_of(input_size), input.len())?;
+
+    Ok(input.len() / input_size)
+}
+
+/// Checks the input gradient and the parameter gradients computed by `backward` against central
+/// finite differences of a weighted sum of the outputs, building a fresh layer for every
+/// evaluation.
+#[cfg(test)]
+pub(crate) fn assert_gradients_match<L: Layer>(layer: impl Fn() -> L, input: &[f64]) {
+    let step = 1e-6;
+    let weight = |index: usize| 1.0 - 0.3 * index as f64;
+    let objective = |layer: &mut L, input: &[f64]| -> f64 {
+        layer.forward(input).unwrap().iter().enumerate().map(|(index, output)| weight(index) * output).sum()
+    };
+    let assert_close = |actual: f64, expected: f64, what: &str| {
+        assert!((actual - expected).abs() < 1e-6, "{what}: {actual} differs from {expected}");
+    };
+
+    let mut reference = layer();
+    let outputs = reference.forward(input).unwrap().len();
+    let input_gradient = reference.backward(&(0..outputs).map(weight).collect::<Vec<_>>()).unwrap();
+
+    for index in 0..input.len() {
+        let perturbed = |delta: f64| {
+            let mut input = input.to_vec();
+            input[index] += delta;
+            objective(&mut layer(), &input)
+        };
+        assert_close(input_gradient[index], (perturbed(step) - perturbed(-step)) / (2.0 * step), "input gradient");
+    }
+
+    let parameter_gradients: Vec<Vec<f64>> = reference.parameters_mut().into_iter().map(|(_, gradients)| gradients.to_vec()).collect();
+    for (group, gradients) in parameter_gradients.iter().enumerate() {
+        for (index, &gradient) in gradients.iter().enumerate() {
+            let perturbed = |delta: f64| {
+                let mut layer = layer();
+                layer.parameters_mut()[group].0[index] += delta;
+                objective(&mut layer, input)
+            };
+            assert_close(gradient, (perturbed(step) - perturbed(-step)) / (2.0 * step), "parameter gradient");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xavier_uniform_is_bounded_and_reproducible() {
+        let weights = xavier_uniform(4, 2, 100, 42);
+        let limit = 1.0;
+
+        assert_eq!(weights.len(), 100);
+        assert!(weights.iter().all(|weight| weight.abs() <= limit));
+        assert!(weights.iter().any(|&weight| weight > 0.0) && weights.iter().any(|&weight| weight < 0.0));
+        assert_eq!(weights, xavier_uniform(4, 2, 100, 42));
+        assert_ne!(weights, xavier_uniform(4, 2, 100, 7));
+    }
+
+    #[test]
+    fn feature_windows_skip_incomplete_time_steps() {
+        let first = vec![None, Some(1.0), Some(2.0), Some(3.0), None, Some(5.0)];
+        let second = vec![Some(10.0), Some(20.0), Some(30.0), Some(40.0), Some(50.0), Some(60.0)];
+
+        let windows = feature_windows(&[first, second], 2).unwrap();
+        assert_eq!(windows, vec![
+            None,
+            None,
+            Some(vec![1.0, 20.0, 2.0, 30.0]),
+            Some(vec![2.0, 30.0, 3.0, 40.0]),
+            None,
+            None
+        ]);
+    }
+
+    #[test]
+    fn feature_windows_invalid_inputs() {
+        let series = vec![Some(1.0); 3];
+
+        assert!(matches!(feature_windows(std::slice::from_ref(&series), 0), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(feature_windows(&[], 2), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(feature_windows(&[series, vec![Some(1.0); 2]], 2), Err(QmachinaError::LengthMismatch { expected: 3, got: 2 })));
+    }
+
+    #[test]
+    fn time_steps_of_a_window() {
+        assert_eq!(time_steps(&[1.0; 6], 2).unwrap(), 3);
+        assert!(matches!(time_steps(&[1.0; 5], 2), Err(QmachinaError::LengthMismatch { expected: 6, got: 5 })));
+        assert!(matches!(time_steps(&[], 2), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(time_steps(&[1.0], 0), Err(QmachinaError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,231 @@
+//! This module contains the layer normalisation.
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use super::{time_steps, Layer};
+
+/// The constant added to the variance when none is given.
+const DEFAULT_EPSILON: f64 = 1e-5;
+
+/// Represents a layer normalisation (Ba, Kiros and Hinton, 2016), which normalises the features
+/// of each time step to a zero mean and a unit variance before scaling and shifting them:
+///
+/// \[
+/// y = \gamma \odot \frac{x - \mu}{\sqrt{\sigma^2 + \epsilon}} + \beta
+/// \]
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `features` values,
+/// and every time step is normalised on its own, so a single time step normalises a plain
+/// vector. The gains `gamma` start at one and the biases `beta` at zero.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::norm::LayerNorm;
+///
+/// let mut norm = LayerNorm::new(2).with_epsilon(0.0);
+/// assert_eq!(norm.forward(&[1.0, 3.0, 10.0, -10.0]).unwrap(), vec![-1.0, 1.0, 1.0, -1.0]);
+/// ```
+pub struct LayerNorm {
+    features: usize,
+    epsilon: f64,
+    gain: Vec<f64>,
+    bias: Vec<f64>,
+    gain_gradients: Vec<f64>,
+    bias_gradients: Vec<f64>,
+    normalized: Vec<f64>,
+    inverse_deviations: Vec<f64>
+}
+
+impl LayerNorm {
+    /// Constructs a new `LayerNorm` with unit gains and zero biases.
+    ///
+    /// # Parameters
+    ///
+    /// * `features` - The number of values of each time step.
+    pub fn new(features: usize) -> Self {
+        Self {
+            features,
+            epsilon: DEFAULT_EPSILON,
+            gain: vec![1.0; features],
+            bias: vec![0.0; features],
+            gain_gradients: vec![0.0; features],
+            bias_gradients: vec![0.0; features],
+            normalized: Vec::new(),
+            inverse_deviations: Vec::new()
+        }
+    }
+
+    /// Constructs a new `LayerNorm` with the given gains and biases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the gains and the biases differ in number or contain invalid values
+    /// (NaN or infinite).
+    pub fn from_weights(gain: Vec<f64>, bias: Vec<f64>) -> Result<Self> {
+        ensure_same_length(gain.len(), bias.len())?;
+        ensure_finite(&gain)?;
+        ensure_finite(&bias)?;
+
+        let features = gain.len();
+        Ok(Self { gain, bias, ..Self::new(features) })
+    }
+
+    /// Returns the `LayerNorm` adding `epsilon` to the variance, which keeps the normalisation
+    /// of constant time steps finite. It defaults to `1e-5`.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Returns the number of values of each time step.
+    pub fn features(&self) -> usize {
+        self.features
+    }
+
+    /// Returns the gains.
+    pub fn gain(&self) -> &[f64] {
+        &self.gain
+    }
+
+    /// Returns the biases.
+    pub fn bias(&self) -> &[f64] {
+        &self.bias
+    }
+}
+
+impl Layer for LayerNorm {
+    /// Normalises, scales and shifts the features of every time step of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps, or if the
+    /// epsilon is negative or not finite.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        if !(self.epsilon >= 0.0 && self.epsilon.is_finite()) {
+            return Err(QmachinaError::InvalidParameter("The epsilon must be non-negative and finite."));
+        }
+        time_steps(input, self.features)?;
+
+        self.normalized.clear();
+        self.inverse_deviations.clear();
+        for step in input.chunks(self.features) {
+            let mean = step.iter().sum::<f64>() / self.features as f64;
+            let variance = step.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / self.features as f64;
+            let inverse_deviation = 1.0 / (variance + self.epsilon).sqrt();
+
+            self.normalized.extend(step.iter().map(|x| (x - mean) * inverse_deviation));
+            self.inverse_deviations.push(inverse_deviation);
+        }
+
+        Ok(self.normalized.chunks(self.features)
+            .flat_map(|step| step.iter().zip(&self.gain).zip(&self.bias).map(|((x, gain), bias)| gain * x + bias))
+            .collect())
+    }
+
+    /// Backpropagates through the scaling and the normalisation, accumulating the gradients of
+    /// the gains and biases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.normalized.is_empty() {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.normalized.len(), output_gradient.len())?;
+
+        let count = self.features as f64;
+        let mut input_gradient = Vec::with_capacity(output_gradient.len());
+        for ((gradient, normalized), inverse_deviation) in output_gradient.chunks(self.features)
+            .zip(self.normalized.chunks(self.features))
+            .zip(&self.inverse_deviations)
+        {
+            let normalized_gradient: Vec<f64> = gradient.iter().zip(&self.gain).map(|(g, gain)| g * gain).collect();
+            let sum = normalized_gradient.iter().sum::<f64>();
+            let projection = normalized_gradient.iter().zip(normalized).map(|(g, x)| g * x).sum::<f64>();
+
+            for (feature, (&g, &x)) in gradient.iter().zip(normalized).enumerate() {
+                self.gain_gradients[feature] += g * x;
+                self.bias_gradients[feature] += g;
+            }
+            input_gradient.extend(normalized_gradient.iter()
+                .zip(normalized)
+                .map(|(g, x)| inverse_deviation * (g - (sum + x * projection) / count)));
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the gains and the biases, each with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        vec![
+            (&mut self.gain, &self.gain_gradients),
+            (&mut self.bias, &self.bias_gradients)
+        ]
+    }
+
+    /// Clears the accumulated gain and bias gradients.
+    fn zero_grad(&mut self) {
+        self.gain_gradients.fill(0.0);
+        self.bias_gradients.fill(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+
+    /// Four time steps of three features.
+    fn input() -> Vec<f64> {
+        (0..12).map(|index| ((index * 5 % 7) as f64 - 3.0) / 2.0 + index as f64 * 0.1).collect()
+    }
+
+    /// Test that every time step is normalised to a zero mean and a unit variance, then scaled
+    /// and shifted.
+    #[test]
+    fn normalizes_each_time_step() {
+        let output = LayerNorm::new(3).with_epsilon(0.0).forward(&input()).unwrap();
+        for step in output.chunks(3) {
+            let mean = step.iter().sum::<f64>() / 3.0;
+            let variance = step.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 3.0;
+            assert!(mean.abs() < 1e-12);
+            assert!((variance - 1.0).abs() < 1e-12);
+        }
+
+        let mut norm = LayerNorm::from_weights(vec![2.0, 1.0, 0.0], vec![0.5, 0.0, -1.0]).unwrap().with_epsilon(0.0);
+        let scaled = norm.forward(&input()).unwrap();
+        for (scaled, output) in scaled.chunks(3).zip(output.chunks(3)) {
+            assert!((scaled[0] - (2.0 * output[0] + 0.5)).abs() < 1e-12);
+            assert!((scaled[1] - output[1]).abs() < 1e-12);
+            assert_eq!(scaled[2], -1.0);
+        }
+
+        // Epsilon keeps a constant time step finite
+        assert_eq!(LayerNorm::new(2).forward(&[4.0, 4.0]).unwrap(), vec![0.0, 0.0]);
+    }
+
+    /// Test the gradients against finite differences.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(|| LayerNorm::new(3), &input());
+        assert_gradients_match(|| LayerNorm::from_weights(vec![0.5, -1.5, 2.0], vec![0.1, 0.2, 0.3]).unwrap(), &input());
+    }
+
+    /// Test invalid parameters, inputs and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut norm = LayerNorm::new(3);
+        assert!(matches!(norm.backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(norm.forward(&[1.0; 4]), Err(QmachinaError::LengthMismatch { expected: 6, got: 4 })));
+        assert!(matches!(LayerNorm::new(3).with_epsilon(-1.0).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+
+        norm.forward(&input()).unwrap();
+        assert!(matches!(norm.backward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 12, got: 3 })));
+
+        assert!(LayerNorm::from_weights(vec![1.0; 2], vec![0.0; 3]).is_err());
+        assert!(LayerNorm::from_weights(vec![f64::NAN], vec![0.0]).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! This module contains the pooling layers, which downsample sequences along time.
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use super::{time_steps, Layer};
+
+/// The windows of a pooling layer: their size, their stride and the number of channels of each
+/// time step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Windows {
+    channels: usize,
+    size: usize,
+    stride: usize
+}
+
+impl Windows {
+    /// Returns the number of windows over an input, checking its length.
+    fn count(&self, input: &[f64]) -> Result<usize> {
+        if self.size == 0 || self.stride == 0 {
+            return Err(QmachinaError::InvalidParameter("The pool size and the stride must be positive."));
+        }
+        let length = time_steps(input, self.channels)?;
+        if length < self.size {
+            return Err(QmachinaError::InsufficientData { required: self.size, got: length });
+        }
+
+        Ok((length - self.size) / self.stride + 1)
+    }
+
+    /// Returns the indices of the input values pooled into the output of a channel at a time
+    /// step.
+    fn indices(&self, step: usize, channel: usize) -> impl Iterator<Item = usize> {
+        let (start, channels) = (step * self.stride, self.channels);
+        (start..start + self.size).map(move |position| position * channels + channel)
+    }
+}
+
+/// Represents a max pooling layer, which keeps the largest value of each channel over windows of
+/// `size` time steps.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `channels` values,
+/// such as the output of a `Conv1d` layer. The windows move by `stride` time steps, which
+/// defaults to the size of the windows so that they do not overlap. The gradient of an output
+/// flows back to the input that was the largest.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::pool::MaxPool1d;
+///
+/// // Two channels over four time steps
+/// let mut pool = MaxPool1d::new(2, 2);
+/// let output = pool.forward(&[1.0, -1.0, 3.0, -2.0, 0.0, 5.0, 2.0, 4.0]).unwrap();
+/// assert_eq!(output, vec![3.0, -1.0, 2.0, 5.0]);
+/// ```
+pub struct MaxPool1d {
+    windows: Windows,
+    input_length: usize,
+    selected: Vec<usize>
+}
+
+impl MaxPool1d {
+    /// Constructs a new `MaxPool1d` layer over non-overlapping windows.
+    ///
+    /// # Parameters
+    ///
+    /// * `channels` - The number of values of each time step.
+    /// * `size` - The number of time steps of each window.
+    pub fn new(channels: usize, size: usize) -> Self {
+        Self { windows: Windows { channels, size, stride: size }, input_length: 0, selected: Vec::new() }
+    }
+
+    /// Returns the `MaxPool1d` layer moving its windows by `stride` time steps.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.windows.stride = stride;
+        self
+    }
+}
+
+impl Layer for MaxPool1d {
+    /// Keeps the largest value of each channel in each window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty, not a whole number of time steps or shorter than
+    /// a window, or if the size or the stride is zero.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let count = self.windows.count(input)?;
+
+        self.selected.clear();
+        for step in 0..count {
+            for channel in 0..self.windows.channels {
+                let largest = self.windows.indices(step, channel)
+                    .reduce(|largest, index| if input[index] > input[largest] { index } else { largest })
+                    .expect("A window holds at least one time step.");
+                self.selected.push(largest);
+            }
+        }
+        self.input_length = input.len();
+
+        Ok(self.selected.iter().map(|&index| input[index]).collect())
+    }
+
+    /// Routes the gradient of each output to the input it was taken from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.input_length == 0 {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.selected.len(), output_gradient.len())?;
+
+        let mut input_gradient = vec![0.0; self.input_length];
+        for (&index, &gradient) in self.selected.iter().zip(output_gradient) {
+            input_gradient[index] += gradient;
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns no parameters, as pooling has none.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        Vec::new()
+    }
+
+    /// Does nothing, as pooling has no parameters.
+    fn zero_grad(&mut self) {}
+}
+
+/// Represents an average pooling layer, which averages each channel over windows of `size` time
+/// steps.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `channels` values.
+/// The windows move by `stride` time steps, which defaults to the size of the windows so that
+/// they do not overlap. A single window spanning the whole sequence averages it over time, to
+/// feed the features of a sequence to a `Dense` layer.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::pool::AvgPool1d;
+///
+/// let mut pool = AvgPool1d::new(1, 2).with_stride(1);
+/// assert_eq!(pool.forward(&[1.0, 3.0, 8.0]).unwrap(), vec![2.0, 5.5]);
+/// ```
+pub struct AvgPool1d {
+    windows: Windows,
+    input_length: usize,
+    output_length: usize
+}
+
+impl AvgPool1d {
+    /// Constructs a new `AvgPool1d` layer over non-overlapping windows.
+    ///
+    /// # Parameters
+    ///
+    /// * `channels` - The number of values of each time step.
+    /// * `size` - The number of time steps of each window.
+    pub fn new(channels: usize, size: usize) -> Self {
+        Self { windows: Windows { channels, size, stride: size }, input_length: 0, output_length: 0 }
+    }
+
+    /// Returns the `AvgPool1d` layer moving its windows by `stride` time steps.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.windows.stride = stride;
+        self
+    }
+}
+
+impl Layer for AvgPool1d {
+    /// Averages each channel in each window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty, not a whole number of time steps or shorter than
+    /// a window, or if the size or the stride is zero.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let count = self.windows.count(input)?;
+        let size = self.windows.size as f64;
+
+        let output: Vec<f64> = (0..count)
+            .flat_map(|step| (0..self.windows.channels).map(move |channel| (step, channel)))
+            .map(|(step, channel)| self.windows.indices(step, channel).map(|index| input[index]).sum::<f64>() / size)
+            .collect();
+        self.input_length = input.len();
+        self.output_length = output.len();
+
+        Ok(output)
+    }
+
+    /// Spreads the gradient of each output evenly over the inputs of its window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.input_length == 0 {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.output_length, output_gradient.len())?;
+
+        let channels = self.windows.channels;
+        let mut input_gradient = vec![0.0; self.input_length];
+        for (output, &gradient) in output_gradient.iter().enumerate() {
+            for index in self.windows.indices(output / channels, output % channels) {
+                input_gradient[index] += gradient / self.windows.size as f64;
+            }
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns no parameters, as pooling has none.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        Vec::new()
+    }
+
+    /// Does nothing, as pooling has no parameters.
+    fn zero_grad(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+
+    /// Six time steps of two channels, without ties.
+    const INPUT: [f64; 12] = [0.5, -1.0, 2.0, 0.3, -0.7, 1.1, 0.9, -0.2, 1.4, 0.8, -1.5, 0.6];
+
+    /// Test max pooling over non-overlapping and overlapping windows.
+    #[test]
+    fn max_pool_forward() {
+        assert_eq!(MaxPool1d::new(2, 3).forward(&INPUT).unwrap(), vec![2.0, 1.1, 1.4, 0.8]);
+        assert_eq!(MaxPool1d::new(2, 2).with_stride(2).forward(&INPUT).unwrap(), vec![2.0, 0.3, 0.9, 1.1, 1.4, 0.8]);
+        assert_eq!(MaxPool1d::new(2, 5).with_stride(1).forward(&INPUT).unwrap(), vec![2.0, 1.1, 2.0, 1.1]);
+    }
+
+    /// Test average pooling, including a window spanning the whole sequence.
+    #[test]
+    fn avg_pool_forward() {
+        let output = AvgPool1d::new(2, 6).forward(&INPUT).unwrap();
+        assert!((output[0] - 2.6 / 6.0).abs() < 1e-12);
+        assert!((output[1] - 1.6 / 6.0).abs() < 1e-12);
+
+        assert_eq!(AvgPool1d::new(1, 2).with_stride(3).forward(&[1.0, 3.0, 0.0, 5.0, 7.0]).unwrap(), vec![2.0, 6.0]);
+    }
+
+    /// Test the gradients of both poolings against finite differences.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(|| MaxPool1d::new(2, 2), &INPUT);
+        assert_gradients_match(|| MaxPool1d::new(2, 3).with_stride(1), &INPUT);
+        assert_gradients_match(|| AvgPool1d::new(2, 2), &INPUT);
+        assert_gradients_match(|| AvgPool1d::new(2, 4).with_stride(1), &INPUT);
+    }
+
+    /// Test invalid configurations, inputs and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut pool = MaxPool1d::new(2, 2);
+        assert!(matches!(pool.backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(pool.forward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 4, got: 3 })));
+        assert!(matches!(pool.forward(&[1.0; 2]), Err(QmachinaError::InsufficientData { required: 2, got: 1 })));
+        assert!(matches!(MaxPool1d::new(2, 0).forward(&INPUT), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(AvgPool1d::new(2, 2).with_stride(0).forward(&INPUT), Err(QmachinaError::InvalidParameter(_))));
+
+        let mut pool = AvgPool1d::new(2, 2);
+        pool.forward(&INPUT).unwrap();
+        assert!(matches!(pool.backward(&[1.0; 2]), Err(QmachinaError::LengthMismatch { expected: 6, got: 2 })));
+    }
+}
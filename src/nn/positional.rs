@@ -0,0 +1,305 @@
+//! This module contains the positional encodings, which tell attention layers the time step of
+//! each feature vector.
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+use super::{time_steps, xavier_uniform, Layer};
+
+/// The seed used to initialise the encodings when none is given.
+const DEFAULT_SEED: u64 = 0x0905_17E5;
+
+/// The base of the wavelengths of the sinusoidal encoding.
+const BASE: f64 = 10_000.0;
+
+/// Represents the fixed sinusoidal positional encoding of Vaswani et al. (2017), added to the
+/// features of each time step.
+///
+/// At time step `t`, the features `2i` and `2i + 1` receive `sin(t / 10000^(2i / d))` and
+/// `cos(t / 10000^(2i / d))`, where `d` is the model size. The wavelengths form a geometric
+/// progression, so that the encoding of a shifted time step is a linear function of the
+/// original one. The encoding has no parameters and works for sequences of any length.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::positional::SinusoidalPositionalEncoding;
+///
+/// let mut encoding = SinusoidalPositionalEncoding::new(2);
+/// let output = encoding.forward(&[0.0, 0.0, 0.0, 0.0]).unwrap();
+/// assert_eq!(output[..2], [0.0, 1.0]);
+/// assert_eq!(output[2..], [1f64.sin(), 1f64.cos()]);
+/// ```
+pub struct SinusoidalPositionalEncoding {
+    model_size: usize,
+    output_length: usize
+}
+
+impl SinusoidalPositionalEncoding {
+    /// Constructs a new `SinusoidalPositionalEncoding`.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_size` - The number of features of each time step.
+    pub fn new(model_size: usize) -> Self {
+        Self { model_size, output_length: 0 }
+    }
+
+    /// Returns the number of features of each time step.
+    pub fn model_size(&self) -> usize {
+        self.model_size
+    }
+
+    /// Returns the encoding of `steps` time steps, flattened in time order.
+    pub fn encoding(&self, steps: usize) -> Vec<f64> {
+        (0..steps)
+            .flat_map(|step| (0..self.model_size).map(move |feature| (step, feature)))
+            .map(|(step, feature)| {
+                let frequency = BASE.powf(-((feature - feature % 2) as f64) / self.model_size as f64);
+                let angle = step as f64 * frequency;
+                if feature % 2 == 0 { angle.sin() } else { angle.cos() }
+            })
+            .collect()
+    }
+}
+
+impl Layer for SinusoidalPositionalEncoding {
+    /// Adds the encoding of its time step to every time step of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let steps = time_steps(input, self.model_size)?;
+        self.output_length = input.len();
+
+        Ok(input.iter().zip(self.encoding(steps)).map(|(x, encoding)| x + encoding).collect())
+    }
+
+    /// Passes the gradient through, as the encoding is a constant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.output_length == 0 {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.output_length, output_gradient.len())?;
+
+        Ok(output_gradient.to_vec())
+    }
+
+    /// Returns no parameters, as the encoding is fixed.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        Vec::new()
+    }
+
+    /// Does nothing, as the encoding is fixed.
+    fn zero_grad(&mut self) {}
+}
+
+/// Represents a learned positional encoding, a trainable vector per time step added to its
+/// features.
+///
+/// The encodings of up to `max_steps` time steps are stored row-major, one row of `model_size`
+/// values per time step, and are initialised from the Xavier uniform distribution. A sequence
+/// shorter than `max_steps` uses the encodings of its first time steps.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::nn::Layer;
+/// use qmachina::nn::positional::LearnedPositionalEncoding;
+///
+/// let mut encoding = LearnedPositionalEncoding::from_weights(3, 1, vec![0.1, 0.2, 0.3]).unwrap();
+/// assert_eq!(encoding.forward(&[1.0, 1.0]).unwrap(), vec![1.1, 1.2]);
+/// assert!(encoding.forward(&[1.0; 4]).is_err());
+/// ```
+pub struct LearnedPositionalEncoding {
+    max_steps: usize,
+    model_size: usize,
+    encodings: Vec<f64>,
+    gradients: Vec<f64>,
+    output_length: usize
+}
+
+impl LearnedPositionalEncoding {
+    /// Constructs a new `LearnedPositionalEncoding` with Xavier-initialised encodings.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The largest number of time steps of a sequence.
+    /// * `model_size` - The number of features of each time step.
+    pub fn new(max_steps: usize, model_size: usize) -> Self {
+        let encodings = xavier_uniform(max_steps, model_size, max_steps * model_size, DEFAULT_SEED);
+        Self::with_encodings(max_steps, model_size, encodings)
+    }
+
+    /// Builds the encoding around its values, with cleared gradients.
+    fn with_encodings(max_steps: usize, model_size: usize, encodings: Vec<f64>) -> Self {
+        Self {
+            max_steps,
+            model_size,
+            gradients: vec![0.0; encodings.len()],
+            encodings,
+            output_length: 0
+        }
+    }
+
+    /// Constructs a new `LearnedPositionalEncoding` with the given encodings.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_steps` - The largest number of time steps of a sequence.
+    /// * `model_size` - The number of features of each time step.
+    /// * `encodings` - The row-major encodings, `max_steps` rows of `model_size` values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of encodings does not match the sizes, or if they contain
+    /// invalid values (NaN or infinite).
+    pub fn from_weights(max_steps: usize, model_size: usize, encodings: Vec<f64>) -> Result<Self> {
+        ensure_same_length(max_steps * model_size, encodings.len())?;
+        ensure_finite(&encodings)?;
+
+        Ok(Self::with_encodings(max_steps, model_size, encodings))
+    }
+
+    /// Returns the `LearnedPositionalEncoding` with its encodings re-initialised from the given
+    /// seed.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed of the random generator used for the Xavier initialisation.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the largest number of time steps of a sequence.
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Returns the number of features of each time step.
+    pub fn model_size(&self) -> usize {
+        self.model_size
+    }
+
+    /// Returns the row-major encodings.
+    pub fn encodings(&self) -> &[f64] {
+        &self.encodings
+    }
+}
+
+impl Layer for LearnedPositionalEncoding {
+    /// Adds the encoding of its time step to every time step of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty, not a whole number of time steps or longer than
+    /// `max_steps` time steps.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        if time_steps(input, self.model_size)? > self.max_steps {
+            return Err(QmachinaError::InvalidParameter("The sequence is longer than the learned positional encodings."));
+        }
+        self.output_length = input.len();
+
+        Ok(input.iter().zip(&self.encodings).map(|(x, encoding)| x + encoding).collect())
+    }
+
+    /// Passes the gradient through, accumulating it into the gradients of the encodings used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.output_length == 0 {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.output_length, output_gradient.len())?;
+
+        for (gradient, output_gradient) in self.gradients.iter_mut().zip(output_gradient) {
+            *gradient += output_gradient;
+        }
+
+        Ok(output_gradient.to_vec())
+    }
+
+    /// Returns the encodings with their accumulated gradients.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        vec![(&mut self.encodings, &self.gradients)]
+    }
+
+    /// Clears the accumulated gradients of the encodings.
+    fn zero_grad(&mut self) {
+        self.gradients.fill(0.0);
+    }
+
+    /// Re-initialises the encodings from the given seed.
+    fn reseed(&mut self, seed: u64) {
+        self.encodings = xavier_uniform(self.max_steps, self.model_size, self.encodings.len(), seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+
+    /// Test the sinusoidal encoding against its definition.
+    #[test]
+    fn sinusoidal_encoding_values() {
+        let encoding = SinusoidalPositionalEncoding::new(4).encoding(3);
+        assert_eq!(encoding.len(), 12);
+        assert_eq!(encoding[..4], [0.0, 1.0, 0.0, 1.0]);
+
+        // At time step 2, the second pair of features has a frequency of 10000^(-2/4) = 0.01
+        assert_eq!(encoding[8..10], [2f64.sin(), 2f64.cos()]);
+        assert!((encoding[10] - 0.02f64.sin()).abs() < 1e-15);
+        assert!((encoding[11] - 0.02f64.cos()).abs() < 1e-15);
+
+        // An odd model size ends with a sine
+        let encoding = SinusoidalPositionalEncoding::new(3).encoding(2);
+        assert_eq!(encoding[3..5], [1f64.sin(), 1f64.cos()]);
+        assert!((encoding[5] - 10_000f64.powf(-2.0 / 3.0).sin()).abs() < 1e-15);
+    }
+
+    /// Test the gradients of both encodings against finite differences.
+    #[test]
+    fn backward_matches_finite_differences() {
+        let input: Vec<f64> = (0..8).map(|index| index as f64 * 0.3 - 1.0).collect();
+        assert_gradients_match(|| SinusoidalPositionalEncoding::new(2), &input);
+        assert_gradients_match(|| LearnedPositionalEncoding::new(5, 2).with_seed(3), &input);
+    }
+
+    /// Test that a learned encoding only trains the encodings of the time steps it saw.
+    #[test]
+    fn learned_encoding_gradients() {
+        let mut encoding = LearnedPositionalEncoding::new(3, 2);
+        encoding.forward(&[1.0; 4]).unwrap();
+        assert_eq!(encoding.backward(&[0.5, -1.0, 2.0, 3.0]).unwrap(), vec![0.5, -1.0, 2.0, 3.0]);
+        assert_eq!(encoding.parameters_mut()[0].1, &[0.5, -1.0, 2.0, 3.0, 0.0, 0.0]);
+
+        encoding.zero_grad();
+        assert!(encoding.parameters_mut()[0].1.iter().all(|&gradient| gradient == 0.0));
+    }
+
+    /// Test invalid inputs and gradients.
+    /// Expected result is an error.
+    #[test]
+    fn invalid_inputs() {
+        let mut encoding = LearnedPositionalEncoding::new(2, 2);
+        assert!(matches!(encoding.backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(encoding.forward(&[1.0; 6]), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(encoding.forward(&[1.0; 3]), Err(QmachinaError::LengthMismatch { expected: 4, got: 3 })));
+        assert!(LearnedPositionalEncoding::from_weights(2, 2, vec![0.0; 3]).is_err());
+
+        let mut encoding = SinusoidalPositionalEncoding::new(2);
+        assert!(matches!(encoding.backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(encoding.forward(&[]), Err(QmachinaError::EmptyInput)));
+        encoding.forward(&[1.0; 4]).unwrap();
+        assert!(matches!(encoding.backward(&[1.0; 2]), Err(QmachinaError::LengthMismatch { expected: 4, got: 2 })));
+    }
+}
@@ -0,0 +1,395 @@
+//! This module contains the `Sequential` model, a stack of layers trained with backpropagation.
+use std::sync::Arc;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::loss::DifferentiableLoss;
+use crate::optim::Optimizer;
+use crate::optim::sgd::SgdOptimizer;
+use super::Layer;
+
+/// Represents a feed-forward model made of layers applied one after the other.
+///
+/// Training uses mini-batch gradient descent: the gradient of the loss is backpropagated for each
+/// sample of the batch, the parameter gradients are averaged over the batch and an `Optimizer`
+/// updates the parameters, plain SGD unless another one is given.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::activation::tanh::TanhActivationFunction;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+/// use qmachina::nn::dense::Dense;
+/// use qmachina::nn::sequential::Sequential;
+///
+/// let mut model = Sequential::new()
+///     .with_layer(Dense::new(2, 4, Box::new(TanhActivationFunction)))
+///     .with_layer(Dense::new(4, 1, Box::new(IdentityActivationFunction)));
+///
+/// let inputs = vec![vec![0.1, 0.2], vec![-0.3, 0.5]];
+/// let targets = vec![vec![0.3], vec![0.2]];
+///
+/// let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+/// let second = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+/// assert!(second < first);
+/// ```
+#[derive(Default)]
+pub struct Sequential {
+    layers: Vec<Box<dyn Layer>>,
+    seed: Option<u64>
+}
+
+impl Sequential {
+    /// Constructs a new `Sequential` model without layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the `Sequential` model with the given layer appended.
+    ///
+    /// # Parameters
+    ///
+    /// * `layer` - The layer applied to the output of the current last layer.
+    pub fn with_layer<L: Layer + 'static>(mut self, mut layer: L) -> Self {
+        if let Some(seed) = self.seed {
+            layer.reseed(layer_seed(seed, self.layers.len()));
+        }
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Returns the `Sequential` model with the parameters of every layer, added before or after,
+    /// re-initialised from a seed derived from the given one and the position of the layer.
+    ///
+    /// Layers constructed without a seed start from the same weights whenever they have the same
+    /// shape, and this gives each of them its own initialisation from a single seed, replacing
+    /// the seeds given to the layers themselves.
+    ///
+    /// # Parameters
+    ///
+    /// * `seed` - The seed the seeds of the layers are derived from.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        for (position, layer) in self.layers.iter_mut().enumerate() {
+            layer.reseed(layer_seed(seed, position));
+        }
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the number of layers of the model.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if the model has no layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Runs a single sample through every layer.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The input values of the sample.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<f64>)` containing the output of the last layer, or an error if a layer
+    /// rejects its input.
+    pub fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        self.layers.iter_mut()
+            .try_fold(input.to_vec(), |values, layer| layer.forward(&values))
+    }
+
+    /// Backpropagates the gradient of the loss through every layer, from the last to the first,
+    /// accumulating the parameter gradients of the last sample passed to `forward`.
+    ///
+    /// # Parameters
+    ///
+    /// * `output_gradient` - The gradient of the loss with respect to the output of the model.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<f64>)` containing the gradient with respect to the input of the model, or
+    /// an error if a layer cannot backpropagate the gradient.
+    pub fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        self.layers.iter_mut()
+            .rev()
+            .try_fold(output_gradient.to_vec(), |gradient, layer| layer.backward(&gradient))
+    }
+
+    /// Clears the accumulated gradients of every layer.
+    pub fn zero_grad(&mut self) {
+        self.layers.iter_mut().for_each(|layer| layer.zero_grad());
+    }
+
+    /// Updates the parameters of every layer from their accumulated gradients.
+    ///
+    /// Each parameter group of each layer is given its own group index, in layer order, so that
+    /// stateful optimizers keep consistent state across steps.
+    ///
+    /// # Parameters
+    ///
+    /// * `optimizer` - The optimizer applying the update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the optimizer rejects a parameter group.
+    pub fn step(&mut self, optimizer: &mut dyn Optimizer) -> Result<()> {
+        let mut group = 0;
+        for layer in self.layers.iter_mut() {
+            for (parameters, gradients) in layer.parameters_mut() {
+                optimizer.update(group, parameters, gradients)?;
+                group += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs one step of mini-batch stochastic gradient descent.
+    ///
+    /// # Parameters
+    ///
+    /// * `inputs` - The input values of each sample of the batch.
+    /// * `targets` - The target values of each sample of the batch.
+    /// * `loss` - The loss function to minimise.
+    /// * `learning_rate` - The size of the step taken against the gradient.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(f64)` containing the mean loss over the batch before the update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `train_step_with`.
+    pub fn train_step<L: DifferentiableLoss<f64>>(
+        &mut self,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+        loss: &L,
+        learning_rate: f64
+    ) -> Result<f64> {
+        self.train_step_with(inputs, targets, loss, &mut SgdOptimizer::new(learning_rate))
+    }
+
+    /// Runs one training step on a mini-batch, updating the parameters with the given optimizer.
+    ///
+    /// # Parameters
+    ///
+    /// * `inputs` - The input values of each sample of the batch.
+    /// * `targets` - The target values of each sample of the batch.
+    /// * `loss` - The loss function to minimise.
+    /// * `optimizer` - The optimizer applying the update.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(f64)` containing the mean loss over the batch before the update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch is empty, if inputs and targets have different lengths, or if
+    /// a layer or the loss fails, in which case the parameters are left untouched. An error is also
+    /// returned if the optimizer rejects the gradients.
+    pub fn train_step_with<L: DifferentiableLoss<f64>>(
+        &mut self,
+        inputs: &[Vec<f64>],
+        targets: &[Vec<f64>],
+        loss: &L,
+        optimizer: &mut dyn Optimizer
+    ) -> Result<f64> {
+        if inputs.is_empty() {
+            return Err(QmachinaError::EmptyInput);
+        }
+        ensure_same_length(inputs.len(), targets.len())?;
+
+        let batch_size = inputs.len() as f64;
+        self.zero_grad();
+        let mut total_loss = 0.0;
+        for (input, target) in inputs.iter().zip(targets) {
+            let predictions: Arc<[f64]> = self.forward(input)?.into();
+            let target: Arc<[f64]> = target.as_slice().into();
+
+            total_loss += loss.compute(predictions.clone(), target.clone())?;
+            // Scaling the loss gradient averages the accumulated parameter gradients over the batch.
+            let gradient: Vec<f64> = loss.gradient(predictions, target)?
+                .into_iter()
+                .map(|gradient| gradient / batch_size)
+                .collect();
+            self.backward(&gradient)?;
+        }
+
+        self.step(optimizer)?;
+
+        Ok(total_loss / batch_size)
+    }
+}
+
+/// Derives the seed of the layer at `position` from the seed of the model, far enough apart
+/// for the consecutive seeds used inside composite layers not to overlap.
+fn layer_seed(seed: u64, position: usize) -> u64 {
+    seed ^ (position as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation::identity::IdentityActivationFunction;
+    use crate::activation::sigmoid::SigmoidActivationFunction;
+    use crate::activation::tanh::TanhActivationFunction;
+    use crate::loss::LossFunction;
+    use crate::loss::bce::BinaryCrossEntropyLossFunction;
+    use crate::loss::mse::MeanSquaredErrorLossFunction;
+    use crate::nn::dense::Dense;
+    use crate::optim::adam::AdamOptimizer;
+    use crate::optim::momentum::MomentumOptimizer;
+
+    fn model() -> Sequential {
+        Sequential::new()
+            .with_layer(Dense::from_weights(2, 2, vec![0.4, -0.6, 0.3, 0.8], vec![0.1, -0.2], Box::new(TanhActivationFunction)).unwrap())
+            .with_layer(Dense::from_weights(2, 1, vec![0.5, -0.7], vec![0.05], Box::new(IdentityActivationFunction)).unwrap())
+    }
+
+    fn loss_at(model: &mut Sequential, input: &[f64], target: f64) -> f64 {
+        let prediction = model.forward(input).unwrap();
+        MeanSquaredErrorLossFunction.compute(prediction.into(), Arc::new([target])).unwrap()
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let input = [0.7, -0.2];
+        let target = 0.4;
+        let step = 1e-6;
+
+        let mut network = model();
+        let prediction = network.forward(&input).unwrap();
+        let gradient = MeanSquaredErrorLossFunction.gradient(prediction.into(), Arc::new([target])).unwrap();
+        let input_gradient = network.backward(&gradient).unwrap();
+
+        for index in 0..input.len() {
+            let mut forward = input;
+            let mut backward = input;
+            forward[index] += step;
+            backward[index] -= step;
+
+            let expected = (loss_at(&mut model(), &forward, target) - loss_at(&mut model(), &backward, target)) / (2.0 * step);
+            assert!((input_gradient[index] - expected).abs() < 1e-6, "Input gradient {} mismatch", index);
+        }
+    }
+
+    #[test]
+    fn learns_linear_relationship() {
+        let inputs: Vec<Vec<f64>> = (0..20).map(|i| vec![i as f64 / 10.0 - 1.0, (i % 5) as f64 / 5.0]).collect();
+        let targets: Vec<Vec<f64>> = inputs.iter().map(|x| vec![0.5 * x[0] - 0.3 * x[1] + 0.1]).collect();
+
+        let mut network = Sequential::new()
+            .with_layer(Dense::new(2, 8, Box::new(TanhActivationFunction)).with_seed(3))
+            .with_layer(Dense::new(8, 1, Box::new(IdentityActivationFunction)).with_seed(4));
+
+        let initial = network.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+        let mut last = initial;
+        for _ in 0..500 {
+            last = network.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.1).unwrap();
+        }
+
+        assert!(last < 1e-3, "Loss should converge, got {}", last);
+        assert!(last < initial / 10.0);
+    }
+
+    #[test]
+    fn learns_xor_classification() {
+        let inputs = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+        let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+
+        let mut network = Sequential::new()
+            .with_layer(Dense::new(2, 4, Box::new(TanhActivationFunction)).with_seed(11))
+            .with_layer(Dense::new(4, 1, Box::new(SigmoidActivationFunction)).with_seed(12));
+
+        for _ in 0..3000 {
+            network.train_step(&inputs, &targets, &BinaryCrossEntropyLossFunction, 0.5).unwrap();
+        }
+
+        for (input, target) in inputs.iter().zip(&targets) {
+            let prediction = network.forward(input).unwrap()[0];
+            assert!((prediction - target[0]).abs() < 0.2, "XOR({:?}) predicted {}", input, prediction);
+        }
+    }
+
+    #[test]
+    fn invalid_batches_fail_without_updating() {
+        let mut network = model();
+        let before = network.forward(&[1.0, 1.0]).unwrap();
+
+        assert!(network.train_step(&[], &[], &MeanSquaredErrorLossFunction, 0.1).is_err());
+        assert!(network.train_step(&[vec![1.0, 1.0]], &[], &MeanSquaredErrorLossFunction, 0.1).is_err());
+        assert!(network.train_step(&[vec![1.0]], &[vec![1.0]], &MeanSquaredErrorLossFunction, 0.1).is_err());
+        assert!(network.train_step(&[vec![1.0, 1.0]], &[vec![1.0, 2.0]], &MeanSquaredErrorLossFunction, 0.1).is_err());
+
+        assert_eq!(network.forward(&[1.0, 1.0]).unwrap(), before);
+    }
+
+    #[test]
+    fn empty_model_is_identity() {
+        let mut network = Sequential::new();
+        assert!(network.is_empty());
+        assert_eq!(network.forward(&[1.0, 2.0]).unwrap(), vec![1.0, 2.0]);
+        assert_eq!(network.backward(&[0.5]).unwrap(), vec![0.5]);
+        assert_eq!(model().len(), 2);
+    }
+
+    #[test]
+    fn trains_with_adam() {
+        let inputs: Vec<Vec<f64>> = (0..20).map(|i| vec![i as f64 / 10.0 - 1.0]).collect();
+        let targets: Vec<Vec<f64>> = inputs.iter().map(|x| vec![x[0] * x[0]]).collect();
+
+        let mut network = Sequential::new()
+            .with_layer(Dense::new(1, 8, Box::new(TanhActivationFunction)).with_seed(5))
+            .with_layer(Dense::new(8, 1, Box::new(IdentityActivationFunction)).with_seed(6));
+        let mut adam = AdamOptimizer::new(0.02);
+
+        let initial = network.train_step_with(&inputs, &targets, &MeanSquaredErrorLossFunction, &mut adam).unwrap();
+        let mut last = initial;
+        for _ in 0..1000 {
+            last = network.train_step_with(&inputs, &targets, &MeanSquaredErrorLossFunction, &mut adam).unwrap();
+        }
+
+        assert!(last < 1e-3, "Loss should converge, got {}", last);
+    }
+
+    #[test]
+    fn step_uses_one_group_per_parameter_set() {
+        let mut network = model();
+        network.forward(&[1.0, -1.0]).unwrap();
+        network.backward(&[1.0]).unwrap();
+
+        let mut momentum = MomentumOptimizer::new(0.1, 0.9);
+        network.step(&mut momentum).unwrap();
+        network.step(&mut momentum).unwrap();
+        // Four groups: the weights and biases of both layers.
+        assert!(momentum.update(3, &mut [0.0], &[0.0]).is_ok());
+        assert!(momentum.update(2, &mut [0.0], &[0.0]).is_err(), "Group 2 holds the 2 weights of the last layer");
+    }
+
+    #[test]
+    fn seed_gives_each_layer_its_own_weights() {
+        let build = || Sequential::new()
+            .with_layer(Dense::new(3, 3, Box::new(TanhActivationFunction)))
+            .with_layer(Dense::new(3, 3, Box::new(TanhActivationFunction)));
+
+        let mut unseeded = build();
+        let unseeded: Vec<Vec<f64>> = unseeded.layers.iter_mut().map(|layer| layer.parameters_mut()[0].0.to_vec()).collect();
+        assert_eq!(unseeded[0], unseeded[1], "Layers of the same shape share their default weights");
+
+        let mut seeded = build().with_seed(7);
+        let seeded: Vec<Vec<f64>> = seeded.layers.iter_mut().map(|layer| layer.parameters_mut()[0].0.to_vec()).collect();
+        assert_ne!(seeded[0], seeded[1]);
+
+        // The seed applies to the layers added after it, whatever the order of the calls
+        let mut later = Sequential::new()
+            .with_seed(7)
+            .with_layer(Dense::new(3, 3, Box::new(TanhActivationFunction)))
+            .with_layer(Dense::new(3, 3, Box::new(TanhActivationFunction)).with_seed(1));
+        let later: Vec<Vec<f64>> = later.layers.iter_mut().map(|layer| layer.parameters_mut()[0].0.to_vec()).collect();
+        assert_eq!(later, seeded);
+    }
+}
@@ -0,0 +1,260 @@
+//! This module contains the residual block of a Temporal Convolutional Network (TCN).
+use crate::activation::BatchActivationFunction;
+use crate::activation::identity::IdentityActivationFunction;
+use crate::activation::relu::ReLUActivationFunction;
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use super::conv::{Conv1d, Padding};
+use super::Layer;
+
+/// Represents the residual block of a Temporal Convolutional Network, as introduced by Bai,
+/// Kolter and Koltun (2018).
+///
+/// The block applies two dilated causal convolutions, each followed by
+/// `ReLUActivationFunction`, and adds the result to its input before a final ReLU:
+///
+/// \[
+/// y = \mathrm{ReLU}\big(\mathrm{conv}_2(\mathrm{conv}_1(x)) + \mathrm{res}(x)\big)
+/// \]
+///
+/// where the residual connection `res` is the identity, or a convolution of kernel size 1 when
+/// the number of channels changes. As the convolutions are causal with a stride of 1, the output
+/// has the length of the input and an output time step only depends on the inputs up to it.
+/// Stacking blocks with dilations 1, 2, 4, ... grows the receptive field exponentially with the
+/// depth, `1 + 2 * (kernel_size - 1) * sum(dilations)` time steps.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `in_channels`
+/// features, such as the rows of `feature_windows`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+/// use qmachina::nn::dense::Dense;
+/// use qmachina::nn::sequential::Sequential;
+/// use qmachina::nn::tcn::TemporalBlock;
+///
+/// // Windows of 8 time steps of 2 features, predicting a value from the last hidden time step
+/// let mut model = Sequential::new()
+///     .with_layer(TemporalBlock::new(2, 4, 2, 1))
+///     .with_layer(TemporalBlock::new(4, 4, 2, 2))
+///     .with_layer(Dense::new(8 * 4, 1, Box::new(IdentityActivationFunction)));
+///
+/// let inputs: Vec<Vec<f64>> = (0..4).map(|sample| (0..16).map(|i| ((sample * 16 + i) as f64).sin()).collect()).collect();
+/// let targets = vec![vec![0.5], vec![-0.2], vec![0.1], vec![0.3]];
+///
+/// let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+/// let second = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+/// assert!(second < first);
+/// ```
+pub struct TemporalBlock {
+    first: Conv1d,
+    second: Conv1d,
+    downsample: Option<Conv1d>,
+    pre_activation: Vec<f64>
+}
+
+impl TemporalBlock {
+    /// Constructs a new `TemporalBlock` with Xavier-initialised convolutions.
+    ///
+    /// # Parameters
+    ///
+    /// * `in_channels` - The number of features of each input time step.
+    /// * `out_channels` - The number of features of each output time step.
+    /// * `kernel_size` - The number of taps of both convolutions.
+    /// * `dilation` - The number of time steps between the taps of both convolutions.
+    pub fn new(in_channels: usize, out_channels: usize, kernel_size: usize, dilation: usize) -> Self {
+        let causal = |conv: Conv1d| conv.with_padding(Padding::Causal).with_dilation(dilation);
+
+        Self {
+            first: causal(Conv1d::new(in_channels, out_channels, kernel_size, Box::new(ReLUActivationFunction))),
+            second: causal(Conv1d::new(out_channels, out_channels, kernel_size, Box::new(ReLUActivationFunction)).with_seed(1)),
+            downsample: (in_channels != out_channels)
+                .then(|| Conv1d::new(in_channels, out_channels, 1, Box::new(IdentityActivationFunction)).with_seed(2)),
+            pre_activation: Vec::new()
+        }
+    }
+
+    /// Returns the `TemporalBlock` with the weights of its convolutions re-initialised from
+    /// seeds derived from the given one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the number of features of each input time step.
+    pub fn in_channels(&self) -> usize {
+        self.first.in_channels()
+    }
+
+    /// Returns the number of features of each output time step.
+    pub fn out_channels(&self) -> usize {
+        self.first.out_channels()
+    }
+}
+
+impl Layer for TemporalBlock {
+    /// Runs the convolutions and the residual connection over the time steps of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let hidden = self.first.forward(input)?;
+        let mut output = self.second.forward(&hidden)?;
+        let residual = match &mut self.downsample {
+            Some(downsample) => downsample.forward(input)?,
+            None => input.to_vec()
+        };
+
+        // Causal convolutions with a stride of 1 keep the length of the input, as does the
+        // residual connection
+        for (value, residual) in output.iter_mut().zip(residual) {
+            *value += residual;
+        }
+        self.pre_activation = output;
+
+        Ok(ReLUActivationFunction.activate_batch(&self.pre_activation))
+    }
+
+    /// Backpropagates through the final ReLU, then through both the convolutions and the
+    /// residual connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        if self.pre_activation.is_empty() {
+            return Err(QmachinaError::InvalidState("Backward pass requires a forward pass first."));
+        }
+        ensure_same_length(self.pre_activation.len(), output_gradient.len())?;
+
+        let delta: Vec<f64> = output_gradient.iter()
+            .zip(ReLUActivationFunction.derivate_batch(&self.pre_activation))
+            .map(|(gradient, derivative)| gradient * derivative)
+            .collect();
+
+        let hidden_gradient = self.second.backward(&delta)?;
+        let mut input_gradient = self.first.backward(&hidden_gradient)?;
+        let residual_gradient = match &mut self.downsample {
+            Some(downsample) => downsample.backward(&delta)?,
+            None => delta
+        };
+        for (gradient, residual) in input_gradient.iter_mut().zip(residual_gradient) {
+            *gradient += residual;
+        }
+
+        Ok(input_gradient)
+    }
+
+    /// Returns the parameters of both convolutions, then those of the residual convolution if
+    /// any, each with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        let mut parameters = self.first.parameters_mut();
+        parameters.extend(self.second.parameters_mut());
+        if let Some(downsample) = &mut self.downsample {
+            parameters.extend(downsample.parameters_mut());
+        }
+        parameters
+    }
+
+    /// Clears the accumulated gradients of the convolutions.
+    fn zero_grad(&mut self) {
+        self.first.zero_grad();
+        self.second.zero_grad();
+        if let Some(downsample) = &mut self.downsample {
+            downsample.zero_grad();
+        }
+    }
+
+    /// Re-initialises the weights of the convolutions from seeds derived from the given one.
+    fn reseed(&mut self, seed: u64) {
+        self.first.reseed(seed);
+        self.second.reseed(seed.wrapping_add(1));
+        if let Some(downsample) = &mut self.downsample {
+            downsample.reseed(seed.wrapping_add(2));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::assert_gradients_match;
+    use crate::nn::feature_windows;
+    use crate::nn::pool::AvgPool1d;
+    use crate::nn::sequential::Sequential;
+    use crate::nn::dense::Dense;
+    use crate::loss::mse::MeanSquaredErrorLossFunction;
+    use crate::technical_analysis::Indicator;
+    use crate::technical_analysis::REFERENCE_PRICES;
+    use crate::technical_analysis::rsi::RelativeStrengthIndex;
+    use crate::technical_analysis::sma::SimpleMovingAverage;
+
+    /// Twelve time steps of two channels.
+    fn input() -> Vec<f64> {
+        (0..24).map(|index| ((index * 5 % 13) as f64 - 5.5) / 5.0).collect()
+    }
+
+    /// Test that the block keeps the length of the sequence and does not see the future.
+    #[test]
+    fn output_is_causal_and_keeps_the_length() {
+        let mut block = TemporalBlock::new(2, 3, 3, 2).with_seed(4);
+        let output = block.forward(&input()).unwrap();
+        assert_eq!(output.len(), 12 * 3);
+        assert!(output.iter().all(|&value| value >= 0.0));
+
+        let mut changed = input();
+        changed[16..].fill(10.0);
+        assert_eq!(block.forward(&changed).unwrap()[..24], output[..24]);
+    }
+
+    /// Test the gradients against finite differences, with and without a residual convolution.
+    /// The seeds keep every pre-activation away from the kink of ReLU, as the biases start at
+    /// zero.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(|| TemporalBlock::new(2, 3, 2, 2).with_seed(3), &input());
+        assert_gradients_match(|| TemporalBlock::new(2, 2, 3, 1).with_seed(1), &input());
+    }
+
+    /// Test that the parameters include the residual convolution only when the channels change.
+    #[test]
+    fn parameters_of_the_residual_connection() {
+        assert_eq!(TemporalBlock::new(2, 3, 2, 1).parameters_mut().len(), 6);
+        assert_eq!(TemporalBlock::new(3, 3, 2, 1).parameters_mut().len(), 4);
+        assert!(matches!(TemporalBlock::new(2, 3, 2, 1).backward(&[1.0]), Err(QmachinaError::InvalidState(_))));
+    }
+
+    /// Test training a TCN on windows of indicators of a price series.
+    /// Expected result is a decreasing loss.
+    #[test]
+    fn trains_on_indicator_windows() {
+        let rsi = RelativeStrengthIndex::new(5).compute_series(&REFERENCE_PRICES).unwrap();
+        let sma = SimpleMovingAverage::new(3).compute_series(&REFERENCE_PRICES).unwrap();
+        let scale = |series: Vec<Option<f64>>, factor: f64| series.into_iter().map(|value| value.map(|v| v / factor)).collect();
+        let windows = feature_windows(&[scale(rsi, 100.0), scale(sma, 50.0)], 6).unwrap();
+
+        // Predict the next return from each complete window
+        let (inputs, targets): (Vec<Vec<f64>>, Vec<Vec<f64>>) = windows.into_iter()
+            .enumerate()
+            .take(REFERENCE_PRICES.len() - 1)
+            .filter_map(|(end, window)| Some((window?, vec![10.0 * (REFERENCE_PRICES[end + 1] / REFERENCE_PRICES[end] - 1.0)])))
+            .unzip();
+        assert!(!inputs.is_empty());
+
+        let mut model = Sequential::new()
+            .with_layer(TemporalBlock::new(2, 4, 2, 1).with_seed(1))
+            .with_layer(TemporalBlock::new(4, 4, 2, 2).with_seed(2))
+            .with_layer(AvgPool1d::new(4, 6))
+            .with_layer(Dense::new(4, 1, Box::new(IdentityActivationFunction)).with_seed(3));
+
+        let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+        let mut last = first;
+        for _ in 0..50 {
+            last = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+        }
+        assert!(last < first, "Loss went from {first} to {last}");
+    }
+}
@@ -0,0 +1,250 @@
+//! This module contains the encoder block of a Transformer.
+use crate::activation::identity::IdentityActivationFunction;
+use crate::activation::relu::ReLUActivationFunction;
+use crate::error::Result;
+use super::attention::MultiHeadAttention;
+use super::conv::Conv1d;
+use super::norm::LayerNorm;
+use super::Layer;
+
+/// Represents the encoder block of a Transformer (Vaswani et al., 2017) over a sequence.
+///
+/// The block applies a multi-head self-attention and a position-wise feed-forward network, each
+/// as a residual branch preceded by a layer normalisation:
+///
+/// \[
+/// h = x + \mathrm{MHA}(\mathrm{LN}_1(x)), \quad y = h + W_2\,\mathrm{ReLU}(W_1\,\mathrm{LN}_2(h) + b_1) + b_2
+/// \]
+///
+/// Normalising the input of the branches rather than their sum (pre-norm) keeps a path free of
+/// normalisation from the input to the output, which lets small models train without a
+/// learning-rate warm-up. The feed-forward network expands each time step to `hidden_size`
+/// features with `ReLUActivationFunction` and projects it back to `model_size` features.
+///
+/// A sample is a sequence of time steps flattened in time order, each holding `model_size`
+/// features, and the output has the same layout. Positions are not encoded by the block, so
+/// that it is usually preceded by a `SinusoidalPositionalEncoding` or a
+/// `LearnedPositionalEncoding`.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::activation::identity::IdentityActivationFunction;
+/// use qmachina::loss::mse::MeanSquaredErrorLossFunction;
+/// use qmachina::nn::conv::Conv1d;
+/// use qmachina::nn::dense::Dense;
+/// use qmachina::nn::pool::AvgPool1d;
+/// use qmachina::nn::positional::SinusoidalPositionalEncoding;
+/// use qmachina::nn::sequential::Sequential;
+/// use qmachina::nn::transformer::TransformerEncoderBlock;
+///
+/// // Windows of 6 time steps of 2 features, embedded into 4 features per time step
+/// let mut model = Sequential::new()
+///     .with_layer(Conv1d::new(2, 4, 1, Box::new(IdentityActivationFunction)))
+///     .with_layer(SinusoidalPositionalEncoding::new(4))
+///     .with_layer(TransformerEncoderBlock::new(4, 2, 8).with_causal_mask(true))
+///     .with_layer(AvgPool1d::new(4, 6))
+///     .with_layer(Dense::new(4, 1, Box::new(IdentityActivationFunction)));
+///
+/// let inputs: Vec<Vec<f64>> = (0..4).map(|sample| (0..12).map(|i| ((sample * 12 + i) as f64).sin()).collect()).collect();
+/// let targets = vec![vec![0.5], vec![-0.2], vec![0.1], vec![0.3]];
+///
+/// let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.02).unwrap();
+/// let second = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.02).unwrap();
+/// assert!(second < first);
+/// ```
+pub struct TransformerEncoderBlock {
+    attention_norm: LayerNorm,
+    attention: MultiHeadAttention,
+    feed_forward_norm: LayerNorm,
+    expand: Conv1d,
+    project: Conv1d
+}
+
+impl TransformerEncoderBlock {
+    /// Constructs a new `TransformerEncoderBlock` without a mask, with Xavier-initialised
+    /// weights.
+    ///
+    /// # Parameters
+    ///
+    /// * `model_size` - The number of features of each time step.
+    /// * `heads` - The number of attention heads, which must divide the model size.
+    /// * `hidden_size` - The number of features of each time step inside the feed-forward
+    ///   network.
+    pub fn new(model_size: usize, heads: usize, hidden_size: usize) -> Self {
+        Self {
+            attention_norm: LayerNorm::new(model_size),
+            attention: MultiHeadAttention::new(model_size, heads),
+            feed_forward_norm: LayerNorm::new(model_size),
+            expand: Conv1d::new(model_size, hidden_size, 1, Box::new(ReLUActivationFunction)).with_seed(1),
+            project: Conv1d::new(hidden_size, model_size, 1, Box::new(IdentityActivationFunction)).with_seed(2)
+        }
+    }
+
+    /// Returns the `TransformerEncoderBlock` with its weights re-initialised from seeds derived
+    /// from the given one.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.reseed(seed);
+        self
+    }
+
+    /// Returns the `TransformerEncoderBlock` with or without a causal mask in its attention, so
+    /// that the output at a time step only depends on the inputs up to it.
+    pub fn with_causal_mask(self, causal: bool) -> Self {
+        Self { attention: self.attention.with_causal_mask(causal), ..self }
+    }
+
+    /// Returns the self-attention of the block.
+    pub fn attention(&self) -> &MultiHeadAttention {
+        &self.attention
+    }
+}
+
+impl Layer for TransformerEncoderBlock {
+    /// Runs the attention and the feed-forward branches over the time steps of a sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty or not a whole number of time steps, or if the
+    /// number of heads does not divide the model size.
+    fn forward(&mut self, input: &[f64]) -> Result<Vec<f64>> {
+        let attended = self.attention.forward(&self.attention_norm.forward(input)?)?;
+        let hidden: Vec<f64> = input.iter().zip(attended).map(|(x, a)| x + a).collect();
+
+        let expanded = self.expand.forward(&self.feed_forward_norm.forward(&hidden)?)?;
+        let projected = self.project.forward(&expanded)?;
+
+        Ok(hidden.iter().zip(projected).map(|(h, p)| h + p).collect())
+    }
+
+    /// Backpropagates through both residual branches, accumulating the gradients of the
+    /// normalisations, the attention and the feed-forward network.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `forward` was not called first or if the gradient length does not
+    /// match the output of the last forward pass.
+    fn backward(&mut self, output_gradient: &[f64]) -> Result<Vec<f64>> {
+        let expanded_gradient = self.project.backward(output_gradient)?;
+        let branch_gradient = self.feed_forward_norm.backward(&self.expand.backward(&expanded_gradient)?)?;
+        let hidden_gradient: Vec<f64> = output_gradient.iter().zip(branch_gradient).map(|(g, b)| g + b).collect();
+
+        let branch_gradient = self.attention_norm.backward(&self.attention.backward(&hidden_gradient)?)?;
+        Ok(hidden_gradient.iter().zip(branch_gradient).map(|(g, b)| g + b).collect())
+    }
+
+    /// Returns the parameters of the attention branch, then those of the feed-forward branch,
+    /// each with its accumulated gradient.
+    fn parameters_mut(&mut self) -> Vec<(&mut [f64], &[f64])> {
+        let mut parameters = self.attention_norm.parameters_mut();
+        parameters.extend(self.attention.parameters_mut());
+        parameters.extend(self.feed_forward_norm.parameters_mut());
+        parameters.extend(self.expand.parameters_mut());
+        parameters.extend(self.project.parameters_mut());
+        parameters
+    }
+
+    /// Clears the accumulated gradients of every sub-layer.
+    fn zero_grad(&mut self) {
+        self.attention_norm.zero_grad();
+        self.attention.zero_grad();
+        self.feed_forward_norm.zero_grad();
+        self.expand.zero_grad();
+        self.project.zero_grad();
+    }
+
+    /// Re-initialises the weights of the attention and of the feed-forward network from seeds
+    /// derived from the given one.
+    fn reseed(&mut self, seed: u64) {
+        self.attention.reseed(seed);
+        self.expand.reseed(seed.wrapping_add(4));
+        self.project.reseed(seed.wrapping_add(5));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::QmachinaError;
+    use crate::loss::mse::MeanSquaredErrorLossFunction;
+    use crate::nn::assert_gradients_match;
+    use crate::nn::dense::Dense;
+    use crate::nn::feature_windows;
+    use crate::nn::pool::AvgPool1d;
+    use crate::nn::positional::LearnedPositionalEncoding;
+    use crate::nn::sequential::Sequential;
+    use crate::technical_analysis::Indicator;
+    use crate::technical_analysis::REFERENCE_PRICES;
+    use crate::technical_analysis::ema::ExponentialMovingAverage;
+    use crate::technical_analysis::rsi::RelativeStrengthIndex;
+
+    /// Five time steps of four features.
+    fn input() -> Vec<f64> {
+        (0..20).map(|index| ((index * 7 % 13) as f64 - 6.5) / 4.0).collect()
+    }
+
+    /// Test that a causal block keeps the shape of the sequence and does not see the future.
+    #[test]
+    fn causal_block_ignores_the_future() {
+        let mut block = TransformerEncoderBlock::new(4, 2, 6).with_seed(2).with_causal_mask(true);
+        let output = block.forward(&input()).unwrap();
+        assert_eq!(output.len(), 20);
+
+        let mut changed = input();
+        changed[8..].fill(3.0);
+        let changed_output = block.forward(&changed).unwrap();
+        assert_eq!(changed_output[..8], output[..8]);
+        assert_ne!(changed_output[8..12], output[8..12]);
+    }
+
+    /// Test the gradients against finite differences, with and without a causal mask.
+    #[test]
+    fn backward_matches_finite_differences() {
+        assert_gradients_match(|| TransformerEncoderBlock::new(4, 2, 6).with_seed(2), &input());
+        assert_gradients_match(|| TransformerEncoderBlock::new(4, 1, 3).with_seed(7).with_causal_mask(true), &input());
+    }
+
+    /// Test the parameters of the block and invalid configurations.
+    #[test]
+    fn parameters_and_invalid_inputs() {
+        // Two normalisations, four projections of the attention and two of the feed-forward
+        // network, each with weights and biases
+        assert_eq!(TransformerEncoderBlock::new(4, 2, 6).parameters_mut().len(), 16);
+
+        assert!(matches!(TransformerEncoderBlock::new(4, 2, 6).backward(&input()), Err(QmachinaError::InvalidState(_))));
+        assert!(matches!(TransformerEncoderBlock::new(4, 3, 6).forward(&input()), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(TransformerEncoderBlock::new(4, 2, 6).forward(&input()[..6]), Err(QmachinaError::LengthMismatch { .. })));
+    }
+
+    /// Test training a small Transformer forecaster on windows of indicators of a price series.
+    /// Expected result is a decreasing loss.
+    #[test]
+    fn trains_on_indicator_windows() {
+        let rsi = RelativeStrengthIndex::new(5).compute_series(&REFERENCE_PRICES).unwrap();
+        let ema = ExponentialMovingAverage::new(3).compute_series(&REFERENCE_PRICES).unwrap();
+        let scale = |series: Vec<Option<f64>>, factor: f64| series.into_iter().map(|value| value.map(|v| v / factor)).collect();
+        let windows = feature_windows(&[scale(rsi, 100.0), scale(ema, 50.0)], 5).unwrap();
+
+        // Predict the next return from each complete window
+        let (inputs, targets): (Vec<Vec<f64>>, Vec<Vec<f64>>) = windows.into_iter()
+            .enumerate()
+            .take(REFERENCE_PRICES.len() - 1)
+            .filter_map(|(end, window)| Some((window?, vec![10.0 * (REFERENCE_PRICES[end + 1] / REFERENCE_PRICES[end] - 1.0)])))
+            .unzip();
+        assert!(!inputs.is_empty());
+
+        let mut model = Sequential::new()
+            .with_layer(Conv1d::new(2, 4, 1, Box::new(IdentityActivationFunction)).with_seed(1))
+            .with_layer(LearnedPositionalEncoding::new(5, 4).with_seed(2))
+            .with_layer(TransformerEncoderBlock::new(4, 2, 8).with_seed(3).with_causal_mask(true))
+            .with_layer(AvgPool1d::new(4, 5))
+            .with_layer(Dense::new(4, 1, Box::new(IdentityActivationFunction)).with_seed(4));
+
+        let first = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+        let mut last = first;
+        for _ in 0..50 {
+            last = model.train_step(&inputs, &targets, &MeanSquaredErrorLossFunction, 0.05).unwrap();
+        }
+        assert!(last < first, "Loss went from {first} to {last}");
+    }
+}
@@ -0,0 +1,37 @@
+//! This module contains the numeric abstractions shared by the generic implementations.
+use std::fmt::Debug;
+use std::iter::Sum;
+
+use num_traits::NumAssign;
+
+/// The floating-point types supported by activations, losses and indicators, `f32` and `f64`.
+///
+/// `Float` extends `num_traits::Float` with the compound assignment operators and summation used
+/// by running statistics.
+pub trait Float: num_traits::Float + NumAssign + Sum + for<'a> Sum<&'a Self> + Default + Debug + 'static {}
+
+impl Float for f32 {}
+
+impl Float for f64 {}
+
+/// Converts an `f64` constant to the floating-point type `F`.
+pub(crate) fn constant<F: Float>(value: f64) -> F {
+    F::from(value).expect("Floating-point types can represent any f64 constant.")
+}
+
+/// Converts a count, such as a period or a number of elements, to the floating-point type `F`.
+pub(crate) fn count<F: Float>(value: usize) -> F {
+    F::from(value).expect("Floating-point types can represent any count.")
+}
+
+/// Converts a value of the floating-point type `F` to `f64`, for computations carried out in
+/// double precision.
+pub(crate) fn to_f64<F: Float>(value: F) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Returns the sign of a value as `-1`, `0` or `1`. Unlike `signum`, zero maps to zero, which is
+/// the subgradient used for absolute values at their kink.
+pub(crate) fn sign<F: Float>(value: F) -> F {
+    if value == F::zero() { F::zero() } else { value.signum() }
+}
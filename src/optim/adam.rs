@@ -0,0 +1,177 @@
+//! This module contains the definition and implementation of the Adam optimizer.
+use crate::error::Result;
+
+use super::{ensure_valid_gradients, group_state, Optimizer};
+
+/// Represents the Adam (adaptive moment estimation) optimizer.
+///
+/// Adam keeps moving averages of the gradients and of their squares,
+/// `m = beta1 * m + (1 - beta1) * g` and `v = beta2 * v + (1 - beta2) * g^2`, corrects their bias
+/// towards zero with `m' = m / (1 - beta1^t)` and `v' = v / (1 - beta2^t)`, and takes the step
+/// `p = p - learning_rate * m' / (sqrt(v') + epsilon)`, where `t` counts the updates of the group.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::adam::AdamOptimizer;
+///
+/// let mut adam = AdamOptimizer::new(0.1);
+/// let mut parameters = [1.0];
+/// adam.step(&mut parameters, &[4.0]).unwrap();
+///
+/// // The first step has the size of the learning rate.
+/// assert!((parameters[0] - 0.9).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdamOptimizer {
+    learning_rate: f64,
+    beta1: f64,
+    beta2: f64,
+    epsilon: f64,
+    first_moments: Vec<Vec<f64>>,
+    second_moments: Vec<Vec<f64>>,
+    steps: Vec<i32>
+}
+
+impl AdamOptimizer {
+    /// Constructs a new `AdamOptimizer` with betas of 0.9 and 0.999 and an epsilon of 1e-8.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The base size of the steps.
+    pub fn new(learning_rate: f64) -> Self {
+        Self {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            first_moments: Vec::new(),
+            second_moments: Vec::new(),
+            steps: Vec::new()
+        }
+    }
+
+    /// Returns the `AdamOptimizer` using the given decay rates for the moment estimates.
+    ///
+    /// # Parameters
+    ///
+    /// * `beta1` - The decay of the average of gradients.
+    /// * `beta2` - The decay of the average of squared gradients.
+    pub fn with_betas(mut self, beta1: f64, beta2: f64) -> Self {
+        self.beta1 = beta1;
+        self.beta2 = beta2;
+        self
+    }
+
+    /// Returns the `AdamOptimizer` using the given epsilon.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The value added to the denominator for numerical stability.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Returns the decay rates of the first and second moment estimates.
+    pub fn betas(&self) -> (f64, f64) {
+        (self.beta1, self.beta2)
+    }
+
+    /// Returns the epsilon added to the denominator.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+impl Optimizer for AdamOptimizer {
+    /// Updates the moment estimates of the group and takes a bias-corrected step.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths, if the gradients
+    /// contain invalid values (NaN or infinite), or if the group changed size.
+    fn update(&mut self, group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        ensure_valid_gradients(parameters, gradients)?;
+        let first_moments = group_state(&mut self.first_moments, group, parameters.len())?;
+        let second_moments = group_state(&mut self.second_moments, group, parameters.len())?;
+
+        if self.steps.len() <= group {
+            self.steps.resize(group + 1, 0);
+        }
+        self.steps[group] = self.steps[group].saturating_add(1);
+        let first_correction = 1.0 - self.beta1.powi(self.steps[group]);
+        let second_correction = 1.0 - self.beta2.powi(self.steps[group]);
+
+        for (((parameter, &gradient), m), v) in parameters.iter_mut()
+            .zip(gradients)
+            .zip(first_moments.iter_mut())
+            .zip(second_moments.iter_mut())
+        {
+            *m = self.beta1 * *m + (1.0 - self.beta1) * gradient;
+            *v = self.beta2 * *v + (1.0 - self.beta2) * gradient * gradient;
+
+            let m_hat = *m / first_correction;
+            let v_hat = *v / second_correction;
+            *parameter -= self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Sets a new learning rate, keeping the moment estimates.
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    /// Clears the moment estimates and step counts of every group.
+    fn reset(&mut self) {
+        self.first_moments.clear();
+        self.second_moments.clear();
+        self.steps.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_match_manual_computation() {
+        let mut adam = AdamOptimizer::new(0.1).with_betas(0.5, 0.75).with_epsilon(0.0);
+        let mut parameters = [0.0];
+
+        adam.step(&mut parameters, &[2.0]).unwrap();
+        assert!((parameters[0] + 0.1).abs() < 1e-12);
+
+        // m = 0.5 * 1 + 0.5 * 1 = 1, v = 0.75 * 1 + 0.25 * 1 = 1
+        // m' = 1 / 0.75, v' = 1 / 0.4375
+        adam.step(&mut parameters, &[1.0]).unwrap();
+        let expected = -0.1 - 0.1 * (1.0 / 0.75) / (1.0_f64 / 0.4375).sqrt();
+        assert!((parameters[0] - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn groups_are_bias_corrected_independently() {
+        let mut adam = AdamOptimizer::new(0.1);
+        let mut first = [0.0];
+        let mut second = [0.0];
+
+        for _ in 0..5 {
+            adam.update(0, &mut first, &[1.0]).unwrap();
+        }
+        adam.update(1, &mut second, &[3.0]).unwrap();
+        assert!((second[0] + 0.1).abs() < 1e-6, "The first step of a group should have the size of the learning rate");
+
+        adam.reset();
+        let mut third = [0.0];
+        adam.update(0, &mut third, &[-7.0]).unwrap();
+        assert!((third[0] - 0.1).abs() < 1e-6);
+    }
+}
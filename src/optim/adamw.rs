@@ -0,0 +1,171 @@
+//! This module contains the definition and implementation of the AdamW optimizer.
+use crate::error::Result;
+
+use super::{ensure_valid_gradients, Optimizer};
+use super::adam::AdamOptimizer;
+
+/// Represents the AdamW optimizer, Adam with decoupled weight decay.
+///
+/// Instead of adding an L2 penalty to the gradients, which Adam would rescale, AdamW shrinks the
+/// parameters directly, `p = p - learning_rate * weight_decay * p`, before taking the usual Adam
+/// step.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::adamw::AdamWOptimizer;
+///
+/// let mut adamw = AdamWOptimizer::new(0.1).with_weight_decay(0.5);
+/// let mut parameters = [2.0];
+/// adamw.step(&mut parameters, &[0.0]).unwrap();
+///
+/// // Without gradient, only the weight decay applies.
+/// assert!((parameters[0] - 1.9).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdamWOptimizer {
+    adam: AdamOptimizer,
+    weight_decay: f64
+}
+
+impl AdamWOptimizer {
+    /// Constructs a new `AdamWOptimizer` with the Adam defaults and a weight decay of 0.01.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The base size of the steps.
+    pub fn new(learning_rate: f64) -> Self {
+        Self {
+            adam: AdamOptimizer::new(learning_rate),
+            weight_decay: 0.01
+        }
+    }
+
+    /// Returns the `AdamWOptimizer` using the given weight decay.
+    ///
+    /// # Parameters
+    ///
+    /// * `weight_decay` - The fraction of each parameter removed per unit of learning rate.
+    pub fn with_weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+
+    /// Returns the `AdamWOptimizer` using the given decay rates for the moment estimates.
+    ///
+    /// # Parameters
+    ///
+    /// * `beta1` - The decay of the average of gradients.
+    /// * `beta2` - The decay of the average of squared gradients.
+    pub fn with_betas(mut self, beta1: f64, beta2: f64) -> Self {
+        self.adam = self.adam.with_betas(beta1, beta2);
+        self
+    }
+
+    /// Returns the `AdamWOptimizer` using the given epsilon.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The value added to the denominator for numerical stability.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.adam = self.adam.with_epsilon(epsilon);
+        self
+    }
+
+    /// Returns the weight decay.
+    pub fn weight_decay(&self) -> f64 {
+        self.weight_decay
+    }
+
+    /// Returns the decay rates of the first and second moment estimates.
+    pub fn betas(&self) -> (f64, f64) {
+        self.adam.betas()
+    }
+
+    /// Returns the epsilon added to the denominator.
+    pub fn epsilon(&self) -> f64 {
+        self.adam.epsilon()
+    }
+}
+
+impl Optimizer for AdamWOptimizer {
+    /// Decays the parameters of the group, then takes an Adam step.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths, if the gradients
+    /// contain invalid values (NaN or infinite), or if the group changed size. The parameters are
+    /// left untouched on error.
+    fn update(&mut self, group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        ensure_valid_gradients(parameters, gradients)?;
+
+        let original = parameters.to_vec();
+        let shrink = 1.0 - self.adam.learning_rate() * self.weight_decay;
+        parameters.iter_mut().for_each(|parameter| *parameter *= shrink);
+
+        let result = self.adam.update(group, parameters, gradients);
+        if result.is_err() {
+            parameters.copy_from_slice(&original);
+        }
+
+        result
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64 {
+        self.adam.learning_rate()
+    }
+
+    /// Sets a new learning rate, which also scales the weight decay.
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.adam.set_learning_rate(learning_rate);
+    }
+
+    /// Clears the moment estimates and step counts of every group.
+    fn reset(&mut self) {
+        self.adam.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_weight_decay_matches_adam() {
+        let mut adam = AdamOptimizer::new(0.05);
+        let mut adamw = AdamWOptimizer::new(0.05).with_weight_decay(0.0);
+        let mut expected = [1.0, -2.0];
+        let mut parameters = [1.0, -2.0];
+
+        for gradients in [[0.3, -0.1], [0.2, 0.4], [-0.5, 0.1]] {
+            adam.step(&mut expected, &gradients).unwrap();
+            adamw.step(&mut parameters, &gradients).unwrap();
+        }
+
+        assert_eq!(parameters, expected);
+    }
+
+    #[test]
+    fn weight_decay_is_decoupled_from_gradient_scale() {
+        let mut adamw = AdamWOptimizer::new(0.1).with_weight_decay(0.1);
+        let mut parameters = [10.0];
+        adamw.step(&mut parameters, &[1000.0]).unwrap();
+
+        // Shrinks by 10 * 0.1 * 0.1, then the Adam step of size 0.1.
+        assert!((parameters[0] - (10.0 * 0.99 - 0.1)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn failed_update_leaves_parameters_untouched() {
+        let mut adamw = AdamWOptimizer::new(0.1).with_weight_decay(0.1);
+        let mut parameters = [1.0, 2.0];
+        adamw.step(&mut parameters, &[0.1, 0.1]).unwrap();
+
+        let before = [1.0, 2.0, 3.0];
+        let mut resized = before;
+        assert!(adamw.step(&mut resized, &[0.1, 0.1, 0.1]).is_err());
+        assert_eq!(resized, before);
+    }
+}
@@ -0,0 +1,164 @@
+//! This module contains gradient-descent optimizers and learning-rate schedulers.
+//!
+//! Optimizers update parameters in place from their gradients. They work on plain slices, so
+//! they can train the layers of a `Sequential` model as well as standalone learnable values such
+//! as the alpha of a PReLU.
+
+use crate::error::{ensure_finite, ensure_same_length, QmachinaError, Result};
+
+pub mod sgd;
+pub mod momentum;
+pub mod rmsprop;
+pub mod adam;
+pub mod adamw;
+pub mod scheduler;
+
+/// The `Optimizer` trait defines a common interface for gradient-descent optimizers.
+///
+/// A model usually has several parameter groups (for instance the weights and the biases of each
+/// layer). Stateful optimizers such as Adam keep their moment estimates per group, identified by
+/// the `group` index passed to `update`, so the same group must always be given the same index.
+///
+/// # Example
+///
+/// Learning the alpha of a PReLU:
+///
+/// ```
+/// use qmachina::activation::param_relu::PReLUActivationFunction;
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::sgd::SgdOptimizer;
+///
+/// let mut prelu = PReLUActivationFunction::new(0.25);
+/// let mut optimizer = SgdOptimizer::new(0.1);
+///
+/// let mut alpha = [prelu.alpha()];
+/// optimizer.step(&mut alpha, &[prelu.alpha_derivate(-2.0)]).unwrap();
+/// prelu.update_alpha(alpha[0]);
+///
+/// assert!((prelu.alpha() - 0.45).abs() < 1e-12);
+/// ```
+pub trait Optimizer {
+    /// Updates a group of parameters in place from their gradients.
+    ///
+    /// # Parameters
+    ///
+    /// * `group` - The index identifying the parameter group across steps.
+    /// * `parameters` - The parameters to update.
+    /// * `gradients` - The gradient of the loss with respect to each parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths, if the gradients
+    /// contain invalid values (NaN or infinite), or if the group changed size since its last
+    /// update.
+    fn update(&mut self, group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()>;
+
+    /// Updates a single group of parameters, the first one, in place from their gradients.
+    ///
+    /// # Parameters
+    ///
+    /// * `parameters` - The parameters to update.
+    /// * `gradients` - The gradient of the loss with respect to each parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `update`.
+    fn step(&mut self, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        self.update(0, parameters, gradients)
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64;
+
+    /// Sets a new learning rate, keeping the state of the optimizer.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The new learning rate.
+    fn set_learning_rate(&mut self, learning_rate: f64);
+
+    /// Clears the state accumulated across steps, such as velocities and moment estimates.
+    fn reset(&mut self);
+}
+
+/// Checks that parameters and gradients can be used for an update.
+pub(crate) fn ensure_valid_gradients(parameters: &[f64], gradients: &[f64]) -> Result<()> {
+    ensure_same_length(parameters.len(), gradients.len())?;
+    ensure_finite(gradients)
+}
+
+/// Returns the state of a parameter group, creating it filled with zeros on first use.
+pub(crate) fn group_state(states: &mut Vec<Vec<f64>>, group: usize, len: usize) -> Result<&mut [f64]> {
+    if states.len() <= group {
+        states.resize_with(group + 1, Vec::new);
+    }
+
+    let state = &mut states[group];
+    if state.is_empty() {
+        state.resize(len, 0.0);
+    }
+    if state.len() != len {
+        return Err(QmachinaError::InvalidState("Parameter group size changed between optimization steps."));
+    }
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::adam::AdamOptimizer;
+    use crate::optim::adamw::AdamWOptimizer;
+    use crate::optim::momentum::MomentumOptimizer;
+    use crate::optim::rmsprop::RmsPropOptimizer;
+    use crate::optim::sgd::SgdOptimizer;
+
+    /// Minimises `(x - 3)^2 + (y + 1)^2` from the origin.
+    fn minimise(optimizer: &mut dyn Optimizer, steps: usize) -> [f64; 2] {
+        let mut parameters = [0.0, 0.0];
+        for _ in 0..steps {
+            let gradients = [2.0 * (parameters[0] - 3.0), 2.0 * (parameters[1] + 1.0)];
+            optimizer.step(&mut parameters, &gradients).unwrap();
+        }
+        parameters
+    }
+
+    #[test]
+    fn every_optimizer_minimises_a_quadratic() {
+        let optimizers: Vec<(&str, Box<dyn Optimizer>)> = vec![
+            ("SGD", Box::new(SgdOptimizer::new(0.1))),
+            ("Momentum", Box::new(MomentumOptimizer::new(0.05, 0.9))),
+            ("Nesterov", Box::new(MomentumOptimizer::new(0.05, 0.9).with_nesterov(true))),
+            ("RMSProp", Box::new(RmsPropOptimizer::new(0.01))),
+            ("Adam", Box::new(AdamOptimizer::new(0.1))),
+            ("AdamW", Box::new(AdamWOptimizer::new(0.1).with_weight_decay(0.0)))
+        ];
+
+        for (name, mut optimizer) in optimizers {
+            let [x, y] = minimise(optimizer.as_mut(), 1000);
+            assert!((x - 3.0).abs() < 1e-2 && (y + 1.0).abs() < 1e-2, "{} ended at ({}, {})", name, x, y);
+        }
+    }
+
+    #[test]
+    fn invalid_gradients_fail() {
+        let mut optimizer = AdamOptimizer::new(0.1);
+        assert!(optimizer.step(&mut [1.0, 2.0], &[1.0]).is_err());
+        assert!(optimizer.step(&mut [1.0], &[f64::NAN]).is_err());
+
+        optimizer.step(&mut [1.0, 2.0], &[0.1, 0.1]).unwrap();
+        assert!(optimizer.step(&mut [1.0, 2.0, 3.0], &[0.1, 0.1, 0.1]).is_err(), "Group size changed");
+        assert!(optimizer.update(1, &mut [1.0, 2.0, 3.0], &[0.1, 0.1, 0.1]).is_ok());
+    }
+
+    #[test]
+    fn group_state_is_created_lazily() {
+        let mut states = Vec::new();
+        assert_eq!(group_state(&mut states, 2, 3).unwrap(), &[0.0; 3]);
+        assert_eq!(states.len(), 3);
+
+        group_state(&mut states, 2, 3).unwrap()[0] = 1.0;
+        assert_eq!(group_state(&mut states, 2, 3).unwrap()[0], 1.0);
+        assert!(group_state(&mut states, 2, 4).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! This module contains the definition and implementation of SGD with momentum, including the
+//! Nesterov variant.
+use crate::error::Result;
+
+use super::{ensure_valid_gradients, group_state, Optimizer};
+
+/// Represents the Stochastic Gradient Descent optimizer with momentum.
+///
+/// A velocity accumulates past gradients, `v = momentum * v + g`, and the parameters move along
+/// it, `p = p - learning_rate * v`. With Nesterov momentum the step looks ahead along the
+/// velocity, `p = p - learning_rate * (g + momentum * v)`, which usually dampens oscillations.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::momentum::MomentumOptimizer;
+///
+/// let mut optimizer = MomentumOptimizer::new(0.1, 0.9).with_nesterov(true);
+/// let mut parameters = [1.0];
+/// optimizer.step(&mut parameters, &[1.0]).unwrap();
+/// assert!((parameters[0] - 0.81).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MomentumOptimizer {
+    learning_rate: f64,
+    momentum: f64,
+    nesterov: bool,
+    velocities: Vec<Vec<f64>>
+}
+
+impl MomentumOptimizer {
+    /// Constructs a new `MomentumOptimizer` using classical momentum.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The size of the step taken along the velocity.
+    /// * `momentum` - The fraction of the velocity kept at each step, usually 0.9.
+    pub fn new(learning_rate: f64, momentum: f64) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            nesterov: false,
+            velocities: Vec::new()
+        }
+    }
+
+    /// Returns the `MomentumOptimizer` using Nesterov momentum or classical momentum.
+    ///
+    /// # Parameters
+    ///
+    /// * `nesterov` - Whether to use Nesterov momentum.
+    pub fn with_nesterov(mut self, nesterov: bool) -> Self {
+        self.nesterov = nesterov;
+        self
+    }
+
+    /// Returns the momentum coefficient.
+    pub fn momentum(&self) -> f64 {
+        self.momentum
+    }
+
+    /// Returns `true` if the optimizer uses Nesterov momentum.
+    pub fn is_nesterov(&self) -> bool {
+        self.nesterov
+    }
+}
+
+impl Optimizer for MomentumOptimizer {
+    /// Updates the velocity of the group and moves the parameters along it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths, if the gradients
+    /// contain invalid values (NaN or infinite), or if the group changed size.
+    fn update(&mut self, group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        ensure_valid_gradients(parameters, gradients)?;
+        let velocities = group_state(&mut self.velocities, group, parameters.len())?;
+
+        for ((parameter, &gradient), velocity) in parameters.iter_mut().zip(gradients).zip(velocities.iter_mut()) {
+            *velocity = self.momentum * *velocity + gradient;
+
+            let direction = if self.nesterov { gradient + self.momentum * *velocity } else { *velocity };
+            *parameter -= self.learning_rate * direction;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Sets a new learning rate, keeping the velocities.
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    /// Clears the velocities of every group.
+    fn reset(&mut self) {
+        self.velocities.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classical_momentum_accumulates_velocity() {
+        let mut optimizer = MomentumOptimizer::new(0.1, 0.5);
+        let mut parameters = [0.0];
+
+        optimizer.step(&mut parameters, &[1.0]).unwrap();
+        assert!((parameters[0] + 0.1).abs() < 1e-12);
+
+        // v = 0.5 * 1 + 1 = 1.5
+        optimizer.step(&mut parameters, &[1.0]).unwrap();
+        assert!((parameters[0] + 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn nesterov_looks_ahead() {
+        let mut optimizer = MomentumOptimizer::new(0.1, 0.5).with_nesterov(true);
+        let mut parameters = [0.0];
+
+        // v = 1, step = 1 + 0.5 * 1
+        optimizer.step(&mut parameters, &[1.0]).unwrap();
+        assert!((parameters[0] + 0.15).abs() < 1e-12);
+
+        // v = 1.5, step = 1 + 0.5 * 1.5
+        optimizer.step(&mut parameters, &[1.0]).unwrap();
+        assert!((parameters[0] + 0.325).abs() < 1e-12);
+    }
+
+    #[test]
+    fn groups_have_independent_velocities() {
+        let mut optimizer = MomentumOptimizer::new(1.0, 0.9);
+        let mut first = [0.0];
+        let mut second = [0.0];
+
+        optimizer.update(0, &mut first, &[1.0]).unwrap();
+        optimizer.update(0, &mut first, &[1.0]).unwrap();
+        optimizer.update(1, &mut second, &[1.0]).unwrap();
+        assert_eq!(second, [-1.0], "A new group should start without velocity");
+
+        optimizer.reset();
+        optimizer.update(0, &mut first, &[0.0]).unwrap();
+        assert!((first[0] + 2.9).abs() < 1e-12, "Reset should clear the velocity");
+    }
+}
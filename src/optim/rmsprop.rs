@@ -0,0 +1,139 @@
+//! This module contains the definition and implementation of the RMSProp optimizer.
+use crate::error::Result;
+
+use super::{ensure_valid_gradients, group_state, Optimizer};
+
+/// Represents the RMSProp optimizer.
+///
+/// RMSProp keeps a moving average of the squared gradients, `s = decay * s + (1 - decay) * g^2`,
+/// and divides each step by its square root, `p = p - learning_rate * g / (sqrt(s) + epsilon)`,
+/// so that every parameter gets its own effective learning rate.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::rmsprop::RmsPropOptimizer;
+///
+/// let mut optimizer = RmsPropOptimizer::new(0.01).with_decay(0.99);
+/// let mut parameters = [1.0, 1.0];
+/// optimizer.step(&mut parameters, &[100.0, 0.01]).unwrap();
+///
+/// // Both parameters move by the same amount, whatever the scale of their gradients.
+/// assert!((parameters[0] - parameters[1]).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RmsPropOptimizer {
+    learning_rate: f64,
+    decay: f64,
+    epsilon: f64,
+    squared_averages: Vec<Vec<f64>>
+}
+
+impl RmsPropOptimizer {
+    /// Constructs a new `RmsPropOptimizer` with a decay of 0.9 and an epsilon of 1e-8.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The base size of the steps.
+    pub fn new(learning_rate: f64) -> Self {
+        Self {
+            learning_rate,
+            decay: 0.9,
+            epsilon: 1e-8,
+            squared_averages: Vec::new()
+        }
+    }
+
+    /// Returns the `RmsPropOptimizer` using the given decay for the average of squared gradients.
+    ///
+    /// # Parameters
+    ///
+    /// * `decay` - The fraction of the average kept at each step.
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Returns the `RmsPropOptimizer` using the given epsilon.
+    ///
+    /// # Parameters
+    ///
+    /// * `epsilon` - The value added to the denominator for numerical stability.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// Returns the decay of the average of squared gradients.
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Returns the epsilon added to the denominator.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+}
+
+impl Optimizer for RmsPropOptimizer {
+    /// Updates the average of squared gradients of the group and takes a normalised step.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths, if the gradients
+    /// contain invalid values (NaN or infinite), or if the group changed size.
+    fn update(&mut self, group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        ensure_valid_gradients(parameters, gradients)?;
+        let averages = group_state(&mut self.squared_averages, group, parameters.len())?;
+
+        for ((parameter, &gradient), average) in parameters.iter_mut().zip(gradients).zip(averages.iter_mut()) {
+            *average = self.decay * *average + (1.0 - self.decay) * gradient * gradient;
+            *parameter -= self.learning_rate * gradient / (average.sqrt() + self.epsilon);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Sets a new learning rate, keeping the averages of squared gradients.
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    /// Clears the averages of squared gradients of every group.
+    fn reset(&mut self) {
+        self.squared_averages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_matches_manual_computation() {
+        let mut optimizer = RmsPropOptimizer::new(0.1).with_decay(0.5).with_epsilon(0.0);
+        let mut parameters = [0.0];
+
+        // s = 0.5 * 4 = 2
+        optimizer.step(&mut parameters, &[2.0]).unwrap();
+        assert!((parameters[0] + 0.2 / 2.0_f64.sqrt()).abs() < 1e-12);
+
+        // s = 0.5 * 2 + 0.5 * 1 = 1.5
+        optimizer.step(&mut parameters, &[1.0]).unwrap();
+        assert!((parameters[0] + 0.2 / 2.0_f64.sqrt() + 0.1 / 1.5_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_gradient_does_not_move() {
+        let mut optimizer = RmsPropOptimizer::new(0.1);
+        let mut parameters = [1.5];
+        optimizer.step(&mut parameters, &[0.0]).unwrap();
+        assert_eq!(parameters, [1.5]);
+    }
+}
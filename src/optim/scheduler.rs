@@ -0,0 +1,210 @@
+//! This module contains learning-rate schedulers, which adjust the learning rate of an optimizer
+//! as training progresses.
+use std::f64::consts::PI;
+
+use super::Optimizer;
+
+/// The `LearningRateScheduler` trait defines how the learning rate evolves with the epoch.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::adam::AdamOptimizer;
+/// use qmachina::optim::scheduler::{LearningRateScheduler, StepDecayScheduler};
+///
+/// let scheduler = StepDecayScheduler::new(0.1, 10, 0.5);
+/// let mut adam = AdamOptimizer::new(0.1);
+///
+/// scheduler.apply(&mut adam, 25);
+/// assert!((adam.learning_rate() - 0.025).abs() < 1e-12);
+/// ```
+pub trait LearningRateScheduler {
+    /// Returns the learning rate to use at the given epoch, starting from 0.
+    ///
+    /// # Parameters
+    ///
+    /// * `epoch` - The current epoch.
+    fn learning_rate_at(&self, epoch: usize) -> f64;
+
+    /// Sets the learning rate of the optimizer to the one of the given epoch.
+    ///
+    /// # Parameters
+    ///
+    /// * `optimizer` - The optimizer to update.
+    /// * `epoch` - The current epoch.
+    fn apply(&self, optimizer: &mut dyn Optimizer, epoch: usize) {
+        optimizer.set_learning_rate(self.learning_rate_at(epoch));
+    }
+}
+
+/// Represents a step decay schedule, which multiplies the learning rate by `gamma` every
+/// `step_size` epochs: `initial * gamma^(epoch / step_size)`.
+#[derive(Debug, Clone)]
+pub struct StepDecayScheduler {
+    initial_learning_rate: f64,
+    step_size: usize,
+    gamma: f64
+}
+
+impl StepDecayScheduler {
+    /// Constructs a new `StepDecayScheduler`.
+    ///
+    /// # Parameters
+    ///
+    /// * `initial_learning_rate` - The learning rate of the first epochs.
+    /// * `step_size` - The number of epochs between decays. A step size of 0 is replaced by 1.
+    /// * `gamma` - The factor applied at each decay.
+    pub fn new(initial_learning_rate: f64, step_size: usize, gamma: f64) -> Self {
+        Self {
+            initial_learning_rate,
+            step_size: if step_size == 0 { 1 } else { step_size },
+            gamma
+        }
+    }
+
+    /// Returns the number of epochs between decays.
+    pub fn step_size(&self) -> usize {
+        self.step_size
+    }
+
+    /// Returns the factor applied at each decay.
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+}
+
+impl LearningRateScheduler for StepDecayScheduler {
+    fn learning_rate_at(&self, epoch: usize) -> f64 {
+        self.initial_learning_rate * self.gamma.powf((epoch / self.step_size) as f64)
+    }
+}
+
+/// Represents an exponential decay schedule, `initial * gamma^epoch`.
+#[derive(Debug, Clone)]
+pub struct ExponentialDecayScheduler {
+    initial_learning_rate: f64,
+    gamma: f64
+}
+
+impl ExponentialDecayScheduler {
+    /// Constructs a new `ExponentialDecayScheduler`.
+    ///
+    /// # Parameters
+    ///
+    /// * `initial_learning_rate` - The learning rate of the first epoch.
+    /// * `gamma` - The factor applied at every epoch.
+    pub fn new(initial_learning_rate: f64, gamma: f64) -> Self {
+        Self { initial_learning_rate, gamma }
+    }
+
+    /// Returns the factor applied at every epoch.
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+}
+
+impl LearningRateScheduler for ExponentialDecayScheduler {
+    fn learning_rate_at(&self, epoch: usize) -> f64 {
+        self.initial_learning_rate * self.gamma.powf(epoch as f64)
+    }
+}
+
+/// Represents a cosine annealing schedule, which lowers the learning rate from its initial value
+/// to a minimum along half a cosine over `total_epochs`:
+/// `minimum + (initial - minimum) * (1 + cos(pi * epoch / total_epochs)) / 2`.
+///
+/// The learning rate stays at the minimum after `total_epochs`.
+#[derive(Debug, Clone)]
+pub struct CosineAnnealingScheduler {
+    initial_learning_rate: f64,
+    minimum_learning_rate: f64,
+    total_epochs: usize
+}
+
+impl CosineAnnealingScheduler {
+    /// Constructs a new `CosineAnnealingScheduler` annealing down to 0.
+    ///
+    /// # Parameters
+    ///
+    /// * `initial_learning_rate` - The learning rate of the first epoch.
+    /// * `total_epochs` - The number of epochs to reach the minimum. A value of 0 is replaced by 1.
+    pub fn new(initial_learning_rate: f64, total_epochs: usize) -> Self {
+        Self {
+            initial_learning_rate,
+            minimum_learning_rate: 0.0,
+            total_epochs: if total_epochs == 0 { 1 } else { total_epochs }
+        }
+    }
+
+    /// Returns the `CosineAnnealingScheduler` annealing down to the given learning rate.
+    ///
+    /// # Parameters
+    ///
+    /// * `minimum_learning_rate` - The learning rate reached after `total_epochs`.
+    pub fn with_minimum(mut self, minimum_learning_rate: f64) -> Self {
+        self.minimum_learning_rate = minimum_learning_rate;
+        self
+    }
+
+    /// Returns the number of epochs to reach the minimum.
+    pub fn total_epochs(&self) -> usize {
+        self.total_epochs
+    }
+
+    /// Returns the minimum learning rate.
+    pub fn minimum(&self) -> f64 {
+        self.minimum_learning_rate
+    }
+}
+
+impl LearningRateScheduler for CosineAnnealingScheduler {
+    fn learning_rate_at(&self, epoch: usize) -> f64 {
+        let progress = epoch.min(self.total_epochs) as f64 / self.total_epochs as f64;
+        let range = self.initial_learning_rate - self.minimum_learning_rate;
+        self.minimum_learning_rate + range * (1.0 + (PI * progress).cos()) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::sgd::SgdOptimizer;
+
+    #[test]
+    fn step_decay_drops_every_step_size() {
+        let scheduler = StepDecayScheduler::new(1.0, 3, 0.1);
+        let rates: Vec<f64> = (0..7).map(|epoch| scheduler.learning_rate_at(epoch)).collect();
+
+        assert_eq!(rates[..3], [1.0; 3]);
+        assert!(rates[3..6].iter().all(|rate| (rate - 0.1).abs() < 1e-12));
+        assert!((rates[6] - 0.01).abs() < 1e-12);
+        assert_eq!(StepDecayScheduler::new(1.0, 0, 0.5).step_size(), 1);
+    }
+
+    #[test]
+    fn exponential_decay_compounds() {
+        let scheduler = ExponentialDecayScheduler::new(0.2, 0.5);
+        assert_eq!(scheduler.learning_rate_at(0), 0.2);
+        assert!((scheduler.learning_rate_at(3) - 0.025).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cosine_annealing_reaches_minimum() {
+        let scheduler = CosineAnnealingScheduler::new(0.1, 10).with_minimum(0.01);
+
+        assert!((scheduler.learning_rate_at(0) - 0.1).abs() < 1e-12);
+        assert!((scheduler.learning_rate_at(5) - 0.055).abs() < 1e-12);
+        assert!((scheduler.learning_rate_at(10) - 0.01).abs() < 1e-12);
+        assert!((scheduler.learning_rate_at(50) - 0.01).abs() < 1e-12, "Should stay at the minimum");
+    }
+
+    #[test]
+    fn apply_sets_optimizer_learning_rate() {
+        let scheduler = ExponentialDecayScheduler::new(1.0, 0.5);
+        let mut sgd = SgdOptimizer::new(1.0);
+
+        scheduler.apply(&mut sgd, 2);
+        assert_eq!(sgd.learning_rate(), 0.25);
+    }
+}
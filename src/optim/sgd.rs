@@ -0,0 +1,92 @@
+//! This module contains the definition and implementation of plain Stochastic Gradient Descent.
+use crate::error::Result;
+
+use super::{ensure_valid_gradients, Optimizer};
+
+/// Represents the Stochastic Gradient Descent (SGD) optimizer.
+///
+/// Each parameter moves against its gradient, `p = p - learning_rate * g`. SGD keeps no state
+/// between steps.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::optim::Optimizer;
+/// use qmachina::optim::sgd::SgdOptimizer;
+///
+/// let mut sgd = SgdOptimizer::new(0.5);
+/// let mut parameters = [1.0, -1.0];
+/// sgd.step(&mut parameters, &[0.2, -0.4]).unwrap();
+/// assert_eq!(parameters, [0.9, -0.8]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SgdOptimizer {
+    learning_rate: f64
+}
+
+impl SgdOptimizer {
+    /// Constructs a new `SgdOptimizer` with the given learning rate.
+    ///
+    /// # Parameters
+    ///
+    /// * `learning_rate` - The size of the step taken against the gradient.
+    pub fn new(learning_rate: f64) -> Self {
+        Self { learning_rate }
+    }
+}
+
+impl Optimizer for SgdOptimizer {
+    /// Moves each parameter against its gradient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parameters and gradients have different lengths or if the gradients
+    /// contain invalid values (NaN or infinite).
+    fn update(&mut self, _group: usize, parameters: &mut [f64], gradients: &[f64]) -> Result<()> {
+        ensure_valid_gradients(parameters, gradients)?;
+
+        for (parameter, gradient) in parameters.iter_mut().zip(gradients) {
+            *parameter -= self.learning_rate * gradient;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the current learning rate.
+    fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    /// Sets a new learning rate.
+    fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    /// SGD is stateless, so there is nothing to clear.
+    fn reset(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_moves_against_gradient() {
+        let mut sgd = SgdOptimizer::new(0.1);
+        let mut parameters = [1.0, 2.0, 3.0];
+        sgd.step(&mut parameters, &[1.0, -2.0, 0.0]).unwrap();
+
+        assert_eq!(parameters, [0.9, 2.2, 3.0]);
+    }
+
+    #[test]
+    fn learning_rate_can_be_changed() {
+        let mut sgd = SgdOptimizer::new(0.1);
+        sgd.set_learning_rate(1.0);
+        assert_eq!(sgd.learning_rate(), 1.0);
+
+        let mut parameters = [1.0];
+        sgd.step(&mut parameters, &[0.5]).unwrap();
+        assert_eq!(parameters, [0.5]);
+    }
+}
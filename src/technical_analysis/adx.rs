@@ -0,0 +1,276 @@
+//! This module contains definitions and implementations for the Directional Movement System.
+//!
+//! The Directional Movement System, developed by J. Welles Wilder, is made of the positive and
+//! negative Directional Indicators (+DI and -DI), which measure the strength of upward and
+//! downward moves, and of the Average Directional Index (ADX), which measures the strength of
+//! the trend regardless of its direction.
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Holds the values produced by the Directional Movement System at a single point in time.
+///
+/// # Fields
+///
+/// * `plus_di`: The positive Directional Indicator (+DI), from 0 to 100.
+/// * `minus_di`: The negative Directional Indicator (-DI), from 0 to 100.
+/// * `adx`: The Average Directional Index (ADX), from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalMovementOutput<F = f64> {
+    pub plus_di: F,
+    pub minus_di: F,
+    pub adx: F
+}
+
+/// Represents the Average Directional Index (ADX) together with the Directional Indicators.
+///
+/// As in TA-Lib, the directional movements and true ranges are seeded with the sum of their
+/// first `period - 1` values and then smoothed with Wilder's running sums, the first ADX is the
+/// mean of the first `period` directional indices (DX), and the following values use Wilder's
+/// smoothing. The first value is therefore available after `2 * period` bars.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::adx::AverageDirectionalIndex;
+/// use qmachina::technical_analysis::bar::Bar;
+///
+/// let bars: Vec<Bar> = (0..6)
+///     .map(|i| {
+///         let price = 10.0 + i as f64;
+///         Bar::new(i, price, price + 0.5, price - 0.5, price, 100.0)
+///     })
+///     .collect();
+///
+/// let output = AverageDirectionalIndex::new(3).compute(&bars).unwrap();
+/// assert_eq!(output.minus_di, 0.0);
+/// assert_eq!(output.adx, 100.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AverageDirectionalIndex<F = f64> {
+    period: usize,
+    previous: Option<Bar<F>>,
+    observed: usize,
+    plus_dm: F,
+    minus_dm: F,
+    true_range: F,
+    dx_sum: F,
+    adx: Option<F>
+}
+
+impl<F: Float> AverageDirectionalIndex<F> {
+    /// Constructs a new `AverageDirectionalIndex` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The smoothing period of the directional movements and of the ADX.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: if period == 0 { 1 } else { period },
+            previous: None,
+            observed: 0,
+            plus_dm: F::zero(),
+            minus_dm: F::zero(),
+            true_range: F::zero(),
+            dx_sum: F::zero(),
+            adx: None
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for AverageDirectionalIndex<F> {
+    /// Returns the period of the indicator.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, DirectionalMovementOutput<F>> for AverageDirectionalIndex<F> {
+    /// Computes +DI, -DI and the ADX for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(DirectionalMovementOutput)`, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `2 * period` bars or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<DirectionalMovementOutput<F>> {
+        ensure_sufficient(2 * self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, 2 * self.period)
+    }
+
+    /// Computes +DI, -DI and the ADX for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<DirectionalMovementOutput>>)` aligned with `data`, with `None`
+    /// during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<DirectionalMovementOutput<F>>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, DirectionalMovementOutput<F>> for AverageDirectionalIndex<F> {
+    /// Feeds a new bar into the Directional Movement System.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(DirectionalMovementOutput)` once the ADX is available, or `None` while
+    /// warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<DirectionalMovementOutput<F>> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let previous = self.previous.replace(bar)?;
+        let up_move = bar.high - previous.high;
+        let down_move = previous.low - bar.low;
+        let plus_dm = if up_move > down_move && up_move > F::zero() { up_move } else { F::zero() };
+        let minus_dm = if down_move > up_move && down_move > F::zero() { down_move } else { F::zero() };
+        let true_range = bar.true_range(Some(previous.close));
+
+        let period = count::<F>(self.period);
+        self.observed += 1;
+        if self.observed < self.period {
+            self.plus_dm += plus_dm;
+            self.minus_dm += minus_dm;
+            self.true_range += true_range;
+            return None;
+        }
+        self.plus_dm += plus_dm - self.plus_dm / period;
+        self.minus_dm += minus_dm - self.minus_dm / period;
+        self.true_range += true_range - self.true_range / period;
+
+        let (plus_di, minus_di) = if self.true_range > F::zero() {
+            (constant::<F>(100.0) * self.plus_dm / self.true_range, constant::<F>(100.0) * self.minus_dm / self.true_range)
+        } else {
+            (F::zero(), F::zero())
+        };
+        let dx = if plus_di + minus_di > F::zero() {
+            constant::<F>(100.0) * (plus_di - minus_di).abs() / (plus_di + minus_di)
+        } else {
+            F::zero()
+        };
+
+        self.adx = match self.adx {
+            Some(adx) => Some((adx * (period - F::one()) + dx) / period),
+            None => {
+                self.dx_sum += dx;
+                (self.observed == 2 * self.period - 1).then(|| self.dx_sum / period)
+            }
+        };
+
+        self.adx.map(|adx| DirectionalMovementOutput { plus_di, minus_di, adx })
+    }
+
+    /// Clears the previous bar and all smoothed values.
+    fn reset(&mut self) {
+        self.previous = None;
+        self.observed = 0;
+        self.plus_dm = F::zero();
+        self.minus_dm = F::zero();
+        self.true_range = F::zero();
+        self.dx_sum = F::zero();
+        self.adx = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    /// +DI, -DI and ADX of period 5 on `SAMPLE_BARS` from the algorithm of TA-Lib's
+    /// `TA_PLUS_DI`, `TA_MINUS_DI` and `TA_ADX`.
+    const EXPECTED: [(f64, f64, f64); 7] = [
+        (16.45116448834738, 26.94126691983317, 26.200050456927404),
+        (15.305909248270236, 21.163304615975875, 24.17228145717025),
+        (31.024569812016104, 15.590578782036602, 25.959703029683897),
+        (31.48167514706037, 12.503845700932963, 29.396888136143083),
+        (30.312911024562514, 10.249174506075807, 33.410363036594134),
+        (24.181218967367727, 17.16618966522125, 30.121503950578024),
+        (19.3009712420263, 20.429038664605752, 24.66506983113805)
+    ];
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = AverageDirectionalIndex::new(5).compute_series(&SAMPLE_BARS).unwrap();
+
+        assert_eq!(series.len(), SAMPLE_BARS.len(), "Series should be aligned with the input");
+        assert!(series[..9].iter().all(Option::is_none), "ADX needs 2 * period bars");
+        for (value, (plus_di, minus_di, adx)) in series[9..].iter().zip(EXPECTED) {
+            let value = value.unwrap();
+            assert!((value.plus_di - plus_di).abs() < 1e-9, "Expected +DI {}, got {}", plus_di, value.plus_di);
+            assert!((value.minus_di - minus_di).abs() < 1e-9, "Expected -DI {}, got {}", minus_di, value.minus_di);
+            assert!((value.adx - adx).abs() < 1e-9, "Expected ADX {}, got {}", adx, value.adx);
+        }
+    }
+
+    #[test]
+    fn insufficient_data_fails() {
+        let adx = AverageDirectionalIndex::new(5);
+        assert!(adx.compute(&SAMPLE_BARS[..9].to_vec()).is_err());
+        assert!(adx.compute(&SAMPLE_BARS[..10].to_vec()).is_ok());
+    }
+
+    #[test]
+    fn flat_market_has_no_direction() {
+        let bars = vec![Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0); 6];
+        let output = AverageDirectionalIndex::new(3).compute(&bars).unwrap();
+        assert_eq!(output, DirectionalMovementOutput { plus_di: 0.0, minus_di: 0.0, adx: 0.0 });
+    }
+
+    #[test]
+    fn invalid_bar_fails() {
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[4].high = f64::NAN;
+        assert!(AverageDirectionalIndex::new(5).compute(&bars).is_err());
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut adx = AverageDirectionalIndex::new(2);
+        for bar in SAMPLE_BARS {
+            adx.update(bar);
+        }
+        adx.reset();
+
+        for bar in &SAMPLE_BARS[..3] {
+            assert!(adx.update(*bar).is_none(), "Reset should restart the warm-up");
+        }
+        assert!(adx.update(SAMPLE_BARS[3]).is_some());
+    }
+}
@@ -0,0 +1,209 @@
+//! This module contains definitions and implementations for the Average True Range (ATR).
+//!
+//! The ATR, developed by J. Welles Wilder, is a volatility indicator that smooths the True Range
+//! of each period with Wilder's moving average.
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the Average True Range (ATR) indicator.
+///
+/// The true range is only defined from the second bar onwards, as it needs the previous close.
+/// The first ATR value is the mean of the first `period` true ranges, and each following value
+/// is `(previous * (period - 1) + true_range) / period`, matching TA-Lib.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::atr::AverageTrueRange;
+/// use qmachina::technical_analysis::bar::Bar;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 11.0, 9.0, 10.0, 100.0),
+///     Bar::new(1, 10.0, 12.0, 10.0, 11.0, 100.0),
+///     Bar::new(2, 11.0, 11.5, 10.5, 11.0, 100.0),
+/// ];
+/// let atr = AverageTrueRange::new(2).compute(&bars).unwrap();
+/// assert_eq!(atr, 1.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AverageTrueRange<F = f64> {
+    period: usize,
+    previous_close: Option<F>,
+    observed: usize,
+    sum: F,
+    average: Option<F>
+}
+
+impl<F: Float> AverageTrueRange<F> {
+    /// Constructs a new `AverageTrueRange` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of true ranges averaged by the indicator.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: if period == 0 { 1 } else { period },
+            previous_close: None,
+            observed: 0,
+            sum: F::zero(),
+            average: None
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for AverageTrueRange<F> {
+    /// Returns the period of the ATR.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the ATR, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for AverageTrueRange<F> {
+    /// Computes the ATR of the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the ATR, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `period + 1` bars or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period + 1, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period + 1)
+    }
+
+    /// Computes the ATR for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for AverageTrueRange<F> {
+    /// Feeds a new bar into the ATR.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current ATR once `period` true ranges have been observed, or
+    /// `None` while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let previous_close = self.previous_close.replace(bar.close)?;
+        let true_range = bar.true_range(Some(previous_close));
+        let period = count::<F>(self.period);
+
+        self.average = match self.average {
+            Some(average) => Some((average * (period - F::one()) + true_range) / period),
+            None => {
+                self.observed += 1;
+                self.sum += true_range;
+                (self.observed == self.period).then(|| self.sum / period)
+            }
+        };
+
+        self.average
+    }
+
+    /// Clears the previous close and the smoothed average.
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.observed = 0;
+        self.sum = F::zero();
+        self.average = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    const EXPECTED: [f64; 11] = [
+        0.94, 0.892, 0.8736, 0.87888, 0.883104, 0.8864832,
+        0.949186559999999, 0.939349248, 0.9114793984, 0.90918351872, 0.907346814976
+    ];
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = AverageTrueRange::new(5).compute_series(&SAMPLE_BARS).unwrap();
+
+        assert_eq!(series.len(), SAMPLE_BARS.len(), "Series should be aligned with the input");
+        assert!(series[..5].iter().all(Option::is_none), "ATR needs period + 1 bars");
+        for (value, expected) in series[5..].iter().zip(EXPECTED) {
+            assert!((value.unwrap() - expected).abs() < 1e-9, "Expected {}, got {:?}", expected, value);
+        }
+    }
+
+    #[test]
+    fn compute_returns_last_value() {
+        let atr = AverageTrueRange::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((atr - EXPECTED[10]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insufficient_data_fails() {
+        let atr = AverageTrueRange::new(5);
+        assert!(atr.compute(&SAMPLE_BARS[..5].to_vec()).is_err());
+        assert!(atr.compute(&SAMPLE_BARS[..6].to_vec()).is_ok());
+    }
+
+    #[test]
+    fn invalid_bar_fails() {
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[3].volume = f64::INFINITY;
+        assert!(AverageTrueRange::new(5).compute(&bars).is_err());
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut atr = AverageTrueRange::new(2);
+        for bar in &SAMPLE_BARS[..4] {
+            atr.update(*bar);
+        }
+        atr.reset();
+
+        assert!(atr.update(SAMPLE_BARS[0]).is_none(), "Reset should clear the previous close");
+        assert!(atr.update(SAMPLE_BARS[1]).is_none());
+        assert!(atr.update(SAMPLE_BARS[2]).is_some());
+    }
+}
@@ -0,0 +1,169 @@
+//! This module contains the OHLCV bar type consumed by multi-input indicators.
+//!
+//! Indicators such as the Average True Range or the Stochastic Oscillator need more than the
+//! closing price of each period. They are implemented as `Indicator<Bar, _>`, where every bar
+//! holds the open, high, low, close and volume of a single period.
+use std::collections::VecDeque;
+
+use crate::error::{QmachinaError, Result};
+use crate::numeric::{constant, Float};
+
+/// Represents a single OHLCV bar (also known as a candle).
+///
+/// # Fields
+///
+/// * `timestamp`: The start of the period covered by the bar. The unit (seconds, milliseconds...)
+///   is left to the caller, as indicators only rely on the order of the bars.
+/// * `open`: The first traded price of the period.
+/// * `high`: The highest traded price of the period.
+/// * `low`: The lowest traded price of the period.
+/// * `close`: The last traded price of the period.
+/// * `volume`: The traded volume of the period.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::bar::Bar;
+///
+/// let bar: Bar = Bar::new(1_700_000_000, 44.0, 44.6, 43.8, 44.3, 1200.0);
+/// assert!(bar.is_valid());
+/// assert!((bar.typical_price() - 44.2333).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar<F = f64> {
+    pub timestamp: i64,
+    pub open: F,
+    pub high: F,
+    pub low: F,
+    pub close: F,
+    pub volume: F
+}
+
+/// An alias of `Bar`, for callers used to the candlestick terminology.
+pub type Candle<F = f64> = Bar<F>;
+
+impl<F: Float> Bar<F> {
+    /// Constructs a new `Bar`.
+    ///
+    /// # Parameters
+    ///
+    /// * `timestamp` - The start of the period covered by the bar.
+    /// * `open` - The first traded price of the period.
+    /// * `high` - The highest traded price of the period.
+    /// * `low` - The lowest traded price of the period.
+    /// * `close` - The last traded price of the period.
+    /// * `volume` - The traded volume of the period.
+    pub const fn new(timestamp: i64, open: F, high: F, low: F, close: F, volume: F) -> Self {
+        Self { timestamp, open, high, low, close, volume }
+    }
+
+    /// Returns the typical price of the bar, the average of its high, low and close.
+    pub fn typical_price(&self) -> F {
+        (self.high + self.low + self.close) / constant(3.0)
+    }
+
+    /// Returns the true range of the bar.
+    ///
+    /// The true range is the largest of the high-low range and the distances from the previous
+    /// close to the high and to the low. Without a previous close it is the high-low range.
+    ///
+    /// # Parameters
+    ///
+    /// * `previous_close` - The closing price of the previous bar, if any.
+    pub fn true_range(&self, previous_close: Option<F>) -> F {
+        let range = self.high - self.low;
+
+        match previous_close {
+            Some(previous_close) => range
+                .max((self.high - previous_close).abs())
+                .max((self.low - previous_close).abs()),
+            None => range
+        }
+    }
+
+    /// Checks whether the bar can be used in calculations.
+    ///
+    /// A bar is valid when all its prices and its volume are finite, the high is not below the
+    /// low, and the volume is not negative.
+    pub fn is_valid(&self) -> bool {
+        [self.open, self.high, self.low, self.close, self.volume].iter().all(|value| value.is_finite())
+            && self.high >= self.low
+            && self.volume >= F::zero()
+    }
+}
+
+/// Validates that every bar of a data series is valid.
+///
+/// # Errors
+///
+/// Returns an error if any of the bars is invalid, as defined by `Bar::is_valid`.
+pub(crate) fn ensure_valid_bars<F: Float>(data: &[Bar<F>]) -> Result<()> {
+    if let Some(index) = data.iter().position(|bar| !bar.is_valid()) {
+        return Err(QmachinaError::InvalidValue { index });
+    }
+
+    Ok(())
+}
+
+/// Returns the highest high and the lowest low of a window of bars.
+pub(crate) fn highest_high_lowest_low<F: Float>(window: &VecDeque<Bar<F>>) -> (F, F) {
+    window.iter().fold((F::neg_infinity(), F::infinity()), |(high, low), bar| {
+        (high.max(bar.high), low.min(bar.low))
+    })
+}
+
+/// Sample bars shared by the tests of the bar indicators.
+#[cfg(test)]
+pub(crate) const SAMPLE_BARS: [Bar; 16] = [
+    Bar::new(0, 44.0, 44.6, 43.8, 44.3, 1200.0),
+    Bar::new(1, 44.3, 44.9, 44.1, 44.8, 1500.0),
+    Bar::new(2, 44.8, 45.2, 44.4, 44.6, 1100.0),
+    Bar::new(3, 44.6, 45.0, 43.9, 44.0, 1700.0),
+    Bar::new(4, 44.0, 44.5, 43.6, 44.4, 1300.0),
+    Bar::new(5, 44.4, 45.3, 44.2, 45.1, 1900.0),
+    Bar::new(6, 45.1, 45.6, 44.9, 45.4, 1600.0),
+    Bar::new(7, 45.4, 45.5, 44.7, 44.9, 1400.0),
+    Bar::new(8, 44.9, 45.2, 44.3, 44.5, 1250.0),
+    Bar::new(9, 44.5, 44.8, 43.9, 44.1, 1800.0),
+    Bar::new(10, 44.1, 44.9, 44.0, 44.7, 1550.0),
+    Bar::new(11, 44.7, 45.8, 44.6, 45.6, 2100.0),
+    Bar::new(12, 45.6, 46.1, 45.2, 45.9, 1950.0),
+    Bar::new(13, 45.9, 46.3, 45.5, 45.7, 1700.0),
+    Bar::new(14, 45.7, 46.0, 45.1, 45.3, 1500.0),
+    Bar::new(15, 45.3, 45.7, 44.8, 45.5, 1350.0)
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typical_price() {
+        let bar = Bar::new(0, 10.0, 12.0, 9.0, 11.0, 100.0);
+        assert_eq!(bar.typical_price(), 32.0 / 3.0);
+    }
+
+    #[test]
+    fn true_range_uses_previous_close() {
+        let bar = Bar::new(0, 10.0, 12.0, 9.0, 11.0, 100.0);
+
+        assert_eq!(bar.true_range(None), 3.0, "Without a previous close the true range is the high-low range");
+        assert_eq!(bar.true_range(Some(10.0)), 3.0);
+        assert_eq!(bar.true_range(Some(14.0)), 5.0, "Gap down should extend the range to the previous close");
+        assert_eq!(bar.true_range(Some(7.0)), 5.0, "Gap up should extend the range to the previous close");
+    }
+
+    #[test]
+    fn invalid_bars() {
+        assert!(!Bar::new(0, 10.0, 9.0, 12.0, 11.0, 100.0).is_valid(), "High below low should be invalid");
+        assert!(!Bar::new(0, 10.0, 12.0, 9.0, f64::NAN, 100.0).is_valid(), "NaN close should be invalid");
+        assert!(!Bar::new(0, 10.0, 12.0, 9.0, 11.0, -1.0).is_valid(), "Negative volume should be invalid");
+        assert!(ensure_valid_bars(&SAMPLE_BARS).is_ok());
+    }
+
+    #[test]
+    fn highest_high_lowest_low_of_window() {
+        let window: VecDeque<Bar> = SAMPLE_BARS[11..].iter().copied().collect();
+        assert_eq!(highest_high_lowest_low(&window), (46.3, 44.6));
+    }
+}
@@ -6,10 +6,41 @@
 //! and a lower band at K times an N-period standard deviation below the middle band.
 //!
 //! Typically, the middle band is the 20-day SMA and the standard deviation is set to 2.
-use anyhow::{Result, anyhow};
+use std::collections::VecDeque;
 
-use crate::technical_analysis::{Indicator, PeriodIndicator};
-use super::sma::SimpleMovingAverage;
+use crate::error::{ensure_finite, ensure_sufficient, Result};
+use crate::numeric::{constant, count, Float};
+use crate::technical_analysis::{Indicator, MovingAverage, MovingAverageKind, PeriodIndicator, StreamingIndicator};
+
+/// The estimator used for the standard deviation of the closing prices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StandardDeviation {
+    /// Divides the sum of squared deviations by `N`, as in Bollinger's original definition.
+    #[default]
+    Population,
+    /// Divides the sum of squared deviations by `N - 1` (Bessel's correction).
+    Sample
+}
+
+/// Holds the values produced by the Bollinger Bands at a single point in time.
+///
+/// # Fields
+///
+/// * `upper`: The middle band plus `k` standard deviations.
+/// * `middle`: The moving average of the closing prices.
+/// * `lower`: The middle band minus `k` standard deviations.
+/// * `percent_b`: The position of the last closing price relative to the bands, where 0 is the
+///   lower band and 1 is the upper band. It is 0.5 when both bands collapse onto the middle band.
+/// * `bandwidth`: The width of the bands relative to the middle band, `(upper - lower) / middle`.
+///   It is 0 when the middle band is 0, such as for a series of returns centred on zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerOutput<F = f64> {
+    pub upper: F,
+    pub middle: F,
+    pub lower: F,
+    pub percent_b: F,
+    pub bandwidth: F
+}
 
 /// Represents Bollinger Bands indicator.
 ///
@@ -18,81 +49,211 @@ use super::sma::SimpleMovingAverage;
 /// by a specified number (usually 2). The upper and lower bands are then set this number
 /// of standard deviations above and below the moving average.
 ///
+/// The multiplier, the standard deviation estimator and the moving average used for the
+/// middle band can be configured. The standard deviation is always measured around the mean
+/// of the closing prices in the window, regardless of the middle band used.
+///
 /// # Examples
 ///
 /// ```
 /// use std::sync::Arc;
-/// use qmachina::technical_analysis::bollinger::BollingerBands;
-/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bollinger::{BollingerBands, StandardDeviation};
+/// use qmachina::technical_analysis::{Indicator, MovingAverageKind};
 ///
-/// let bb = BollingerBands::new(5);
+/// let bb: BollingerBands = BollingerBands::new(5);
 /// let data = vec![100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0]; // Sample data
-/// let (upper_band, lower_band) = bb.compute(&data).unwrap();
+/// let bands = bb.compute(&data).unwrap();
+///
+/// let wide_bb: BollingerBands = BollingerBands::new(5)
+///     .with_multiplier(2.5)
+///     .with_standard_deviation(StandardDeviation::Sample)
+///     .with_middle_band(MovingAverageKind::Exponential);
+/// let wide_bands = wide_bb.compute(&data).unwrap();
 /// ```
-pub struct BollingerBands {
+pub struct BollingerBands<F = f64> {
     period: usize,
-    sma: SimpleMovingAverage
+    multiplier: F,
+    standard_deviation: StandardDeviation,
+    middle_band: MovingAverageKind,
+    middle: Box<dyn MovingAverage<F>>,
+    window: VecDeque<F>,
+    mean: F,
+    squared_deviations: F,
+    replaced: usize
 }
 
-impl BollingerBands {
+impl<F: Float> BollingerBands<F> {
     /// Constructs a new `BollingerBands` with the given period.
     ///
+    /// The bands default to a multiplier of 2.0, the population standard deviation and
+    /// an SMA middle band.
+    ///
     /// # Parameters
     ///
-    /// * `period` - The period over which the SMA and standard deviation are calculated.
+    /// * `period` - The period over which the middle band and standard deviation are calculated.
     pub fn new(period: usize) -> Self {
         let period = if period == 0 { 1 } else { period };
-        let sma = SimpleMovingAverage::new(period);
+        let middle_band = MovingAverageKind::default();
 
         Self {
             period,
-            sma
+            multiplier: constant(2.0),
+            standard_deviation: StandardDeviation::default(),
+            middle_band,
+            middle: middle_band.create(period),
+            window: VecDeque::with_capacity(period + 1),
+            mean: F::zero(),
+            squared_deviations: F::zero(),
+            replaced: 0
         }
     }
-}
 
-impl Indicator<f64, (f64, f64)> for BollingerBands {
-    /// Computes the Bollinger Bands values using an `Arc<[f64]>` as input data.
+    /// Returns the `BollingerBands` using the given standard deviation multiplier.
     ///
     /// # Parameters
     ///
-    /// * `data` - An `Vec<f64>` containing the closing prices for the calculation.
+    /// * `multiplier` - The number of standard deviations between the middle band and the outer bands.
+    pub fn with_multiplier(mut self, multiplier: F) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Returns the `BollingerBands` using the given standard deviation estimator.
     ///
-    /// # Returns
+    /// # Parameters
     ///
-    /// Returns `Ok((f64, f64))` containing the upper and lower bands, or an error if the calculation fails.
+    /// * `standard_deviation` - Whether to use the population or the sample standard deviation.
+    pub fn with_standard_deviation(mut self, standard_deviation: StandardDeviation) -> Self {
+        self.standard_deviation = standard_deviation;
+        self
+    }
+
+    /// Returns the `BollingerBands` using the given moving average for the middle band.
     ///
-    /// # Errors
+    /// # Parameters
     ///
-    /// Returns an error if the length of the data is less than the specified period.
-    fn compute(&self, data: &Vec<f64>) -> Result<(f64, f64)> {
-        if data.len() < self.period {
-            return Err(anyhow!("Data length is less than the period."));
-        }
+    /// * `middle_band` - The kind of moving average used for the middle band.
+    pub fn with_middle_band(mut self, middle_band: MovingAverageKind) -> Self {
+        self.middle_band = middle_band;
+        self.middle = middle_band.create(self.period);
+        self.reset();
+        self
+    }
 
-        let sma_value = self.sma.compute(data.as_ref())?;
+    /// Returns the standard deviation multiplier.
+    pub fn multiplier(&self) -> F {
+        self.multiplier
+    }
 
-        let data = &data[data.len() - self.period..];
+    /// Returns the standard deviation estimator.
+    pub fn standard_deviation(&self) -> StandardDeviation {
+        self.standard_deviation
+    }
 
-        let variance: f64 = data.iter()
-            .take(self.period)
+    /// Returns the kind of moving average used for the middle band.
+    pub fn middle_band(&self) -> MovingAverageKind {
+        self.middle_band
+    }
+
+    /// Returns the mean of the window and the sum of squared deviations around it.
+    ///
+    /// The deviations are computed from the mean in a second pass rather than from running sums
+    /// of the values and their squares, whose difference cancels catastrophically for prices far
+    /// from zero.
+    fn moments(window: &[F]) -> (F, F) {
+        let mean = window.iter().copied().sum::<F>() / count::<F>(window.len());
+        let squared_deviations = window.iter()
             .map(|&value| {
-                let diff = value - sma_value;
+                let diff = value - mean;
                 diff * diff
             })
-            .sum::<f64>() / self.period as f64;
+            .sum();
+
+        (mean, squared_deviations)
+    }
+
+    /// Builds the output from the middle band, the sum of squared deviations over the
+    /// window and the last closing price.
+    fn output(&self, middle: F, squared_deviations: F, last: F) -> BollingerOutput<F> {
+        let denominator = match self.standard_deviation {
+            StandardDeviation::Population => count::<F>(self.period),
+            StandardDeviation::Sample => count::<F>(self.period - 1)
+        };
+
+        let std_dev = if denominator > F::zero() {
+            (squared_deviations.max(F::zero()) / denominator).sqrt()
+        } else {
+            F::zero()
+        };
+
+        let upper = middle + self.multiplier * std_dev;
+        let lower = middle - self.multiplier * std_dev;
+        let width = upper - lower;
+        let percent_b = if width == F::zero() { constant(0.5) } else { (last - lower) / width };
+        let bandwidth = if middle == F::zero() { F::zero() } else { width / middle };
+
+        BollingerOutput {
+            upper,
+            middle,
+            lower,
+            percent_b,
+            bandwidth
+        }
+    }
+}
+
+impl<F: Float> Indicator<F, BollingerOutput<F>> for BollingerBands<F> {
+    /// Computes the Bollinger Bands values using an `Arc<[f64]>` as input data.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the closing prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(BollingerOutput)` containing the bands, %B and bandwidth, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the length of the data is less than the specified period or if the data contains
+    /// invalid values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<BollingerOutput<F>> {
+        ensure_sufficient(self.period, data.len())?;
 
-        let std_dev = variance.sqrt();
+        let middle = self.middle.compute(data)?;
 
-        let upper_band = sma_value + 2.0 * std_dev;
-        let lower_band = sma_value - 2.0 * std_dev;
+        let window = &data[data.len() - self.period..];
+        ensure_finite(window)?;
 
-        Ok((upper_band, lower_band))
+        Ok(self.output(middle, Self::moments(window).1, window[self.period - 1]))
+    }
+
+    /// Computes the Bollinger Bands for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the closing prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<BollingerOutput>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<BollingerOutput<F>>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period)
+            .with_multiplier(self.multiplier)
+            .with_standard_deviation(self.standard_deviation)
+            .with_middle_band(self.middle_band);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
     }
 }
 
-impl PeriodIndicator for BollingerBands {
-    /// Returns the current period used in the RSI calculation.
+impl<F: Float> PeriodIndicator for BollingerBands<F> {
+    /// Returns the current period used in the Bollinger Bands calculation.
     ///
     /// # Returns
     ///
@@ -101,32 +262,109 @@ impl PeriodIndicator for BollingerBands {
         self.period
     }
 
-    /// Sets a new period for the RSI calculation.
+    /// Sets a new period for the Bollinger Bands calculation.
     ///
     /// # Parameters
     ///
     /// * `period` - The new period to set, represented as a `usize`.
+    ///
+    /// The middle band follows the new period and any streaming state is reset.
     fn set_period(&mut self, period: usize) {
         self.period = if period == 0 { 1 } else { period };
+        self.middle.set_period(self.period);
+        self.reset();
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, BollingerOutput<F>> for BollingerBands<F> {
+    /// Feeds a new closing price into the Bollinger Bands.
+    ///
+    /// The middle band is streamed by its own moving average, while the mean and the sum of
+    /// squared deviations of the last `period` prices follow Welford's updates as prices enter
+    /// and leave the window, so each update runs in constant time. Both are recomputed from the
+    /// window each time it has been fully replaced, which keeps rounding errors from building up
+    /// however long the feed, at an amortised constant cost.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest closing price.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(BollingerOutput)` once enough data has been observed, or `None` while
+    /// warming up. Invalid values (NaN or infinite) are rejected with `None` and leave the
+    /// state untouched.
+    fn update(&mut self, value: F) -> Option<BollingerOutput<F>> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let middle = self.middle.update(value);
+
+        self.window.push_back(value);
+        if self.window.len() > self.period {
+            let removed = self.window.pop_front()?;
+            let mean = self.mean + (value - removed) / count::<F>(self.period);
+            self.squared_deviations += (value - removed) * (value - mean + removed - self.mean);
+            self.mean = mean;
+
+            self.replaced += 1;
+            if self.replaced == self.period {
+                self.replaced = 0;
+                (self.mean, self.squared_deviations) = Self::moments(self.window.make_contiguous());
+            }
+        } else {
+            let delta = value - self.mean;
+            self.mean += delta / count::<F>(self.window.len());
+            self.squared_deviations += delta * (value - self.mean);
+        }
+
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        Some(self.output(middle?, self.squared_deviations, value))
+    }
+
+    /// Clears the streaming window and the middle band state.
+    fn reset(&mut self) {
+        self.middle.reset();
+        self.window.clear();
+        self.mean = F::zero();
+        self.squared_deviations = F::zero();
+        self.replaced = 0;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::technical_analysis::ema::ExponentialMovingAverage;
+    use crate::technical_analysis::sma::SimpleMovingAverage;
+
+    const DATA: [f64; 10] = [100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0];
+
+    fn assert_close(actual: BollingerOutput, expected: BollingerOutput) {
+        assert!((actual.upper - expected.upper).abs() < 1e-9, "Upper band should match");
+        assert!((actual.middle - expected.middle).abs() < 1e-9, "Middle band should match");
+        assert!((actual.lower - expected.lower).abs() < 1e-9, "Lower band should match");
+        assert!((actual.percent_b - expected.percent_b).abs() < 1e-9, "%B should match");
+        assert!((actual.bandwidth - expected.bandwidth).abs() < 1e-9, "Bandwidth should match");
+    }
 
     #[test]
     fn bollinger_bands_creation_with_valid_period() {
-        let bb = BollingerBands::new(20);
+        let bb: BollingerBands = BollingerBands::new(20);
         assert_eq!(bb.period(), 20, "Bollinger Bands period should be 20");
     }
 
     #[test]
     fn compute_sufficient_data_arc() {
-        let bb = BollingerBands::new(5);
+        let bb: BollingerBands = BollingerBands::new(5);
         let data = vec![100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0];
 
-        let (upper_band, lower_band) = bb.compute(&data).unwrap();
+        let bands = bb.compute(&data).unwrap();
+        let (upper_band, lower_band) = (bands.upper, bands.lower);
 
         // Validate the upper and lower band values
         // These values should be calculated based on the expected Bollinger Bands calculation
@@ -154,4 +392,224 @@ mod tests {
         let result = bb.compute(&data);
         assert!(result.is_err(), "Should return an error due to invalid (NaN) data");
     }
+
+    #[test]
+    fn set_period_updates_middle_band() {
+        let mut bb: BollingerBands = BollingerBands::new(20);
+        bb.set_period(5);
+
+        let data = vec![100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0];
+        let bands = bb.compute(&data).unwrap();
+
+        assert!((bands.upper - 101.82).abs() < 0.01, "Upper band should use the new period");
+        assert!((bands.lower - 96.17).abs() < 0.01, "Lower band should use the new period");
+    }
+
+    #[test]
+    fn streaming_matches_batch() {
+        let data = vec![100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0, 99.5, 101.5];
+        let batch = BollingerBands::new(5);
+        let mut stream = BollingerBands::new(5);
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => {
+                    let streamed = streamed.expect("Stream should be warm once batch succeeds");
+                    assert_close(streamed, expected);
+                }
+                Err(_) => assert!(streamed.is_none(), "Stream should still be warming up"),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut bb = BollingerBands::new(2);
+        bb.update(100.0);
+        bb.update(200.0);
+        bb.reset();
+
+        assert!(bb.update(10.0).is_none(), "Reset should clear the window");
+
+        let bands = bb.update(10.0).unwrap();
+        assert_eq!((bands.upper, bands.middle, bands.lower), (10.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let bb = BollingerBands::new(5);
+        let data = vec![100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0];
+        let series = bb.compute_series(&data).unwrap();
+
+        assert_eq!(series.len(), data.len(), "Series should be aligned with the input");
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+
+        for (end, value) in (1..=data.len()).zip(series) {
+            match bb.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert_close(value.unwrap(), expected),
+                Err(_) => assert!(value.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn compute_series_with_invalid_data() {
+        let bb = BollingerBands::new(3);
+        let data = vec![100.0, 101.0, f64::NAN, 103.0, 104.0];
+        assert!(bb.compute_series(&data).is_err(), "Should return an error due to invalid (NaN) data");
+    }
+
+    #[test]
+    fn middle_band_is_the_sma_by_default() {
+        let bb = BollingerBands::new(5);
+        let bands = bb.compute(&DATA.to_vec()).unwrap();
+        let sma = SimpleMovingAverage::new(5).compute(&DATA.to_vec()).unwrap();
+
+        assert!((bands.middle - sma).abs() < 1e-9, "Middle band should be the SMA");
+        assert!((bands.upper - bands.middle - (bands.middle - bands.lower)).abs() < 1e-9, "Bands should be symmetric");
+    }
+
+    #[test]
+    fn multiplier_scales_band_width() {
+        let narrow = BollingerBands::new(5).with_multiplier(1.0).compute(&DATA.to_vec()).unwrap();
+        let wide = BollingerBands::new(5).with_multiplier(3.0).compute(&DATA.to_vec()).unwrap();
+
+        let narrow_width = narrow.upper - narrow.lower;
+        let wide_width = wide.upper - wide.lower;
+        assert!((wide_width - 3.0 * narrow_width).abs() < 1e-9, "Width should scale with the multiplier");
+    }
+
+    #[test]
+    fn sample_standard_deviation() {
+        let bb = BollingerBands::new(5).with_multiplier(1.0).with_standard_deviation(StandardDeviation::Sample);
+        let bands = bb.compute(&DATA.to_vec()).unwrap();
+
+        // Last 5 values: 101, 100, 99, 98, 97 -> squared deviations sum to 10.
+        let expected_std_dev = (10.0f64 / 4.0).sqrt();
+        assert!((bands.upper - 99.0 - expected_std_dev).abs() < 1e-9, "Sample deviation should use N - 1");
+        assert_eq!(bb.standard_deviation(), StandardDeviation::Sample);
+    }
+
+    #[test]
+    fn exponential_middle_band() {
+        let bb = BollingerBands::new(5).with_middle_band(MovingAverageKind::Exponential);
+        let bands = bb.compute(&DATA.to_vec()).unwrap();
+        let ema = ExponentialMovingAverage::new(5).compute(&DATA.to_vec()).unwrap();
+
+        assert_eq!(bb.middle_band(), MovingAverageKind::Exponential);
+        assert!((bands.middle - ema).abs() < 1e-9, "Middle band should be the EMA");
+
+        let default_bands = BollingerBands::new(5).compute(&DATA.to_vec()).unwrap();
+        let default_width = default_bands.upper - default_bands.lower;
+        assert!((bands.upper - bands.lower - default_width).abs() < 1e-9, "Deviation should not depend on the middle band");
+    }
+
+    #[test]
+    fn percent_b_and_bandwidth() {
+        let bb = BollingerBands::new(5);
+        let bands = bb.compute(&DATA.to_vec()).unwrap();
+
+        let expected_percent_b = (97.0 - bands.lower) / (bands.upper - bands.lower);
+        let expected_bandwidth = (bands.upper - bands.lower) / bands.middle;
+        assert!((bands.percent_b - expected_percent_b).abs() < 1e-9);
+        assert!((bands.bandwidth - expected_bandwidth).abs() < 1e-9);
+        assert!(bands.percent_b < 0.5, "Last close is below the middle band");
+    }
+
+    #[test]
+    fn percent_b_with_collapsed_bands() {
+        let bb = BollingerBands::new(3);
+        let bands = bb.compute(&vec![5.0, 5.0, 5.0]).unwrap();
+
+        assert_eq!(bands.percent_b, 0.5, "%B should be neutral when the bands collapse");
+        assert_eq!(bands.bandwidth, 0.0);
+    }
+
+    #[test]
+    fn bandwidth_with_zero_middle_band() {
+        let data: [f64; 6] = [-1.0, 0.0, 1.0, 0.5, -0.5, 0.0];
+        let bb = BollingerBands::new(3);
+        let bands = bb.compute(&data.to_vec()).unwrap();
+
+        assert_eq!(bands.middle, 0.0);
+        assert!(bands.upper > 0.0, "The bands should not collapse");
+        assert_eq!(bands.bandwidth, 0.0, "Bandwidth should be 0 instead of infinite when the middle band is 0");
+
+        let mut stream = BollingerBands::new(3);
+        let streamed = data.iter().filter_map(|&value| stream.update(value)).collect::<Vec<_>>();
+        assert!(streamed.iter().all(|output| output.bandwidth.is_finite()), "Streamed bandwidth should stay finite");
+        assert_eq!(streamed[0].bandwidth, 0.0);
+    }
+
+    #[test]
+    fn configured_streaming_matches_batch() {
+        let data = [100.0, 101.0, 102.0, 103.0, 102.0, 101.0, 100.0, 99.0, 98.0, 97.0, 99.5, 101.5];
+        let configure = || BollingerBands::new(4)
+            .with_multiplier(1.5)
+            .with_standard_deviation(StandardDeviation::Sample)
+            .with_middle_band(MovingAverageKind::Exponential);
+
+        let batch = configure();
+        let mut stream = configure();
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert_close(streamed.unwrap(), expected),
+                Err(_) => assert!(streamed.is_none()),
+            }
+        }
+    }
+
+    /// Test a long feed of prices far from zero.
+    /// Expected result is the same bands as `compute` over the last window, without drift.
+    #[test]
+    fn long_streaming_feed_does_not_drift() {
+        let data: Vec<f64> = (0..200_000)
+            .map(|i| 60_000.0 + (i as f64 * 0.37).sin() + 0.5 * (i as f64 * 0.011).cos())
+            .collect();
+        let mut stream = BollingerBands::new(20);
+        let streamed = data.iter().filter_map(|&value| stream.update(value)).last().unwrap();
+        let expected = BollingerBands::new(20).compute(&data[data.len() - 20..].to_vec()).unwrap();
+
+        assert_close(streamed, expected);
+        assert_eq!(streamed.upper - streamed.middle, expected.upper - expected.middle, "Deviation should match exactly");
+    }
+
+    /// Test every position of a long feed of prices far from zero, between the points where
+    /// the window is recomputed.
+    /// Expected result is the same bands as `compute` over each window.
+    #[test]
+    fn sliding_deviation_matches_every_window() {
+        let data: Vec<f64> = (0..20_000)
+            .map(|i| 60_000.0 + (i as f64 * 0.37).sin() + 0.5 * (i as f64 * 0.011).cos())
+            .collect();
+        let bb = BollingerBands::new(7).with_standard_deviation(StandardDeviation::Sample);
+        let series = bb.compute_series(&data).unwrap();
+
+        for end in 7..=data.len() {
+            let streamed = series[end - 1].unwrap();
+            let expected = bb.compute(&data[end - 7..end].to_vec()).unwrap();
+            let (streamed_width, expected_width) = (streamed.upper - streamed.lower, expected.upper - expected.lower);
+            assert!((streamed_width - expected_width).abs() < 1e-6 * expected_width, "Width {streamed_width} should be {expected_width} at {end}");
+        }
+    }
+
+    /// Test single-precision prices at a high level with small moves.
+    /// Expected result is the same bands from `compute_series` as from `compute`.
+    #[test]
+    fn f32_series_matches_compute() {
+        let data: Vec<f32> = (0..500).map(|i| 1000.0 + (i as f32 * 0.7).sin() * 0.5).collect();
+        let bb: BollingerBands<f32> = BollingerBands::new(20);
+        let series = bb.compute_series(&data).unwrap();
+
+        for end in [20, 100, 250, 500] {
+            let streamed = series[end - 1].unwrap();
+            let expected = bb.compute(&data[..end].to_vec()).unwrap();
+            let (streamed_width, expected_width) = (streamed.upper - streamed.lower, expected.upper - expected.lower);
+            assert!((streamed_width - expected_width).abs() < 1e-3 * expected_width, "Width {streamed_width} should be {expected_width}");
+            assert!((streamed.middle - expected.middle).abs() < 1e-3);
+        }
+    }
 }
@@ -0,0 +1,195 @@
+//! This module contains definitions and implementations for the Commodity Channel Index (CCI).
+//!
+//! The CCI, developed by Donald Lambert, measures the deviation of the typical price from its
+//! moving average, scaled by the mean absolute deviation so that most values fall between
+//! -100 and 100.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Lambert's constant, chosen so that roughly 70 to 80% of CCI values fall between -100 and 100.
+const LAMBERT_CONSTANT: f64 = 0.015;
+
+/// Represents the Commodity Channel Index (CCI).
+///
+/// The CCI is `(typical price - SMA) / (0.015 * mean deviation)`, where the SMA and the mean
+/// absolute deviation are taken over the typical prices of the last `period` bars. It is 0
+/// when all typical prices in the window are equal.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::cci::CommodityChannelIndex;
+///
+/// let bars: Vec<Bar> = vec![
+///     Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0),
+///     Bar::new(1, 11.0, 11.0, 11.0, 11.0, 100.0),
+/// ];
+/// let cci = CommodityChannelIndex::new(2).compute(&bars).unwrap();
+/// assert!((cci - 66.6667).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommodityChannelIndex<F = f64> {
+    period: usize,
+    window: VecDeque<F>
+}
+
+impl<F: Float> CommodityChannelIndex<F> {
+    /// Constructs a new `CommodityChannelIndex` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of typical prices averaged by the indicator.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for CommodityChannelIndex<F> {
+    /// Returns the period of the CCI.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the CCI, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for CommodityChannelIndex<F> {
+    /// Computes the CCI for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the CCI, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the period or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes the CCI for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for CommodityChannelIndex<F> {
+    /// Feeds a new bar into the CCI.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current CCI once `period` bars have been observed, or `None`
+    /// while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let typical_price = bar.typical_price();
+        self.window.push_back(typical_price);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let period = count::<F>(self.period);
+        let mean = self.window.iter().sum::<F>() / period;
+        let mean_deviation = self.window.iter().map(|&value| (value - mean).abs()).sum::<F>() / period;
+
+        if mean_deviation == F::zero() {
+            return Some(F::zero());
+        }
+
+        Some((typical_price - mean) / (constant::<F>(LAMBERT_CONSTANT) * mean_deviation))
+    }
+
+    /// Clears the typical price window.
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let cci = CommodityChannelIndex::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((cci - -70.77625570776264).abs() < 1e-9);
+    }
+
+    #[test]
+    fn constant_prices_yield_zero() {
+        let bars = vec![Bar::new(0, 10.0, 11.0, 9.0, 10.0, 100.0); 4];
+        assert_eq!(CommodityChannelIndex::new(4).compute(&bars).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let cci = CommodityChannelIndex::new(5);
+        let series = cci.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+        for (end, value) in (5..=SAMPLE_BARS.len()).zip(&series[4..]) {
+            assert!((value.unwrap() - cci.compute(&SAMPLE_BARS[..end].to_vec()).unwrap()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let cci = CommodityChannelIndex::new(5);
+        assert!(cci.compute(&SAMPLE_BARS[..4].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[15].close = f64::NEG_INFINITY;
+        assert!(cci.compute(&bars).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! This module contains definitions and implementations for the Chaikin Money Flow (CMF).
+//!
+//! The CMF, developed by Marc Chaikin, measures buying and selling pressure over a period by
+//! weighting each bar's volume with the position of its close within its high-low range.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::Float;
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the Chaikin Money Flow (CMF).
+///
+/// The money flow multiplier of a bar is `((close - low) - (high - close)) / (high - low)`,
+/// ranging from -1 (close at the low) to 1 (close at the high), and is 0 for bars without range.
+/// The CMF is the sum of the multipliers times volume divided by the sum of volume over the last
+/// `period` bars, and is 0 when no volume was traded.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::cmf::ChaikinMoneyFlow;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 12.0, 10.0, 12.0, 300.0),
+///     Bar::new(1, 12.0, 12.0, 10.0, 10.0, 100.0),
+/// ];
+/// assert_eq!(ChaikinMoneyFlow::new(2).compute(&bars).unwrap(), 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChaikinMoneyFlow<F = f64> {
+    period: usize,
+    window: VecDeque<(F, F)>
+}
+
+impl<F: Float> ChaikinMoneyFlow<F> {
+    /// Constructs a new `ChaikinMoneyFlow` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of bars summed by the indicator.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for ChaikinMoneyFlow<F> {
+    /// Returns the period of the CMF.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the CMF, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for ChaikinMoneyFlow<F> {
+    /// Computes the CMF for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the CMF, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the period or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes the CMF for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for ChaikinMoneyFlow<F> {
+    /// Feeds a new bar into the CMF.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current CMF once `period` bars have been observed, or `None`
+    /// while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let range = bar.high - bar.low;
+        let multiplier = if range > F::zero() { ((bar.close - bar.low) - (bar.high - bar.close)) / range } else { F::zero() };
+
+        self.window.push_back((multiplier * bar.volume, bar.volume));
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let (money_flow_volume, volume) = self.window.iter()
+            .fold((F::zero(), F::zero()), |(flow, volume), &(bar_flow, bar_volume)| (flow + bar_flow, volume + bar_volume));
+
+        if volume == F::zero() {
+            return Some(F::zero());
+        }
+
+        Some(money_flow_volume / volume)
+    }
+
+    /// Clears the money flow window.
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let cmf = ChaikinMoneyFlow::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((cmf - 0.1802325581395344).abs() < 1e-9);
+    }
+
+    #[test]
+    fn degenerate_bars_yield_zero() {
+        let bars = vec![Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0), Bar::new(1, 10.0, 11.0, 9.0, 11.0, 0.0)];
+        assert_eq!(ChaikinMoneyFlow::new(2).compute(&bars).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let cmf = ChaikinMoneyFlow::new(5);
+        let series = cmf.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+        for (end, value) in (5..=SAMPLE_BARS.len()).zip(&series[4..]) {
+            assert_eq!(value.unwrap(), cmf.compute(&SAMPLE_BARS[..end].to_vec()).unwrap());
+        }
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let cmf = ChaikinMoneyFlow::new(5);
+        assert!(cmf.compute(&SAMPLE_BARS[..4].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[12].high = 40.0;
+        assert!(cmf.compute(&bars).is_err());
+    }
+}
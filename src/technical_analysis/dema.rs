@@ -0,0 +1,169 @@
+//! This module contains definitions and implementations for Double Exponential Moving Average (DEMA).
+//!
+//! The DEMA, introduced by Patrick Mulloy, reduces the lag of an EMA by subtracting the EMA of
+//! the EMA from twice the EMA: `DEMA = 2 * EMA - EMA(EMA)`.
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::ema::RecursiveEma;
+
+/// Represents a Double Exponential Moving Average (DEMA) indicator.
+///
+/// Both EMAs run over the whole history of the data and are seeded with an SMA, as in TA-Lib,
+/// so the first value is available after `2 * period - 1` data points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::dema::DoubleExponentialMovingAverage;
+///
+/// let dema: DoubleExponentialMovingAverage = DoubleExponentialMovingAverage::new(2);
+/// let value = dema.compute(&vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+/// assert!((value - 4.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DoubleExponentialMovingAverage<F = f64> {
+    period: usize,
+    ema: RecursiveEma<F>,
+    ema_of_ema: RecursiveEma<F>
+}
+
+impl<F: Float> DoubleExponentialMovingAverage<F> {
+    /// Constructs a new `DoubleExponentialMovingAverage` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of both EMAs.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            ema: RecursiveEma::new(period),
+            ema_of_ema: RecursiveEma::new(period)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for DoubleExponentialMovingAverage<F> {
+    /// Returns the current period used in the DEMA calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the DEMA calculation, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period);
+    }
+}
+
+impl<F: Float> Indicator<F, F> for DoubleExponentialMovingAverage<F> {
+    /// Computes the DEMA at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the DEMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated DEMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `2 * period - 1` values or contains invalid
+    /// values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, 2 * self.period - 1)
+    }
+
+    /// Computes the DEMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for DoubleExponentialMovingAverage<F> {
+    /// Feeds a new data point into the DEMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current DEMA once both EMAs are seeded, or `None` while warming
+    /// up. Invalid values (NaN or infinite) are rejected with `None` and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let ema = self.ema.update(value)?;
+        let ema_of_ema = self.ema_of_ema.update(ema)?;
+
+        Some(constant::<F>(2.0) * ema - ema_of_ema)
+    }
+
+    /// Clears the state of both EMAs.
+    fn reset(&mut self) {
+        self.ema.reset();
+        self.ema_of_ema.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = DoubleExponentialMovingAverage::new(5).compute_series(&DATA).unwrap();
+
+        assert!(series[..8].iter().all(Option::is_none), "DEMA needs 2 * period - 1 values");
+        assert!((series[8].unwrap() - 45.7660888889).abs() < 1e-9);
+        assert!((series[9].unwrap() - 46.0675325103).abs() < 1e-9);
+        assert!((series[32].unwrap() - 42.92530937935754).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let dema = DoubleExponentialMovingAverage::new(5);
+        assert!(dema.compute(&DATA[..8].to_vec()).is_err());
+        assert!(dema.compute(&DATA[..9].to_vec()).is_ok());
+        assert!(dema.compute(&vec![f64::NAN; 9]).is_err());
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut dema = DoubleExponentialMovingAverage::new(2);
+        for value in DATA {
+            dema.update(value);
+        }
+        dema.reset();
+
+        assert!(dema.update(1.0).is_none(), "Reset should clear both EMAs");
+        assert!(dema.update(2.0).is_none());
+        assert!(dema.update(3.0).is_some());
+    }
+}
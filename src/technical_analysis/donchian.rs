@@ -0,0 +1,181 @@
+//! This module contains definitions and implementations for Donchian Channels.
+//!
+//! Donchian Channels, developed by Richard Donchian, track the highest high and the lowest low
+//! over a period, with the middle line halfway between them. They are commonly used for
+//! breakout strategies.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, ChannelOutput, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, highest_high_lowest_low, Bar};
+
+/// Represents the Donchian Channels indicator.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::{ChannelOutput, Indicator};
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::donchian::DonchianChannels;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 12.0, 9.0, 11.0, 100.0),
+///     Bar::new(1, 11.0, 13.0, 10.0, 12.0, 100.0),
+/// ];
+/// let channel = DonchianChannels::new(2).compute(&bars).unwrap();
+/// assert_eq!(channel, ChannelOutput { upper: 13.0, middle: 11.0, lower: 9.0 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct DonchianChannels<F = f64> {
+    period: usize,
+    window: VecDeque<Bar<F>>
+}
+
+impl<F: Float> DonchianChannels<F> {
+    /// Constructs a new `DonchianChannels` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of bars used to find the highest high and lowest low.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for DonchianChannels<F> {
+    /// Returns the period of the channels.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the channels, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, ChannelOutput<F>> for DonchianChannels<F> {
+    /// Computes the Donchian Channels for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ChannelOutput)`, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the period or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<ChannelOutput<F>> {
+        ensure_sufficient(self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes the Donchian Channels for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<ChannelOutput>>)` aligned with `data`, with `None` during the
+    /// warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<ChannelOutput<F>>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, ChannelOutput<F>> for DonchianChannels<F> {
+    /// Feeds a new bar into the channels.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(ChannelOutput)` once `period` bars have been observed, or `None` while
+    /// warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<ChannelOutput<F>> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        self.window.push_back(bar);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let (upper, lower) = highest_high_lowest_low(&self.window);
+        Some(ChannelOutput {
+            upper,
+            middle: (upper + lower) / constant(2.0),
+            lower
+        })
+    }
+
+    /// Clears the bar window.
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let channel = DonchianChannels::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert_eq!(channel.upper, 46.3);
+        assert_eq!(channel.lower, 44.6);
+        assert!((channel.middle - 45.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let donchian = DonchianChannels::new(5);
+        let series = donchian.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+        for (end, value) in (5..=SAMPLE_BARS.len()).zip(&series[4..]) {
+            assert_eq!(value.unwrap(), donchian.compute(&SAMPLE_BARS[..end].to_vec()).unwrap());
+        }
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let donchian = DonchianChannels::new(5);
+        assert!(donchian.compute(&SAMPLE_BARS[..4].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[13].low = f64::NEG_INFINITY;
+        assert!(donchian.compute(&bars).is_err());
+    }
+}
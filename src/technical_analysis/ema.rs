@@ -3,12 +3,16 @@
 //! The EMA is a type of moving average that places a greater weight and significance
 //! on the most recent data points. It's used in technical analysis to smooth out price
 //! and data series for trend identification.
-use anyhow::{Result, anyhow};
-use super::{Indicator, PeriodIndicator};
+use std::collections::VecDeque;
+use crate::error::{ensure_finite, ensure_sufficient, QmachinaError, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
 
-pub struct ExponentialMovingAverage {
+pub struct ExponentialMovingAverage<F = f64> {
     period: usize,
-    smoothing: f64
+    smoothing: F,
+    window: VecDeque<F>,
+    weighted_sum: F
 }
 
 /// Represents an Exponential Moving Average (EMA) indicator.
@@ -23,55 +27,76 @@ pub struct ExponentialMovingAverage {
 /// ```
 /// use qmachina::technical_analysis::ema::ExponentialMovingAverage;
 ///
-/// let ema = ExponentialMovingAverage::new(5);
+/// let ema: ExponentialMovingAverage = ExponentialMovingAverage::new(5);
 /// ```
-impl ExponentialMovingAverage {
+impl<F: Float> ExponentialMovingAverage<F> {
     pub fn new(period: usize) -> Self {
         let period = if period == 0 { 1 } else { period };
-        let smoothing = 2.0 / (period as f64 + 1.0);
+        let smoothing = constant::<F>(2.0) / (count::<F>(period) + F::one());
 
         Self {
             period,
-            smoothing
+            smoothing,
+            window: VecDeque::with_capacity(period + 1),
+            weighted_sum: F::zero()
         }
     }
 }
 
-impl Indicator<f64, f64> for ExponentialMovingAverage {
+impl<F: Float> Indicator<F, F> for ExponentialMovingAverage<F> {
     /// Computes the EMA value using an `Arc<[f64]>` as input data.
     ///
     /// # Parameters
     ///
-    /// * `data` - An `Vec<f64>` containing the data points for which the EMA is calculated.
+    /// * `data` - A `Vec<F>` containing the data points for which the EMA is calculated.
     ///
     /// # Returns
     ///
-    /// Returns `Ok(f64)` containing the calculated EMA value, or an error if the calculation fails.
+    /// Returns `Ok(F)` containing the calculated EMA value, or an error if the calculation fails.
     ///
     /// # Errors
     ///
     /// Returns an error if the length of the data is less than the EMA period or if the data contains
     /// invalid values (NaN or infinite).
-    fn compute(&self, data: &Vec<f64>) -> Result<f64> {
-        if data.len() < self.period {
-            return Err(anyhow!("Period is larger than the sampled data."));
-        }
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
 
-        let slice = &data[(data.len() - self.period)..];
+        let start = data.len() - self.period;
+        let slice = &data[start..];
+        if let Some(index) = slice.iter().position(|value| !value.is_finite()) {
+            return Err(QmachinaError::InvalidValue { index: start + index });
+        }
 
         let mut ema = slice[0];
         for &value in &slice[1..] {
-            if value.is_nan() || value.is_infinite() {
-                return Err(anyhow!("Invalid data encountered during calculations."));
-            }
             ema = (value - ema) * self.smoothing + ema;
         }
 
         Ok(ema)
     }
+
+    /// Computes the EMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
 }
 
-impl PeriodIndicator for ExponentialMovingAverage {
+impl<F: Float> PeriodIndicator for ExponentialMovingAverage<F> {
     /// Returns the current period used in the EMA calculation.
     ///
     /// # Returns
@@ -86,8 +111,166 @@ impl PeriodIndicator for ExponentialMovingAverage {
     /// # Parameters
     ///
     /// * `period` - The new period to set, represented as a `usize`.
+    ///
+    /// The smoothing factor is recalculated for the new period and any streaming state is reset.
     fn set_period(&mut self, period: usize) {
         self.period = if period == 0 { 1 } else { period };
+        self.smoothing = constant::<F>(2.0) / (count::<F>(self.period) + F::one());
+        self.reset();
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for ExponentialMovingAverage<F> {
+    /// Feeds a new data point into the EMA.
+    ///
+    /// The batch EMA is seeded with the first value of the last `period` data points. Unrolled,
+    /// that is a geometrically weighted sum over the window, which can be slid forward in constant
+    /// time by decaying the running sum and dropping the weight of the value leaving the window.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the EMA over the last `period` values once enough data has been
+    /// observed, or `None` while warming up. Invalid values (NaN or infinite) are rejected with
+    /// `None` and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let decay = F::one() - self.smoothing;
+        let window_decay = decay.powi(self.period as i32);
+
+        self.window.push_back(value);
+        self.weighted_sum = decay * self.weighted_sum + value;
+
+        if self.window.len() > self.period {
+            if let Some(oldest) = self.window.pop_front() {
+                self.weighted_sum -= window_decay * oldest;
+            }
+        }
+
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let seed = self.window.front().copied()?;
+        Some(self.smoothing * self.weighted_sum + window_decay * seed)
+    }
+
+    /// Clears the streaming window and running weighted sum.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.weighted_sum = F::zero();
+    }
+}
+
+/// An exponential moving average running over the whole history of a stream.
+///
+/// Unlike `ExponentialMovingAverage`, which only looks at the last `period` values, this EMA is
+/// seeded with the SMA of the first `period` values and then follows the usual recursion for
+/// every following value, as in TA-Lib. Composite averages such as the DEMA, TEMA, ZLEMA and T3
+/// are defined over this recursion and chain several of them.
+#[derive(Debug, Clone)]
+pub(crate) struct RecursiveEma<F = f64> {
+    period: usize,
+    smoothing: F,
+    observed: usize,
+    sum: F,
+    value: Option<F>
+}
+
+impl<F: Float> RecursiveEma<F> {
+    /// Constructs a new `RecursiveEma` with the given period, replacing a period of 0 by 1.
+    pub(crate) fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            smoothing: constant::<F>(2.0) / (count::<F>(period) + F::one()),
+            observed: 0,
+            sum: F::zero(),
+            value: None
+        }
+    }
+
+    /// Feeds a new value, returning the EMA once the SMA seed is available.
+    pub(crate) fn update(&mut self, value: F) -> Option<F> {
+        self.value = match self.value {
+            Some(ema) => Some((value - ema) * self.smoothing + ema),
+            None => {
+                self.observed += 1;
+                self.sum += value;
+                (self.observed == self.period).then(|| self.sum / count::<F>(self.period))
+            }
+        };
+
+        self.value
+    }
+
+    /// Clears the seed and the running value.
+    pub(crate) fn reset(&mut self) {
+        self.observed = 0;
+        self.sum = F::zero();
+        self.value = None;
+    }
+}
+
+impl<F: Float> Indicator<F, F> for RecursiveEma<F> {
+    /// Computes the EMA at the last position of the data, over its whole history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the period or contains invalid values (NaN
+    /// or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes the EMA for every position of the data, with `None` before the SMA seed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| RecursiveEma::update(&mut stream, value)).collect())
+    }
+}
+
+impl<F: Float> PeriodIndicator for RecursiveEma<F> {
+    /// Returns the period of the EMA.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period, recalculating the smoothing factor and resetting the state.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period);
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for RecursiveEma<F> {
+    /// Feeds a new value, rejecting invalid values (NaN or infinite) with `None` and leaving the
+    /// state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        RecursiveEma::update(self, value)
+    }
+
+    /// Clears the seed and the running value.
+    fn reset(&mut self) {
+        RecursiveEma::reset(self);
     }
 }
 
@@ -97,19 +280,19 @@ mod tests {
 
     #[test]
     fn ema_creation_with_valid_period() {
-        let ema = ExponentialMovingAverage::new(5);
+        let ema: ExponentialMovingAverage = ExponentialMovingAverage::new(5);
         assert_eq!(ema.period(), 5, "EMA period should be 5");
     }
 
     #[test]
     fn ema_creation_with_zero_period() {
-        let ema = ExponentialMovingAverage::new(0);
+        let ema: ExponentialMovingAverage = ExponentialMovingAverage::new(0);
         assert_eq!(ema.period(), 1, "EMA period should default to 1 for zero input");
     }
 
     #[test]
     fn compute_sufficient_data_arc() {
-        let ema = ExponentialMovingAverage::new(3);
+        let ema: ExponentialMovingAverage = ExponentialMovingAverage::new(3);
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
 
         let result = ema.compute(&data).unwrap();
@@ -134,12 +317,98 @@ mod tests {
         assert!(result.is_err(), "Should return an error due to invalid (NaN) data");
     }
 
+    #[test]
+    fn compute_with_invalid_seed() {
+        let ema = ExponentialMovingAverage::new(3);
+        let data = vec![1.0, f64::NAN, 2.0, 3.0];
+        let result = ema.compute(&data);
+        assert!(matches!(result, Err(QmachinaError::InvalidValue { index: 1 })), "The seed of the window should be checked");
+    }
+
     #[test]
     fn period_get_set() {
-        let mut ema = ExponentialMovingAverage::new(3);
+        let mut ema: ExponentialMovingAverage = ExponentialMovingAverage::new(3);
         assert_eq!(ema.period(), 3, "Initial period should be 3");
 
         ema.set_period(10);
         assert_eq!(ema.period(), 10, "Period after set_period should be 10");
     }
+
+    #[test]
+    fn set_period_updates_smoothing() {
+        let mut ema: ExponentialMovingAverage = ExponentialMovingAverage::new(10);
+        ema.set_period(3);
+
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let result = ema.compute(&data).unwrap();
+        assert!((result - 4.25).abs() < f64::EPSILON, "EMA should use the smoothing of the new period");
+    }
+
+    #[test]
+    fn streaming_matches_batch() {
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 11.7, 12.5, 13.0, 12.6, 13.4, 14.0];
+        let batch: ExponentialMovingAverage = ExponentialMovingAverage::new(5);
+        let mut stream: ExponentialMovingAverage = ExponentialMovingAverage::new(5);
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => {
+                    let streamed = streamed.expect("Stream should be warm once batch succeeds");
+                    assert!((streamed - expected).abs() < 1e-9, "Streamed EMA should match batch EMA");
+                }
+                Err(_) => assert!(streamed.is_none(), "Stream should still be warming up"),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut ema = ExponentialMovingAverage::new(3);
+        for value in [1.0, 2.0, 3.0, 4.0] {
+            ema.update(value);
+        }
+        ema.reset();
+
+        assert_eq!(ema.update(1.0), None, "Reset should clear the window");
+        assert_eq!(ema.update(2.0), None);
+        assert_eq!(ema.update(3.0), Some(2.25));
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let indicator: ExponentialMovingAverage = ExponentialMovingAverage::new(3);
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let series = indicator.compute_series(&data).unwrap();
+
+        assert_eq!(series.len(), data.len(), "Series should be aligned with the input");
+        assert!(series[..2].iter().all(Option::is_none), "Warm-up values should be None");
+
+        for (end, value) in (1..=data.len()).zip(series) {
+            match indicator.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert!((value.unwrap() - expected).abs() < 1e-9, "Series EMA should match compute"),
+                Err(_) => assert!(value.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn compute_series_with_invalid_data() {
+        let indicator = ExponentialMovingAverage::new(3);
+        let data = vec![1.0, 2.0, f64::NAN, 4.0, 5.0];
+        assert!(indicator.compute_series(&data).is_err(), "Should return an error due to invalid (NaN) data");
+    }
+
+    #[test]
+    fn recursive_ema_is_seeded_with_sma() {
+        let mut ema: RecursiveEma = RecursiveEma::new(3);
+        assert_eq!(ema.update(1.0), None);
+        assert_eq!(ema.update(2.0), None);
+        assert_eq!(ema.update(3.0), Some(2.0), "The seed should be the SMA of the first values");
+        assert_eq!(ema.update(4.0), Some(3.0));
+        assert_eq!(ema.update(6.0), Some(4.5), "The EMA should keep the whole history");
+
+        ema.reset();
+        assert_eq!(ema.update(1.0), None);
+    }
 }
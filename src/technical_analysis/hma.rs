@@ -0,0 +1,169 @@
+//! This module contains definitions and implementations for Hull Moving Average (HMA).
+//!
+//! The HMA, developed by Alan Hull, combines weighted moving averages of different lengths to
+//! follow prices closely while staying smooth:
+//! `HMA = WMA(2 * WMA(period / 2) - WMA(period), sqrt(period))`.
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::wma::WeightedMovingAverage;
+
+/// Represents a Hull Moving Average (HMA) indicator.
+///
+/// The half period is rounded down and the smoothing period is the integer square root of the
+/// period, both being at least 1. The first value is available after
+/// `period + sqrt(period) - 1` data points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::hma::HullMovingAverage;
+///
+/// let hma = HullMovingAverage::new(4);
+/// let data: Vec<f64> = (0..10).map(|value| value as f64).collect();
+/// assert!((hma.compute(&data).unwrap() - 9.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HullMovingAverage<F = f64> {
+    period: usize,
+    half: WeightedMovingAverage<F>,
+    full: WeightedMovingAverage<F>,
+    smoothing: WeightedMovingAverage<F>
+}
+
+impl<F: Float> HullMovingAverage<F> {
+    /// Constructs a new `HullMovingAverage` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the slowest weighted moving average.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            half: WeightedMovingAverage::new(period / 2),
+            full: WeightedMovingAverage::new(period),
+            smoothing: WeightedMovingAverage::new((period as f64).sqrt() as usize)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for HullMovingAverage<F> {
+    /// Returns the current period used in the HMA calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the HMA calculation, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period);
+    }
+}
+
+impl<F: Float> Indicator<F, F> for HullMovingAverage<F> {
+    /// Computes the HMA at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the HMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated HMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `period + sqrt(period) - 1` values or contains
+    /// invalid values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, self.period + self.smoothing.period() - 1)
+    }
+
+    /// Computes the HMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for HullMovingAverage<F> {
+    /// Feeds a new data point into the HMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current HMA once the smoothing WMA is warm, or `None` while
+    /// warming up. Invalid values (NaN or infinite) are rejected with `None` and leave the state
+    /// untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let half = self.half.update(value);
+        let full = self.full.update(value)?;
+
+        self.smoothing.update(constant::<F>(2.0) * half? - full)
+    }
+
+    /// Clears the state of the three weighted moving averages.
+    fn reset(&mut self) {
+        self.half.reset();
+        self.full.reset();
+        self.smoothing.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = HullMovingAverage::new(9).compute_series(&DATA).unwrap();
+
+        assert!(series[..10].iter().all(Option::is_none), "HMA needs period + sqrt(period) - 1 values");
+        assert!((series[10].unwrap() - 46.2907407407).abs() < 1e-9);
+        assert!((series[11].unwrap() - 46.3395185185).abs() < 1e-9);
+        assert!((series[32].unwrap() - 42.8811111111111).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let hma = HullMovingAverage::new(9);
+        assert!(hma.compute(&DATA[..10].to_vec()).is_err());
+        assert!(hma.compute(&DATA[..11].to_vec()).is_ok());
+        assert!(hma.compute(&vec![f64::NAN; 11]).is_err());
+    }
+
+    #[test]
+    fn small_periods_are_clamped() {
+        let mut hma = HullMovingAverage::new(1);
+        assert_eq!(hma.update(4.0), Some(4.0), "HMA(1) should follow the data");
+        assert_eq!(hma.update(6.0), Some(6.0));
+    }
+}
@@ -0,0 +1,227 @@
+//! This module contains definitions and implementations for Kaufman's Adaptive Moving Average (KAMA).
+//!
+//! The KAMA, developed by Perry Kaufman, adapts its smoothing to the efficiency of the market:
+//! it follows prices closely when they trend and flattens out when they move sideways.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+
+/// Represents Kaufman's Adaptive Moving Average (KAMA) indicator.
+///
+/// The efficiency ratio is the net price change over `period` data points divided by the sum
+/// of the absolute changes over the same data points. It scales the smoothing constant between
+/// the ones of a slow and of a fast EMA (30 and 2 periods by default), and the squared result is
+/// used to move the average towards the price. The KAMA is seeded with the price preceding the
+/// first complete efficiency ratio, so the first value is available after `period + 1` data
+/// points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::kama::KaufmanAdaptiveMovingAverage;
+///
+/// let kama = KaufmanAdaptiveMovingAverage::new(10).with_smoothing_periods(2, 20);
+/// let data = vec![10.0, 10.2, 10.1, 10.4, 10.6, 10.5, 10.8, 11.0, 11.2, 11.1, 11.4, 11.6];
+/// let value = kama.compute(&data).unwrap();
+/// assert!(value > 11.0 && value < 11.6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KaufmanAdaptiveMovingAverage<F = f64> {
+    period: usize,
+    fast_period: usize,
+    slow_period: usize,
+    window: VecDeque<F>,
+    value: Option<F>
+}
+
+impl<F: Float> KaufmanAdaptiveMovingAverage<F> {
+    /// Constructs a new `KaufmanAdaptiveMovingAverage` with the given efficiency ratio period.
+    ///
+    /// The smoothing constants default to those of a 2-period and a 30-period EMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of price changes used for the efficiency ratio.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            fast_period: 2,
+            slow_period: 30,
+            window: VecDeque::with_capacity(period + 2),
+            value: None
+        }
+    }
+
+    /// Returns the `KaufmanAdaptiveMovingAverage` using the given fast and slow EMA periods for
+    /// its smoothing constants.
+    ///
+    /// # Parameters
+    ///
+    /// * `fast_period` - The EMA period used when the market is perfectly efficient.
+    /// * `slow_period` - The EMA period used when the market has no direction.
+    pub fn with_smoothing_periods(mut self, fast_period: usize, slow_period: usize) -> Self {
+        self.fast_period = if fast_period == 0 { 1 } else { fast_period };
+        self.slow_period = if slow_period == 0 { 1 } else { slow_period };
+        self.reset();
+        self
+    }
+
+    /// Returns the fast EMA period.
+    pub fn fast_period(&self) -> usize {
+        self.fast_period
+    }
+
+    /// Returns the slow EMA period.
+    pub fn slow_period(&self) -> usize {
+        self.slow_period
+    }
+}
+
+impl<F: Float> PeriodIndicator for KaufmanAdaptiveMovingAverage<F> {
+    /// Returns the current efficiency ratio period.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new efficiency ratio period, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<F, F> for KaufmanAdaptiveMovingAverage<F> {
+    /// Computes the KAMA at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the KAMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated KAMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `period + 1` values or contains invalid values
+    /// (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, self.period + 1)
+    }
+
+    /// Computes the KAMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period).with_smoothing_periods(self.fast_period, self.slow_period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for KaufmanAdaptiveMovingAverage<F> {
+    /// Feeds a new data point into the KAMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current KAMA once `period + 1` values have been observed, or
+    /// `None` while warming up. Invalid values (NaN or infinite) are rejected with `None` and leave
+    /// the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let previous = self.window.back().copied();
+        self.window.push_back(value);
+        if self.window.len() > self.period + 1 {
+            self.window.pop_front();
+        }
+        if self.window.len() <= self.period {
+            return None;
+        }
+
+        let change = (value - self.window.front().copied()?).abs();
+        let volatility: F = self.window.iter()
+            .zip(self.window.iter().skip(1))
+            .map(|(&previous, &current)| (current - previous).abs())
+            .sum();
+        let efficiency_ratio = if volatility > F::zero() { change / volatility } else { F::zero() };
+
+        let fast = constant::<F>(2.0) / (count::<F>(self.fast_period) + F::one());
+        let slow = constant::<F>(2.0) / (count::<F>(self.slow_period) + F::one());
+        let smoothing = (efficiency_ratio * (fast - slow) + slow).powi(2);
+
+        let kama = self.value.or(previous)?;
+        self.value = Some(kama + smoothing * (value - kama));
+
+        self.value
+    }
+
+    /// Clears the price window and the running average.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = KaufmanAdaptiveMovingAverage::new(10).compute_series(&DATA).unwrap();
+
+        assert!(series[..10].iter().all(Option::is_none), "KAMA needs period + 1 values");
+        assert!((series[10].unwrap() - 46.0592559141).abs() < 1e-9);
+        assert!((series[11].unwrap() - 46.0543835621).abs() < 1e-9);
+        assert!((series[32].unwrap() - 44.64139382456961).abs() < 1e-9);
+    }
+
+    #[test]
+    fn efficient_market_uses_fast_smoothing() {
+        let kama = KaufmanAdaptiveMovingAverage::new(3).with_smoothing_periods(1, 30);
+        let value = kama.compute(&vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(value, 4.0, "An efficiency ratio of 1 with a 1-period fast EMA should jump to the price");
+    }
+
+    #[test]
+    fn flat_market_keeps_seed() {
+        let kama = KaufmanAdaptiveMovingAverage::new(3);
+        assert_eq!(kama.compute(&vec![5.0; 6]).unwrap(), 5.0);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let kama = KaufmanAdaptiveMovingAverage::new(10);
+        assert!(kama.compute(&DATA[..10].to_vec()).is_err());
+        assert!(kama.compute(&DATA[..11].to_vec()).is_ok());
+        assert!(kama.compute(&vec![f64::NAN; 11]).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! This module contains definitions and implementations for Keltner Channels.
+//!
+//! Keltner Channels are a volatility envelope made of a moving average of the closing prices
+//! and of two bands placed a multiple of the Average True Range above and below it. The common
+//! setting uses a 20-period EMA and a band width of twice the ATR.
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, ChannelOutput, Indicator, MovingAverage, MovingAverageKind, PeriodIndicator, StreamingIndicator};
+use super::atr::AverageTrueRange;
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the Keltner Channels indicator.
+///
+/// The middle band is an EMA of the closing prices by default, and the bands are placed
+/// `multiplier` ATRs away from it. The ATR period defaults to the middle band period.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::{Indicator, MovingAverageKind};
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::keltner::KeltnerChannels;
+///
+/// let bars: Vec<Bar> = (0..30)
+///     .map(|i| {
+///         let price = 100.0 + (i as f64 / 3.0).sin();
+///         Bar::new(i, price, price + 1.0, price - 1.0, price, 1000.0)
+///     })
+///     .collect();
+///
+/// let keltner = KeltnerChannels::new(20)
+///     .with_atr_period(10)
+///     .with_multiplier(1.5)
+///     .with_middle_band(MovingAverageKind::Simple);
+/// let channel = keltner.compute(&bars).unwrap();
+/// assert!(channel.upper > channel.middle && channel.middle > channel.lower);
+/// ```
+pub struct KeltnerChannels<F = f64> {
+    period: usize,
+    multiplier: F,
+    middle_band: MovingAverageKind,
+    middle: Box<dyn MovingAverage<F>>,
+    atr: AverageTrueRange<F>
+}
+
+impl<F: Float> KeltnerChannels<F> {
+    /// Constructs a new `KeltnerChannels` with the given period.
+    ///
+    /// The channels default to an EMA middle band, an ATR over the same period and a multiplier
+    /// of 2.0.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the middle band moving average.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+        let middle_band = MovingAverageKind::Exponential;
+
+        Self {
+            period,
+            multiplier: constant(2.0),
+            middle_band,
+            middle: middle_band.create(period),
+            atr: AverageTrueRange::new(period)
+        }
+    }
+
+    /// Returns the `KeltnerChannels` using the given ATR multiplier.
+    ///
+    /// # Parameters
+    ///
+    /// * `multiplier` - The number of ATRs between the middle band and the outer bands.
+    pub fn with_multiplier(mut self, multiplier: F) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Returns the `KeltnerChannels` using the given ATR period.
+    ///
+    /// # Parameters
+    ///
+    /// * `atr_period` - The period of the Average True Range.
+    pub fn with_atr_period(mut self, atr_period: usize) -> Self {
+        self.atr = AverageTrueRange::new(atr_period);
+        self.reset();
+        self
+    }
+
+    /// Returns the `KeltnerChannels` using the given moving average for the middle band.
+    ///
+    /// # Parameters
+    ///
+    /// * `middle_band` - The kind of moving average used for the middle band.
+    pub fn with_middle_band(mut self, middle_band: MovingAverageKind) -> Self {
+        self.middle_band = middle_band;
+        self.middle = middle_band.create(self.period);
+        self.reset();
+        self
+    }
+
+    /// Returns the ATR multiplier.
+    pub fn multiplier(&self) -> F {
+        self.multiplier
+    }
+
+    /// Returns the ATR period.
+    pub fn atr_period(&self) -> usize {
+        self.atr.period()
+    }
+
+    /// Returns the kind of moving average used for the middle band.
+    pub fn middle_band(&self) -> MovingAverageKind {
+        self.middle_band
+    }
+
+    /// Creates a fresh instance with the same configuration and no streaming state.
+    fn configured(&self) -> Self {
+        Self::new(self.period)
+            .with_multiplier(self.multiplier)
+            .with_atr_period(self.atr.period())
+            .with_middle_band(self.middle_band)
+    }
+}
+
+impl<F: Float> PeriodIndicator for KeltnerChannels<F> {
+    /// Returns the period of the middle band.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the middle band, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.middle = self.middle_band.create(self.period);
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, ChannelOutput<F>> for KeltnerChannels<F> {
+    /// Computes the Keltner Channels for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(ChannelOutput)`, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the middle band period or than the ATR
+    /// period plus one, or if it contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<ChannelOutput<F>> {
+        let required = self.period.max(self.atr.period() + 1);
+        ensure_sufficient(required, data.len())?;
+
+        last_value(self.compute_series(data)?, required)
+    }
+
+    /// Computes the Keltner Channels for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<ChannelOutput>>)` aligned with `data`, with `None` during the
+    /// warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<ChannelOutput<F>>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = self.configured();
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, ChannelOutput<F>> for KeltnerChannels<F> {
+    /// Feeds a new bar into the middle band and the ATR.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(ChannelOutput)` once both the middle band and the ATR are warm, or `None`
+    /// while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<ChannelOutput<F>> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let middle = self.middle.update(bar.close);
+        let atr = self.atr.update(bar);
+        let (middle, atr) = (middle?, atr?);
+
+        Some(ChannelOutput {
+            upper: middle + self.multiplier * atr,
+            middle,
+            lower: middle - self.multiplier * atr
+        })
+    }
+
+    /// Resets the middle band and the ATR.
+    fn reset(&mut self) {
+        self.middle.reset();
+        self.atr.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let channel = KeltnerChannels::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+
+        assert!((channel.upper - 47.359138074396455).abs() < 1e-9);
+        assert!((channel.middle - 45.54444444444445).abs() < 1e-9);
+        assert!((channel.lower - 43.72975081449245).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bands_use_atr_multiplier() {
+        let bars = SAMPLE_BARS.to_vec();
+        let channel = KeltnerChannels::new(5)
+            .with_atr_period(3)
+            .with_multiplier(1.5)
+            .with_middle_band(MovingAverageKind::Simple)
+            .compute(&bars)
+            .unwrap();
+
+        let close: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+        let middle = MovingAverageKind::Simple.create(5).compute(&close).unwrap();
+        let atr = AverageTrueRange::new(3).compute(&bars).unwrap();
+
+        assert!((channel.middle - middle).abs() < 1e-9, "Middle band should be the configured moving average");
+        assert!((channel.upper - (middle + 1.5 * atr)).abs() < 1e-9);
+        assert!((channel.lower - (middle - 1.5 * atr)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn warm_up_waits_for_atr() {
+        let keltner = KeltnerChannels::new(3).with_atr_period(5);
+        let series = keltner.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert!(series[..5].iter().all(Option::is_none), "The ATR needs period + 1 bars");
+        assert!(series[5..].iter().all(Option::is_some));
+        assert!(keltner.compute(&SAMPLE_BARS[..5].to_vec()).is_err());
+    }
+
+    #[test]
+    fn invalid_bar_fails() {
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[6].close = f64::NAN;
+        assert!(KeltnerChannels::new(5).compute(&bars).is_err());
+    }
+}
@@ -2,11 +2,34 @@
 //!
 //! The MACD is a trend-following momentum indicator that shows the relationship
 //! between two moving averages of a security’s price. It is calculated by subtracting
-//! the long-term Exponential Moving Average (EMA) from the short-term EMA.
-use anyhow::{Result, anyhow};
+//! the long-term Exponential Moving Average (EMA) from the short-term EMA. The signal
+//! line is an EMA of the MACD line, and the histogram is the difference between both.
+use crate::error::{ensure_finite, ensure_same_length, ensure_sufficient, QmachinaError, Result};
+use crate::numeric::Float;
+use crate::technical_analysis::{last_value, Indicator, MovingAverage, MovingAverageKind, StreamingIndicator};
 
-use crate::technical_analysis::{Indicator, PeriodIndicator};
-use super::ema::ExponentialMovingAverage;
+/// Holds the values produced by the MACD indicator at a single point in time.
+///
+/// # Fields
+///
+/// * `macd`: The MACD line, the fast EMA minus the slow EMA.
+/// * `signal`: The signal line, an EMA of the MACD line.
+/// * `histogram`: The MACD line minus the signal line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdOutput<F = f64> {
+    pub macd: F,
+    pub signal: F,
+    pub histogram: F
+}
+
+/// Represents a crossover between the MACD line and its signal line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacdCrossover {
+    /// The MACD line crossed above the signal line.
+    Bullish,
+    /// The MACD line crossed below the signal line.
+    Bearish
+}
 
 /// Represents the Moving Average Convergence Divergence (MACD) indicator.
 ///
@@ -15,11 +38,19 @@ use super::ema::ExponentialMovingAverage;
 /// It consists of the MACD line (the difference between two exponential moving averages)
 /// and the signal line, which is an EMA of the MACD line.
 ///
+/// As in TA-Lib, the EMAs run over the whole history of the data and are seeded with an SMA, and
+/// the fast EMA starts `slow_ema_period - fast_ema_period` values later, so that both averages
+/// produce their first value at the same position. The first output is thus available after
+/// `slow_ema_period + signal_ema_period - 1` values.
+///
+/// The EMAs, `MovingAverageKind::RecursiveExponential` by default, can be swapped for any other
+/// `MovingAverageKind`, separately for the fast and slow averages and for the signal line.
+///
 /// # Fields
 ///
-/// * `slow_ema`: The long-term (slow) Exponential Moving Average (EMA).
-/// * `fast_ema`: The short-term (fast) Exponential Moving Average (EMA).
-/// * `signal_ema`: The EMA of the MACD line, known as the signal line.
+/// * `slow_ema`: The long-term (slow) moving average, an EMA by default.
+/// * `fast_ema`: The short-term (fast) moving average, an EMA by default.
+/// * `signal_ema`: The moving average of the MACD line, known as the signal line.
 ///
 /// # Examples
 ///
@@ -30,7 +61,7 @@ use super::ema::ExponentialMovingAverage;
 /// use qmachina::technical_analysis::macd::MACD;
 ///
 /// // Create a MACD indicator with specific periods
-/// let macd = MACD::new(26, 12, 9);
+/// let macd: MACD = MACD::new(26, 12, 9);
 ///
 /// // Example data (price values)
 /// let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0,
@@ -38,41 +69,101 @@ use super::ema::ExponentialMovingAverage;
 ///                 20.5, 21.0, 21.5, 22.0, 22.5, 23.0, 23.5, 24.0, 24.5, 25.0, 25.5, 26.0
 /// ];
 ///
-/// // Compute the MACD value
-/// let macd_value = macd.compute(&data).expect("Failed to compute MACD");
+/// // Compute the MACD line, signal line and histogram
+/// let output = macd.compute(&data).expect("Failed to compute MACD");
+/// assert_eq!(output.histogram, output.macd - output.signal);
 ///
 /// let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5];
-/// // Generate the signal line value
+/// // Generate the signal line value from MACD values assembled by hand
 /// let signal_value = macd.generate_signal(&data).expect("Failed to compute signal line");
 /// ```
-pub struct MACD {
-    slow_ema: ExponentialMovingAverage,
-    fast_ema: ExponentialMovingAverage,
-    signal_ema: ExponentialMovingAverage
+///
+/// Using other moving averages:
+///
+/// ```
+/// use qmachina::technical_analysis::{Indicator, MovingAverageKind};
+/// use qmachina::technical_analysis::macd::MACD;
+///
+/// let macd: MACD = MACD::new(10, 4, 3)
+///     .with_moving_average(MovingAverageKind::Hull)
+///     .with_signal_moving_average(MovingAverageKind::Simple);
+///
+/// let data: Vec<f64> = (0..20).map(|value| 10.0 + (value as f64 / 2.0).sin()).collect();
+/// let output = macd.compute(&data).expect("Failed to compute MACD");
+/// ```
+pub struct MACD<F = f64> {
+    moving_average: MovingAverageKind,
+    signal_moving_average: MovingAverageKind,
+    slow_ema: Box<dyn MovingAverage<F>>,
+    fast_ema: Box<dyn MovingAverage<F>>,
+    signal_ema: Box<dyn MovingAverage<F>>,
+    skipped: usize
 }
 
-impl MACD {
-    /// Constructs a new `MACD` instance.
+impl<F: Float> MACD<F> {
+    /// Constructs a new `MACD` instance using EMAs for every moving average.
     ///
     /// # Arguments
     ///
     /// * `slow_ema_period` - The period for the slow EMA.
     /// * `fast_ema_period` - The period for the fast EMA.
+    /// * `signal_ema_period` - The period for the signal line EMA.
     pub fn new(slow_ema_period: usize, fast_ema_period: usize, signal_ema_period: usize) -> Self {
         let slow_ema_period = if slow_ema_period == 0 { 1 } else { slow_ema_period };
         let fast_ema_period = if fast_ema_period == 0 { 1 } else { fast_ema_period };
         let signal_ema_period = if signal_ema_period == 0 { 1 } else { signal_ema_period };
 
+        let kind = MovingAverageKind::RecursiveExponential;
+
         Self {
-            slow_ema: ExponentialMovingAverage::new(slow_ema_period),
-            fast_ema: ExponentialMovingAverage::new(fast_ema_period),
-            signal_ema: ExponentialMovingAverage::new(signal_ema_period)
+            moving_average: kind,
+            signal_moving_average: kind,
+            slow_ema: kind.create(slow_ema_period),
+            fast_ema: kind.create(fast_ema_period),
+            signal_ema: kind.create(signal_ema_period),
+            skipped: 0
         }
     }
 
+    /// Returns the `MACD` using the given kind of moving average for the fast and slow averages.
+    ///
+    /// # Arguments
+    ///
+    /// * `moving_average` - The kind of moving average the MACD line is built from.
+    pub fn with_moving_average(mut self, moving_average: MovingAverageKind) -> Self {
+        self.moving_average = moving_average;
+        self.slow_ema = moving_average.create(self.slow_ema.period());
+        self.fast_ema = moving_average.create(self.fast_ema.period());
+        self.reset();
+        self
+    }
+
+    /// Returns the `MACD` using the given kind of moving average for the signal line.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal_moving_average` - The kind of moving average applied to the MACD line.
+    pub fn with_signal_moving_average(mut self, signal_moving_average: MovingAverageKind) -> Self {
+        self.signal_moving_average = signal_moving_average;
+        self.signal_ema = signal_moving_average.create(self.signal_ema.period());
+        self.reset();
+        self
+    }
+
+    /// Returns the kind of moving average used for the fast and slow averages.
+    pub fn moving_average(&self) -> MovingAverageKind {
+        self.moving_average
+    }
+
+    /// Returns the kind of moving average used for the signal line.
+    pub fn signal_moving_average(&self) -> MovingAverageKind {
+        self.signal_moving_average
+    }
+
     /// Generates the signal line value from a series of MACD values.
     ///
-    /// This method computes the signal line, which is an EMA of the MACD line.
+    /// This method computes the signal line, which is an EMA (or the configured signal moving
+    /// average) of the MACD line.
     ///
     /// # Arguments
     ///
@@ -80,46 +171,198 @@ impl MACD {
     ///
     /// # Returns
     ///
-    /// Returns a `Result` which is either the signal line value (`f64`) or an error (`anyhow::Error`).
+    /// Returns a `Result` which is either the signal line value (`F`) or an error (`QmachinaError`).
     ///
     /// # Errors
     ///
     /// Returns an error if the length of the `macd_values` is not equal to the specified `period`.
-    pub fn generate_signal(&self, macd_values: &Vec<f64>) -> Result<f64> {
-        if macd_values.len() != self.signal_ema.period() {
-            return Err(anyhow!("Period is larger or smaller than the Data."));
-        }
+    pub fn generate_signal(&self, macd_values: &Vec<F>) -> Result<F> {
+        ensure_same_length(self.signal_ema.period(), macd_values.len())?;
 
         let signal_value = self.signal_ema.compute(macd_values)?;
 
         Ok(signal_value)
     }
-}
 
-impl Indicator<f64, f64> for MACD {
-    fn compute(&self, data: &Vec<f64>) -> Result<f64> {
+    /// Detects crossovers between the MACD line and the signal line over a series of prices.
+    ///
+    /// A bullish crossover is reported when the histogram turns positive after being negative,
+    /// and a bearish crossover when it turns negative after being positive. Periods where the
+    /// histogram sits exactly at zero do not reset the previous side.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A slice containing the prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector aligned with `data`, holding the crossover detected at each position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as `compute_series`.
+    pub fn crossovers(&self, data: &[F]) -> Result<Vec<Option<MacdCrossover>>> {
+        let mut previously_above: Option<bool> = None;
+
+        let crossovers = self.compute_series(data)?
+            .into_iter()
+            .map(|output| {
+                let histogram = output?.histogram;
+                if histogram == F::zero() {
+                    return None;
+                }
+
+                let above = histogram > F::zero();
+                let crossover = match previously_above {
+                    Some(false) if above => Some(MacdCrossover::Bullish),
+                    Some(true) if !above => Some(MacdCrossover::Bearish),
+                    _ => None
+                };
+                previously_above = Some(above);
+
+                crossover
+            })
+            .collect();
+
+        Ok(crossovers)
+    }
+
+    /// Returns the number of prices needed for the first output, from the warm-up of each
+    /// moving average. The MACD line needs both averages, the fast one starting
+    /// `slow_ema_period - fast_ema_period` prices late, and the signal line then needs its own
+    /// warm-up of MACD values.
+    fn required(&self) -> usize {
+        let slow = self.slow_ema.period();
+        let fast = self.fast_ema.period();
+        let macd_warm_up = self.moving_average.warm_up(slow)
+            .max(slow.saturating_sub(fast) + self.moving_average.warm_up(fast));
+
+        macd_warm_up + self.signal_moving_average.warm_up(self.signal_ema.period()) - 1
+    }
+
+    /// Validates that the fast EMA period is less than the slow EMA period.
+    fn validate_periods(&self) -> Result<()> {
         if self.fast_ema.period().ge(&self.slow_ema.period()) {
-            return Err(anyhow!("The fast EMA must be less than the slow EMA."));
+            return Err(QmachinaError::InvalidParameter("The fast EMA must be less than the slow EMA."));
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Float> Indicator<F, MacdOutput<F>> for MACD<F> {
+    /// Computes the MACD line, signal line and histogram from raw prices.
+    ///
+    /// The MACD line is computed for every position of the data, and the signal line is the
+    /// EMA of the MACD line.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(MacdOutput)` for the last position of the data, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fast EMA period is not less than the slow EMA period, if the data
+    /// is shorter than the warm-up of the moving averages, `slow_ema_period + signal_ema_period - 1`
+    /// for EMAs, or if the data contains invalid values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<MacdOutput<F>> {
+        self.validate_periods()?;
+
+        let required = self.required();
+        ensure_sufficient(required, data.len())?;
+
+        last_value(self.compute_series(data)?, required)
+    }
+
+    /// Computes the MACD line, signal line and histogram for every position of the data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<MacdOutput>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite), or if the fast EMA
+    /// period is not less than the slow EMA period.
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<MacdOutput<F>>>> {
+        self.validate_periods()?;
+        ensure_finite(data)?;
+
+        let mut stream = MACD::new(self.slow_ema.period(), self.fast_ema.period(), self.signal_ema.period())
+            .with_moving_average(self.moving_average)
+            .with_signal_moving_average(self.signal_moving_average);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, MacdOutput<F>> for MACD<F> {
+    /// Feeds a new price into the moving averages of the MACD and, once available, the MACD line
+    /// into the signal line average. The fast average skips the first
+    /// `slow_ema_period - fast_ema_period` prices, as in TA-Lib.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest price.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(MacdOutput)` once the signal line is warm, or `None` while warming up, when
+    /// the value is invalid (NaN or infinite), or when the fast EMA period is not less than the
+    /// slow EMA period.
+    fn update(&mut self, value: F) -> Option<MacdOutput<F>> {
+        if !value.is_finite() {
+            return None;
         }
 
-        if data.len().lt(&self.slow_ema.period()) {
-            return Err(anyhow!("Slow EMA period is larger than the Data length."));
+        let slow_ema_value = self.slow_ema.update(value);
+        let fast_ema_value = if self.skipped < self.slow_ema.period().saturating_sub(self.fast_ema.period()) {
+            self.skipped += 1;
+            None
+        } else {
+            self.fast_ema.update(value)
+        };
+
+        if self.fast_ema.period() >= self.slow_ema.period() {
+            return None;
         }
 
-        let fast_ema_value = self.fast_ema.compute(data)?;
-        let slow_ema_value = self.slow_ema.compute(data)?;
+        let macd = fast_ema_value? - slow_ema_value?;
+        let signal = self.signal_ema.update(macd)?;
 
-        Ok(fast_ema_value - slow_ema_value)
+        Some(MacdOutput {
+            macd,
+            signal,
+            histogram: macd - signal
+        })
+    }
+
+    /// Resets the state of all three moving averages.
+    fn reset(&mut self) {
+        self.skipped = 0;
+        self.fast_ema.reset();
+        self.slow_ema.reset();
+        self.signal_ema.reset();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::technical_analysis::sma::SimpleMovingAverage;
+    use crate::technical_analysis::ema::RecursiveEma;
+    use crate::technical_analysis::REFERENCE_PRICES;
 
     #[test]
     fn initialization_with_valid_periods() {
-        let macd = MACD::new(26, 12, 9);
+        let macd: MACD = MACD::new(26, 12, 9);
         assert_eq!(macd.slow_ema.period(), 26);
         assert_eq!(macd.fast_ema.period(), 12);
     }
@@ -168,9 +411,236 @@ mod tests {
 
     #[test]
     fn signal_generation_with_empty_macd_values() {
-        let macd = MACD::new(26, 12, 9);
+        let macd: MACD = MACD::new(26, 12, 9);
         let macd_values = vec![]; // Empty vector
         let result = macd.generate_signal(&macd_values);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn streaming_matches_batch() {
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0, 13.2, 12.8];
+        let batch: MACD = MACD::new(6, 3, 4);
+        let mut stream: MACD = MACD::new(6, 3, 4);
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => {
+                    let streamed = streamed.expect("Stream should be warm once batch succeeds");
+                    assert!((streamed.macd - expected.macd).abs() < 1e-9, "Streamed MACD should match batch MACD");
+                    assert!((streamed.signal - expected.signal).abs() < 1e-9, "Streamed signal should match batch signal");
+                }
+                Err(_) => assert!(streamed.is_none(), "Stream should still be warming up"),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_with_fast_not_less_than_slow() {
+        let mut macd = MACD::new(3, 3, 2);
+        for value in [10.0, 10.5, 11.0, 10.8] {
+            assert!(macd.update(value).is_none(), "Invalid periods should never yield a value");
+        }
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut macd = MACD::new(3, 2, 2);
+        for value in [10.0, 11.0, 12.0] {
+            macd.update(value);
+        }
+        macd.reset();
+
+        assert!(macd.update(10.0).is_none(), "Reset should clear all EMAs");
+        assert!(macd.update(11.0).is_none());
+        assert!(macd.update(12.0).is_none());
+        assert!(macd.update(13.0).is_some());
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let macd = MACD::new(6, 3, 4);
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0, 13.2, 12.8];
+        let series = macd.compute_series(&data).unwrap();
+
+        assert_eq!(series.len(), data.len(), "Series should be aligned with the input");
+        assert!(series[..8].iter().all(Option::is_none), "Warm-up values should be None");
+        assert!(series[8..].iter().all(Option::is_some), "Values after the warm-up should be Some");
+
+        for (end, value) in (1..=data.len()).zip(series) {
+            match macd.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert_eq!(value.unwrap(), expected, "Series MACD should match compute"),
+                Err(_) => assert!(value.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn compute_series_fails_with_invalid_periods() {
+        let macd = MACD::new(12, 26, 9);
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5];
+        assert!(macd.compute_series(&data).is_err());
+    }
+
+    #[test]
+    fn compute_series_with_invalid_data() {
+        let macd = MACD::new(3, 2, 2);
+        let data = vec![10.0, f64::NAN, 11.0, 10.8, 11.5];
+        assert!(macd.compute_series(&data).is_err());
+    }
+
+    #[test]
+    fn compute_builds_signal_from_macd_line() {
+        let macd = MACD::new(6, 3, 4);
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0, 13.2, 12.8];
+
+        // The fast EMA starts 6 - 3 values later, so that both EMAs start at the sixth value
+        let fast = RecursiveEma::new(3).compute_series(&data[3..]).unwrap();
+        let slow = RecursiveEma::new(6).compute_series(&data).unwrap();
+        let macd_line: Vec<f64> = fast.iter()
+            .zip(&slow[3..])
+            .filter_map(|(fast, slow)| Some(fast.as_ref()? - slow.as_ref()?))
+            .collect();
+        assert_eq!(macd_line.len(), data.len() - 5);
+
+        let output = macd.compute(&data).unwrap();
+        let expected_signal = RecursiveEma::new(4).compute(&macd_line).unwrap();
+
+        assert!((output.macd - macd_line[macd_line.len() - 1]).abs() < 1e-9, "MACD should be the fast EMA minus the slow EMA");
+        assert!((output.signal - expected_signal).abs() < 1e-9, "Signal should be the EMA of the MACD line");
+        assert_eq!(output.histogram, output.macd - output.signal, "Histogram should be MACD minus signal");
+    }
+
+    #[test]
+    fn error_on_insufficient_data_for_signal() {
+        let macd = MACD::new(6, 3, 4);
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0];
+        assert!(macd.compute(&data).is_err(), "Signal line needs slow + signal - 1 values");
+
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5];
+        assert!(macd.compute(&data).is_ok());
+    }
+
+    #[test]
+    fn crossovers_detect_bullish_and_bearish() {
+        let macd = MACD::new(4, 2, 2);
+        let data = vec![10.0, 10.0, 10.0, 10.0, 9.0, 8.0, 7.0, 8.0, 9.0, 10.0, 11.0, 10.0, 9.0, 8.0];
+
+        let crossovers = macd.crossovers(&data).unwrap();
+        let series = macd.compute_series(&data).unwrap();
+        assert_eq!(crossovers.len(), data.len(), "Crossovers should be aligned with the input");
+
+        let mut previous: Option<f64> = None;
+        for (output, crossover) in series.iter().zip(&crossovers) {
+            let histogram = output.map(|output| output.histogram);
+            let expected = match (previous, histogram) {
+                (Some(p), Some(h)) if p < 0.0 && h > 0.0 => Some(MacdCrossover::Bullish),
+                (Some(p), Some(h)) if p > 0.0 && h < 0.0 => Some(MacdCrossover::Bearish),
+                _ => None,
+            };
+            assert_eq!(*crossover, expected);
+            if histogram.is_some_and(|h| h != 0.0) {
+                previous = histogram;
+            }
+        }
+
+        assert!(crossovers.contains(&Some(MacdCrossover::Bullish)), "Rebound should produce a bullish crossover");
+        assert!(crossovers.contains(&Some(MacdCrossover::Bearish)), "Reversal should produce a bearish crossover");
+    }
+
+    #[test]
+    fn crossovers_fail_with_invalid_periods() {
+        let macd = MACD::new(2, 4, 2);
+        assert!(macd.crossovers(&[10.0, 11.0, 12.0, 13.0, 14.0]).is_err());
+    }
+
+    #[test]
+    fn configurable_moving_averages() {
+        let macd: MACD = MACD::new(6, 3, 4)
+            .with_moving_average(MovingAverageKind::Simple)
+            .with_signal_moving_average(MovingAverageKind::Weighted);
+        let data = vec![10.0, 10.5, 11.0, 10.8, 11.5, 12.0, 12.5, 13.0, 13.5, 14.0, 13.2, 12.8];
+
+        assert_eq!(macd.moving_average(), MovingAverageKind::Simple);
+        assert_eq!(macd.signal_moving_average(), MovingAverageKind::Weighted);
+
+        let fast: SimpleMovingAverage = SimpleMovingAverage::new(3);
+        let slow: SimpleMovingAverage = SimpleMovingAverage::new(6);
+        let macd_line: Vec<f64> = (data.len() - 4..data.len())
+            .map(|end| {
+                let prefix = data[..=end].to_vec();
+                fast.compute(&prefix).unwrap() - slow.compute(&prefix).unwrap()
+            })
+            .collect();
+        let expected_signal = MovingAverageKind::Weighted.create(4).compute(&macd_line).unwrap();
+
+        let output = macd.compute(&data).unwrap();
+        assert!((output.macd - macd_line[3]).abs() < 1e-9, "MACD should be the fast SMA minus the slow SMA");
+        assert!((output.signal - expected_signal).abs() < 1e-9, "Signal should be the WMA of the MACD line");
+
+        let series = macd.compute_series(&data).unwrap();
+        assert_eq!(series.last().copied().flatten(), Some(output), "Series should keep the configured averages");
+    }
+
+    #[test]
+    fn matches_reference_values() {
+        // Reference values as produced by TA-Lib's MACD with fast, slow and signal periods of
+        // 5, 10 and 4.
+        let expected_macd = [
+            0.447179, 0.453966, 0.430155, 0.348673, 0.287642, 0.294495, 0.251597, 0.125071, 0.134840,
+            0.138058, 0.049627, 0.110506, 0.035500, -0.072715, -0.327335, -0.423653, -0.444453,
+            -0.375832, -0.489867, -0.637530, -0.608224
+        ];
+        let expected_signal = [
+            0.577919, 0.528338, 0.489064, 0.432908, 0.374801, 0.342679, 0.306246, 0.233776, 0.194202,
+            0.171744, 0.122897, 0.117941, 0.084964, 0.021893, -0.117798, -0.240140, -0.321865,
+            -0.343452, -0.402018, -0.496223, -0.541023
+        ];
+
+        let macd = MACD::new(10, 5, 4);
+        let series = macd.compute_series(&REFERENCE_PRICES).unwrap();
+        assert_eq!(macd.moving_average(), MovingAverageKind::RecursiveExponential);
+        let configured = MACD::new(10, 5, 4).with_moving_average(MovingAverageKind::RecursiveExponential);
+        assert_eq!(series, configured.compute_series(&REFERENCE_PRICES).unwrap(), "The default EMAs should be the recursive kind");
+
+        assert!(series[..12].iter().all(Option::is_none), "Warm-up values should be None");
+        for ((output, expected_macd), expected_signal) in series[12..].iter().zip(expected_macd).zip(expected_signal) {
+            let output = output.unwrap();
+            assert!((output.macd - expected_macd).abs() < 1e-6, "MACD line should match the reference value");
+            assert!((output.signal - expected_signal).abs() < 1e-6, "Signal line should match the reference value");
+        }
+
+        let last = macd.compute(&REFERENCE_PRICES.to_vec()).unwrap();
+        assert!((last.histogram - (-0.608224 + 0.541023)).abs() < 1e-6, "Compute should return the last reference value");
+    }
+
+    #[test]
+    fn compute_requires_the_warm_up_of_every_moving_average() {
+        let data: Vec<f64> = (0..80).map(|value| 10.0 + (value as f64 / 4.0).sin()).collect();
+        let kinds = [
+            MovingAverageKind::Simple,
+            MovingAverageKind::Exponential,
+            MovingAverageKind::RecursiveExponential,
+            MovingAverageKind::DoubleExponential,
+            MovingAverageKind::Hull,
+            MovingAverageKind::KaufmanAdaptive,
+            MovingAverageKind::T3
+        ];
+
+        for kind in kinds {
+            for signal_kind in kinds {
+                let macd = MACD::new(8, 3, 4).with_moving_average(kind).with_signal_moving_average(signal_kind);
+                let series = macd.compute_series(&data).unwrap();
+                let required = series.iter().position(Option::is_some).unwrap() + 1;
+
+                assert!(macd.compute(&data[..required].to_vec()).is_ok(), "{:?}/{:?} should compute", kind, signal_kind);
+                let result = macd.compute(&data[..required - 1].to_vec());
+                assert!(
+                    matches!(result, Err(QmachinaError::InsufficientData { required: r, .. }) if r == required),
+                    "{:?}/{:?} should require {} values", kind, signal_kind, required
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,209 @@
+//! This module contains definitions and implementations for the Money Flow Index (MFI).
+//!
+//! The MFI, developed by Gene Quong and Avrum Soudack, is a volume-weighted RSI. It compares
+//! the money flowing in on periods where the typical price rises with the money flowing out on
+//! periods where it falls.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the Money Flow Index (MFI).
+///
+/// The raw money flow of a bar is its typical price times its volume. It counts as positive
+/// when the typical price rose from the previous bar and as negative when it fell. The MFI is
+/// `100 * positive / (positive + negative)` over the last `period` flows, and is 50 when there
+/// is no money flow at all.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::mfi::MoneyFlowIndex;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0),
+///     Bar::new(1, 11.0, 11.0, 11.0, 11.0, 100.0),
+///     Bar::new(2, 10.0, 10.0, 10.0, 10.0, 110.0),
+/// ];
+/// assert_eq!(MoneyFlowIndex::new(2).compute(&bars).unwrap(), 50.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MoneyFlowIndex<F = f64> {
+    period: usize,
+    previous_typical_price: Option<F>,
+    flows: VecDeque<F>
+}
+
+impl<F: Float> MoneyFlowIndex<F> {
+    /// Constructs a new `MoneyFlowIndex` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of money flows summed by the indicator.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            previous_typical_price: None,
+            flows: VecDeque::with_capacity(period + 1)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for MoneyFlowIndex<F> {
+    /// Returns the period of the MFI.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the MFI, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for MoneyFlowIndex<F> {
+    /// Computes the MFI for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the MFI, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `period + 1` bars or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period + 1, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period + 1)
+    }
+
+    /// Computes the MFI for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for MoneyFlowIndex<F> {
+    /// Feeds a new bar into the MFI.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current MFI once `period` money flows have been observed, or
+    /// `None` while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let typical_price = bar.typical_price();
+        let previous_typical_price = self.previous_typical_price.replace(typical_price)?;
+
+        let raw_money_flow = typical_price * bar.volume;
+        let flow = if typical_price > previous_typical_price {
+            raw_money_flow
+        } else if typical_price < previous_typical_price {
+            -raw_money_flow
+        } else {
+            F::zero()
+        };
+
+        self.flows.push_back(flow);
+        if self.flows.len() > self.period {
+            self.flows.pop_front();
+        }
+        if self.flows.len() < self.period {
+            return None;
+        }
+
+        let positive: F = self.flows.iter().filter(|flow| **flow > F::zero()).sum();
+        let negative: F = -self.flows.iter().filter(|flow| **flow < F::zero()).sum::<F>();
+
+        if positive + negative == F::zero() {
+            return Some(constant(50.0));
+        }
+
+        Some(constant::<F>(100.0) * positive / (positive + negative))
+    }
+
+    /// Clears the previous typical price and the money flow window.
+    fn reset(&mut self) {
+        self.previous_typical_price = None;
+        self.flows.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let mfi = MoneyFlowIndex::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((mfi - 66.96423252687028).abs() < 1e-9);
+    }
+
+    #[test]
+    fn one_sided_flows() {
+        let rising: Vec<Bar> = (0..4).map(|i| Bar::new(i, 10.0, 10.0 + i as f64, 9.0, 10.0, 100.0)).collect();
+        assert_eq!(MoneyFlowIndex::new(3).compute(&rising).unwrap(), 100.0);
+
+        let flat = vec![Bar::new(0, 10.0, 10.5, 9.5, 10.0, 100.0); 4];
+        assert_eq!(MoneyFlowIndex::new(3).compute(&flat).unwrap(), 50.0, "No money flow should be neutral");
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let mfi = MoneyFlowIndex::new(5);
+        let series = mfi.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert!(series[..5].iter().all(Option::is_none), "MFI needs period + 1 bars");
+        for (end, value) in (6..=SAMPLE_BARS.len()).zip(&series[5..]) {
+            assert_eq!(value.unwrap(), mfi.compute(&SAMPLE_BARS[..end].to_vec()).unwrap());
+        }
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let mfi = MoneyFlowIndex::new(5);
+        assert!(mfi.compute(&SAMPLE_BARS[..5].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[9].volume = f64::NAN;
+        assert!(mfi.compute(&bars).is_err());
+    }
+}
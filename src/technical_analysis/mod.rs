@@ -1,27 +1,48 @@
 //! This module contains various technical analysis indicators.
-use anyhow::Result;
+use crate::error::{QmachinaError, Result};
+use crate::numeric::Float;
 
 pub mod sma;
 pub mod ema;
+pub mod wma;
+pub mod dema;
+pub mod tema;
+pub mod hma;
+pub mod kama;
+pub mod zlema;
+pub mod t3;
 pub mod rsi;
 pub mod bollinger;
 pub mod macd;
+pub mod bar;
+pub mod true_range;
+pub mod atr;
+pub mod stochastic;
+pub mod williams_r;
+pub mod cci;
+pub mod adx;
+pub mod obv;
+pub mod vwap;
+pub mod mfi;
+pub mod cmf;
+pub mod keltner;
+pub mod donchian;
 
 /// The `Indicator` trait defines a common interface for technical analysis indicators.
 /// It is designed to compute an indicator value based on a given set of data.
 ///
 /// This trait is generic over the types `T` and `V`, allowing it to be used with
-/// different data types and output types. The `compute` method returns a `Result<V, anyhow::Error>`,
-/// providing a convenient way to handle errors that might occur during the computation
-/// of the indicator. This approach leverages the `anyhow` crate for simplified error handling.
+/// different data types and output types. The `compute` method returns a `Result<V, QmachinaError>`,
+/// so callers can match on the reason a computation failed, such as
+/// `QmachinaError::InsufficientData` while the indicator is still warming up.
 ///
 /// # Type Parameters
 ///
 /// - `T`: The type of the input data for the indicator. This type should be capable
-///        of representing the data series used for computation and must support the operations
-///        required for the indicator's calculation.
+///   of representing the data series used for computation and must support the operations
+///   required for the indicator's calculation.
 /// - `V`: The type of the output value for the indicator. This type should be a numeric
-///        type (like `f64`) that represents the result of the indicator's computation.
+///   type (like `f64`) that represents the result of the indicator's computation.
 ///
 /// # Example
 ///
@@ -29,7 +50,7 @@ pub mod macd;
 ///
 /// ```
 /// use std::sync::Arc;
-/// use anyhow::Result;
+/// use qmachina::{QmachinaError, Result};
 /// use qmachina::technical_analysis::{Indicator, PeriodIndicator};
 ///
 /// pub struct SimpleMovingAverage {
@@ -39,7 +60,7 @@ pub mod macd;
 /// impl Indicator<f64, f64> for SimpleMovingAverage {
 ///     fn compute(&self, data: &Vec<f64>) -> Result<f64> {
 ///         if data.len() < self.period {
-///             return Err(anyhow::anyhow!("Data length is less than the period."));
+///             return Err(QmachinaError::InsufficientData { required: self.period, got: data.len() });
 ///         }
 ///         let sum: f64 = data.iter().take(self.period).sum();
 ///         Ok(sum / self.period as f64)
@@ -49,7 +70,7 @@ pub mod macd;
 ///
 /// # Errors
 ///
-/// This trait method may return an `Err` variant, encapsulated in `anyhow::Error`,
+/// This trait method may return an `Err` variant, encapsulated in `QmachinaError`,
 /// to indicate various failure conditions, such as insufficient data length for computation.
 ///
 /// # Panics
@@ -69,7 +90,72 @@ pub trait Indicator<T, V> {
     /// # Returns
     /// A `Result` wrapping the computed value (`V`) of the indicator, or an error if the 
     /// computation cannot be performed.
+    #[allow(clippy::ptr_arg)]
     fn compute(&self, data: &Vec<T>) -> Result<V>;
+
+    /// Computes the indicator for every position of the provided data.
+    ///
+    /// The output is aligned with the input: element `i` holds the value `compute` would return
+    /// for `data[..=i]`, and is `None` while the indicator is still warming up. This makes it
+    /// possible to build full feature columns without recomputing each window by hand.
+    ///
+    /// The default implementation calls `compute` on every prefix of the data, which is
+    /// quadratic in the data length. Prefixes failing with `QmachinaError::InsufficientData` map
+    /// to `None`, while any other error is returned. Indicators in this module override it with
+    /// a linear implementation.
+    ///
+    /// # Parameters
+    /// * `data`: Input data of type `T`, upon which the indicator calculation is based.
+    ///
+    /// # Returns
+    /// A `Result` wrapping a vector with one entry per input element, or an error if the
+    /// computation cannot be performed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::technical_analysis::Indicator;
+    /// use qmachina::technical_analysis::sma::SimpleMovingAverage;
+    ///
+    /// let sma = SimpleMovingAverage::new(3);
+    /// let series = sma.compute_series(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+    ///
+    /// assert_eq!(series, vec![None, None, Some(2.0), Some(3.0)]);
+    /// ```
+    fn compute_series(&self, data: &[T]) -> Result<Vec<Option<V>>>
+    where
+        T: Clone,
+    {
+        (1..=data.len())
+            .map(|end| match self.compute(&data[..end].to_vec()) {
+                Ok(value) => Ok(Some(value)),
+                Err(QmachinaError::InsufficientData { .. }) => Ok(None),
+                Err(error) => Err(error)
+            })
+            .collect()
+    }
+}
+
+/// Closing prices shared by the tests of the moving averages, from Wilder's RSI example.
+#[cfg(test)]
+pub(crate) const REFERENCE_PRICES: [f64; 33] = [
+    44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89,
+    46.03, 45.61, 46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25,
+    45.71, 46.45, 45.78, 45.35, 44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13
+];
+
+/// Returns the last value of a series computed by `compute_series`.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::InsufficientData` if the indicator is still warming up at the last
+/// position, `required` being the number of values it needs.
+pub(crate) fn last_value<V>(series: Vec<Option<V>>, required: usize) -> Result<V> {
+    let got = series.len();
+    series.into_iter()
+        .last()
+        .flatten()
+        .ok_or(QmachinaError::InsufficientData { required, got })
 }
 
 /// The `PeriodIndicator` trait extends the functionality of indicators that
@@ -109,3 +195,248 @@ pub trait PeriodIndicator {
     fn period(&self) -> usize;
     fn set_period(&mut self, period: usize);
 }
+
+/// The `StreamingIndicator` trait defines an incremental interface for technical analysis
+/// indicators that are fed one observation at a time, such as from a live market feed.
+///
+/// Where `Indicator::compute` re-scans the whole input on every call, a streaming indicator
+/// keeps its own running state and updates it for every new value without re-scanning the
+/// series, in constant time for most indicators. Once enough values have been observed, each
+/// call to `update` yields the same value that `Indicator::compute` would return for the full
+/// series seen so far.
+///
+/// # Type Parameters
+///
+/// - `T`: The type of each observation fed into the indicator.
+/// - `V`: The type of the output value produced by the indicator.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::technical_analysis::StreamingIndicator;
+/// use qmachina::technical_analysis::sma::SimpleMovingAverage;
+///
+/// let mut sma = SimpleMovingAverage::new(3);
+///
+/// assert_eq!(sma.update(1.0), None);
+/// assert_eq!(sma.update(2.0), None);
+/// assert_eq!(sma.update(3.0), Some(2.0));
+/// assert_eq!(sma.update(4.0), Some(3.0));
+/// ```
+pub trait StreamingIndicator<T, V> {
+    /// Feeds a new observation into the indicator and returns the updated value.
+    ///
+    /// # Parameters
+    /// * `value`: The newest observation of type `T`.
+    ///
+    /// # Returns
+    /// `Some(V)` with the current indicator value, or `None` while the indicator is still
+    /// warming up or when the observation is rejected as invalid (NaN or infinite).
+    fn update(&mut self, value: T) -> Option<V>;
+
+    /// Clears all internal state, returning the indicator to its freshly constructed state.
+    fn reset(&mut self);
+}
+
+/// The `MovingAverage` trait groups the capabilities shared by every moving average in this
+/// module: batch computation, streaming updates and a configurable period.
+///
+/// It is implemented automatically for any type implementing `Indicator<F, F>`,
+/// `StreamingIndicator<F, F>` and `PeriodIndicator`, which allows composite indicators
+/// to hold any moving average behind a `Box<dyn MovingAverage<F>>`. The value type defaults
+/// to `f64`.
+pub trait MovingAverage<F = f64>: Indicator<F, F> + StreamingIndicator<F, F> + PeriodIndicator {}
+
+impl<F, T> MovingAverage<F> for T where T: Indicator<F, F> + StreamingIndicator<F, F> + PeriodIndicator {}
+
+/// Selects the moving average used inside composite indicators.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::technical_analysis::{Indicator, MovingAverageKind};
+///
+/// let ema = MovingAverageKind::Exponential.create(3);
+/// let value = ema.compute(&vec![1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+/// assert_eq!(value, 4.25);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MovingAverageKind {
+    /// The Simple Moving Average (SMA).
+    #[default]
+    Simple,
+    /// The Exponential Moving Average (EMA) over the last `period` values.
+    Exponential,
+    /// The Exponential Moving Average over the whole history, seeded with the SMA of the first
+    /// `period` values as in TA-Lib.
+    RecursiveExponential,
+    /// The Weighted Moving Average (WMA).
+    Weighted,
+    /// The Double Exponential Moving Average (DEMA).
+    DoubleExponential,
+    /// The Triple Exponential Moving Average (TEMA).
+    TripleExponential,
+    /// The Hull Moving Average (HMA).
+    Hull,
+    /// Kaufman's Adaptive Moving Average (KAMA), with the default 2 and 30 period smoothing constants.
+    KaufmanAdaptive,
+    /// The Zero-Lag Exponential Moving Average (ZLEMA).
+    ZeroLagExponential,
+    /// The T3 Moving Average, with the default volume factor of 0.7.
+    T3
+}
+
+impl MovingAverageKind {
+    /// Creates a new moving average of this kind with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the moving average.
+    ///
+    /// The value type `F`, `f32` or `f64`, is usually inferred from the data given to the
+    /// moving average.
+    pub fn create<F: Float + 'static>(&self, period: usize) -> Box<dyn MovingAverage<F>> {
+        match self {
+            MovingAverageKind::Simple => Box::new(sma::SimpleMovingAverage::new(period)),
+            MovingAverageKind::Exponential => Box::new(ema::ExponentialMovingAverage::new(period)),
+            MovingAverageKind::RecursiveExponential => Box::new(ema::RecursiveEma::new(period)),
+            MovingAverageKind::Weighted => Box::new(wma::WeightedMovingAverage::new(period)),
+            MovingAverageKind::DoubleExponential => Box::new(dema::DoubleExponentialMovingAverage::new(period)),
+            MovingAverageKind::TripleExponential => Box::new(tema::TripleExponentialMovingAverage::new(period)),
+            MovingAverageKind::Hull => Box::new(hma::HullMovingAverage::new(period)),
+            MovingAverageKind::KaufmanAdaptive => Box::new(kama::KaufmanAdaptiveMovingAverage::new(period)),
+            MovingAverageKind::ZeroLagExponential => Box::new(zlema::ZeroLagExponentialMovingAverage::new(period)),
+            MovingAverageKind::T3 => Box::new(t3::T3MovingAverage::new(period))
+        }
+    }
+
+    /// Returns the number of values a moving average of this kind needs before producing its
+    /// first value.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the moving average, where 0 is treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::technical_analysis::MovingAverageKind;
+    ///
+    /// assert_eq!(MovingAverageKind::Simple.warm_up(10), 10);
+    /// assert_eq!(MovingAverageKind::TripleExponential.warm_up(10), 28);
+    /// ```
+    pub fn warm_up(&self, period: usize) -> usize {
+        let period = period.max(1);
+        match self {
+            MovingAverageKind::Simple
+            | MovingAverageKind::Exponential
+            | MovingAverageKind::RecursiveExponential
+            | MovingAverageKind::Weighted => period,
+            MovingAverageKind::DoubleExponential => 2 * period - 1,
+            MovingAverageKind::TripleExponential => 3 * period - 2,
+            MovingAverageKind::Hull => period + (period as f64).sqrt() as usize - 1,
+            MovingAverageKind::KaufmanAdaptive => period + 1,
+            MovingAverageKind::ZeroLagExponential => (period - 1) / 2 + period,
+            MovingAverageKind::T3 => 6 * period - 5
+        }
+    }
+}
+
+/// Holds the values produced by a price channel, such as the Keltner or Donchian channels,
+/// at a single point in time.
+///
+/// # Fields
+///
+/// * `upper`: The upper boundary of the channel.
+/// * `middle`: The centre line of the channel.
+/// * `lower`: The lower boundary of the channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelOutput<F = f64> {
+    pub upper: F,
+    pub middle: F,
+    pub lower: F
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [MovingAverageKind; 10] = [
+        MovingAverageKind::Simple,
+        MovingAverageKind::Exponential,
+        MovingAverageKind::RecursiveExponential,
+        MovingAverageKind::Weighted,
+        MovingAverageKind::DoubleExponential,
+        MovingAverageKind::TripleExponential,
+        MovingAverageKind::Hull,
+        MovingAverageKind::KaufmanAdaptive,
+        MovingAverageKind::ZeroLagExponential,
+        MovingAverageKind::T3
+    ];
+
+    #[test]
+    fn every_kind_creates_a_moving_average() {
+        for kind in KINDS {
+            let moving_average = kind.create(4);
+            assert_eq!(moving_average.period(), 4, "{:?} should keep the requested period", kind);
+            assert!(moving_average.compute(&REFERENCE_PRICES.to_vec()).is_ok(), "{:?} should compute", kind);
+        }
+    }
+
+    #[test]
+    fn every_kind_warms_up_as_documented() {
+        let data: Vec<f64> = (0..60).map(|value| 10.0 + (value as f64 / 3.0).sin()).collect();
+        for kind in KINDS {
+            for period in 0..=9 {
+                let series = kind.create(period).compute_series(&data).unwrap();
+                let first = series.iter().position(Option::is_some).unwrap();
+                assert_eq!(kind.warm_up(period), first + 1, "{:?} with a period of {} should warm up as documented", kind, period);
+            }
+        }
+    }
+
+    #[test]
+    fn every_kind_streams_like_its_series() {
+        for kind in KINDS {
+            let series = kind.create(4).compute_series(&REFERENCE_PRICES).unwrap();
+
+            let mut stream = kind.create(4);
+            for (&value, expected) in REFERENCE_PRICES.iter().zip(series) {
+                assert_eq!(stream.update(value), expected, "{:?} stream should match its series", kind);
+            }
+        }
+    }
+
+    #[test]
+    fn every_kind_reports_its_warm_up() {
+        for kind in KINDS {
+            let series = kind.create(4).compute_series(&REFERENCE_PRICES).unwrap();
+            let warm_up = series.iter().position(Option::is_some).unwrap() + 1;
+
+            match kind.create(4).compute(&REFERENCE_PRICES[..warm_up - 1].to_vec()) {
+                Err(QmachinaError::InsufficientData { required, got }) => {
+                    assert_eq!(required, warm_up, "{:?} should report the values it needs", kind);
+                    assert_eq!(got, warm_up - 1);
+                }
+                other => panic!("{:?} should report insufficient data, got {:?}", kind, other),
+            }
+        }
+    }
+
+    /// An indicator relying on the default `compute_series`, returning the last of at least two values.
+    struct LastOfTwo;
+
+    impl Indicator<f64, f64> for LastOfTwo {
+        fn compute(&self, data: &Vec<f64>) -> Result<f64> {
+            crate::error::ensure_sufficient(2, data.len())?;
+            crate::error::ensure_finite(data)?;
+            Ok(data[data.len() - 1])
+        }
+    }
+
+    #[test]
+    fn default_compute_series_maps_only_insufficient_data_to_none() {
+        assert_eq!(LastOfTwo.compute_series(&[1.0, 2.0, 3.0]).unwrap(), vec![None, Some(2.0), Some(3.0)]);
+        assert!(matches!(LastOfTwo.compute_series(&[1.0, 2.0, f64::NAN]), Err(QmachinaError::InvalidValue { index: 2 })));
+    }
+}
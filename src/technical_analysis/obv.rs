@@ -0,0 +1,156 @@
+//! This module contains definitions and implementations for On-Balance Volume (OBV).
+//!
+//! The OBV, developed by Joseph Granville, is a running total of volume that adds the volume of
+//! up periods and subtracts the volume of down periods, so that volume flows can be compared
+//! against price moves.
+use crate::error::Result;
+use crate::numeric::Float;
+use super::{last_value, Indicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the On-Balance Volume (OBV) indicator.
+///
+/// The OBV starts at 0 on the first bar. Each following bar adds its volume when it closes
+/// above the previous close, subtracts it when it closes below, and leaves the total unchanged
+/// otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::obv::OnBalanceVolume;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 10.5, 9.5, 10.0, 100.0),
+///     Bar::new(1, 10.0, 11.5, 10.0, 11.0, 250.0),
+///     Bar::new(2, 11.0, 11.0, 10.0, 10.5, 80.0),
+/// ];
+/// assert_eq!(OnBalanceVolume::new().compute(&bars).unwrap(), 170.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OnBalanceVolume<F = f64> {
+    previous_close: Option<F>,
+    value: F
+}
+
+impl<F: Float> OnBalanceVolume<F> {
+    /// Constructs a new `OnBalanceVolume`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for OnBalanceVolume<F> {
+    /// Computes the OBV at the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the OBV, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is empty or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        last_value(self.compute_series(data)?, 1)
+    }
+
+    /// Computes the OBV for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new();
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for OnBalanceVolume<F> {
+    /// Feeds a new bar into the running volume total.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current OBV. Invalid bars are rejected with `None` and leave
+    /// the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        if let Some(previous_close) = self.previous_close {
+            if bar.close > previous_close {
+                self.value += bar.volume;
+            } else if bar.close < previous_close {
+                self.value -= bar.volume;
+            }
+        }
+        self.previous_close = Some(bar.close);
+
+        Some(self.value)
+    }
+
+    /// Clears the running total and the previous close.
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.value = F::zero();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        assert_eq!(OnBalanceVolume::new().compute(&SAMPLE_BARS.to_vec()).unwrap(), 2800.0);
+    }
+
+    #[test]
+    fn unchanged_close_keeps_total() {
+        let bars = vec![
+            Bar::new(0, 10.0, 10.5, 9.5, 10.0, 100.0),
+            Bar::new(1, 10.0, 10.5, 9.5, 10.0, 500.0),
+        ];
+        assert_eq!(OnBalanceVolume::new().compute_series(&bars).unwrap(), vec![Some(0.0), Some(0.0)]);
+    }
+
+    #[test]
+    fn empty_or_invalid_data_fails() {
+        assert!(OnBalanceVolume::new().compute(&Vec::<Bar>::new()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[2].volume = -1.0;
+        assert!(OnBalanceVolume::new().compute(&bars).is_err());
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut obv: OnBalanceVolume = OnBalanceVolume::new();
+        for bar in SAMPLE_BARS {
+            obv.update(bar);
+        }
+        obv.reset();
+
+        assert_eq!(obv.update(SAMPLE_BARS[1]), Some(0.0), "Reset should restart the running total");
+    }
+}
@@ -3,11 +3,36 @@
 //! The RSI is a momentum oscillator used in technical analysis to measure the velocity and
 //! magnitude of directional price movements. It provides signals about overbought or oversold
 //! conditions in an asset.
-use anyhow::{Result, anyhow};
-use super::{Indicator, PeriodIndicator};
+//!
+//! The average gains and losses are smoothed over the configured period either with Wilder's
+//! smoothing (the original definition, also used by TA-Lib) or with Cutler's simple average.
+use std::collections::VecDeque;
+use crate::error::{ensure_finite, ensure_sufficient, QmachinaError, Result};
+use crate::numeric::{constant, count, Float};
+use super::{Indicator, PeriodIndicator, StreamingIndicator};
+
+/// The smoothing method used to average gains and losses in the RSI calculation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RsiSmoothing {
+    /// Wilder's smoothing. The first averages are the simple mean of the first `period` changes,
+    /// after which each average is updated as `(previous * (period - 1) + current) / period`.
+    #[default]
+    Wilder,
+    /// Cutler's variant. The averages are the simple mean of the last `period` changes, which
+    /// makes the RSI independent of the starting point of the data.
+    Cutler
+}
 
-pub struct RelativeStrengthIndex {
-    period: usize
+pub struct RelativeStrengthIndex<F = f64> {
+    period: usize,
+    smoothing: RsiSmoothing,
+    previous: Option<F>,
+    changes: VecDeque<F>,
+    observed: usize,
+    gains: F,
+    losses: F,
+    average_gain: F,
+    average_loss: F
 }
 
 /// Represents a Relative Strength Index (RSI) indicator.
@@ -20,24 +45,62 @@ pub struct RelativeStrengthIndex {
 /// Creating an RSI with a period of 14 and standard overbought/oversold thresholds:
 ///
 /// ```
-/// use qmachina::technical_analysis::rsi::RelativeStrengthIndex;
+/// use qmachina::technical_analysis::rsi::{RelativeStrengthIndex, RsiSmoothing};
 ///
-/// let rsi = RelativeStrengthIndex::new(14);
+/// let rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(14);
+/// let cutler_rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(14).with_smoothing(RsiSmoothing::Cutler);
 /// ```
-impl RelativeStrengthIndex {
-    /// Constructs a new `RelativeStrengthIndex` with the given period and thresholds.
+impl<F: Float> RelativeStrengthIndex<F> {
+    /// Constructs a new `RelativeStrengthIndex` with the given period, using Wilder's smoothing.
     ///
     /// # Parameters
     ///
     /// * `period` - The look-back period for calculating the RSI.
     pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
         Self {
-            period: if period == 0 { 1 } else { period }
+            period,
+            smoothing: RsiSmoothing::default(),
+            previous: None,
+            changes: VecDeque::with_capacity(period + 1),
+            observed: 0,
+            gains: F::zero(),
+            losses: F::zero(),
+            average_gain: F::zero(),
+            average_loss: F::zero()
+        }
+    }
+
+    /// Returns the `RelativeStrengthIndex` using the given smoothing method.
+    ///
+    /// # Parameters
+    ///
+    /// * `smoothing` - The method used to average gains and losses.
+    pub fn with_smoothing(mut self, smoothing: RsiSmoothing) -> Self {
+        self.smoothing = smoothing;
+        self.reset();
+        self
+    }
+
+    /// Returns the smoothing method used to average gains and losses.
+    pub fn smoothing(&self) -> RsiSmoothing {
+        self.smoothing
+    }
+
+    /// Converts average gains and losses into the RSI value.
+    ///
+    /// A flat market, with neither gains nor losses, is reported as the neutral value of 50.
+    fn relative_strength_index(average_gain: F, average_loss: F) -> F {
+        if average_gain == F::zero() && average_loss == F::zero() {
+            return constant(50.0);
         }
+
+        constant::<F>(100.0) * average_gain / (average_gain + average_loss)
     }
 }
 
-impl PeriodIndicator for RelativeStrengthIndex {
+impl<F: Float> PeriodIndicator for RelativeStrengthIndex<F> {
     /// Returns the current period used in the RSI calculation.
     ///
     /// # Returns
@@ -52,55 +115,136 @@ impl PeriodIndicator for RelativeStrengthIndex {
     /// # Parameters
     ///
     /// * `period` - The new period to set, represented as a `usize`.
+    ///
+    /// Changing the period also resets any streaming state.
     fn set_period(&mut self, period: usize) {
         self.period = if period == 0 { 1 } else { period };
+        self.reset();
     }
 }
 
-impl Indicator<f64, f64> for RelativeStrengthIndex {
+impl<F: Float> Indicator<F, F> for RelativeStrengthIndex<F> {
     /// Computes the RSI value using an `Arc<[f64]>` as input data.
     ///
     /// # Parameters
     ///
-    /// * `data` - An `Vec<f64>` containing the price changes for which the RSI is calculated.
+    /// * `data` - A `Vec<F>` containing the prices for which the RSI is calculated.
     ///
     /// # Returns
     ///
-    /// Returns `Ok(f64)` containing the calculated RSI value, or an error if the calculation fails.
+    /// Returns `Ok(F)` containing the calculated RSI value, or an error if the calculation fails.
     ///
     /// # Errors
     ///
     /// Returns an error if the length of the data is insufficient for RSI calculation or if the data contains
     /// invalid values (NaN or infinite).
-    fn compute(&self, data: &Vec<f64>) -> Result<f64> {
-        if data.len() < self.period + 1 {
-            return Err(anyhow!("Insufficient data for RSI calculation."));
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        ensure_sufficient(self.period + 1, data.len())?;
+
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period).with_smoothing(self.smoothing);
+        data.iter()
+            .fold(None, |_, &value| stream.update(value))
+            .ok_or(QmachinaError::InsufficientData { required: self.period + 1, got: data.len() })
+    }
+
+    /// Computes the RSI for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the prices for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period).with_smoothing(self.smoothing);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for RelativeStrengthIndex<F> {
+    /// Feeds a new price into the RSI, updating the smoothed gains and losses incrementally.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest price.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the RSI once at least `period + 1` prices have been observed,
+    /// or `None` while warming up. Invalid values (NaN or infinite) are rejected with `None`
+    /// and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
         }
 
-        let mut gains = 0.0;
-        let mut losses = 0.0;
+        let previous = self.previous.replace(value)?;
+        let change = value - previous;
+        let gain = change.max(F::zero());
+        let loss = (-change).max(F::zero());
+        self.observed += 1;
+
+        match self.smoothing {
+            RsiSmoothing::Wilder => {
+                let period = count::<F>(self.period);
 
-        for window in data.windows(2) {
-            let change = window[1] - window[0];
-            if change > 0.0 {
-                gains += change;
-            } else {
-                losses -= change;
+                if self.observed < self.period {
+                    self.gains += gain;
+                    self.losses += loss;
+                    return None;
+                }
+
+                if self.observed == self.period {
+                    self.average_gain = (self.gains + gain) / period;
+                    self.average_loss = (self.losses + loss) / period;
+                } else {
+                    self.average_gain = (self.average_gain * (period - F::one()) + gain) / period;
+                    self.average_loss = (self.average_loss * (period - F::one()) + loss) / period;
+                }
             }
-        }
+            RsiSmoothing::Cutler => {
+                self.changes.push_back(change);
+                self.gains += gain;
+                self.losses += loss;
 
-        if gains == 0.0 {
-            return Ok(0.0);
-        }
+                if self.changes.len() > self.period {
+                    if let Some(oldest) = self.changes.pop_front() {
+                        self.gains -= oldest.max(F::zero());
+                        self.losses -= (-oldest).max(F::zero());
+                    }
+                }
+
+                if self.changes.len() < self.period {
+                    return None;
+                }
 
-        if losses == 0.0 {
-            return Ok(100.0);
+                // Running sums of non-negative values can only drift below zero through rounding.
+                self.average_gain = self.gains.max(F::zero()) / count::<F>(self.period);
+                self.average_loss = self.losses.max(F::zero()) / count::<F>(self.period);
+            }
         }
 
-        let relative_strength = gains / losses;
-        let rsi = 100.0 - (100.0 / (1.0 + relative_strength));
+        Some(Self::relative_strength_index(self.average_gain, self.average_loss))
+    }
 
-        Ok(rsi)
+    /// Clears the smoothed gains and losses and the last observed price.
+    fn reset(&mut self) {
+        self.previous = None;
+        self.changes.clear();
+        self.observed = 0;
+        self.gains = F::zero();
+        self.losses = F::zero();
+        self.average_gain = F::zero();
+        self.average_loss = F::zero();
     }
 }
 
@@ -110,13 +254,13 @@ mod tests {
 
     #[test]
     fn creation_with_valid_period() {
-        let rsi = RelativeStrengthIndex::new(14);
+        let rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(14);
         assert_eq!(rsi.period(), 14, "RSI period should be 14");
     }
 
     #[test]
     fn creation_with_zero_period() {
-        let rsi = RelativeStrengthIndex::new(0);
+        let rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(0);
         assert_eq!(rsi.period(), 1, "RSI period should default to 1 for zero input");
     }
 
@@ -145,4 +289,149 @@ mod tests {
         let result = rsi.compute(&data);
         assert!(result.is_err(), "Should return an error due to invalid (NaN) data");
     }
+
+    #[test]
+    fn streaming_matches_batch() {
+        let data = vec![44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03];
+        let batch: RelativeStrengthIndex = RelativeStrengthIndex::new(5);
+        let mut stream: RelativeStrengthIndex = RelativeStrengthIndex::new(5);
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => {
+                    let streamed = streamed.expect("Stream should be warm once batch succeeds");
+                    assert!((streamed - expected).abs() < 1e-9, "Streamed RSI should match batch RSI");
+                }
+                Err(_) => assert!(streamed.is_none(), "Stream should still be warming up"),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut rsi = RelativeStrengthIndex::new(2);
+        for value in [1.0, 2.0, 1.5] {
+            rsi.update(value);
+        }
+        rsi.reset();
+
+        assert_eq!(rsi.update(1.0), None, "Reset should clear the previous price");
+        assert_eq!(rsi.update(2.0), None);
+        assert_eq!(rsi.update(3.0), Some(100.0));
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let indicator: RelativeStrengthIndex = RelativeStrengthIndex::new(3);
+        let data = vec![44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42];
+        let series = indicator.compute_series(&data).unwrap();
+
+        assert_eq!(series.len(), data.len(), "Series should be aligned with the input");
+        assert!(series[..3].iter().all(Option::is_none), "Warm-up values should be None");
+
+        for (end, value) in (1..=data.len()).zip(series) {
+            match indicator.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert!((value.unwrap() - expected).abs() < 1e-9, "Series RSI should match compute"),
+                Err(_) => assert!(value.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn compute_series_with_invalid_data() {
+        let indicator = RelativeStrengthIndex::new(3);
+        let data = vec![1.0, 2.0, f64::NAN, 4.0, 5.0];
+        assert!(indicator.compute_series(&data).is_err(), "Should return an error due to invalid (NaN) data");
+    }
+
+    const REFERENCE_PRICES: [f64; 33] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35,
+        44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13
+    ];
+
+    #[test]
+    fn wilder_matches_reference_values() {
+        // Reference values as produced by TA-Lib's RSI with a period of 14.
+        let expected = [
+            70.4641, 66.2496, 66.4809, 69.3469, 66.2947, 57.9150, 62.8807, 63.2088, 56.0116, 62.3399,
+            54.6710, 50.3868, 40.0194, 41.4926, 41.9024, 45.4995, 37.3228, 33.0905, 37.7888
+        ];
+
+        let rsi = RelativeStrengthIndex::new(14);
+        let series = rsi.compute_series(&REFERENCE_PRICES).unwrap();
+
+        assert!(series[..14].iter().all(Option::is_none), "Warm-up values should be None");
+        for (value, expected) in series[14..].iter().zip(expected) {
+            assert!((value.unwrap() - expected).abs() < 1e-4, "Wilder RSI should match the reference value");
+        }
+
+        let last = rsi.compute(&REFERENCE_PRICES.to_vec()).unwrap();
+        assert!((last - 37.7888).abs() < 1e-4, "Compute should return the last reference value");
+    }
+
+    #[test]
+    fn cutler_matches_reference_values() {
+        let expected = [
+            70.4641, 70.0210, 69.8312, 80.5677, 73.3333, 59.8063, 62.5282, 60.0000, 48.4778, 53.8784,
+            48.9524, 43.8628, 37.7329, 32.2635, 32.7181, 38.1426, 31.7483, 25.0996, 30.2177
+        ];
+
+        let rsi = RelativeStrengthIndex::new(14).with_smoothing(RsiSmoothing::Cutler);
+        let series = rsi.compute_series(&REFERENCE_PRICES).unwrap();
+
+        assert!(series[..14].iter().all(Option::is_none), "Warm-up values should be None");
+        for (value, expected) in series[14..].iter().zip(expected) {
+            assert!((value.unwrap() - expected).abs() < 1e-4, "Cutler RSI should match the reference value");
+        }
+    }
+
+    #[test]
+    fn cutler_only_uses_the_last_period() {
+        let rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(3).with_smoothing(RsiSmoothing::Cutler);
+        let long = vec![50.0, 10.0, 1.0, 2.0, 1.5, 2.5];
+        let short = vec![1.0, 2.0, 1.5, 2.5];
+
+        let expected = rsi.compute(&short).unwrap();
+        assert!((rsi.compute(&long).unwrap() - expected).abs() < 1e-9, "Older data should be ignored");
+        assert!((expected - 100.0 * 2.0 / 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_prices_are_neutral() {
+        let rsi = RelativeStrengthIndex::new(3);
+        let data = vec![1.0, 1.0, 1.0, 1.0];
+        assert_eq!(rsi.compute(&data).unwrap(), 50.0, "Flat prices should yield a neutral RSI");
+    }
+
+    #[test]
+    fn only_losses_and_only_gains() {
+        let rsi = RelativeStrengthIndex::new(3);
+        assert_eq!(rsi.compute(&vec![4.0, 3.0, 2.0, 1.0]).unwrap(), 0.0);
+        assert_eq!(rsi.compute(&vec![1.0, 2.0, 3.0, 4.0]).unwrap(), 100.0);
+    }
+
+    #[test]
+    fn smoothing_defaults_to_wilder() {
+        let rsi: RelativeStrengthIndex = RelativeStrengthIndex::new(14);
+        assert_eq!(rsi.smoothing(), RsiSmoothing::Wilder);
+
+        let rsi = rsi.with_smoothing(RsiSmoothing::Cutler);
+        assert_eq!(rsi.smoothing(), RsiSmoothing::Cutler);
+    }
+
+    #[test]
+    fn cutler_streaming_matches_batch() {
+        let batch: RelativeStrengthIndex = RelativeStrengthIndex::new(5).with_smoothing(RsiSmoothing::Cutler);
+        let mut stream: RelativeStrengthIndex = RelativeStrengthIndex::new(5).with_smoothing(RsiSmoothing::Cutler);
+
+        for end in 1..=REFERENCE_PRICES.len() {
+            let streamed = stream.update(REFERENCE_PRICES[end - 1]);
+            match batch.compute(&REFERENCE_PRICES[..end].to_vec()) {
+                Ok(expected) => assert!((streamed.unwrap() - expected).abs() < 1e-9),
+                Err(_) => assert!(streamed.is_none()),
+            }
+        }
+    }
 }
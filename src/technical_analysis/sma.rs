@@ -2,13 +2,16 @@
 //!
 //! The SMA is a commonly used indicator in technical analysis that averages a certain number
 //! of past data points to smooth out price data.
-use std::ops::Div;
-use anyhow::{Result, anyhow};
+use std::collections::VecDeque;
 
-use super::{Indicator, PeriodIndicator};
+use crate::error::{ensure_finite, ensure_sufficient, QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::{Indicator, PeriodIndicator, StreamingIndicator};
 
-pub struct SimpleMovingAverage {
-    period: usize
+pub struct SimpleMovingAverage<F = f64> {
+    period: usize,
+    window: VecDeque<F>,
+    sum: F
 }
 
 /// Represents a Simple Moving Average (SMA) indicator.
@@ -23,22 +26,36 @@ pub struct SimpleMovingAverage {
 /// ```
 /// use qmachina::technical_analysis::sma::SimpleMovingAverage;
 ///
-/// let sma = SimpleMovingAverage::new(5);
+/// let sma: SimpleMovingAverage = SimpleMovingAverage::new(5);
 /// ```
-impl SimpleMovingAverage {
+///
+/// The value type defaults to `f64`. Single precision halves the memory of the window:
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::sma::SimpleMovingAverage;
+///
+/// let sma = SimpleMovingAverage::<f32>::new(3);
+/// assert_eq!(sma.compute(&vec![1.0, 2.0, 3.0, 4.0, 5.0]).unwrap(), 4.0_f32);
+/// ```
+impl<F: Float> SimpleMovingAverage<F> {
     /// Constructs a new `SimpleMovingAverage` with the given period.
     ///
     /// # Parameters
     ///
     /// * `period` - The number of data points to include in the moving average calculation.
     pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
         Self {
-            period: if period == 0 { 1 } else { period }
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: F::zero()
         }
     }
 }
 
-impl PeriodIndicator for SimpleMovingAverage {
+impl<F: Float> PeriodIndicator for SimpleMovingAverage<F> {
     /// Returns the current period used in the SMA calculation.
     ///
     /// # Returns
@@ -52,42 +69,102 @@ impl PeriodIndicator for SimpleMovingAverage {
     ///
     /// # Parameters
     ///
-    /// * `period` - The new period to set, represented as a `usize`.
-    ///
-    /// # Panics
+    /// * `period` - The new period to set, represented as a `usize`. A period of 0 is not valid
+    ///   for a moving average and is replaced by 1.
     ///
-    /// Panics if the `period` is set to 0. A period of 0 is not valid for a moving average.
+    /// Changing the period also resets any streaming state.
     fn set_period(&mut self, period: usize) {
         self.period = if period == 0 { 1 } else { period };
+        self.reset();
     }
 }
 
-impl Indicator<f64, f64> for SimpleMovingAverage {
+impl<F: Float> Indicator<F, F> for SimpleMovingAverage<F> {
     /// Computes the SMA value using an `Arc<[f64]>` as input data.
     ///
     /// # Parameters
     ///
-    /// * `data` - An `Vec<f64>` containing the data points for which the SMA is calculated.
+    /// * `data` - A `Vec<F>` containing the data points for which the SMA is calculated.
     ///
     /// # Returns
     ///
-    /// Returns `Ok(f64)` containing the calculated SMA value, or an error if the calculation fails.
+    /// Returns `Ok(F)` containing the calculated SMA value, or an error if the calculation fails.
     ///
     /// # Errors
     ///
     /// Returns an error if the length of the data is less than the SMA period or if the data contains
     /// invalid values (NaN or infinite).
-    fn compute(&self, data: &Vec<f64>) -> Result<f64> {
-        if data.len() < self.period {
-            return Err(anyhow!("Period is larger than the sampled data."));
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
+
+        let start = data.len() - self.period;
+        if let Some(index) = data[start..].iter().position(|value| !value.is_finite()) {
+            return Err(QmachinaError::InvalidValue { index: start + index });
+        }
+
+        let sum: F = data[start..].iter().sum();
+
+        Ok(sum.div(count::<F>(self.period)))
+    }
+
+    /// Computes the SMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for SimpleMovingAverage<F> {
+    /// Feeds a new data point into the SMA, keeping a running sum over the last `period` values.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the SMA of the last `period` values once enough data has been
+    /// observed, or `None` while warming up. Invalid values (NaN or infinite) are rejected with
+    /// `None` and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        self.window.push_back(value);
+        self.sum += value;
+
+        if self.window.len() > self.period {
+            if let Some(oldest) = self.window.pop_front() {
+                self.sum -= oldest;
+            }
         }
 
-        let sum: f64 = data.iter().skip(data.len() - self.period).take(self.period).sum();
-        if sum.is_nan() || sum.is_infinite() {
-            return Err(anyhow!("Invalid data encountered during calculations."));
+        if self.window.len() < self.period {
+            return None;
         }
 
-        Ok(sum.div(self.period as f64))
+        Some(self.sum.div(count::<F>(self.period)))
+    }
+
+    /// Clears the streaming window and running sum.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = F::zero();
     }
 }
 
@@ -97,13 +174,13 @@ mod tests {
 
     #[test]
     fn sma_creation_with_valid_period() {
-        let sma = SimpleMovingAverage::new(5);
+        let sma: SimpleMovingAverage = SimpleMovingAverage::new(5);
         assert_eq!(sma.period(), 5, "SMA period should be 5");
     }
 
     #[test]
     fn sma_creation_with_zero_period() {
-        let sma = SimpleMovingAverage::new(0);
+        let sma: SimpleMovingAverage = SimpleMovingAverage::new(0);
         assert_eq!(sma.period(), 1, "SMA period should default to 1 for zero input");
     }
 
@@ -122,7 +199,7 @@ mod tests {
         let sma = SimpleMovingAverage::new(5);
         let data = vec![1.0, 2.0];
         let result = sma.compute(&data);
-        assert!(result.is_err(), "Should return an error due to insufficient data");
+        assert!(matches!(result, Err(QmachinaError::InsufficientData { required: 5, got: 2 })), "Should return an error due to insufficient data");
     }
 
     #[test]
@@ -130,15 +207,76 @@ mod tests {
         let sma = SimpleMovingAverage::new(3);
         let data = vec![1.0, f64::NAN, 3.0];
         let result = sma.compute(&data);
-        assert!(result.is_err(), "Should return an error due to invalid (NaN) data");
+        assert!(matches!(result, Err(QmachinaError::InvalidValue { index: 1 })), "Should return an error due to invalid (NaN) data");
     }
 
     #[test]
     fn period_get_set() {
-        let mut sma = SimpleMovingAverage::new(3);
+        let mut sma: SimpleMovingAverage = SimpleMovingAverage::new(3);
         assert_eq!(sma.period(), 3, "Initial period should be 3");
 
         sma.set_period(10);
         assert_eq!(sma.period(), 10, "Period after set_period should be 10");
     }
+
+    #[test]
+    fn streaming_matches_batch() {
+        let data = vec![1.0, 2.5, 3.0, 2.0, 4.5, 5.0, 4.0, 6.5, 7.0, 6.0];
+        let batch: SimpleMovingAverage = SimpleMovingAverage::new(4);
+        let mut stream: SimpleMovingAverage = SimpleMovingAverage::new(4);
+
+        for end in 1..=data.len() {
+            let streamed = stream.update(data[end - 1]);
+            match batch.compute(&data[..end].to_vec()) {
+                Ok(expected) => {
+                    let streamed = streamed.expect("Stream should be warm once batch succeeds");
+                    assert!((streamed - expected).abs() < 1e-9, "Streamed SMA should match batch SMA");
+                }
+                Err(_) => assert!(streamed.is_none(), "Stream should still be warming up"),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_rejects_invalid_data() {
+        let mut sma = SimpleMovingAverage::new(2);
+        assert_eq!(sma.update(1.0), None);
+        assert_eq!(sma.update(f64::NAN), None, "NaN should be rejected");
+        assert_eq!(sma.update(3.0), Some(2.0), "NaN should not enter the window");
+    }
+
+    #[test]
+    fn streaming_reset() {
+        let mut sma = SimpleMovingAverage::new(2);
+        sma.update(1.0);
+        sma.update(2.0);
+        sma.reset();
+
+        assert_eq!(sma.update(10.0), None, "Reset should clear the window");
+        assert_eq!(sma.update(20.0), Some(15.0));
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let indicator: SimpleMovingAverage = SimpleMovingAverage::new(3);
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let series = indicator.compute_series(&data).unwrap();
+
+        assert_eq!(series.len(), data.len(), "Series should be aligned with the input");
+        assert!(series[..2].iter().all(Option::is_none), "Warm-up values should be None");
+
+        for (end, value) in (1..=data.len()).zip(series) {
+            match indicator.compute(&data[..end].to_vec()) {
+                Ok(expected) => assert!((value.unwrap() - expected).abs() < 1e-9, "Series SMA should match compute"),
+                Err(_) => assert!(value.is_none()),
+            }
+        }
+    }
+
+    #[test]
+    fn compute_series_with_invalid_data() {
+        let indicator: SimpleMovingAverage = SimpleMovingAverage::new(3);
+        let data = vec![1.0, 2.0, f64::NAN, 4.0, 5.0];
+        assert!(indicator.compute_series(&data).is_err(), "Should return an error due to invalid (NaN) data");
+    }
 }
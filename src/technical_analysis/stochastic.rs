@@ -0,0 +1,229 @@
+//! This module contains definitions and implementations for the Stochastic Oscillator.
+//!
+//! The Stochastic Oscillator, developed by George Lane, is a momentum indicator comparing the
+//! closing price to the high-low range over a given period. It is made of the %K line and of
+//! the %D line, a simple moving average of %K.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, highest_high_lowest_low, Bar};
+
+/// Holds the values produced by the Stochastic Oscillator at a single point in time.
+///
+/// # Fields
+///
+/// * `k`: The %K line, the position of the close within the high-low range, from 0 to 100.
+/// * `d`: The %D line, the simple moving average of the last `d_period` %K values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticOutput<F = f64> {
+    pub k: F,
+    pub d: F
+}
+
+/// Represents the (fast) Stochastic Oscillator.
+///
+/// %K is `100 * (close - lowest low) / (highest high - lowest low)` over the last `k_period`
+/// bars, and is 50 when the high-low range is empty. %D is the SMA of %K over `d_period` bars.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::stochastic::StochasticOscillator;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 11.0, 9.0, 10.0, 100.0),
+///     Bar::new(1, 10.0, 12.0, 10.0, 12.0, 100.0),
+///     Bar::new(2, 12.0, 12.0, 10.0, 10.0, 100.0),
+/// ];
+/// let output = StochasticOscillator::new(2, 2).compute(&bars).unwrap();
+/// assert_eq!(output.k, 0.0);
+/// assert_eq!(output.d, 50.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StochasticOscillator<F = f64> {
+    k_period: usize,
+    d_period: usize,
+    window: VecDeque<Bar<F>>,
+    k_values: VecDeque<F>
+}
+
+impl<F: Float> StochasticOscillator<F> {
+    /// Constructs a new `StochasticOscillator`.
+    ///
+    /// # Parameters
+    ///
+    /// * `k_period` - The number of bars used to find the highest high and lowest low.
+    /// * `d_period` - The number of %K values averaged into %D.
+    pub fn new(k_period: usize, d_period: usize) -> Self {
+        let k_period = if k_period == 0 { 1 } else { k_period };
+        let d_period = if d_period == 0 { 1 } else { d_period };
+
+        Self {
+            k_period,
+            d_period,
+            window: VecDeque::with_capacity(k_period + 1),
+            k_values: VecDeque::with_capacity(d_period + 1)
+        }
+    }
+
+    /// Returns the number of %K values averaged into %D.
+    pub fn d_period(&self) -> usize {
+        self.d_period
+    }
+}
+
+impl<F: Float> PeriodIndicator for StochasticOscillator<F> {
+    /// Returns the %K period.
+    fn period(&self) -> usize {
+        self.k_period
+    }
+
+    /// Sets a new %K period, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new %K period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.k_period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, StochasticOutput<F>> for StochasticOscillator<F> {
+    /// Computes %K and %D for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(StochasticOutput)`, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `k_period + d_period - 1` bars or contains
+    /// invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<StochasticOutput<F>> {
+        ensure_sufficient(self.k_period + self.d_period - 1, data.len())?;
+
+        last_value(self.compute_series(data)?, self.k_period + self.d_period - 1)
+    }
+
+    /// Computes %K and %D for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<StochasticOutput>>)` aligned with `data`, with `None` during the
+    /// warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<StochasticOutput<F>>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.k_period, self.d_period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, StochasticOutput<F>> for StochasticOscillator<F> {
+    /// Feeds a new bar into the oscillator.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(StochasticOutput)` once `d_period` %K values are available, or `None` while
+    /// warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<StochasticOutput<F>> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        self.window.push_back(bar);
+        if self.window.len() > self.k_period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.k_period {
+            return None;
+        }
+
+        let (highest, lowest) = highest_high_lowest_low(&self.window);
+        let k = if highest > lowest { constant::<F>(100.0) * (bar.close - lowest) / (highest - lowest) } else { constant(50.0) };
+
+        self.k_values.push_back(k);
+        if self.k_values.len() > self.d_period {
+            self.k_values.pop_front();
+        }
+        if self.k_values.len() < self.d_period {
+            return None;
+        }
+
+        let d = self.k_values.iter().sum::<F>() / count::<F>(self.d_period);
+        Some(StochasticOutput { k, d })
+    }
+
+    /// Clears the bar window and the %K history.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.k_values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_matches_reference() {
+        let output = StochasticOscillator::new(5, 3).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((output.k - 52.94117647058828).abs() < 1e-9);
+        assert!((output.d - 61.487638533674414).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_range_is_midpoint() {
+        let bars = vec![Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0); 3];
+        let output = StochasticOscillator::new(3, 1).compute(&bars).unwrap();
+        assert_eq!(output, StochasticOutput { k: 50.0, d: 50.0 });
+    }
+
+    #[test]
+    fn insufficient_data_fails() {
+        let stochastic = StochasticOscillator::new(5, 3);
+        assert!(stochastic.compute(&SAMPLE_BARS[..6].to_vec()).is_err());
+        assert!(stochastic.compute(&SAMPLE_BARS[..7].to_vec()).is_ok());
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let stochastic = StochasticOscillator::new(5, 3);
+        let series = stochastic.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert_eq!(series.len(), SAMPLE_BARS.len());
+        assert!(series[..6].iter().all(Option::is_none), "Warm-up values should be None");
+        for (end, value) in (7..=SAMPLE_BARS.len()).zip(&series[6..]) {
+            assert_eq!(value.unwrap(), stochastic.compute(&SAMPLE_BARS[..end].to_vec()).unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_bar_fails() {
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[10].low = 50.0;
+        assert!(StochasticOscillator::new(5, 3).compute(&bars).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! This module contains definitions and implementations for the T3 Moving Average.
+//!
+//! The T3, developed by Tim Tillson, chains six EMAs and combines the last four with weights
+//! derived from a volume factor, producing an average that is both smooth and responsive.
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::ema::RecursiveEma;
+
+/// Represents the T3 Moving Average indicator.
+///
+/// With a volume factor `v`, the T3 is `c1 * e6 + c2 * e5 + c3 * e4 + c4 * e3`, where `e1` to
+/// `e6` are successive EMAs of the data and
+/// `c1 = -v^3`, `c2 = 3v^2 + 3v^3`, `c3 = -6v^2 - 3v - 3v^3` and `c4 = 1 + 3v + v^3 + 3v^2`.
+/// The EMAs run over the whole history of the data and are seeded with an SMA, as in TA-Lib,
+/// so the first value is available after `6 * period - 5` data points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::t3::T3MovingAverage;
+///
+/// let t3 = T3MovingAverage::new(2).with_volume_factor(0.5);
+/// let data: Vec<f64> = (0..10).map(|value| value as f64).collect();
+/// assert!(t3.compute(&data).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct T3MovingAverage<F = f64> {
+    period: usize,
+    volume_factor: F,
+    emas: [RecursiveEma<F>; 6]
+}
+
+impl<F: Float> T3MovingAverage<F> {
+    /// Constructs a new `T3MovingAverage` with the given period and a volume factor of 0.7.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the six EMAs.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            volume_factor: constant(0.7),
+            emas: std::array::from_fn(|_| RecursiveEma::new(period))
+        }
+    }
+
+    /// Returns the `T3MovingAverage` using the given volume factor.
+    ///
+    /// # Parameters
+    ///
+    /// * `volume_factor` - The weight of the EMA corrections, usually between 0 and 1. A factor
+    ///   of 0 reduces the T3 to the third of its chained EMAs.
+    pub fn with_volume_factor(mut self, volume_factor: F) -> Self {
+        self.volume_factor = volume_factor;
+        self
+    }
+
+    /// Returns the volume factor.
+    pub fn volume_factor(&self) -> F {
+        self.volume_factor
+    }
+}
+
+impl<F: Float> PeriodIndicator for T3MovingAverage<F> {
+    /// Returns the current period used in the T3 calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the T3 calculation, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period).with_volume_factor(self.volume_factor);
+    }
+}
+
+impl<F: Float> Indicator<F, F> for T3MovingAverage<F> {
+    /// Computes the T3 at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the T3 is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated T3 value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `6 * period - 5` values or contains invalid
+    /// values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, 6 * self.period - 5)
+    }
+
+    /// Computes the T3 for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period).with_volume_factor(self.volume_factor);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for T3MovingAverage<F> {
+    /// Feeds a new data point into the chain of EMAs.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current T3 once the six EMAs are seeded, or `None` while
+    /// warming up. Invalid values (NaN or infinite) are rejected with `None` and leave the state
+    /// untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let mut emas = [F::zero(); 6];
+        let mut input = value;
+        for (ema, output) in self.emas.iter_mut().zip(emas.iter_mut()) {
+            input = ema.update(input)?;
+            *output = input;
+        }
+
+        let v = self.volume_factor;
+        let c1 = -v.powi(3);
+        let c2 = constant::<F>(3.0) * v.powi(2) + constant::<F>(3.0) * v.powi(3);
+        let c3 = -constant::<F>(6.0) * v.powi(2) - constant::<F>(3.0) * v - constant::<F>(3.0) * v.powi(3);
+        let c4 = F::one() + constant::<F>(3.0) * v + v.powi(3) + constant::<F>(3.0) * v.powi(2);
+
+        Some(c1 * emas[5] + c2 * emas[4] + c3 * emas[3] + c4 * emas[2])
+    }
+
+    /// Clears the state of the six EMAs.
+    fn reset(&mut self) {
+        self.emas.iter_mut().for_each(RecursiveEma::reset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = T3MovingAverage::new(3).compute_series(&DATA).unwrap();
+
+        assert!(series[..12].iter().all(Option::is_none), "T3 needs 6 * period - 5 values");
+        assert!((series[12].unwrap() - 45.9494748454).abs() < 1e-9);
+        assert!((series[13].unwrap() - 46.0205946231).abs() < 1e-9);
+        assert!((series[32].unwrap() - 43.12893469419427).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_volume_factor_is_third_ema() {
+        let t3 = T3MovingAverage::new(3).with_volume_factor(0.0);
+
+        let mut emas: [RecursiveEma; 3] = std::array::from_fn(|_| RecursiveEma::new(3));
+        let mut third = None;
+        for value in DATA {
+            third = emas[0].update(value)
+                .and_then(|value| emas[1].update(value))
+                .and_then(|value| emas[2].update(value));
+        }
+
+        let t3_value = t3.compute(&DATA.to_vec()).unwrap();
+        assert!((t3_value - third.unwrap()).abs() < 1e-9, "T3 with a volume factor of 0 should be the third EMA");
+    }
+
+    #[test]
+    fn set_period_keeps_volume_factor() {
+        let mut t3 = T3MovingAverage::new(3).with_volume_factor(0.4);
+        t3.set_period(5);
+
+        assert_eq!(t3.period(), 5);
+        assert_eq!(t3.volume_factor(), 0.4);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let t3 = T3MovingAverage::new(3);
+        assert!(t3.compute(&DATA[..12].to_vec()).is_err());
+        assert!(t3.compute(&DATA[..13].to_vec()).is_ok());
+        assert!(t3.compute(&vec![f64::NAN; 13]).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! This module contains definitions and implementations for Triple Exponential Moving Average (TEMA).
+//!
+//! The TEMA, introduced by Patrick Mulloy, further reduces the lag of the DEMA by chaining three
+//! EMAs: `TEMA = 3 * EMA - 3 * EMA(EMA) + EMA(EMA(EMA))`.
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::ema::RecursiveEma;
+
+/// Represents a Triple Exponential Moving Average (TEMA) indicator.
+///
+/// The three EMAs run over the whole history of the data and are seeded with an SMA, as in
+/// TA-Lib, so the first value is available after `3 * period - 2` data points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::tema::TripleExponentialMovingAverage;
+///
+/// let tema: TripleExponentialMovingAverage = TripleExponentialMovingAverage::new(2);
+/// let value = tema.compute(&vec![1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+/// assert!((value - 5.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TripleExponentialMovingAverage<F = f64> {
+    period: usize,
+    emas: [RecursiveEma<F>; 3]
+}
+
+impl<F: Float> TripleExponentialMovingAverage<F> {
+    /// Constructs a new `TripleExponentialMovingAverage` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the three EMAs.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            emas: std::array::from_fn(|_| RecursiveEma::new(period))
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for TripleExponentialMovingAverage<F> {
+    /// Returns the current period used in the TEMA calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the TEMA calculation, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period);
+    }
+}
+
+impl<F: Float> Indicator<F, F> for TripleExponentialMovingAverage<F> {
+    /// Computes the TEMA at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the TEMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated TEMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `3 * period - 2` values or contains invalid
+    /// values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, 3 * self.period - 2)
+    }
+
+    /// Computes the TEMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for TripleExponentialMovingAverage<F> {
+    /// Feeds a new data point into the TEMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current TEMA once the three EMAs are seeded, or `None` while
+    /// warming up. Invalid values (NaN or infinite) are rejected with `None` and leave the state
+    /// untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let [first, second, third] = &mut self.emas;
+        let ema = first.update(value)?;
+        let ema_of_ema = second.update(ema)?;
+        let ema_of_ema_of_ema = third.update(ema_of_ema)?;
+
+        Some(constant::<F>(3.0) * ema - constant::<F>(3.0) * ema_of_ema + ema_of_ema_of_ema)
+    }
+
+    /// Clears the state of the three EMAs.
+    fn reset(&mut self) {
+        self.emas.iter_mut().for_each(RecursiveEma::reset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = TripleExponentialMovingAverage::new(5).compute_series(&DATA).unwrap();
+
+        assert!(series[..12].iter().all(Option::is_none), "TEMA needs 3 * period - 2 values");
+        assert!((series[12].unwrap() - 45.8153542692).abs() < 1e-9);
+        assert!((series[13].unwrap() - 46.1287707795).abs() < 1e-9);
+        assert!((series[32].unwrap() - 42.87654560368569).abs() < 1e-9);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let tema = TripleExponentialMovingAverage::new(5);
+        assert!(tema.compute(&DATA[..12].to_vec()).is_err());
+        assert!(tema.compute(&DATA[..13].to_vec()).is_ok());
+
+        let mut data = DATA.to_vec();
+        data[20] = f64::INFINITY;
+        assert!(tema.compute(&data).is_err());
+    }
+
+    #[test]
+    fn linear_trend_has_no_lag() {
+        let data: Vec<f64> = (0..20).map(|value| value as f64).collect();
+        let tema = TripleExponentialMovingAverage::new(4).compute(&data).unwrap();
+        assert!((tema - 19.0).abs() < 1e-9, "TEMA should track a linear trend without lag");
+    }
+}
@@ -0,0 +1,160 @@
+//! This module contains definitions and implementations for the True Range.
+//!
+//! The True Range, introduced by J. Welles Wilder, measures the volatility of a single period
+//! including any gap from the previous close. It is the largest of the high-low range and the
+//! distances from the previous close to the current high and low.
+use crate::error::{QmachinaError, Result};
+use crate::numeric::Float;
+use super::{Indicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the True Range indicator.
+///
+/// The first bar has no previous close, so its true range is its high-low range.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::true_range::TrueRange;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 11.0, 9.5, 10.5, 100.0),
+///     Bar::new(1, 12.0, 12.5, 12.0, 12.2, 150.0),
+/// ];
+/// assert_eq!(TrueRange::new().compute(&bars).unwrap(), 2.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrueRange<F = f64> {
+    previous_close: Option<F>
+}
+
+impl<F: Float> TrueRange<F> {
+    /// Constructs a new `TrueRange`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for TrueRange<F> {
+    /// Computes the true range of the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the true range of the last bar, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is empty or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        let last = data.last().ok_or(QmachinaError::EmptyInput)?;
+        ensure_valid_bars(data)?;
+
+        let previous_close = data.len().checked_sub(2).map(|index| data[index].close);
+        Ok(last.true_range(previous_close))
+    }
+
+    /// Computes the true range of every bar in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new();
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for TrueRange<F> {
+    /// Feeds a new bar and returns its true range.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the true range of the bar. Invalid bars are rejected with `None`
+    /// and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let true_range = bar.true_range(self.previous_close);
+        self.previous_close = Some(bar.close);
+
+        Some(true_range)
+    }
+
+    /// Forgets the previous close.
+    fn reset(&mut self) {
+        self.previous_close = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn compute_last_true_range() {
+        let true_range = TrueRange::new().compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((true_range - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn single_bar_uses_high_low_range() {
+        let true_range = TrueRange::new().compute(&SAMPLE_BARS[..1].to_vec()).unwrap();
+        assert!((true_range - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_or_invalid_data_fails() {
+        assert!(TrueRange::new().compute(&Vec::<Bar>::new()).is_err());
+
+        let mut bars = SAMPLE_BARS[..3].to_vec();
+        bars[1].high = f64::NAN;
+        assert!(TrueRange::new().compute(&bars).is_err());
+        assert!(TrueRange::new().compute_series(&bars).is_err());
+    }
+
+    #[test]
+    fn compute_series_matches_compute() {
+        let true_range = TrueRange::new();
+        let series = true_range.compute_series(&SAMPLE_BARS).unwrap();
+
+        assert_eq!(series.len(), SAMPLE_BARS.len());
+        for (end, value) in (1..=SAMPLE_BARS.len()).zip(series) {
+            assert_eq!(value.unwrap(), true_range.compute(&SAMPLE_BARS[..end].to_vec()).unwrap());
+        }
+    }
+
+    #[test]
+    fn streaming_rejects_invalid_bar() {
+        let mut true_range: TrueRange = TrueRange::new();
+        true_range.update(Bar::new(0, 10.0, 11.0, 9.0, 10.0, 100.0));
+
+        assert!(true_range.update(Bar::new(1, 10.0, 9.0, 11.0, 10.0, 100.0)).is_none());
+        assert_eq!(true_range.update(Bar::new(2, 13.0, 13.0, 12.0, 12.5, 100.0)), Some(3.0), "Previous close should be kept");
+
+        true_range.reset();
+        assert_eq!(true_range.update(Bar::new(3, 13.0, 13.0, 12.0, 12.5, 100.0)), Some(1.0));
+    }
+}
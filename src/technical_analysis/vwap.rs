@@ -0,0 +1,255 @@
+//! This module contains definitions and implementations for the Volume Weighted Average Price (VWAP).
+//!
+//! The VWAP is the average typical price weighted by the traded volume. It is commonly anchored
+//! at the start of a session, or computed over a rolling window of bars.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, QmachinaError, Result};
+use crate::numeric::Float;
+use super::{Indicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, Bar};
+
+/// Represents the Volume Weighted Average Price (VWAP).
+///
+/// An anchored VWAP accumulates every bar it is given, so the data passed to it should start
+/// at the desired anchor (for instance the first bar of the session). A rolling VWAP only
+/// considers the last `period` bars. The VWAP is undefined while the accumulated volume is zero.
+///
+/// A rolling VWAP updates its sums in constant time as bars enter and leave the window, and
+/// recomputes them from the window each time it has been fully replaced, so that rounding errors
+/// do not build up over long streams. A window without traded volume has sums of exactly zero.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::vwap::VolumeWeightedAveragePrice;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0),
+///     Bar::new(1, 12.0, 12.0, 12.0, 12.0, 300.0),
+///     Bar::new(2, 11.0, 11.0, 11.0, 11.0, 100.0),
+/// ];
+///
+/// assert_eq!(VolumeWeightedAveragePrice::new().compute(&bars).unwrap(), 11.4);
+/// assert_eq!(VolumeWeightedAveragePrice::rolling(2).compute(&bars).unwrap(), 11.75);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VolumeWeightedAveragePrice<F = f64> {
+    period: Option<usize>,
+    window: VecDeque<(F, F)>,
+    price_volume: F,
+    volume: F,
+    traded: usize,
+    evicted: usize
+}
+
+impl<F: Float> VolumeWeightedAveragePrice<F> {
+    /// Constructs a new anchored `VolumeWeightedAveragePrice`, accumulating every bar.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new rolling `VolumeWeightedAveragePrice` over the last `period` bars.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of bars in the rolling window.
+    pub fn rolling(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period: Some(period),
+            window: VecDeque::with_capacity(period + 1),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the rolling window length, or `None` for an anchored VWAP.
+    pub fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for VolumeWeightedAveragePrice<F> {
+    /// Computes the VWAP at the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the VWAP, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the rolling period (or empty), contains
+    /// invalid bars, or has no traded volume.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period.unwrap_or(1), data.len())?;
+
+        self.compute_series(data)?
+            .pop()
+            .flatten()
+            .ok_or(QmachinaError::UndefinedResult("VWAP is undefined without traded volume."))
+    }
+
+    /// Computes the VWAP for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period
+    /// and wherever the accumulated volume is zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = match self.period {
+            Some(period) => Self::rolling(period),
+            None => Self::new()
+        };
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for VolumeWeightedAveragePrice<F> {
+    /// Feeds a new bar into the VWAP.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current VWAP, or `None` while a rolling VWAP is warming up or
+    /// while the accumulated volume is zero. Invalid bars are rejected with `None` and leave the
+    /// state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        let price_volume = bar.typical_price() * bar.volume;
+        self.price_volume += price_volume;
+        self.volume += bar.volume;
+
+        if let Some(period) = self.period {
+            self.window.push_back((price_volume, bar.volume));
+            if bar.volume > F::zero() {
+                self.traded += 1;
+            }
+            if self.window.len() > period {
+                if let Some((price_volume, volume)) = self.window.pop_front() {
+                    self.price_volume -= price_volume;
+                    self.volume -= volume;
+                    if volume > F::zero() {
+                        self.traded -= 1;
+                    }
+                }
+
+                self.evicted += 1;
+                if self.evicted == period {
+                    self.evicted = 0;
+                    self.price_volume = self.window.iter().fold(F::zero(), |sum, &(price_volume, _)| sum + price_volume);
+                    self.volume = self.window.iter().fold(F::zero(), |sum, &(_, volume)| sum + volume);
+                }
+            }
+            if self.traded == 0 {
+                self.price_volume = F::zero();
+                self.volume = F::zero();
+            }
+            if self.window.len() < period {
+                return None;
+            }
+        }
+
+        (self.volume > F::zero()).then(|| self.price_volume / self.volume)
+    }
+
+    /// Clears the accumulated price-volume and volume.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.price_volume = F::zero();
+        self.volume = F::zero();
+        self.traded = 0;
+        self.evicted = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+
+    #[test]
+    fn anchored_matches_reference() {
+        let vwap = VolumeWeightedAveragePrice::new().compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((vwap - 44.93668005354752).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rolling_matches_reference() {
+        let vwap = VolumeWeightedAveragePrice::rolling(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((vwap - 45.54612403100776).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rolling_warm_up() {
+        let series = VolumeWeightedAveragePrice::rolling(5).compute_series(&SAMPLE_BARS).unwrap();
+
+        assert_eq!(series.len(), SAMPLE_BARS.len());
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+        assert!(series[4..].iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn long_rolling_stream_matches_window() {
+        let bars: Vec<Bar> = (0..20_000)
+            .map(|i| {
+                let price = 1e4 * (1.5 + (i as f64 * 0.37).sin());
+                let volume = if (i / 50) % 7 == 3 { 0.0 } else { 1e6 * (1.1 + (i as f64 * 0.11).cos()) };
+                Bar::new(i, price, price * 1.01, price * 0.99, price, volume)
+            })
+            .collect();
+
+        let series = VolumeWeightedAveragePrice::rolling(20).compute_series(&bars).unwrap();
+        for (end, value) in series.iter().enumerate().skip(19) {
+            let window = &bars[end - 19..=end];
+            let volume: f64 = window.iter().map(|bar| bar.volume).sum();
+            match value {
+                Some(vwap) => {
+                    let expected = window.iter().map(|bar| bar.typical_price() * bar.volume).sum::<f64>() / volume;
+                    assert!((vwap - expected).abs() < 1e-9 * expected, "Expected {expected} at bar {end}, got {vwap}");
+                }
+                None => assert_eq!(volume, 0.0, "VWAP should be defined at bar {end}")
+            }
+        }
+        assert!(series.iter().skip(19).any(Option::is_none), "Some windows should have no traded volume");
+    }
+
+    #[test]
+    fn zero_volume_is_undefined() {
+        let bars = vec![Bar::new(0, 10.0, 10.5, 9.5, 10.0, 0.0); 3];
+        assert!(VolumeWeightedAveragePrice::new().compute(&bars).is_err());
+        assert_eq!(VolumeWeightedAveragePrice::new().compute_series(&bars).unwrap(), vec![None; 3]);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        assert!(VolumeWeightedAveragePrice::new().compute(&Vec::<Bar>::new()).is_err());
+        assert!(VolumeWeightedAveragePrice::rolling(5).compute(&SAMPLE_BARS[..4].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[7].low = f64::NAN;
+        assert!(VolumeWeightedAveragePrice::new().compute(&bars).is_err());
+    }
+}
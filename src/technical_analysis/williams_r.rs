@@ -0,0 +1,189 @@
+//! This module contains definitions and implementations for Williams %R.
+//!
+//! Williams %R, developed by Larry Williams, is a momentum indicator measuring the distance of
+//! the close from the highest high of the period, ranging from -100 (at the lowest low) to 0
+//! (at the highest high).
+use std::collections::VecDeque;
+
+use crate::error::{ensure_sufficient, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::bar::{ensure_valid_bars, highest_high_lowest_low, Bar};
+
+/// Represents the Williams %R indicator.
+///
+/// %R is `-100 * (highest high - close) / (highest high - lowest low)` over the last `period`
+/// bars, and is -50 when the high-low range is empty.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::bar::Bar;
+/// use qmachina::technical_analysis::williams_r::WilliamsR;
+///
+/// let bars = vec![
+///     Bar::new(0, 10.0, 12.0, 9.0, 10.0, 100.0),
+///     Bar::new(1, 10.0, 11.0, 8.0, 9.0, 100.0),
+/// ];
+/// assert_eq!(WilliamsR::new(2).compute(&bars).unwrap(), -75.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WilliamsR<F = f64> {
+    period: usize,
+    window: VecDeque<Bar<F>>
+}
+
+impl<F: Float> WilliamsR<F> {
+    /// Constructs a new `WilliamsR` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of bars used to find the highest high and lowest low.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1)
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for WilliamsR<F> {
+    /// Returns the period of the indicator.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<Bar<F>, F> for WilliamsR<F> {
+    /// Computes Williams %R for the last bar.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - An `Vec<Bar>` containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the %R value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is shorter than the period or contains invalid bars.
+    fn compute(&self, data: &Vec<Bar<F>>) -> Result<F> {
+        ensure_sufficient(self.period, data.len())?;
+
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes Williams %R for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the bars for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid bars.
+    fn compute_series(&self, data: &[Bar<F>]) -> Result<Vec<Option<F>>> {
+        ensure_valid_bars(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&bar| stream.update(bar)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<Bar<F>, F> for WilliamsR<F> {
+    /// Feeds a new bar into the indicator.
+    ///
+    /// # Parameters
+    ///
+    /// * `bar` - The newest bar.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current %R once `period` bars have been observed, or `None`
+    /// while warming up. Invalid bars are rejected with `None` and leave the state untouched.
+    fn update(&mut self, bar: Bar<F>) -> Option<F> {
+        if !bar.is_valid() {
+            return None;
+        }
+
+        self.window.push_back(bar);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let (highest, lowest) = highest_high_lowest_low(&self.window);
+        if highest > lowest {
+            Some(-constant::<F>(100.0) * (highest - bar.close) / (highest - lowest))
+        } else {
+            Some(-constant::<F>(50.0))
+        }
+    }
+
+    /// Clears the bar window.
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::bar::SAMPLE_BARS;
+    use crate::technical_analysis::stochastic::StochasticOscillator;
+
+    #[test]
+    fn compute_matches_reference() {
+        let williams_r = WilliamsR::new(5).compute(&SAMPLE_BARS.to_vec()).unwrap();
+        assert!((williams_r - -47.05882352941172).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mirrors_stochastic_k() {
+        let williams_r = WilliamsR::new(5).compute_series(&SAMPLE_BARS).unwrap();
+        let stochastic = StochasticOscillator::new(5, 1).compute_series(&SAMPLE_BARS).unwrap();
+
+        for (r, output) in williams_r.iter().zip(stochastic) {
+            match (r, output) {
+                (Some(r), Some(output)) => assert!((r - (output.k - 100.0)).abs() < 1e-9, "%R should be %K - 100"),
+                (r, output) => assert_eq!(r.is_none(), output.is_none())
+            }
+        }
+    }
+
+    #[test]
+    fn flat_range_is_midpoint() {
+        let bars = vec![Bar::new(0, 10.0, 10.0, 10.0, 10.0, 100.0); 2];
+        assert_eq!(WilliamsR::new(2).compute(&bars).unwrap(), -50.0);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let williams_r = WilliamsR::new(5);
+        assert!(williams_r.compute(&SAMPLE_BARS[..4].to_vec()).is_err());
+
+        let mut bars = SAMPLE_BARS.to_vec();
+        bars[0].open = f64::NAN;
+        assert!(williams_r.compute(&bars).is_err());
+    }
+}
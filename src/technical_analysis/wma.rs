@@ -0,0 +1,194 @@
+//! This module contains definitions and implementations for Weighted Moving Average (WMA).
+//!
+//! The WMA assigns linearly increasing weights to the data points of its window, so that the
+//! most recent value weighs `period` times more than the oldest one.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, count, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+
+/// Represents a Weighted Moving Average (WMA) indicator.
+///
+/// The WMA of the last `period` values is `sum(i * x_i) / (period * (period + 1) / 2)`, where
+/// `i` goes from 1 for the oldest value to `period` for the newest.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::wma::WeightedMovingAverage;
+///
+/// let wma = WeightedMovingAverage::new(3);
+/// assert_eq!(wma.compute(&vec![1.0, 2.0, 3.0, 4.0]).unwrap(), 20.0 / 6.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeightedMovingAverage<F = f64> {
+    period: usize,
+    window: VecDeque<F>,
+    sum: F,
+    weighted_sum: F
+}
+
+impl<F: Float> WeightedMovingAverage<F> {
+    /// Constructs a new `WeightedMovingAverage` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The number of data points to include in the moving average calculation.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: F::zero(),
+            weighted_sum: F::zero()
+        }
+    }
+}
+
+impl<F: Float> PeriodIndicator for WeightedMovingAverage<F> {
+    /// Returns the current period used in the WMA calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the WMA calculation, resetting any streaming state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        self.period = if period == 0 { 1 } else { period };
+        self.reset();
+    }
+}
+
+impl<F: Float> Indicator<F, F> for WeightedMovingAverage<F> {
+    /// Computes the WMA of the last `period` data points.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the WMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated WMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the length of the data is less than the WMA period or if the data
+    /// contains invalid values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, self.period)
+    }
+
+    /// Computes the WMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for WeightedMovingAverage<F> {
+    /// Feeds a new data point into the WMA.
+    ///
+    /// Sliding the window forward lowers the weight of every value in it by one, which removes
+    /// the plain sum of the window from the weighted sum, so each update runs in constant time.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the WMA of the last `period` values once enough data has been
+    /// observed, or `None` while warming up. Invalid values (NaN or infinite) are rejected with
+    /// `None` and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        if self.window.len() < self.period {
+            self.weighted_sum += count::<F>(self.window.len() + 1) * value;
+            self.sum += value;
+        } else {
+            let oldest = self.window.pop_front()?;
+            self.weighted_sum += count::<F>(self.period) * value - self.sum;
+            self.sum += value - oldest;
+        }
+        self.window.push_back(value);
+
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let period = count::<F>(self.period);
+        Some(self.weighted_sum / (period * (period + F::one()) / constant(2.0)))
+    }
+
+    /// Clears the streaming window and running sums.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = F::zero();
+        self.weighted_sum = F::zero();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_matches_reference() {
+        let wma = WeightedMovingAverage::new(5);
+        assert!((wma.compute(&DATA.to_vec()).unwrap() - 43.32733333333333).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_series_matches_window_weights() {
+        let wma = WeightedMovingAverage::new(5);
+        let series = wma.compute_series(&DATA).unwrap();
+
+        assert!(series[..4].iter().all(Option::is_none), "Warm-up values should be None");
+        for (end, value) in (5..=DATA.len()).zip(&series[4..]) {
+            let expected = DATA[end - 5..end].iter()
+                .enumerate()
+                .map(|(index, value)| (index + 1) as f64 * value)
+                .sum::<f64>() / 15.0;
+            assert!((value.unwrap() - expected).abs() < 1e-9, "Streamed WMA should match the window weights");
+        }
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let wma = WeightedMovingAverage::new(5);
+        assert!(wma.compute(&vec![1.0, 2.0, 3.0, 4.0]).is_err());
+        assert!(wma.compute(&vec![1.0, 2.0, f64::NAN, 4.0, 5.0]).is_err());
+    }
+
+    #[test]
+    fn period_one_is_identity() {
+        let mut wma = WeightedMovingAverage::new(0);
+        assert_eq!(wma.period(), 1);
+        assert_eq!(wma.update(3.0), Some(3.0));
+        assert_eq!(wma.update(5.0), Some(5.0));
+    }
+}
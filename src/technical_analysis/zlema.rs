@@ -0,0 +1,183 @@
+//! This module contains definitions and implementations for Zero-Lag Exponential Moving Average (ZLEMA).
+//!
+//! The ZLEMA, introduced by John Ehlers and Ric Way, removes most of the lag of an EMA by
+//! feeding it de-lagged data: each value is extended by its momentum over `(period - 1) / 2`
+//! data points, `2 * x[t] - x[t - lag]`.
+use std::collections::VecDeque;
+
+use crate::error::{ensure_finite, Result};
+use crate::numeric::{constant, Float};
+use super::{last_value, Indicator, PeriodIndicator, StreamingIndicator};
+use super::ema::RecursiveEma;
+
+/// Represents a Zero-Lag Exponential Moving Average (ZLEMA) indicator.
+///
+/// The EMA runs over the whole history of the de-lagged data and is seeded with an SMA, so the
+/// first value is available after `lag + period` data points.
+///
+/// # Examples
+///
+/// ```
+/// use qmachina::technical_analysis::Indicator;
+/// use qmachina::technical_analysis::zlema::ZeroLagExponentialMovingAverage;
+///
+/// let zlema: ZeroLagExponentialMovingAverage = ZeroLagExponentialMovingAverage::new(3);
+/// let data: Vec<f64> = (0..10).map(|value| value as f64).collect();
+/// assert!((zlema.compute(&data).unwrap() - 9.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ZeroLagExponentialMovingAverage<F = f64> {
+    period: usize,
+    lag: usize,
+    window: VecDeque<F>,
+    ema: RecursiveEma<F>
+}
+
+impl<F: Float> ZeroLagExponentialMovingAverage<F> {
+    /// Constructs a new `ZeroLagExponentialMovingAverage` with the given period.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The period of the EMA, which also sets the lag as `(period - 1) / 2`.
+    pub fn new(period: usize) -> Self {
+        let period = if period == 0 { 1 } else { period };
+        let lag = (period - 1) / 2;
+
+        Self {
+            period,
+            lag,
+            window: VecDeque::with_capacity(lag + 2),
+            ema: RecursiveEma::new(period)
+        }
+    }
+
+    /// Returns the number of data points used to de-lag the data.
+    pub fn lag(&self) -> usize {
+        self.lag
+    }
+}
+
+impl<F: Float> PeriodIndicator for ZeroLagExponentialMovingAverage<F> {
+    /// Returns the current period used in the ZLEMA calculation.
+    fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Sets a new period for the ZLEMA calculation, updating the lag and resetting any streaming
+    /// state.
+    ///
+    /// # Parameters
+    ///
+    /// * `period` - The new period. A period of 0 is replaced by 1.
+    fn set_period(&mut self, period: usize) {
+        *self = Self::new(period);
+    }
+}
+
+impl<F: Float> Indicator<F, F> for ZeroLagExponentialMovingAverage<F> {
+    /// Computes the ZLEMA at the last data point.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A `Vec<F>` containing the data points for which the ZLEMA is calculated.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(F)` containing the calculated ZLEMA value, or an error if the calculation fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data has fewer than `lag + period` values or contains invalid
+    /// values (NaN or infinite).
+    fn compute(&self, data: &Vec<F>) -> Result<F> {
+        last_value(self.compute_series(data)?, self.lag + self.period)
+    }
+
+    /// Computes the ZLEMA for every position of the provided data in a single pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - A slice containing the data points for the calculation.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Option<F>>)` aligned with `data`, with `None` during the warm-up period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data contains invalid values (NaN or infinite).
+    fn compute_series(&self, data: &[F]) -> Result<Vec<Option<F>>> {
+        ensure_finite(data)?;
+
+        let mut stream = Self::new(self.period);
+        Ok(data.iter().map(|&value| stream.update(value)).collect())
+    }
+}
+
+impl<F: Float> StreamingIndicator<F, F> for ZeroLagExponentialMovingAverage<F> {
+    /// Feeds a new data point into the ZLEMA.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The newest data point.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(F)` with the current ZLEMA once the EMA is seeded, or `None` while warming
+    /// up. Invalid values (NaN or infinite) are rejected with `None` and leave the state untouched.
+    fn update(&mut self, value: F) -> Option<F> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        self.window.push_back(value);
+        if self.window.len() <= self.lag {
+            return None;
+        }
+        if self.window.len() > self.lag + 1 {
+            self.window.pop_front();
+        }
+
+        let lagged = self.window.front().copied()?;
+        self.ema.update(constant::<F>(2.0) * value - lagged)
+    }
+
+    /// Clears the lag window and the EMA.
+    fn reset(&mut self) {
+        self.window.clear();
+        self.ema.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::technical_analysis::REFERENCE_PRICES as DATA;
+
+    #[test]
+    fn compute_series_matches_reference() {
+        let series = ZeroLagExponentialMovingAverage::new(5).compute_series(&DATA).unwrap();
+
+        assert!(series[..6].iter().all(Option::is_none), "ZLEMA needs lag + period values");
+        assert!((series[6].unwrap() - 44.704).abs() < 1e-9);
+        assert!((series[7].unwrap() - 45.1393333333).abs() < 1e-9);
+        assert!((series[32].unwrap() - 42.79972017368104).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lag_follows_period() {
+        let mut zlema: ZeroLagExponentialMovingAverage = ZeroLagExponentialMovingAverage::new(5);
+        assert_eq!(zlema.lag(), 2);
+
+        zlema.set_period(8);
+        assert_eq!(zlema.lag(), 3);
+    }
+
+    #[test]
+    fn insufficient_or_invalid_data_fails() {
+        let zlema: ZeroLagExponentialMovingAverage = ZeroLagExponentialMovingAverage::new(5);
+        assert!(zlema.compute(&DATA[..6].to_vec()).is_err());
+        assert!(zlema.compute(&DATA[..7].to_vec()).is_ok());
+        assert!(zlema.compute(&vec![f64::NAN; 7]).is_err());
+    }
+}
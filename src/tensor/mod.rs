@@ -0,0 +1,374 @@
+//! This module contains a dense, row-major n-dimensional `Tensor`, the array type shared by the
+//! machine learning modules to represent batches of samples and their features.
+//!
+//! A `Tensor` stores its values in a flat `Vec` along with its shape. The last axis is contiguous,
+//! so a 2-D tensor of shape `[samples, features]` stores each sample as a row, which is the layout
+//! expected by `BatchLossFunction` and by the adapters of activations and losses.
+//!
+//! Element-wise operations broadcast their operands like NumPy: shapes are aligned on their last
+//! axes, and an axis of length 1, or a missing one, is repeated to match the other operand.
+//!
+//! ```
+//! use qmachina::tensor::Tensor;
+//!
+//! let features = Tensor::from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+//! let means = features.mean_axis(0).unwrap();
+//! assert_eq!(means.as_slice(), &[2.0, 3.0]);
+//!
+//! let centered = features.sub(&means).unwrap();
+//! assert_eq!(centered.as_slice(), &[-1.0, -1.0, 1.0, 1.0]);
+//! ```
+
+use std::ops::Range;
+
+use crate::error::{ensure_same_length, QmachinaError, Result};
+use crate::numeric::Float;
+
+pub mod ops;
+pub mod reduce;
+
+/// Represents a dense n-dimensional array of floating-point values in row-major order.
+///
+/// The value type defaults to `f64`. A tensor with an empty shape is a scalar holding a single
+/// value, and a tensor with an axis of length 0 holds no values.
+///
+/// # Example
+///
+/// ```
+/// use qmachina::tensor::Tensor;
+///
+/// let matrix = Tensor::new(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+/// assert_eq!(matrix.shape(), &[2, 3]);
+/// assert_eq!(matrix.get(&[1, 0]), Some(4.0));
+///
+/// let product = matrix.matmul(&matrix.transpose()).unwrap();
+/// assert_eq!(product.as_slice(), &[14.0, 32.0, 32.0, 77.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tensor<F = f64> {
+    shape: Vec<usize>,
+    data: Vec<F>
+}
+
+impl<F: Float> Tensor<F> {
+    /// Constructs a new `Tensor` from its shape and its values in row-major order.
+    ///
+    /// # Parameters
+    ///
+    /// * `shape` - The length of each axis.
+    /// * `data` - The values, as many as the product of the lengths of the axes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::LengthMismatch` if the number of values does not match the shape.
+    pub fn new(shape: &[usize], data: Vec<F>) -> Result<Self> {
+        ensure_same_length(shape.iter().product(), data.len())?;
+
+        Ok(Self { shape: shape.to_vec(), data })
+    }
+
+    /// Constructs a tensor of the given shape filled with `value`.
+    pub fn full(shape: &[usize], value: F) -> Self {
+        Self { shape: shape.to_vec(), data: vec![value; shape.iter().product()] }
+    }
+
+    /// Constructs a tensor of the given shape filled with zeros.
+    pub fn zeros(shape: &[usize]) -> Self {
+        Self::full(shape, F::zero())
+    }
+
+    /// Constructs a tensor of the given shape filled with ones.
+    pub fn ones(shape: &[usize]) -> Self {
+        Self::full(shape, F::one())
+    }
+
+    /// Constructs a scalar tensor, with an empty shape, holding a single value.
+    pub fn scalar(value: F) -> Self {
+        Self { shape: Vec::new(), data: vec![value] }
+    }
+
+    /// Constructs a 1-D tensor from a vector of values.
+    pub fn from_vec(data: Vec<F>) -> Self {
+        Self { shape: vec![data.len()], data }
+    }
+
+    /// Constructs a 2-D tensor from rows of equal length, such as one row of features per sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::LengthMismatch` if a row differs in length from the first one.
+    pub fn from_rows(rows: &[Vec<F>]) -> Result<Self> {
+        let columns = rows.first().map_or(0, Vec::len);
+        let mut data = Vec::with_capacity(rows.len() * columns);
+        for row in rows {
+            ensure_same_length(columns, row.len())?;
+            data.extend_from_slice(row);
+        }
+
+        Ok(Self { shape: vec![rows.len(), columns], data })
+    }
+
+    /// Returns the length of each axis.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the number of axes, 0 for a scalar.
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether the tensor holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the values in row-major order.
+    pub fn as_slice(&self) -> &[F] {
+        &self.data
+    }
+
+    /// Returns the values in row-major order, for modification in place.
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        &mut self.data
+    }
+
+    /// Consumes the tensor and returns its values in row-major order.
+    pub fn into_vec(self) -> Vec<F> {
+        self.data
+    }
+
+    /// Returns the rows of a 2-D tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the tensor is not 2-D.
+    pub fn to_rows(&self) -> Result<Vec<Vec<F>>> {
+        let [rows, columns] = self.shape[..] else {
+            return Err(QmachinaError::InvalidParameter("Rows are only defined for 2-D tensors."));
+        };
+
+        if columns == 0 {
+            return Ok(vec![Vec::new(); rows]);
+        }
+        Ok(self.data.chunks(columns).map(<[F]>::to_vec).collect())
+    }
+
+    /// Returns the value at a multi-dimensional index, or `None` if the index is out of bounds
+    /// or does not have one position per axis.
+    pub fn get(&self, index: &[usize]) -> Option<F> {
+        self.offset(index).map(|offset| self.data[offset])
+    }
+
+    /// Returns a mutable reference to the value at a multi-dimensional index, or `None` if the
+    /// index is out of bounds or does not have one position per axis.
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut F> {
+        self.offset(index).map(move |offset| &mut self.data[offset])
+    }
+
+    /// Returns the same values with another shape.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::LengthMismatch` if the new shape holds a different number of values.
+    pub fn reshape(&self, shape: &[usize]) -> Result<Self> {
+        Self::new(shape, self.data.clone())
+    }
+
+    /// Applies a function to every value.
+    pub fn map(&self, f: impl FnMut(F) -> F) -> Self {
+        Self { shape: self.shape.clone(), data: self.data.iter().copied().map(f).collect() }
+    }
+
+    /// Applies a function to every lane along the last axis, such as every row of a matrix. The
+    /// function must return a lane of the same length. A scalar is a single lane of one value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::LengthMismatch` if the function changes the length of a lane.
+    pub fn map_lanes(&self, mut f: impl FnMut(&[F]) -> Vec<F>) -> Result<Self> {
+        let lane = self.shape.last().copied().unwrap_or(1);
+        if lane == 0 {
+            return Ok(self.clone());
+        }
+
+        let mut data = Vec::with_capacity(self.data.len());
+        for values in self.data.chunks(lane) {
+            let mapped = f(values);
+            ensure_same_length(lane, mapped.len())?;
+            data.extend(mapped);
+        }
+
+        Ok(Self { shape: self.shape.clone(), data })
+    }
+
+    /// Returns the sub-tensor of the positions in `range` along `axis`, keeping the axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist or the range is out
+    /// of bounds.
+    pub fn slice(&self, axis: usize, range: Range<usize>) -> Result<Self> {
+        let length = self.axis_length(axis)?;
+        if range.start > range.end || range.end > length {
+            return Err(QmachinaError::InvalidParameter("The range is out of bounds of the axis."));
+        }
+
+        let inner: usize = self.shape[axis + 1..].iter().product();
+        let mut shape = self.shape.clone();
+        shape[axis] = range.len();
+        if self.data.is_empty() {
+            return Ok(Self { shape, data: Vec::new() });
+        }
+
+        let data = self.data
+            .chunks(length * inner)
+            .flat_map(|outer| &outer[range.start * inner..range.end * inner])
+            .copied()
+            .collect();
+
+        Ok(Self { shape, data })
+    }
+
+    /// Returns the sub-tensor at `index` along `axis`, removing the axis, such as a row of a
+    /// matrix for axis 0 or a column for axis 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist or the index is out
+    /// of bounds.
+    pub fn select(&self, axis: usize, index: usize) -> Result<Self> {
+        let mut selected = self.slice(axis, index..index + 1)?;
+        selected.shape.remove(axis);
+        Ok(selected)
+    }
+
+    /// Returns the length of an axis.
+    fn axis_length(&self, axis: usize) -> Result<usize> {
+        self.shape.get(axis).copied().ok_or(QmachinaError::InvalidParameter("The axis is out of range."))
+    }
+
+    /// Returns the position in `data` of a multi-dimensional index.
+    fn offset(&self, index: &[usize]) -> Option<usize> {
+        if index.len() != self.shape.len() {
+            return None;
+        }
+
+        index.iter().zip(&self.shape).try_fold(0, |offset, (&position, &length)| {
+            (position < length).then_some(offset * length + position)
+        })
+    }
+}
+
+impl<F: Float> From<Vec<F>> for Tensor<F> {
+    /// Converts a vector into a 1-D tensor.
+    fn from(data: Vec<F>) -> Self {
+        Self::from_vec(data)
+    }
+}
+
+/// Checks that two tensors paired value by value have the same shape.
+///
+/// # Errors
+///
+/// Returns `QmachinaError::ShapeMismatch` if the shapes differ.
+pub(crate) fn ensure_same_shape<F>(left: &Tensor<F>, right: &Tensor<F>) -> Result<()> {
+    if left.shape != right.shape {
+        return Err(QmachinaError::ShapeMismatch { left: left.shape.clone(), right: right.shape.clone() });
+    }
+
+    Ok(())
+}
+
+/// Returns the distance, in values, between consecutive positions along each axis of a row-major
+/// array of the given shape.
+pub(crate) fn strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for axis in (0..shape.len().saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1];
+    }
+    strides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Tensor {
+        Tensor::new(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap()
+    }
+
+    #[test]
+    fn construction_checks_the_shape() {
+        assert!(matches!(Tensor::new(&[2, 3], vec![1.0; 5]), Err(QmachinaError::LengthMismatch { expected: 6, got: 5 })));
+        assert_eq!(Tensor::<f64>::zeros(&[2, 0]).len(), 0);
+        assert_eq!(Tensor::scalar(3.0).shape(), &[] as &[usize]);
+        assert_eq!(Tensor::scalar(3.0).get(&[]), Some(3.0));
+        assert_eq!(Tensor::from(vec![1.0, 2.0]).shape(), &[2]);
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let rows = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let tensor = Tensor::from_rows(&rows).unwrap();
+
+        assert_eq!(tensor, matrix());
+        assert_eq!(tensor.to_rows().unwrap(), rows);
+        assert!(matches!(Tensor::from_rows(&[vec![1.0], vec![1.0, 2.0]]), Err(QmachinaError::LengthMismatch { expected: 1, got: 2 })));
+        assert!(matches!(Tensor::from_vec(vec![1.0]).to_rows(), Err(QmachinaError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn indexing() {
+        let mut tensor = matrix();
+
+        assert_eq!(tensor.get(&[0, 2]), Some(3.0));
+        assert_eq!(tensor.get(&[2, 0]), None);
+        assert_eq!(tensor.get(&[0]), None);
+
+        *tensor.get_mut(&[1, 1]).unwrap() = 10.0;
+        assert_eq!(tensor.as_slice(), &[1.0, 2.0, 3.0, 4.0, 10.0, 6.0]);
+    }
+
+    #[test]
+    fn reshape_keeps_the_values() {
+        let reshaped = matrix().reshape(&[3, 2]).unwrap();
+        assert_eq!(reshaped.get(&[2, 1]), Some(6.0));
+        assert!(matches!(matrix().reshape(&[4]), Err(QmachinaError::LengthMismatch { expected: 4, got: 6 })));
+    }
+
+    #[test]
+    fn slice_and_select() {
+        let tensor = Tensor::new(&[2, 3, 2], (0..12).map(f64::from).collect()).unwrap();
+
+        let sliced = tensor.slice(1, 1..3).unwrap();
+        assert_eq!(sliced.shape(), &[2, 2, 2]);
+        assert_eq!(sliced.as_slice(), &[2.0, 3.0, 4.0, 5.0, 8.0, 9.0, 10.0, 11.0]);
+
+        let column = matrix().select(1, 1).unwrap();
+        assert_eq!(column.shape(), &[2]);
+        assert_eq!(column.as_slice(), &[2.0, 5.0]);
+
+        assert_eq!(matrix().select(0, 1).unwrap().as_slice(), &[4.0, 5.0, 6.0]);
+        assert!(matches!(matrix().slice(1, 2..4), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(matrix().select(2, 0), Err(QmachinaError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn map_lanes_maps_rows() {
+        let reversed = matrix().map_lanes(|row| row.iter().rev().copied().collect()).unwrap();
+        assert_eq!(reversed.as_slice(), &[3.0, 2.0, 1.0, 6.0, 5.0, 4.0]);
+        assert!(matches!(matrix().map_lanes(|row| row[..1].to_vec()), Err(QmachinaError::LengthMismatch { expected: 3, got: 1 })));
+    }
+
+    #[test]
+    fn strides_of_row_major_shapes() {
+        assert_eq!(strides(&[2, 3, 4]), vec![12, 4, 1]);
+        assert_eq!(strides(&[]), Vec::<usize>::new());
+    }
+}
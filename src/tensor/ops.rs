@@ -0,0 +1,277 @@
+//! This module contains the arithmetic of `Tensor`: broadcasting element-wise operations,
+//! matrix multiplication and transposition.
+
+use crate::error::{QmachinaError, Result};
+use crate::numeric::Float;
+use super::{strides, Tensor};
+
+impl<F: Float> Tensor<F> {
+    /// Combines two tensors value by value, broadcasting their shapes.
+    ///
+    /// Shapes are aligned on their last axes. Along each axis, the lengths must be equal or one
+    /// of them must be 1, and missing leading axes are treated as having length 1. The values of
+    /// an axis of length 1 are repeated along the other operand's axis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use qmachina::tensor::Tensor;
+    ///
+    /// let column = Tensor::new(&[2, 1], vec![1.0, 2.0]).unwrap();
+    /// let row = Tensor::from_vec(vec![10.0, 20.0, 30.0]);
+    ///
+    /// let sum = column.zip_with(&row, |a, b| a + b).unwrap();
+    /// assert_eq!(sum.shape(), &[2, 3]);
+    /// assert_eq!(sum.as_slice(), &[11.0, 21.0, 31.0, 12.0, 22.0, 32.0]);
+    /// ```
+    pub fn zip_with(&self, other: &Self, mut f: impl FnMut(F, F) -> F) -> Result<Self> {
+        if self.shape == other.shape {
+            let data = self.data.iter().zip(&other.data).map(|(&a, &b)| f(a, b)).collect();
+            return Ok(Self { shape: self.shape.clone(), data });
+        }
+
+        let shape = broadcast_shape(&self.shape, &other.shape).ok_or_else(|| QmachinaError::ShapeMismatch {
+            left: self.shape.clone(),
+            right: other.shape.clone()
+        })?;
+        let left = broadcast_strides(&self.shape, &shape);
+        let right = broadcast_strides(&other.shape, &shape);
+
+        let length = shape.iter().product();
+        let mut data = Vec::with_capacity(length);
+        let mut index = vec![0; shape.len()];
+        for _ in 0..length {
+            let offset = |strides: &[usize]| index.iter().zip(strides).map(|(i, s)| i * s).sum::<usize>();
+            data.push(f(self.data[offset(&left)], other.data[offset(&right)]));
+            advance(&mut index, &shape);
+        }
+
+        Ok(Self { shape, data })
+    }
+
+    /// Adds two tensors value by value, broadcasting their shapes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together.
+    pub fn add(&self, other: &Self) -> Result<Self> {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    /// Subtracts two tensors value by value, broadcasting their shapes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together.
+    pub fn sub(&self, other: &Self) -> Result<Self> {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    /// Multiplies two tensors value by value, broadcasting their shapes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together.
+    pub fn mul(&self, other: &Self) -> Result<Self> {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    /// Divides two tensors value by value, broadcasting their shapes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if the shapes do not broadcast together.
+    pub fn div(&self, other: &Self) -> Result<Self> {
+        self.zip_with(other, |a, b| a / b)
+    }
+
+    /// Multiplies every value by a factor.
+    pub fn scale(&self, factor: F) -> Self {
+        self.map(|value| value * factor)
+    }
+
+    /// Computes the matrix product of two 2-D tensors, of shapes `[n, k]` and `[k, m]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::ShapeMismatch` if either tensor is not 2-D or if their inner
+    /// dimensions differ.
+    pub fn matmul(&self, other: &Self) -> Result<Self> {
+        let (&[rows, inner], &[other_inner, columns]) = (&self.shape[..], &other.shape[..]) else {
+            return Err(self.mismatch(other));
+        };
+        if inner != other_inner {
+            return Err(self.mismatch(other));
+        }
+
+        let mut data = vec![F::zero(); rows * columns];
+        for (row, output) in data.chunks_mut(columns.max(1)).take(rows).enumerate() {
+            for k in 0..inner {
+                let a = self.data[row * inner + k];
+                for (value, &b) in output.iter_mut().zip(&other.data[k * columns..(k + 1) * columns]) {
+                    *value += a * b;
+                }
+            }
+        }
+
+        Ok(Self { shape: vec![rows, columns], data })
+    }
+
+    /// Returns the tensor with its axes reversed, which is the transpose of a matrix.
+    pub fn transpose(&self) -> Self {
+        let axes: Vec<usize> = (0..self.ndim()).rev().collect();
+        self.permute(&axes).expect("Reversed axes are a permutation.")
+    }
+
+    /// Returns the tensor with its axes reordered, axis `i` of the result being axis `axes[i]`
+    /// of the tensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if `axes` is not a permutation of the axes.
+    pub fn permute(&self, axes: &[usize]) -> Result<Self> {
+        let mut sorted = axes.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(0..self.ndim()) {
+            return Err(QmachinaError::InvalidParameter("The axes must be a permutation of the axes of the tensor."));
+        }
+
+        let shape: Vec<usize> = axes.iter().map(|&axis| self.shape[axis]).collect();
+        let source_strides = strides(&self.shape);
+        let permuted_strides: Vec<usize> = axes.iter().map(|&axis| source_strides[axis]).collect();
+
+        let length = self.data.len();
+        let mut data = Vec::with_capacity(length);
+        let mut index = vec![0; shape.len()];
+        for _ in 0..length {
+            let offset: usize = index.iter().zip(&permuted_strides).map(|(i, s)| i * s).sum();
+            data.push(self.data[offset]);
+            advance(&mut index, &shape);
+        }
+
+        Ok(Self { shape, data })
+    }
+
+    /// Returns the error reporting the shapes of two incompatible tensors.
+    fn mismatch(&self, other: &Self) -> QmachinaError {
+        QmachinaError::ShapeMismatch { left: self.shape.clone(), right: other.shape.clone() }
+    }
+}
+
+/// Moves a multi-dimensional index to the next position in row-major order, like an odometer.
+fn advance(index: &mut [usize], shape: &[usize]) {
+    for axis in (0..shape.len()).rev() {
+        index[axis] += 1;
+        if index[axis] < shape[axis] {
+            return;
+        }
+        index[axis] = 0;
+    }
+}
+
+/// Returns the shape two shapes broadcast to, or `None` if they are incompatible.
+pub(crate) fn broadcast_shape(left: &[usize], right: &[usize]) -> Option<Vec<usize>> {
+    let ndim = left.len().max(right.len());
+    let length = |shape: &[usize], axis: usize| {
+        (axis + shape.len()).checked_sub(ndim).map_or(1, |axis| shape[axis])
+    };
+
+    (0..ndim)
+        .map(|axis| match (length(left, axis), length(right, axis)) {
+            (a, b) if a == b => Some(a),
+            (1, b) => Some(b),
+            (a, 1) => Some(a),
+            _ => None
+        })
+        .collect()
+}
+
+/// Returns the strides of `shape` within the broadcast `target` shape, zero along the axes where
+/// the values are repeated.
+fn broadcast_strides(shape: &[usize], target: &[usize]) -> Vec<usize> {
+    let leading = target.len() - shape.len();
+    let own = strides(shape);
+
+    (0..target.len())
+        .map(|axis| match axis.checked_sub(leading) {
+            Some(axis) if shape[axis] != 1 => own[axis],
+            _ => 0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Tensor {
+        Tensor::new(&[2, 3], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap()
+    }
+
+    #[test]
+    fn broadcast_shapes() {
+        assert_eq!(broadcast_shape(&[2, 3], &[3]), Some(vec![2, 3]));
+        assert_eq!(broadcast_shape(&[4, 1, 3], &[2, 1]), Some(vec![4, 2, 3]));
+        assert_eq!(broadcast_shape(&[], &[2]), Some(vec![2]));
+        assert_eq!(broadcast_shape(&[2, 3], &[2]), None);
+    }
+
+    #[test]
+    fn elementwise_operations_broadcast() {
+        let row = Tensor::from_vec(vec![1.0, 0.0, -1.0]);
+
+        assert_eq!(matrix().add(&row).unwrap().as_slice(), &[2.0, 2.0, 2.0, 5.0, 5.0, 5.0]);
+        assert_eq!(matrix().sub(&matrix()).unwrap(), Tensor::zeros(&[2, 3]));
+        assert_eq!(matrix().mul(&Tensor::scalar(2.0)).unwrap(), matrix().scale(2.0));
+        assert_eq!(matrix().div(&matrix()).unwrap(), Tensor::ones(&[2, 3]));
+
+        let column = Tensor::new(&[2, 1], vec![10.0, 100.0]).unwrap();
+        assert_eq!(matrix().mul(&column).unwrap().as_slice(), &[10.0, 20.0, 30.0, 400.0, 500.0, 600.0]);
+    }
+
+    #[test]
+    fn elementwise_operations_reject_incompatible_shapes() {
+        let result = matrix().add(&Tensor::from_vec(vec![1.0, 2.0]));
+        assert!(matches!(result, Err(QmachinaError::ShapeMismatch { left, right }) if left == vec![2, 3] && right == vec![2]));
+    }
+
+    #[test]
+    fn matmul_of_matrices() {
+        let identity = Tensor::new(&[3, 3], vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]).unwrap();
+        assert_eq!(matrix().matmul(&identity).unwrap(), matrix());
+
+        let product = matrix().transpose().matmul(&matrix()).unwrap();
+        assert_eq!(product.shape(), &[3, 3]);
+        assert_eq!(product.as_slice(), &[17.0, 22.0, 27.0, 22.0, 29.0, 36.0, 27.0, 36.0, 45.0]);
+
+        assert!(matches!(matrix().matmul(&matrix()), Err(QmachinaError::ShapeMismatch { .. })));
+        assert!(matches!(matrix().matmul(&Tensor::from_vec(vec![1.0, 2.0, 3.0])), Err(QmachinaError::ShapeMismatch { .. })));
+    }
+
+    #[test]
+    fn matmul_with_empty_dimensions() {
+        let product = Tensor::<f64>::zeros(&[2, 0]).matmul(&Tensor::zeros(&[0, 3])).unwrap();
+        assert_eq!(product, Tensor::zeros(&[2, 3]));
+        assert_eq!(matrix().matmul(&Tensor::zeros(&[3, 0])).unwrap().shape(), &[2, 0]);
+    }
+
+    #[test]
+    fn transpose_and_permute() {
+        let transposed = matrix().transpose();
+        assert_eq!(transposed.shape(), &[3, 2]);
+        assert_eq!(transposed.as_slice(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+        assert_eq!(transposed.transpose(), matrix());
+
+        let tensor = Tensor::new(&[2, 3, 4], (0..24).map(f64::from).collect()).unwrap();
+        let permuted = tensor.permute(&[1, 2, 0]).unwrap();
+        assert_eq!(permuted.shape(), &[3, 4, 2]);
+        assert_eq!(permuted.get(&[2, 1, 1]), tensor.get(&[1, 2, 1]));
+
+        assert!(matches!(tensor.permute(&[0, 0, 1]), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(tensor.permute(&[0, 1]), Err(QmachinaError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,162 @@
+//! This module contains the reductions of `Tensor`, over all of its values or along an axis.
+
+use crate::error::{QmachinaError, Result};
+use crate::numeric::{count, Float};
+use super::Tensor;
+
+impl<F: Float> Tensor<F> {
+    /// Returns the sum of all values, 0 for an empty tensor.
+    pub fn sum(&self) -> F {
+        self.data.iter().fold(F::zero(), |sum, &value| sum + value)
+    }
+
+    /// Returns the average of all values.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for an empty tensor.
+    pub fn mean(&self) -> Result<F> {
+        if self.is_empty() {
+            return Err(QmachinaError::EmptyInput);
+        }
+        Ok(self.sum() / count(self.len()))
+    }
+
+    /// Returns the largest value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for an empty tensor.
+    pub fn max(&self) -> Result<F> {
+        self.data.iter().copied().reduce(F::max).ok_or(QmachinaError::EmptyInput)
+    }
+
+    /// Returns the smallest value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::EmptyInput` for an empty tensor.
+    pub fn min(&self) -> Result<F> {
+        self.data.iter().copied().reduce(F::min).ok_or(QmachinaError::EmptyInput)
+    }
+
+    /// Sums the values along an axis, removing it, such as the sum of each column of a matrix
+    /// for axis 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist.
+    pub fn sum_axis(&self, axis: usize) -> Result<Self> {
+        self.reduce_axis(axis, |values| values.fold(F::zero(), |sum, value| sum + value))
+    }
+
+    /// Averages the values along an axis, removing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist, and
+    /// `QmachinaError::EmptyInput` if it has length 0.
+    pub fn mean_axis(&self, axis: usize) -> Result<Self> {
+        let length = self.nonempty_axis(axis)?;
+        Ok(self.sum_axis(axis)?.scale(F::one() / count(length)))
+    }
+
+    /// Returns the largest value along an axis, removing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist, and
+    /// `QmachinaError::EmptyInput` if it has length 0.
+    pub fn max_axis(&self, axis: usize) -> Result<Self> {
+        self.nonempty_axis(axis)?;
+        self.reduce_axis(axis, |values| values.fold(F::neg_infinity(), F::max))
+    }
+
+    /// Returns the smallest value along an axis, removing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `QmachinaError::InvalidParameter` if the axis does not exist, and
+    /// `QmachinaError::EmptyInput` if it has length 0.
+    pub fn min_axis(&self, axis: usize) -> Result<Self> {
+        self.nonempty_axis(axis)?;
+        self.reduce_axis(axis, |values| values.fold(F::infinity(), F::min))
+    }
+
+    /// Returns the length of an axis, checking that it holds values.
+    fn nonempty_axis(&self, axis: usize) -> Result<usize> {
+        match self.axis_length(axis)? {
+            0 => Err(QmachinaError::EmptyInput),
+            length => Ok(length)
+        }
+    }
+
+    /// Reduces the values along an axis with `reduce`, which receives the values of each lane
+    /// along the axis.
+    fn reduce_axis(&self, axis: usize, mut reduce: impl FnMut(&mut dyn Iterator<Item = F>) -> F) -> Result<Self> {
+        let length = self.axis_length(axis)?;
+        let outer: usize = self.shape[..axis].iter().product();
+        let inner: usize = self.shape[axis + 1..].iter().product();
+
+        let mut shape = self.shape.clone();
+        shape.remove(axis);
+
+        let mut data = Vec::with_capacity(outer * inner);
+        for o in 0..outer {
+            for i in 0..inner {
+                let mut lane = (0..length).map(|l| self.data[(o * length + l) * inner + i]);
+                data.push(reduce(&mut lane));
+            }
+        }
+
+        Ok(Self { shape, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix() -> Tensor {
+        Tensor::new(&[2, 3], vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0]).unwrap()
+    }
+
+    #[test]
+    fn reductions_over_all_values() {
+        assert_eq!(matrix().sum(), 21.0);
+        assert_eq!(matrix().mean().unwrap(), 3.5);
+        assert_eq!(matrix().max().unwrap(), 6.0);
+        assert_eq!(matrix().min().unwrap(), 1.0);
+
+        let empty = Tensor::<f64>::zeros(&[0, 3]);
+        assert_eq!(empty.sum(), 0.0);
+        assert!(matches!(empty.mean(), Err(QmachinaError::EmptyInput)));
+        assert!(matches!(empty.max(), Err(QmachinaError::EmptyInput)));
+    }
+
+    #[test]
+    fn reductions_along_an_axis() {
+        assert_eq!(matrix().sum_axis(0).unwrap(), Tensor::from_vec(vec![5.0, 7.0, 9.0]));
+        assert_eq!(matrix().sum_axis(1).unwrap(), Tensor::from_vec(vec![9.0, 12.0]));
+        assert_eq!(matrix().mean_axis(1).unwrap(), Tensor::from_vec(vec![3.0, 4.0]));
+        assert_eq!(matrix().max_axis(0).unwrap(), Tensor::from_vec(vec![4.0, 5.0, 6.0]));
+        assert_eq!(matrix().min_axis(1).unwrap(), Tensor::from_vec(vec![1.0, 2.0]));
+        assert_eq!(Tensor::from_vec(vec![1.0, 2.0]).sum_axis(0).unwrap(), Tensor::scalar(3.0));
+    }
+
+    #[test]
+    fn reductions_along_a_middle_axis() {
+        let tensor = Tensor::new(&[2, 3, 2], (0..12).map(f64::from).collect()).unwrap();
+        let summed = tensor.sum_axis(1).unwrap();
+
+        assert_eq!(summed.shape(), &[2, 2]);
+        assert_eq!(summed.as_slice(), &[6.0, 9.0, 24.0, 27.0]);
+    }
+
+    #[test]
+    fn reductions_along_invalid_axes() {
+        assert!(matches!(matrix().sum_axis(2), Err(QmachinaError::InvalidParameter(_))));
+        assert!(matches!(Tensor::<f64>::zeros(&[0, 3]).mean_axis(0), Err(QmachinaError::EmptyInput)));
+        assert_eq!(Tensor::<f64>::zeros(&[0, 3]).sum_axis(0).unwrap(), Tensor::zeros(&[3]));
+    }
+}